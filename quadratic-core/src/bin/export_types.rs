@@ -62,6 +62,7 @@ fn main() {
         grid::NumericFormat,
         grid::NumericFormatKind,
         grid::SheetId,
+        grid::DefinedName,
         grid::js_types::JsRenderCell,
         grid::js_types::JsRenderFill,
         grid::js_types::CellFormatSummary,
@@ -59,6 +59,9 @@ pub struct PendingTransaction {
 
     // cursor saved for an Undo or Redo
     pub cursor_undo_redo: Option<String>,
+
+    // whether volatile code cells have been added to be recomputed
+    pub volatile_computes_added: bool,
 }
 
 impl Default for PendingTransaction {
@@ -80,6 +83,7 @@ impl Default for PendingTransaction {
             complete: false,
             generate_thumbnail: false,
             cursor_undo_redo: None,
+            volatile_computes_added: false,
         }
     }
 }
@@ -19,4 +19,6 @@ pub enum TransactionName {
     SheetDelete,
     DuplicateSheet,
     MoveCells,
+    SetDefinedName,
+    Recalculate,
 }
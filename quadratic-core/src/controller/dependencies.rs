@@ -2,7 +2,11 @@ use std::{self};
 
 use std::collections::HashSet;
 
-use crate::{SheetPos, SheetRect};
+use crate::{
+    formulas::{find_sheet_range_references, formula_uses_name},
+    grid::{CodeCellLanguage, SheetId},
+    CellValue, CodeCellValue, SheetPos, SheetRect,
+};
 
 use super::GridController;
 
@@ -13,11 +17,9 @@ impl GridController {
 
         self.grid.sheets().iter().for_each(|sheet| {
             sheet.code_runs.iter().for_each(|(pos, code_run)| {
-                code_run.cells_accessed.iter().for_each(|cell_accessed| {
-                    if sheet_rect.intersects(*cell_accessed) {
-                        dependent_cells.insert(pos.to_sheet_pos(sheet.id));
-                    }
-                });
+                if code_run.accessed(*sheet_rect) {
+                    dependent_cells.insert(pos.to_sheet_pos(sheet.id));
+                }
             });
         });
 
@@ -27,6 +29,93 @@ impl GridController {
             Some(dependent_cells)
         }
     }
+
+    /// Returns the positions of all code cells that called a volatile function
+    /// the last time they were run.
+    pub fn get_volatile_code_cells(&self) -> Vec<SheetPos> {
+        self.grid
+            .sheets()
+            .iter()
+            .flat_map(|sheet| {
+                sheet
+                    .code_runs
+                    .iter()
+                    .filter(|(_, code_run)| code_run.is_volatile)
+                    .map(|(pos, _)| pos.to_sheet_pos(sheet.id))
+            })
+            .collect()
+    }
+
+    /// Searches all formulas in all sheets for formulas that use the defined
+    /// name `name`, either directly or through other defined names that refer
+    /// to it.
+    pub fn get_code_cells_using_name(&self, name: &str) -> Vec<SheetPos> {
+        // names are case-insensitive
+        let mut names = vec![name.to_lowercase()];
+        let mut i = 0;
+        while let Some(changed) = names.get(i).cloned() {
+            for defined_name in self.grid.defined_names() {
+                let lowercase = defined_name.name.to_lowercase();
+                if !names.contains(&lowercase)
+                    && formula_uses_name(&defined_name.refers_to, &changed)
+                {
+                    names.push(lowercase);
+                }
+            }
+            i += 1;
+        }
+
+        self.grid
+            .sheets()
+            .iter()
+            .flat_map(|sheet| {
+                let names = &names;
+                sheet
+                    .code_runs
+                    .keys()
+                    .filter_map(move |pos| match sheet.cell_value_ref(*pos) {
+                        Some(CellValue::Code(CodeCellValue {
+                            language: CodeCellLanguage::Formula,
+                            code,
+                        })) if names.iter().any(|name| formula_uses_name(code, name)) => {
+                            Some(pos.to_sheet_pos(sheet.id))
+                        }
+                        _ => None,
+                    })
+            })
+            .collect()
+    }
+
+    /// Searches all formulas in all sheets for formulas with a
+    /// three-dimensional reference whose span includes the sheet `sheet_id`.
+    pub fn get_code_cells_spanning_sheet(&self, sheet_id: SheetId) -> Vec<SheetPos> {
+        let spans_sheet = |first: &str, last: &str| {
+            self.grid
+                .sheets_between(first, last)
+                .is_some_and(|sheets| sheets.iter().any(|sheet| sheet.id == sheet_id))
+        };
+        self.grid
+            .sheets()
+            .iter()
+            .flat_map(|sheet| {
+                sheet
+                    .code_runs
+                    .keys()
+                    .filter_map(move |pos| match sheet.cell_value_ref(*pos) {
+                        Some(CellValue::Code(CodeCellValue {
+                            language: CodeCellLanguage::Formula,
+                            code,
+                        })) if find_sheet_range_references(code, *pos)
+                            .iter()
+                            .any(|(first, last)| spans_sheet(first, last)) =>
+                        {
+                            Some(pos.to_sheet_pos(sheet.id))
+                        }
+                        _ => None,
+                    })
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -73,11 +162,13 @@ mod test {
                 std_err: None,
                 std_out: None,
                 spill_error: false,
+                is_volatile: false,
                 result: CodeRunResult::Ok(Value::Single(CellValue::Text("test".to_string()))),
                 return_type: Some("text".into()),
                 line_number: None,
                 output_type: None,
                 cells_accessed: cells_accessed.clone(),
+                lines_accessed: HashSet::new(),
             }),
         );
         let sheet_pos_02 = SheetPos {
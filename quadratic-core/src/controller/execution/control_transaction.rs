@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use chrono::Utc;
 use uuid::Uuid;
 
@@ -48,9 +50,13 @@ impl GridController {
         if transaction.complete {
             match transaction.transaction_type {
                 TransactionType::User => {
-                    let undo = transaction.to_undo_transaction();
-                    self.undo_stack.push(undo.clone());
-                    self.redo_stack.clear();
+                    // recalculations are not undoable, since undoing one
+                    // would only restore the results of volatile functions
+                    if transaction.transaction_name != TransactionName::Recalculate {
+                        let undo = transaction.to_undo_transaction();
+                        self.undo_stack.push(undo.clone());
+                        self.redo_stack.clear();
+                    }
                     self.transactions
                         .unsaved_transactions
                         .insert_or_replace(transaction, true);
@@ -163,8 +169,10 @@ impl GridController {
                 std_out,
                 std_err,
                 spill_error: false,
+                is_volatile: false,
                 last_modified: Utc::now(),
                 cells_accessed: transaction.cells_accessed.clone(),
+                lines_accessed: HashSet::new(),
             };
 
             self.finalize_code_run(&mut transaction, current_sheet_pos, Some(code_run), None);
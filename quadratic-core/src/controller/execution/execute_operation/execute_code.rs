@@ -3,39 +3,61 @@ use crate::{
         active_transactions::pending_transaction::PendingTransaction,
         operations::operation::Operation, GridController,
     },
-    grid::CodeCellLanguage,
+    grid::{CodeCellLanguage, SheetId},
     CellValue, Pos, Rect, SheetPos, SheetRect,
 };
 
 impl GridController {
-    /// Adds operations to compute cells that are dependents within a SheetRect
+    /// Adds operations to compute cells that are dependents within a
+    /// SheetRect, and to compute volatile code cells if they haven't already
+    /// been added in this user transaction.
     pub fn add_compute_operations(
         &mut self,
         transaction: &mut PendingTransaction,
         output: &SheetRect,
         skip_compute: Option<SheetPos>,
     ) {
-        self.get_dependent_code_cells(output)
-            .iter()
-            .for_each(|sheet_positions| {
-                sheet_positions.iter().for_each(|code_cell_sheet_pos| {
-                    if !skip_compute
-                        .is_some_and(|skip_compute| skip_compute == *code_cell_sheet_pos)
-                    {
-                        // only add a compute operation if there isn't already one pending
-                        if !transaction.operations.iter().any(|op| match op {
-                            Operation::ComputeCode { sheet_pos } => {
-                                code_cell_sheet_pos == sheet_pos
-                            }
-                            _ => false,
-                        }) {
-                            transaction.operations.push_back(Operation::ComputeCode {
-                                sheet_pos: *code_cell_sheet_pos,
-                            });
-                        }
-                    }
-                });
-            });
+        let mut sheet_positions = self.get_dependent_code_cells(output).unwrap_or_default();
+
+        // volatile code cells are recomputed once per user transaction
+        if transaction.is_user() && !transaction.volatile_computes_added {
+            transaction.volatile_computes_added = true;
+            sheet_positions.extend(self.get_volatile_code_cells());
+        }
+
+        sheet_positions.iter().for_each(|code_cell_sheet_pos| {
+            if !skip_compute.is_some_and(|skip_compute| skip_compute == *code_cell_sheet_pos) {
+                // only add a compute operation if there isn't already one pending
+                if !transaction.operations.iter().any(|op| match op {
+                    Operation::ComputeCode { sheet_pos } => code_cell_sheet_pos == sheet_pos,
+                    _ => false,
+                }) {
+                    transaction.operations.push_back(Operation::ComputeCode {
+                        sheet_pos: *code_cell_sheet_pos,
+                    });
+                }
+            }
+        });
+    }
+
+    /// Adds operations to compute formulas with a three-dimensional reference
+    /// whose span includes the sheet `sheet_id`, such as when the sheet is
+    /// added, deleted, or moved.
+    pub(crate) fn add_sheet_span_compute_operations(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        sheet_id: SheetId,
+    ) {
+        if !transaction.is_user() {
+            return;
+        }
+        for sheet_pos in self.get_code_cells_spanning_sheet(sheet_id) {
+            // only add a compute operation if there isn't already one pending
+            let compute = Operation::ComputeCode { sheet_pos };
+            if !transaction.operations.contains(&compute) {
+                transaction.operations.push_back(compute);
+            }
+        }
     }
 
     // delete any code runs within the sheet_rect.
@@ -0,0 +1,182 @@
+use crate::controller::{
+    active_transactions::pending_transaction::PendingTransaction, operations::operation::Operation,
+    GridController,
+};
+
+impl GridController {
+    pub(crate) fn execute_set_defined_name(
+        &mut self,
+        transaction: &mut PendingTransaction,
+        op: Operation,
+    ) {
+        if let Operation::SetDefinedName {
+            name,
+            sheet_id,
+            refers_to,
+        } = op
+        {
+            let old = self
+                .grid
+                .set_defined_name(&name, sheet_id, refers_to.clone());
+
+            let reverse = match old {
+                Some(old) => Operation::SetDefinedName {
+                    name: old.name,
+                    sheet_id,
+                    refers_to: Some(old.refers_to),
+                },
+                None => Operation::SetDefinedName {
+                    name: name.clone(),
+                    sheet_id,
+                    refers_to: None,
+                },
+            };
+
+            if transaction.is_user() {
+                // recompute formulas that use the name
+                for sheet_pos in self.get_code_cells_using_name(&name) {
+                    let compute = Operation::ComputeCode { sheet_pos };
+                    let is_pending = transaction.operations.iter().any(|op| *op == compute);
+                    if !is_pending {
+                        transaction.operations.push_back(compute);
+                    }
+                }
+            }
+
+            transaction
+                .forward_operations
+                .push(Operation::SetDefinedName {
+                    name,
+                    sheet_id,
+                    refers_to,
+                });
+            transaction.reverse_operations.insert(0, reverse);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        controller::GridController,
+        grid::{CodeCellLanguage, DefinedName},
+        CellValue, Pos, SheetPos,
+    };
+
+    #[test]
+    fn test_set_defined_name() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(SheetPos::new(sheet_id, 1, 1), "5".into(), None);
+        gc.set_cell_value(SheetPos::new(sheet_id, 1, 2), "7".into(), None);
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 0, 0),
+            CodeCellLanguage::Formula,
+            "Rate * 2".into(),
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.display_value(Pos { x: 0, y: 0 }), None);
+
+        // defining the name recomputes the formula
+        gc.set_defined_name("Rate".into(), None, Some("$B$1".into()), None)
+            .unwrap();
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.display_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(10.into()))
+        );
+        assert_eq!(
+            gc.grid().defined_names(),
+            &[DefinedName {
+                name: "Rate".into(),
+                sheet_id: None,
+                refers_to: "$B$1".into(),
+            }]
+        );
+
+        // changing the name recomputes the formula
+        gc.set_defined_name("rate".into(), None, Some("$B$2".into()), None)
+            .unwrap();
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.display_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(14.into()))
+        );
+
+        // changing a referenced cell recomputes the formula
+        gc.set_cell_value(SheetPos::new(sheet_id, 1, 2), "8".into(), None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.display_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(16.into()))
+        );
+
+        gc.undo(None);
+        gc.undo(None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.display_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(10.into()))
+        );
+        assert_eq!(gc.grid().defined_names()[0].refers_to, "$B$1");
+
+        gc.undo(None);
+        assert!(gc.grid().defined_names().is_empty());
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.display_value(Pos { x: 0, y: 0 }), None);
+
+        gc.redo(None);
+        assert_eq!(gc.grid().defined_names()[0].refers_to, "$B$1");
+    }
+
+    #[test]
+    fn test_set_defined_name_transitive() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(SheetPos::new(sheet_id, 1, 1), "5".into(), None);
+        gc.set_cell_value(SheetPos::new(sheet_id, 1, 2), "7".into(), None);
+        gc.set_cell_value(SheetPos::new(sheet_id, 1, 3), "2".into(), None);
+        gc.set_defined_name("Rate".into(), None, Some("$B$1".into()), None)
+            .unwrap();
+        gc.set_defined_name("Qty".into(), None, Some("$B$3".into()), None)
+            .unwrap();
+        gc.set_defined_name("Total".into(), None, Some("Rate*Qty".into()), None)
+            .unwrap();
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 0, 0),
+            CodeCellLanguage::Formula,
+            "Total * 2".into(),
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.display_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(20.into()))
+        );
+
+        // changing a name that `Total` refers to recomputes the formula
+        gc.set_defined_name("rate".into(), None, Some("$B$2".into()), None)
+            .unwrap();
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.display_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(28.into()))
+        );
+    }
+
+    #[test]
+    fn test_set_defined_name_invalid() {
+        let mut gc = GridController::test();
+        assert!(gc
+            .set_defined_name("A1".into(), None, Some("B2".into()), None)
+            .is_err());
+        assert!(gc
+            .set_defined_name("TRUE".into(), None, Some("B2".into()), None)
+            .is_err());
+        assert!(gc
+            .set_defined_name("Rate".into(), None, Some("SUM(".into()), None)
+            .is_err());
+        assert!(gc.grid().defined_names().is_empty());
+    }
+}
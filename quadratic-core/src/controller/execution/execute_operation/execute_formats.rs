@@ -149,11 +149,13 @@ mod test {
             Some(CodeRun {
                 formatted_code_string: None,
                 spill_error: false,
+                is_volatile: false,
                 output_type: None,
                 std_err: None,
                 std_out: None,
                 result: CodeRunResult::Ok(Value::Single(CellValue::Image("image".to_string()))),
                 cells_accessed: HashSet::new(),
+                lines_accessed: HashSet::new(),
                 return_type: None,
                 line_number: None,
                 last_modified: Utc::now(),
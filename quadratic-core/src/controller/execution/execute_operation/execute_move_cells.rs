@@ -20,6 +20,10 @@ impl GridController {
             // approach.
             let mut operations = VecDeque::new();
             let selection = Selection::rect(source.into(), source.sheet_id);
+
+            // references to the moved cells are found before the cells move
+            let reference_ops = self.move_cell_references_operations(source, dest);
+
             if let Ok((cut_ops, _, html)) = self.cut_to_clipboard_operations(&selection) {
                 operations.extend(cut_ops);
                 if let Ok(paste_ops) = self.paste_html_operations(
@@ -28,6 +32,7 @@ impl GridController {
                     PasteSpecial::None,
                 ) {
                     operations.extend(paste_ops);
+                    operations.extend(reference_ops);
                 }
                 operations.extend(transaction.operations.drain(..));
                 transaction.operations = operations;
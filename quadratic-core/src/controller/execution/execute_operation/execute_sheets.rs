@@ -22,6 +22,7 @@ impl GridController {
             let sheet_id = self.grid.add_sheet(Some(sheet.clone()));
 
             self.send_add_sheet(sheet_id, transaction);
+            self.add_sheet_span_compute_operations(transaction, sheet_id);
 
             transaction
                 .forward_operations
@@ -49,6 +50,7 @@ impl GridController {
                 self.grid.add_sheet(Some(sheet));
 
                 self.send_add_sheet(sheet_id, transaction);
+                self.add_sheet_span_compute_operations(transaction, sheet_id);
 
                 transaction
                     .forward_operations
@@ -66,6 +68,8 @@ impl GridController {
         op: Operation,
     ) {
         if let Operation::DeleteSheet { sheet_id } = op {
+            // formulas that span the sheet are computed after it is deleted
+            self.add_sheet_span_compute_operations(transaction, sheet_id);
             let Some(deleted_sheet) = self.grid.remove_sheet(sheet_id) else {
                 // sheet was already deleted
                 return;
@@ -122,6 +126,9 @@ impl GridController {
         op: Operation,
     ) {
         if let Operation::ReorderSheet { target, order } = op {
+            // formulas that span the sheet before it is moved are recomputed,
+            // as well as formulas that span it afterwards
+            self.add_sheet_span_compute_operations(transaction, target);
             let old_first = self.grid.first_sheet_id();
             let Some(sheet) = self.try_sheet_mut(target) else {
                 // sheet may have been deleted
@@ -130,6 +137,7 @@ impl GridController {
             let original_order = sheet.order.clone();
             sheet.order.clone_from(&order);
             self.grid.move_sheet(target, order.clone());
+            self.add_sheet_span_compute_operations(transaction, target);
 
             if old_first != self.grid.first_sheet_id() {
                 transaction.generate_thumbnail = true;
@@ -236,6 +244,7 @@ impl GridController {
             self.grid.add_sheet(Some(new_sheet));
 
             self.send_add_sheet(new_sheet_id, transaction);
+            self.add_sheet_span_compute_operations(transaction, new_sheet_id);
 
             transaction
                 .forward_operations
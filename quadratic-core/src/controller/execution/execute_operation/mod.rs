@@ -5,6 +5,7 @@ use crate::controller::GridController;
 pub mod execute_borders;
 pub mod execute_code;
 pub mod execute_cursor;
+pub mod execute_defined_names;
 pub mod execute_formats;
 pub mod execute_move_cells;
 pub mod execute_offsets;
@@ -46,6 +47,8 @@ impl GridController {
                 Operation::SetCursorSelection { .. } => {
                     self.execute_set_cursor_selection(transaction, op);
                 }
+
+                Operation::SetDefinedName { .. } => self.execute_set_defined_name(transaction, op),
             }
 
             if cfg!(target_family = "wasm") && !transaction.is_server() {
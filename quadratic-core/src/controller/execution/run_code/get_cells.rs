@@ -1,9 +1,13 @@
 use uuid::Uuid;
 
 use crate::{
-    controller::{execution::TransactionType, GridController},
+    controller::{
+        active_transactions::pending_transaction::PendingTransaction, execution::TransactionType,
+        GridController,
+    },
     error_core::CoreError,
-    Rect, RunError, RunErrorMsg,
+    formulas::Ctx,
+    Rect, RunError, RunErrorMsg, Span,
 };
 use serde::{Deserialize, Serialize};
 
@@ -51,23 +55,8 @@ impl GridController {
                 sheet
             } else {
                 // unable to find sheet by name, generate error
-                let mut msg = format!("Sheet '{}' not found", sheet_name);
-                if let Some(line_number) = line_number {
-                    msg = format!("{} at line {}", msg, line_number);
-                }
-                let run_error = RunError {
-                    span: None,
-                    msg: RunErrorMsg::PythonError(msg.clone().into()),
-                };
-                let error = match self.code_cell_sheet_error(&mut transaction, &run_error) {
-                    Ok(_) => CoreError::CodeCellSheetError(msg.to_owned()),
-                    Err(err) => err,
-                };
-
-                self.start_transaction(&mut transaction);
-                self.finalize_transaction(&mut transaction);
-
-                return Err(error);
+                let msg = format!("Sheet '{}' not found", sheet_name);
+                return Err(self.calculation_get_cells_error(transaction, msg, line_number));
             }
         } else if let Some(sheet) = self.try_sheet(current_sheet) {
             sheet
@@ -98,6 +87,81 @@ impl GridController {
 
         Ok(response)
     }
+
+    /// This is used to get the cells of a defined name during an async
+    /// calculation. The name is resolved from the sheet of the code cell, and
+    /// the cells are then fetched using [`Self::calculation_get_cells`].
+    #[allow(clippy::result_large_err)]
+    pub fn calculation_get_cells_from_name(
+        &mut self,
+        transaction_id: String,
+        name: String,
+        line_number: Option<u32>,
+    ) -> Result<Vec<JsGetCellResponse>, CoreError> {
+        let id = Uuid::parse_str(&transaction_id)
+            .map_err(|_| CoreError::TransactionNotFound("Transaction Id is invalid".into()))?;
+
+        let current_sheet_pos = self
+            .transactions
+            .get_async_transaction(id)
+            .map_err(|_| CoreError::TransactionNotFound("Transaction Id not found".into()))?
+            .current_sheet_pos
+            .ok_or(CoreError::TransactionNotFound(
+                "Transaction's position not found".into(),
+            ))?;
+
+        let range = {
+            let mut ctx = Ctx::new(self.grid(), current_sheet_pos);
+            ctx.with_defined_name(&name, Span::empty(0), |ctx, formula| {
+                formula.ast.to_range(ctx)
+            })
+        };
+        match range {
+            Some(Ok((sheet_name, rect))) => self.calculation_get_cells(
+                transaction_id,
+                rect.min.x,
+                rect.min.y,
+                rect.width() as i64,
+                Some(rect.height() as i64),
+                sheet_name,
+                line_number,
+            ),
+            _ => {
+                // unable to resolve the name to a range, generate error
+                let msg = format!("Name '{}' not found", name);
+                let transaction = self.transactions.remove_awaiting_async(id).map_err(|_| {
+                    CoreError::TransactionNotFound("Transaction Id not found".into())
+                })?;
+                Err(self.calculation_get_cells_error(transaction, msg, line_number))
+            }
+        }
+    }
+
+    /// Sets an error on the code cell of an async calculation that failed to
+    /// get cells, and completes the transaction.
+    fn calculation_get_cells_error(
+        &mut self,
+        mut transaction: PendingTransaction,
+        mut msg: String,
+        line_number: Option<u32>,
+    ) -> CoreError {
+        if let Some(line_number) = line_number {
+            msg = format!("{} at line {}", msg, line_number);
+        }
+        let run_error = RunError {
+            span: None,
+            msg: RunErrorMsg::PythonError(msg.clone().into()),
+        };
+        let error = match self.code_cell_sheet_error(&mut transaction, &run_error) {
+            Ok(_) => CoreError::CodeCellSheetError(msg.to_owned()),
+            Err(err) => err,
+        };
+
+        self.start_transaction(&mut transaction);
+        self.finalize_transaction(&mut transaction);
+
+        error
+    }
 }
 
 #[cfg(test)]
@@ -356,4 +420,57 @@ mod test {
             ])
         );
     }
+
+    #[test]
+    fn test_calculation_get_cells_from_name() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(SheetPos::new(sheet_id, 2, 3), "a".to_string(), None);
+        gc.set_cell_value(SheetPos::new(sheet_id, 2, 4), "b".to_string(), None);
+        gc.set_defined_name("Letters".into(), None, Some("$C$3:$C$4".into()), None)
+            .unwrap();
+
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 0, 0),
+            CodeCellLanguage::Python,
+            "".to_string(),
+            None,
+        );
+        let transaction_id = gc.last_transaction().unwrap().id;
+
+        let result =
+            gc.calculation_get_cells_from_name(transaction_id.to_string(), "letters".into(), None);
+        assert_eq!(
+            result,
+            Ok(vec![
+                JsGetCellResponse {
+                    x: 2,
+                    y: 3,
+                    value: "a".into(),
+                    type_name: "text".into()
+                },
+                JsGetCellResponse {
+                    x: 2,
+                    y: 4,
+                    value: "b".into(),
+                    type_name: "text".into()
+                },
+            ])
+        );
+
+        let result = gc.calculation_get_cells_from_name(
+            transaction_id.to_string(),
+            "Missing".into(),
+            Some(2),
+        );
+        assert!(result.is_err());
+        let sheet = gc.sheet(sheet_id);
+        let error = sheet
+            .code_run(Pos { x: 0, y: 0 })
+            .unwrap()
+            .clone()
+            .std_err
+            .unwrap();
+        assert!(error.contains("Name 'Missing' not found at line 2"));
+    }
 }
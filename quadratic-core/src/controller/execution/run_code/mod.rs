@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use chrono::Utc;
 
 use crate::controller::active_transactions::pending_transaction::PendingTransaction;
@@ -286,8 +288,11 @@ impl GridController {
                     spill_error: false,
                     last_modified: Utc::now(),
 
-                    // keep the old cells_accessed to better rerun after an error
+                    // keep the old cells_accessed and volatility to better
+                    // rerun after an error
                     cells_accessed: old_code_run.cells_accessed.clone(),
+                    lines_accessed: old_code_run.lines_accessed.clone(),
+                    is_volatile: old_code_run.is_volatile,
                 }
             }
             None => CodeRun {
@@ -301,8 +306,10 @@ impl GridController {
                 std_out: None,
                 std_err: Some(error.msg.to_string()),
                 spill_error: false,
+                is_volatile: false,
                 last_modified: Utc::now(),
                 cells_accessed: transaction.cells_accessed.clone(),
+                lines_accessed: HashSet::new(),
             },
         };
         self.finalize_code_run(transaction, sheet_pos, Some(new_code_run), None);
@@ -334,8 +341,10 @@ impl GridController {
                 std_out: None,
                 std_err: None,
                 spill_error: false,
+                is_volatile: false,
                 last_modified: Utc::now(),
                 cells_accessed: transaction.cells_accessed.clone(),
+                lines_accessed: HashSet::new(),
             };
         };
         let result = if js_code_result.success {
@@ -388,8 +397,10 @@ impl GridController {
             std_out: js_code_result.std_out,
             std_err: js_code_result.std_err,
             spill_error: false,
+            is_volatile: false,
             last_modified: Utc::now(),
             cells_accessed: transaction.cells_accessed.clone(),
+            lines_accessed: HashSet::new(),
         };
         transaction.cells_accessed.clear();
         code_run
@@ -441,7 +452,9 @@ mod test {
             output_type: None,
             last_modified: Utc::now(),
             cells_accessed: HashSet::new(),
+            lines_accessed: HashSet::new(),
             spill_error: false,
+            is_volatile: false,
         };
         gc.finalize_code_run(transaction, sheet_pos, Some(new_code_run.clone()), None);
         assert_eq!(transaction.forward_operations.len(), 1);
@@ -470,7 +483,9 @@ mod test {
             output_type: None,
             last_modified: Utc::now(),
             cells_accessed: HashSet::new(),
+            lines_accessed: HashSet::new(),
             spill_error: false,
+            is_volatile: false,
         };
         gc.finalize_code_run(transaction, sheet_pos, Some(new_code_run.clone()), None);
         assert_eq!(transaction.forward_operations.len(), 1);
@@ -16,32 +16,35 @@ impl GridController {
     ) {
         let mut ctx = Ctx::new(self.grid(), sheet_pos);
         transaction.current_sheet_pos = Some(sheet_pos);
-        match parse_formula(&code, sheet_pos.into()) {
-            Ok(parsed) => match parsed.eval(&mut ctx, false) {
-                Ok(value) => {
-                    transaction.cells_accessed = ctx.cells_accessed;
-                    let new_code_run = CodeRun {
-                        std_out: None,
-                        std_err: None,
-                        formatted_code_string: None,
-                        spill_error: false,
-                        last_modified: Utc::now(),
-                        cells_accessed: transaction.cells_accessed.clone(),
-                        result: CodeRunResult::Ok(value),
-                        return_type: None,
-                        line_number: None,
-                        output_type: None,
-                    };
-                    self.finalize_code_run(transaction, sheet_pos, Some(new_code_run), None);
-                }
-                Err(error) => {
-                    let _ = self.code_cell_sheet_error(transaction, &error);
-                }
-            },
+        let result =
+            parse_formula(&code, sheet_pos.into()).and_then(|parsed| parsed.eval(&mut ctx, false));
+
+        // the cells accessed and volatility are recorded even after an error,
+        // so that the formula is still rerun when needed
+        transaction.cells_accessed = ctx.cells_accessed;
+        let (result, std_err, line_number) = match result {
+            Ok(value) => (CodeRunResult::Ok(value), None, None),
             Err(error) => {
-                let _ = self.code_cell_sheet_error(transaction, &error);
+                let std_err = Some(error.msg.to_string());
+                let line_number = error.span.map(|span| span.line_number_of_str(&code) as u32);
+                (CodeRunResult::Err(error), std_err, line_number)
             }
-        }
+        };
+        let new_code_run = CodeRun {
+            std_out: None,
+            std_err,
+            formatted_code_string: None,
+            spill_error: false,
+            is_volatile: ctx.is_volatile,
+            last_modified: Utc::now(),
+            cells_accessed: transaction.cells_accessed.clone(),
+            lines_accessed: ctx.lines_accessed,
+            result,
+            return_type: None,
+            line_number,
+            output_type: None,
+        };
+        self.finalize_code_run(transaction, sheet_pos, Some(new_code_run), None);
     }
 }
 
@@ -62,8 +65,11 @@ mod test {
             transaction_types::JsCodeResult,
             GridController,
         },
-        grid::{CodeCellLanguage, CodeRun, CodeRunResult},
-        Array, ArraySize, CellValue, CodeCellValue, Pos, SheetPos, Value,
+        grid::{
+            file::{export, import},
+            CodeCellLanguage, CodeRun, CodeRunResult, SheetLines,
+        },
+        Array, ArraySize, CellValue, CodeCellValue, Pos, SheetPos, SheetRect, Value,
     };
 
     #[test]
@@ -218,6 +224,223 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_whole_column_reference_recompute() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+
+        gc.set_cell_value(SheetPos::new(sheet_id, 1, 0), "1".into(), None);
+        gc.set_cell_value(SheetPos::new(sheet_id, 1, 1), "2".into(), None);
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 0, 0),
+            CodeCellLanguage::Formula,
+            "SUM(B:B)".into(),
+            None,
+        );
+
+        let sheet = gc.try_sheet(sheet_id).unwrap();
+        assert_eq!(
+            sheet.display_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(3.into()))
+        );
+
+        // The whole column is recorded separately from the cells that were
+        // read, which are sent to the client and saved in the file.
+        let code_run = sheet.code_run(Pos { x: 0, y: 0 }).unwrap();
+        assert_eq!(
+            code_run.cells_accessed,
+            HashSet::from([
+                SheetRect::from(SheetPos::new(sheet_id, 1, 0)),
+                SheetRect::from(SheetPos::new(sheet_id, 1, 1)),
+            ])
+        );
+        let lines = SheetLines::Columns {
+            sheet_id,
+            min: 1,
+            max: 1,
+        };
+        assert_eq!(code_run.lines_accessed, HashSet::from([lines]));
+        let file = export(gc.grid_mut()).unwrap();
+        let grid = import(&file).unwrap();
+        let code_run = grid.sheets()[0].code_run(Pos { x: 0, y: 0 }).unwrap();
+        assert_eq!(code_run.lines_accessed, HashSet::from([lines]));
+
+        // Appending a value outside the current bounds recomputes the formula.
+        gc.set_cell_value(SheetPos::new(sheet_id, 1, 10), "10".into(), None);
+        let sheet = gc.try_sheet(sheet_id).unwrap();
+        assert_eq!(
+            sheet.display_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(13.into()))
+        );
+    }
+
+    #[test]
+    fn test_whole_column_reference_error_recompute() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+
+        gc.set_cell_value(SheetPos::new(sheet_id, 1, 0), "a".into(), None);
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 0, 0),
+            CodeCellLanguage::Formula,
+            "MATCH(\"x\", B:B, 0)".into(),
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        let code_run = sheet.code_run(Pos { x: 0, y: 0 }).unwrap();
+        assert!(matches!(code_run.result, CodeRunResult::Err(_)));
+        let lines = SheetLines::Columns {
+            sheet_id,
+            min: 1,
+            max: 1,
+        };
+        assert_eq!(code_run.lines_accessed, HashSet::from([lines]));
+
+        // Writing the missing value resolves the error.
+        gc.set_cell_value(SheetPos::new(sheet_id, 1, 5), "x".into(), None);
+        let sheet = gc.sheet(sheet_id);
+        assert!(matches!(
+            sheet.display_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(_))
+        ));
+    }
+
+    #[test]
+    fn test_volatile_recompute() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 0, 0),
+            CodeCellLanguage::Formula,
+            "RAND()".into(),
+            None,
+        );
+        let random_value = |gc: &GridController| {
+            gc.sheet(sheet_id)
+                .display_value(Pos { x: 0, y: 0 })
+                .unwrap()
+        };
+        let first = random_value(&gc);
+        let sheet = gc.sheet(sheet_id);
+        assert!(sheet.code_run(Pos { x: 0, y: 0 }).unwrap().is_volatile);
+
+        // Cells that depend on the volatile one are recomputed with it.
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 1, 0),
+            CodeCellLanguage::Formula,
+            "A0".into(),
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert!(!sheet.code_run(Pos { x: 1, y: 0 }).unwrap().is_volatile);
+
+        let second = random_value(&gc);
+        assert_ne!(first, second);
+
+        // Editing unrelated cells recomputes the volatile one, and the cells
+        // that depend on it.
+        gc.set_cell_value(SheetPos::new(sheet_id, 5, 5), "1".into(), None);
+        let third = random_value(&gc);
+        assert_ne!(second, third);
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(Pos { x: 1, y: 0 }),
+            Some(third.clone())
+        );
+
+        // So does recalculating, without adding to the undo stack.
+        let undo_len = gc.undo_stack.len();
+        let operations = gc.recalculate_operations();
+        assert_eq!(
+            operations,
+            vec![Operation::ComputeCode {
+                sheet_pos: SheetPos::new(sheet_id, 0, 0)
+            }]
+        );
+        gc.recalculate(None);
+        assert_ne!(third, random_value(&gc));
+        assert_eq!(gc.undo_stack.len(), undo_len);
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(Pos { x: 1, y: 0 }),
+            Some(random_value(&gc))
+        );
+
+        // A volatile formula that errors is still volatile.
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 3, 0),
+            CodeCellLanguage::Formula,
+            "1 / (RAND() - 2) + \"a\"".into(),
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        let code_run = sheet.code_run(Pos { x: 3, y: 0 }).unwrap();
+        assert!(matches!(code_run.result, CodeRunResult::Err(_)));
+        assert!(code_run.is_volatile);
+
+        // Functions that are not evaluated don't make a formula volatile.
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 2, 0),
+            CodeCellLanguage::Formula,
+            "IF(FALSE, NOW(), 1)".into(),
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert!(!sheet.code_run(Pos { x: 2, y: 0 }).unwrap().is_volatile);
+    }
+
+    #[test]
+    fn test_spill_reference_recompute() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        let set_array = |gc: &mut GridController, code: &str| {
+            gc.set_code_cell(
+                SheetPos::new(sheet_id, 1, 1),
+                CodeCellLanguage::Formula,
+                code.into(),
+                None,
+            );
+        };
+
+        set_array(&mut gc, "{1; 2; 3}");
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 0, 1),
+            CodeCellLanguage::Formula,
+            "SUM(B1#)".into(),
+            None,
+        );
+        let sheet = gc.try_sheet(sheet_id).unwrap();
+        assert_eq!(
+            sheet.display_value(Pos { x: 0, y: 1 }),
+            Some(CellValue::Number(6.into()))
+        );
+
+        // Growing the spill range recomputes the formula.
+        set_array(&mut gc, "{1; 2; 3; 4}");
+        let sheet = gc.try_sheet(sheet_id).unwrap();
+        assert_eq!(
+            sheet.display_value(Pos { x: 0, y: 1 }),
+            Some(CellValue::Number(10.into()))
+        );
+
+        // So does shrinking it.
+        set_array(&mut gc, "{5}");
+        let sheet = gc.try_sheet(sheet_id).unwrap();
+        assert_eq!(
+            sheet.display_value(Pos { x: 0, y: 1 }),
+            Some(CellValue::Number(5.into()))
+        );
+
+        // A cell without code has no spill range.
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 0, 2),
+            CodeCellLanguage::Formula,
+            "SUM(C1#)".into(),
+            None,
+        );
+        let sheet = gc.try_sheet(sheet_id).unwrap();
+        let error = sheet.code_run(Pos { x: 0, y: 2 }).unwrap().get_error();
+        assert_eq!(error.unwrap().msg, crate::RunErrorMsg::BadCellReference);
+    }
+
     #[test]
     fn test_js_code_result_to_code_cell_value_single() {
         let mut gc = GridController::test();
@@ -254,7 +477,9 @@ mod test {
                 line_number: None,
                 output_type: None,
                 cells_accessed: HashSet::new(),
+                lines_accessed: HashSet::new(),
                 spill_error: false,
+                is_volatile: false,
             },
         );
     }
@@ -317,7 +542,9 @@ mod test {
                 line_number: None,
                 output_type: None,
                 cells_accessed: HashSet::new(),
+                lines_accessed: HashSet::new(),
                 spill_error: false,
+                is_volatile: false,
                 last_modified: result.last_modified,
             }
         );
@@ -412,8 +412,10 @@ mod tests {
             line_number: None,
             output_type: None,
             spill_error: false,
+            is_volatile: false,
             last_modified: Utc::now(),
             cells_accessed: HashSet::new(),
+            lines_accessed: HashSet::new(),
             formatted_code_string: None,
         };
         let pos = Pos { x: 0, y: 0 };
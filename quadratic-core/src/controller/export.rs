@@ -45,7 +45,7 @@ impl GridController {
 mod tests {
 
     use super::*;
-    use crate::Rect;
+    use crate::{CellValue, Rect, RunErrorMsg};
 
     #[test]
     fn exports_a_csv() {
@@ -75,4 +75,28 @@ mod tests {
 
         assert_eq!(&result, expected);
     }
+
+    #[test]
+    fn exports_imported_csv_errors() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+
+        let csv = "#N/A,#DIV/0!,#REF!\n#NAME?,#NUM!,#VALUE!\n#SPILL!,#CALC!,#NULL!\n";
+        gc.import_csv(sheet_id, csv.as_bytes(), "errors.csv", Pos::ORIGIN, None)
+            .unwrap();
+
+        let sheet = gc.sheet(sheet_id);
+        let CellValue::Error(error) = sheet.cell_value(Pos::ORIGIN).unwrap() else {
+            panic!("expected an error value");
+        };
+        assert_eq!(error.msg, RunErrorMsg::NoMatch);
+
+        let selected = Selection {
+            sheet_id,
+            rects: Some(vec![Rect::from_numbers(0, 0, 3, 3)]),
+            ..Default::default()
+        };
+        let result = gc.export_csv_selection(selected).unwrap();
+        assert_eq!(result, csv);
+    }
 }
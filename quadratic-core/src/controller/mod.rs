@@ -10,6 +10,8 @@ pub mod operations;
 pub mod send_render;
 pub mod sheet_offsets;
 pub mod sheets;
+#[cfg(test)]
+pub mod test_util;
 pub mod thumbnail;
 pub mod transaction;
 pub mod transaction_summary;
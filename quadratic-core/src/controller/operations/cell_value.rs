@@ -4,7 +4,7 @@ use crate::{
     controller::GridController,
     grid::{formatting::CellFmtArray, NumericDecimals, NumericFormat, NumericFormatKind},
     selection::Selection,
-    CellValue, RunLengthEncoding, SheetPos, SheetRect,
+    CellValue, RunErrorMsg, RunLengthEncoding, SheetPos, SheetRect,
 };
 use bigdecimal::BigDecimal;
 use std::str::FromStr;
@@ -56,6 +56,8 @@ impl GridController {
                 }
             }
             CellValue::Number(number)
+        } else if let Some(msg) = RunErrorMsg::from_excel_code(value) {
+            CellValue::Error(Box::new(msg.without_span()))
         } else if let Some(bool) = CellValue::unpack_boolean(value) {
             bool
         } else if let Ok(bd) = BigDecimal::from_str(&CellValue::strip_commas(value)) {
@@ -150,7 +152,7 @@ mod test {
         controller::{operations::operation::Operation, GridController},
         grid::{CodeCellLanguage, SheetId},
         selection::Selection,
-        CellValue, Rect, SheetPos,
+        CellValue, Rect, RunErrorMsg, SheetPos,
     };
 
     #[test]
@@ -216,6 +218,30 @@ mod test {
         assert_eq!(value, false.into());
     }
 
+    #[test]
+    fn error_to_cell_value() {
+        let mut gc = GridController::test();
+        let sheet_pos = SheetPos {
+            x: 1,
+            y: 2,
+            sheet_id: SheetId::test(),
+        };
+        let (ops, value) = gc.string_to_cell_value(sheet_pos, "#N/A");
+        assert_eq!(ops.len(), 0);
+        assert_eq!(
+            value,
+            CellValue::Error(Box::new(RunErrorMsg::NoMatch.without_span()))
+        );
+        assert_eq!(value.to_string(), "#N/A");
+
+        let (ops, value) = gc.string_to_cell_value(sheet_pos, "#div/0!");
+        assert_eq!(ops.len(), 0);
+        assert_eq!(value.to_string(), "#DIV/0!");
+
+        let (_, value) = gc.string_to_cell_value(sheet_pos, "#hashtag");
+        assert_eq!(value, CellValue::Text("#hashtag".into()));
+    }
+
     #[test]
     fn number_to_cell_value() {
         let mut gc = GridController::test();
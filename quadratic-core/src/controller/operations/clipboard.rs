@@ -4,13 +4,16 @@ use super::operation::Operation;
 use crate::{
     cell_values::CellValues,
     controller::GridController,
-    formulas::replace_internal_cell_references,
+    formulas::{
+        replace_internal_cell_references, replace_moved_cell_references,
+        replace_moved_formula_cell_references,
+    },
     grid::{
         formats::{format::Format, Formats},
         generate_borders_full, BorderSelection, CellBorders, CodeCellLanguage,
     },
     selection::Selection,
-    CellValue, Pos, SheetPos, SheetRect,
+    CellValue, CodeCellValue, Pos, Rect, SheetPos, SheetRect,
 };
 use anyhow::{Error, Result};
 use regex::Regex;
@@ -412,6 +415,79 @@ impl GridController {
     pub fn move_cells_operations(&mut self, source: SheetRect, dest: SheetPos) -> Vec<Operation> {
         vec![Operation::MoveCells { source, dest }]
     }
+
+    /// Returns operations that update references in formulas and defined
+    /// names to cells moved from `source` to `dest`, so that they follow the
+    /// moved cells. Formulas that are themselves moved are left to the paste.
+    pub(crate) fn move_cell_references_operations(
+        &self,
+        source: SheetRect,
+        dest: SheetPos,
+    ) -> Vec<Operation> {
+        let (Some(source_sheet), Some(dest_sheet)) = (
+            self.try_sheet(source.sheet_id),
+            self.try_sheet(dest.sheet_id),
+        ) else {
+            return vec![];
+        };
+        let from = Rect::from(source);
+        let dest_rect = SheetRect::from_sheet_pos_and_size(dest, source.size());
+        let to_sheet = (dest.sheet_id != source.sheet_id).then_some(dest_sheet.name.as_str());
+
+        // formulas that are replaced by the moved cells are left alone, and
+        // the moved formulas are updated at their new position below
+        let mut ops = self.rewrite_formulas_operations(None, |code, sheet_pos| {
+            let is_moved_or_replaced = sheet_pos.is_some_and(|sheet_pos| {
+                source.contains(sheet_pos) || dest_rect.contains(sheet_pos)
+            });
+            if is_moved_or_replaced {
+                return code.to_string();
+            }
+            let is_from_sheet = |sheet: &Option<String>| match sheet {
+                Some(name) => *name == source_sheet.name,
+                None => sheet_pos.is_some_and(|sheet_pos| sheet_pos.sheet_id == source.sheet_id),
+            };
+            let pos = sheet_pos.map_or(Pos::ORIGIN, Pos::from);
+            replace_moved_cell_references(code, pos, &is_from_sheet, from, dest.into(), to_sheet)
+        });
+
+        // the paste keeps relative references in the moved formulas pointing
+        // at the moved cells, but absolute references need to be updated
+        let is_from_sheet = |sheet: &Option<String>| match sheet {
+            Some(name) => *name == source_sheet.name,
+            None => true,
+        };
+        for (pos, code) in source_sheet.iter_formula_cells() {
+            if !from.contains(pos) {
+                continue;
+            }
+            let new_code = replace_moved_formula_cell_references(
+                code,
+                pos,
+                &is_from_sheet,
+                from,
+                dest.into(),
+                to_sheet,
+            );
+            if new_code != code {
+                let sheet_pos = SheetPos {
+                    x: pos.x - from.min.x + dest.x,
+                    y: pos.y - from.min.y + dest.y,
+                    sheet_id: dest.sheet_id,
+                };
+                ops.push(Operation::SetCellValues {
+                    sheet_pos,
+                    values: CellValues::from(CellValue::Code(CodeCellValue {
+                        language: CodeCellLanguage::Formula,
+                        code: new_code,
+                    })),
+                });
+                ops.push(Operation::ComputeCode { sheet_pos });
+            }
+        }
+
+        ops
+    }
 }
 
 #[cfg(test)]
@@ -31,10 +31,7 @@ impl GridController {
 
     // Returns whether a code_cell is dependent on another code_cell.
     fn is_dependent_on(&self, current: &CodeRun, other_pos: SheetPos) -> bool {
-        current
-            .cells_accessed
-            .iter()
-            .any(|sheet_rect| sheet_rect.contains(other_pos))
+        current.accessed(other_pos.into())
     }
 
     /// Orders code cells to ensure earlier computes do not depend on later computes.
@@ -128,10 +125,104 @@ impl GridController {
             .collect()
     }
 
+    /// Recomputes all code cells that called a volatile function the last time
+    /// they were run. Cells that depend on them are recomputed as they change.
+    pub fn recalculate_operations(&self) -> Vec<Operation> {
+        self.get_volatile_code_cells()
+            .into_iter()
+            .map(|sheet_pos| Operation::ComputeCode { sheet_pos })
+            .collect()
+    }
+
+    /// Recalculates all formulas in all Sheets. Other code cells are not rerun.
+    pub fn recalculate_all_formulas_operations(&self) -> Vec<Operation> {
+        let mut code_cell_positions = self
+            .grid()
+            .sheets()
+            .iter()
+            .flat_map(|sheet| {
+                sheet
+                    .code_runs
+                    .iter()
+                    .filter(|(pos, _)| {
+                        matches!(
+                            sheet.cell_value_ref(**pos),
+                            Some(CellValue::Code(CodeCellValue {
+                                language: CodeCellLanguage::Formula,
+                                ..
+                            }))
+                        )
+                    })
+                    .map(|(pos, code_run)| (pos.to_sheet_pos(sheet.id), code_run))
+            })
+            .collect::<Vec<_>>();
+
+        self.order_code_cells(&mut code_cell_positions);
+
+        code_cell_positions
+            .iter()
+            .map(|(sheet_pos, _)| Operation::ComputeCode {
+                sheet_pos: *sheet_pos,
+            })
+            .collect()
+    }
+
     /// Reruns a code cell
     pub fn rerun_code_cell_operations(&self, sheet_pos: SheetPos) -> Vec<Operation> {
         vec![Operation::ComputeCode { sheet_pos }]
     }
+
+    /// Returns operations that replace the code of every formula (and the
+    /// formula of every defined name) that is changed by `replace`, and then
+    /// recompute the changed formulas. `replace` is given the position of the
+    /// formula, or `None` for a defined name. Formulas on the sheet
+    /// `skip_sheet_id` and names defined on it are left alone.
+    pub(crate) fn rewrite_formulas_operations(
+        &self,
+        skip_sheet_id: Option<SheetId>,
+        replace: impl Fn(&str, Option<SheetPos>) -> String,
+    ) -> Vec<Operation> {
+        let mut ops = vec![];
+
+        // names are updated first so that the recomputed formulas use them
+        for defined_name in self.grid.defined_names() {
+            if skip_sheet_id.is_some() && defined_name.sheet_id == skip_sheet_id {
+                continue;
+            }
+            let refers_to = replace(&defined_name.refers_to, None);
+            if refers_to != defined_name.refers_to {
+                ops.push(Operation::SetDefinedName {
+                    name: defined_name.name.clone(),
+                    sheet_id: defined_name.sheet_id,
+                    refers_to: Some(refers_to),
+                });
+            }
+        }
+
+        for sheet in self.grid.sheets() {
+            if Some(sheet.id) == skip_sheet_id {
+                continue;
+            }
+            // formulas without a code run, such as ones that were never run,
+            // are updated as well
+            for (pos, code) in sheet.iter_formula_cells() {
+                let sheet_pos = pos.to_sheet_pos(sheet.id);
+                let new_code = replace(code, Some(sheet_pos));
+                if new_code != code {
+                    ops.push(Operation::SetCellValues {
+                        sheet_pos,
+                        values: CellValues::from(CellValue::Code(CodeCellValue {
+                            language: CodeCellLanguage::Formula,
+                            code: new_code,
+                        })),
+                    });
+                    ops.push(Operation::ComputeCode { sheet_pos });
+                }
+            }
+        }
+
+        ops
+    }
 }
 
 #[cfg(test)]
@@ -357,6 +448,64 @@ mod test {
         check_sheet_operations(&gc);
     }
 
+    #[test]
+    fn test_recalculate_all_formulas() {
+        let mut gc = GridController::default();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(SheetPos::new(sheet_id, 0, 0), "1".into(), None);
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 1, 0),
+            CodeCellLanguage::Formula,
+            "A0 + 1".into(),
+            None,
+        );
+        gc.set_code_cell(
+            SheetPos::new(sheet_id, 2, 0),
+            CodeCellLanguage::Formula,
+            "B0 * 10".into(),
+            None,
+        );
+
+        // Python cells are not recalculated.
+        let sheet = gc.sheet_mut(sheet_id);
+        sheet.test_set_code_run_single(3, 0, CellValue::Number(1.into()));
+        sheet.set_cell_value(
+            Pos { x: 3, y: 0 },
+            CellValue::Code(CodeCellValue {
+                language: CodeCellLanguage::Python,
+                code: "1".into(),
+            }),
+        );
+
+        // Changing the value without a transaction doesn't recompute anything.
+        sheet.set_cell_value(Pos { x: 0, y: 0 }, CellValue::Number(5.into()));
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(Pos { x: 2, y: 0 }),
+            Some(CellValue::Number(20.into()))
+        );
+
+        let operations = gc.recalculate_all_formulas_operations();
+        assert_eq!(
+            operations,
+            vec![
+                Operation::ComputeCode {
+                    sheet_pos: SheetPos::new(sheet_id, 1, 0),
+                },
+                Operation::ComputeCode {
+                    sheet_pos: SheetPos::new(sheet_id, 2, 0),
+                },
+            ]
+        );
+
+        let undo_len = gc.undo_stack.len();
+        gc.recalculate_all_formulas(None);
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(Pos { x: 2, y: 0 }),
+            Some(CellValue::Number(60.into()))
+        );
+        assert_eq!(gc.undo_stack.len(), undo_len);
+    }
+
     #[test]
     fn rerun_all_code_cells_one() {
         let mut gc = GridController::default();
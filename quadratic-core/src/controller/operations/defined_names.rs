@@ -0,0 +1,45 @@
+use anyhow::{anyhow, bail, Result};
+
+use crate::{
+    controller::GridController,
+    formulas::{is_valid_defined_name, parse_formula},
+    grid::SheetId,
+    Pos,
+};
+
+use super::operation::Operation;
+
+impl GridController {
+    /// Returns the operations to set a defined name, or to remove it if
+    /// `refers_to` is `None`. The name is visible on the sheet `sheet_id`, or
+    /// on every sheet if `sheet_id` is `None`.
+    pub fn set_defined_name_operations(
+        &self,
+        name: String,
+        sheet_id: Option<SheetId>,
+        refers_to: Option<String>,
+    ) -> Result<Vec<Operation>> {
+        if !is_valid_defined_name(&name) {
+            bail!("Invalid defined name: {name}");
+        }
+        if let Some(sheet_id) = sheet_id {
+            if self.try_sheet(sheet_id).is_none() {
+                bail!("Sheet not found for defined name {name}");
+            }
+        }
+        let refers_to = refers_to.map(|refers_to| match refers_to.strip_prefix('=') {
+            Some(stripped) => stripped.to_string(),
+            None => refers_to,
+        });
+        if let Some(refers_to) = &refers_to {
+            parse_formula(refers_to, Pos::ORIGIN)
+                .map_err(|e| anyhow!("Invalid formula for defined name {name}: {e}"))?;
+        }
+
+        Ok(vec![Operation::SetDefinedName {
+            name,
+            sheet_id,
+            refers_to,
+        }])
+    }
+}
@@ -1,13 +1,16 @@
-use std::io::Cursor;
+use std::{collections::HashSet, io::Cursor};
 
 use anyhow::{anyhow, bail, Result};
+use lazy_static::lazy_static;
 use lexicon_fractional_index::key_between;
+use regex::Regex;
 
 use crate::{
     cell_values::CellValues,
     controller::GridController,
-    grid::{file::sheet_schema::export_sheet, CodeCellLanguage, Sheet, SheetId},
-    CellValue, CodeCellValue, Pos, SheetPos,
+    formulas::{add_implicit_intersection_operators, is_valid_defined_name, parse_formula, Ctx},
+    grid::{file::sheet_schema::export_sheet, CodeCellLanguage, Grid, Sheet, SheetId},
+    CellValue, CodeCellValue, Pos, Rect, RunErrorMsg, SheetPos, Value,
 };
 use bytes::Bytes;
 use calamine::{Data as ExcelData, Reader as ExcelReader, Xlsx, XlsxError};
@@ -17,6 +20,19 @@ use super::operation::Operation;
 
 const IMPORT_LINES_PER_OPERATION: u32 = 10000;
 
+lazy_static! {
+    /// Matches a call to an Excel function that requires dynamic arrays, such
+    /// as `_xlfn._xlws.FILTER(` or `_xlfn.SINGLE(`, the function that Excel
+    /// saves for the `@` operator.
+    static ref DYNAMIC_ARRAY_FUNCTION_REGEX: Regex = Regex::new(concat!(
+        r"(?i)_xlfn\.(_xlws\.)?(ANCHORARRAY|SINGLE|FILTER|SORT|SORTBY|UNIQUE|SEQUENCE|",
+        r"RANDARRAY|XLOOKUP|XMATCH|LET|LAMBDA|MAP|REDUCE|SCAN|MAKEARRAY|BYROW|BYCOL|VSTACK|",
+        r"HSTACK|TAKE|DROP|TOCOL|TOROW|WRAPROWS|WRAPCOLS|CHOOSEROWS|CHOOSECOLS|EXPAND|",
+        r"TEXTSPLIT)\(",
+    ))
+    .unwrap();
+}
+
 impl GridController {
     /// Imports a CSV file into the grid.
     pub fn import_csv_operations(
@@ -144,7 +160,34 @@ impl GridController {
             y: row as i64 + 1,
         };
 
+        // defined names are added before the sheets, so that formulas that use
+        // them are computed correctly
+        let mut defined_names = HashSet::new();
+        let mut imported_names = vec![];
+        for (name, refers_to) in workbook.defined_names() {
+            // skip Excel's built-in names, such as `_xlnm.Print_Area`
+            if !is_valid_defined_name(name) {
+                continue;
+            }
+            // calamine doesn't return the scope of a name, so names defined on
+            // several sheets would overwrite each other; keep the first one
+            if !defined_names.insert(name.to_lowercase()) {
+                dbgjs!(format!(
+                    "Skipping duplicate defined name {name} in {file_name}: {refers_to}"
+                ));
+                continue;
+            }
+            let refers_to = refers_to.trim_start_matches('=').to_owned();
+            imported_names.push((name.to_owned(), refers_to.clone()));
+            ops.push(Operation::SetDefinedName {
+                name: name.to_owned(),
+                sheet_id: None,
+                refers_to: Some(refers_to),
+            });
+        }
+
         let mut order = key_between(&None, &None).unwrap_or("A0".to_string());
+        let mut imported_sheets = vec![];
         for sheet_name in sheets {
             // add the sheet
             let mut sheet = Sheet::new(SheetId::new(), sheet_name.to_owned(), order.clone());
@@ -182,7 +225,12 @@ impl GridController {
                         ExcelData::Int(ref value) => {
                             CellValue::unpack_str_float(&value.to_string(), CellValue::Blank)
                         }
-                        ExcelData::Error(_) => continue,
+                        ExcelData::Error(ref error) => {
+                            match RunErrorMsg::from_excel_code(&error.to_string()) {
+                                Some(msg) => CellValue::Error(Box::new(msg.without_span())),
+                                None => continue,
+                            }
+                        }
                         ExcelData::Bool(value) => CellValue::Logical(*value),
                     };
 
@@ -199,7 +247,7 @@ impl GridController {
             // formulas
             let formula = workbook.worksheet_formula(&sheet_name).map_err(error)?;
             let insert_at = formula.start().map_or_else(Pos::default, xlsx_range_to_pos);
-            let mut formula_compute_ops = vec![];
+            let mut formulas = vec![];
             for (y, row) in formula.rows().enumerate() {
                 for (x, cell) in row.iter().enumerate() {
                     if !cell.is_empty() {
@@ -207,23 +255,46 @@ impl GridController {
                             x: insert_at.x + x as i64,
                             y: insert_at.y + y as i64,
                         };
-                        let cell_value = CellValue::Code(CodeCellValue {
-                            language: CodeCellLanguage::Formula,
-                            code: cell.to_string(),
-                        });
-                        sheet.set_cell_value(pos, cell_value);
-                        // add code compute operation, to generate code runs
-                        formula_compute_ops.push(Operation::ComputeCode {
-                            sheet_pos: pos.to_sheet_pos(sheet.id),
-                        });
+                        formulas.push((pos, cell.to_owned()));
                     }
                 }
             }
+            imported_sheets.push((sheet, formulas));
+        }
+
+        // Formulas written before dynamic arrays use implicit intersection.
+        // calamine does not distinguish array formulas, so a workbook is
+        // assumed to use dynamic arrays only if it uses a function that
+        // requires them.
+        let dynamic_arrays = imported_sheets
+            .iter()
+            .flat_map(|(_, formulas)| formulas)
+            .any(|(_, code)| DYNAMIC_ARRAY_FUNCTION_REGEX.is_match(code));
+        for (sheet, formulas) in &mut imported_sheets {
+            for (pos, code) in formulas {
+                if !dynamic_arrays {
+                    *code = add_implicit_intersection_operators(code, *pos);
+                }
+                let cell_value = CellValue::Code(CodeCellValue {
+                    language: CodeCellLanguage::Formula,
+                    code: code.clone(),
+                });
+                sheet.set_cell_value(*pos, cell_value);
+            }
+        }
+        if dynamic_arrays {
+            remove_cached_spill_values(&mut imported_sheets, &imported_names);
+        }
+
+        for (sheet, formulas) in imported_sheets {
             // add new sheets
             ops.push(Operation::AddSheetSchema {
                 schema: export_sheet(&sheet),
             });
-            ops.extend(formula_compute_ops);
+            // add code compute operation, to generate code runs
+            ops.extend(formulas.into_iter().map(|(pos, _)| Operation::ComputeCode {
+                sheet_pos: pos.to_sheet_pos(sheet.id),
+            }));
         }
         Ok(ops)
     }
@@ -301,6 +372,52 @@ impl GridController {
     }
 }
 
+/// Removes the values that Excel saved for the cells that dynamic array
+/// formulas spill into, so that the formulas can spill into them again once
+/// they are computed.
+///
+/// calamine doesn't read which formulas are dynamic arrays, so this is a
+/// heuristic: every formula is evaluated once using the saved values and the
+/// workbook's defined names, and a formula that returns an array is assumed to
+/// spill. A formula that depends on something that isn't available until it
+/// is computed in the grid, such as another formula's spill, may be misjudged.
+fn remove_cached_spill_values(
+    imported_sheets: &mut [(Sheet, Vec<(Pos, String)>)],
+    defined_names: &[(String, String)],
+) {
+    let mut grid = Grid::new_blank();
+    for (sheet, _) in imported_sheets.iter() {
+        grid.add_sheet(Some(sheet.clone()));
+    }
+    for (name, refers_to) in defined_names {
+        grid.set_defined_name(name, None, Some(refers_to.clone()));
+    }
+
+    let mut spills = vec![];
+    for (sheet_index, (sheet, formulas)) in imported_sheets.iter().enumerate() {
+        for (pos, code) in formulas {
+            let mut ctx = Ctx::new(&grid, pos.to_sheet_pos(sheet.id));
+            let Ok(Value::Array(array)) =
+                parse_formula(code, *pos).and_then(|formula| formula.eval(&mut ctx, false))
+            else {
+                continue;
+            };
+            let size = array.size();
+            let rect = Rect::from_numbers(pos.x, pos.y, size.w.get() as i64, size.h.get() as i64);
+            spills.push((sheet_index, *pos, rect));
+        }
+    }
+
+    for (sheet_index, anchor, rect) in spills {
+        let sheet = &mut imported_sheets[sheet_index].0;
+        for pos in rect.iter() {
+            if pos != anchor && !matches!(sheet.cell_value_ref(pos), Some(CellValue::Code(_))) {
+                sheet.set_cell_value(pos, CellValue::Blank);
+            }
+        }
+    }
+}
+
 fn read_utf16(bytes: &[u8]) -> Option<String> {
     if bytes.is_empty() && bytes.len() % 2 == 0 {
         return None;
@@ -330,7 +447,7 @@ fn read_utf16(bytes: &[u8]) -> Option<String> {
 mod test {
     use super::read_utf16;
     use super::*;
-    use crate::CellValue;
+    use crate::{grid::DefinedName, CellValue};
 
     const INVALID_ENCODING_FILE: &[u8] =
         include_bytes!("../../../../quadratic-rust-shared/data/csv/encoding_issue.csv");
@@ -434,10 +551,113 @@ mod test {
             sheet.cell_value((3, 2).into()),
             Some(CellValue::Code(CodeCellValue {
                 language: CodeCellLanguage::Formula,
-                code: "C1:C5".into()
+                code: "@C1:C5".into()
             }))
         );
         assert_eq!(sheet.cell_value((3, 1).into()), None);
+        // the formula is implicitly intersected instead of spilling over the
+        // existing values below it
+        assert!(!sheet.code_run((3, 2).into()).unwrap().spill_error);
+        assert_eq!(
+            sheet.display_value((3, 2).into()),
+            Some(CellValue::Number(4.into()))
+        );
+    }
+
+    #[test]
+    fn remove_cached_spill_values_with_defined_names() {
+        let mut sheet = Sheet::test();
+        sheet.test_set_values(0, 1, 1, 3, vec!["1", "2", "3"]);
+        // values that were saved for the spill of the formula in B1
+        sheet.test_set_values(1, 2, 1, 2, vec!["4", "6"]);
+        let formulas = vec![(Pos { x: 1, y: 1 }, "Values * 2".to_string())];
+        let mut imported_sheets = vec![(sheet, formulas)];
+        let defined_names = [("Values".to_string(), "$A$1:$A$3".to_string())];
+        remove_cached_spill_values(&mut imported_sheets, &defined_names);
+
+        let sheet = &imported_sheets[0].0;
+        assert_eq!(sheet.cell_value((1, 2).into()), None);
+        assert_eq!(sheet.cell_value((1, 3).into()), None);
+        assert_eq!(
+            sheet.cell_value((0, 3).into()),
+            Some(CellValue::Number(3.into()))
+        );
+    }
+
+    #[test]
+    fn import_excel_dynamic_arrays() {
+        let mut gc = GridController::test_blank();
+        let file = include_bytes!("../../../test-files/dynamic_array.xlsx");
+        gc.import_excel(file.to_vec(), "dynamic_array.xlsx")
+            .unwrap();
+
+        let sheet_id = gc.grid.sheets()[0].id;
+        let sheet = gc.sheet(sheet_id);
+
+        // formulas in a workbook that uses dynamic arrays are not implicitly
+        // intersected, and spill over the values that Excel saved for them
+        assert_eq!(
+            sheet.cell_value((1, 1).into()),
+            Some(CellValue::Code(CodeCellValue {
+                language: CodeCellLanguage::Formula,
+                code: "_xlfn._xlws.SORT(A1:A3)".into()
+            }))
+        );
+        assert_eq!(
+            sheet.cell_value((2, 1).into()),
+            Some(CellValue::Code(CodeCellValue {
+                language: CodeCellLanguage::Formula,
+                code: "A1:A3*2".into()
+            }))
+        );
+        assert!(!sheet.code_run((1, 1).into()).unwrap().spill_error);
+        assert!(!sheet.code_run((2, 1).into()).unwrap().spill_error);
+        assert_eq!(sheet.cell_value((1, 3).into()), None);
+        assert_eq!(
+            sheet.display_value((1, 3).into()),
+            Some(CellValue::Number(3.into()))
+        );
+        assert_eq!(
+            sheet.display_value((2, 3).into()),
+            Some(CellValue::Number(4.into()))
+        );
+    }
+
+    #[test]
+    fn import_excel_defined_names() {
+        let mut gc = GridController::test_blank();
+        let file = include_bytes!("../../../test-files/defined_names.xlsx");
+        gc.import_excel(file.to_vec(), "defined_names.xlsx")
+            .unwrap();
+
+        // `Local` is defined on both sheets, and only the first definition is
+        // imported
+        assert_eq!(
+            gc.grid.defined_names(),
+            &[
+                DefinedName {
+                    name: "Local".into(),
+                    sheet_id: None,
+                    refers_to: "Sheet1!$A$2".into(),
+                },
+                DefinedName {
+                    name: "Rate".into(),
+                    sheet_id: None,
+                    refers_to: "Sheet1!$A$1".into(),
+                },
+            ]
+        );
+
+        let sheet_id = gc.grid.sheets()[0].id;
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.display_value((1, 1).into()),
+            Some(CellValue::Number(10.into()))
+        );
+        assert_eq!(
+            sheet.display_value((1, 2).into()),
+            Some(CellValue::Number(7.into()))
+        );
     }
 
     #[test]
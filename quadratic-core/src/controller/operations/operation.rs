@@ -116,6 +116,15 @@ pub enum Operation {
         source: SheetRect,
         dest: SheetPos,
     },
+
+    // Sets a defined name, or removes it if `refers_to` is None. The name is
+    // visible on the sheet `sheet_id`, or on every sheet if `sheet_id` is
+    // None.
+    SetDefinedName {
+        name: String,
+        sheet_id: Option<SheetId>,
+        refers_to: Option<String>,
+    },
 }
 
 impl fmt::Display for Operation {
@@ -208,6 +217,15 @@ impl fmt::Display for Operation {
             Operation::AddSheetSchema { schema } => {
                 write!(fmt, "AddSheetSchema {{ schema: {:?} }}", schema)
             }
+            Operation::SetDefinedName {
+                name,
+                sheet_id,
+                refers_to,
+            } => write!(
+                fmt,
+                "SetDefinedName {{ name: {}, sheet_id: {:?}, refers_to: {:?} }}",
+                name, sheet_id, refers_to
+            ),
         }
     }
 }
@@ -3,15 +3,28 @@ use lexicon_fractional_index::key_between;
 
 use crate::{
     controller::GridController,
+    formulas::{replace_sheet_name, replace_sheet_references_with_ref_error},
     grid::{file::sheet_schema::export_sheet, Sheet, SheetId},
-    util,
+    util, Pos,
 };
 
 use super::operation::Operation;
 
 impl GridController {
+    /// Renames a sheet and updates formulas and defined names that refer to
+    /// the sheet by its old name.
     pub fn set_sheet_name_operations(&mut self, sheet_id: SheetId, name: String) -> Vec<Operation> {
-        vec![Operation::SetSheetName { sheet_id, name }]
+        let old_name = self.try_sheet(sheet_id).map(|sheet| sheet.name.clone());
+        let mut ops = vec![Operation::SetSheetName {
+            sheet_id,
+            name: name.clone(),
+        }];
+        if let Some(old_name) = old_name.filter(|old_name| *old_name != name) {
+            ops.extend(self.rewrite_formulas_operations(None, |code, _| {
+                replace_sheet_name(code, &old_name, &name)
+            }));
+        }
+        ops
     }
 
     pub fn set_sheet_color_operations(
@@ -48,8 +61,35 @@ impl GridController {
         }]
     }
 
+    /// Deletes a sheet, along with the names defined on it. Formulas and
+    /// defined names that refer to the sheet are changed to `#REF!`, except
+    /// for three-dimensional references that span other sheets.
     pub fn delete_sheet_operations(&mut self, sheet_id: SheetId) -> Vec<Operation> {
-        vec![Operation::DeleteSheet { sheet_id }]
+        let mut ops = vec![Operation::DeleteSheet { sheet_id }];
+        let Some(sheet) = self.try_sheet(sheet_id) else {
+            return ops;
+        };
+        let sheet_name = sheet.name.clone();
+        let sheet_names = self.sheet_names();
+
+        ops.extend(
+            self.grid
+                .defined_names()
+                .iter()
+                .filter(|defined_name| defined_name.sheet_id == Some(sheet_id))
+                .map(|defined_name| Operation::SetDefinedName {
+                    name: defined_name.name.clone(),
+                    sheet_id: Some(sheet_id),
+                    refers_to: None,
+                }),
+        );
+        ops.extend(
+            self.rewrite_formulas_operations(Some(sheet_id), |code, sheet_pos| {
+                let pos = sheet_pos.map_or(Pos::ORIGIN, Pos::from);
+                replace_sheet_references_with_ref_error(code, pos, &sheet_name, &sheet_names)
+            }),
+        );
+        ops
     }
 
     pub fn move_sheet_operations(
@@ -0,0 +1,23 @@
+//! Helpers shared by the controller tests.
+
+use crate::{controller::GridController, grid::SheetId, CellValue, CodeCellValue, Pos};
+
+/// Returns a grid controller with two sheets, "Sheet 1" and "Sheet 2", along
+/// with their ids.
+pub fn test_with_two_sheets() -> (GridController, SheetId, SheetId) {
+    let mut gc = GridController::test();
+    gc.add_sheet(None);
+    let [sheet_1, sheet_2] = gc.sheet_ids()[..] else {
+        panic!("expected two sheets");
+    };
+    (gc, sheet_1, sheet_2)
+}
+
+/// Returns the code of the formula at `pos` on the sheet `sheet_id`. Panics
+/// if the cell does not contain a formula.
+pub fn formula_code(gc: &GridController, sheet_id: SheetId, pos: Pos) -> String {
+    match gc.sheet(sheet_id).cell_value(pos) {
+        Some(CellValue::Code(CodeCellValue { code, .. })) => code,
+        other => panic!("expected a formula, got {other:?}"),
+    }
+}
@@ -52,7 +52,10 @@ mod test {
     use super::*;
     use crate::{
         color::Rgba,
-        controller::GridController,
+        controller::{
+            test_util::{formula_code, test_with_two_sheets},
+            GridController,
+        },
         grid::{
             formats::format_update::FormatUpdate, generate_borders, js_types::CellFormatSummary,
             set_rect_borders, BorderSelection, BorderStyle, CellBorderLine, CodeCellLanguage,
@@ -812,6 +815,74 @@ mod test {
         );
     }
 
+    #[test]
+    fn move_cells_updates_references() {
+        let (mut gc, sheet_id, other_sheet_id) = test_with_two_sheets();
+
+        set_cell_value(&mut gc, sheet_id, "5", 0, 1);
+        set_cell_value(&mut gc, sheet_id, "7", 0, 2);
+        set_formula_code_cell(&mut gc, sheet_id, "A1 + $A$2", 3, 0);
+        set_formula_code_cell(&mut gc, other_sheet_id, "'Sheet 1'!A1 * 2", 0, 0);
+
+        let formula_pos = Pos { x: 3, y: 0 };
+        let other_pos = Pos { x: 0, y: 0 };
+
+        gc.move_cells(
+            SheetRect::new_pos_span(Pos { x: 0, y: 1 }, Pos { x: 0, y: 2 }, sheet_id),
+            (5, 1, sheet_id).into(),
+            None,
+        );
+
+        assert_eq!(
+            formula_code(&gc, sheet_id, formula_pos),
+            "R[1]C[2] + R{2}C{5}"
+        );
+        assert_eq!(
+            formula_code(&gc, other_sheet_id, other_pos),
+            r#""Sheet 1"!R[1]C[5] * 2"#
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(formula_pos),
+            Some(CellValue::Number(BigDecimal::from(12)))
+        );
+        assert_eq!(
+            gc.sheet(other_sheet_id).display_value(other_pos),
+            Some(CellValue::Number(BigDecimal::from(10)))
+        );
+
+        // the references are restored together with the cells
+        gc.undo(None);
+        assert_eq!(
+            formula_code(&gc, sheet_id, formula_pos),
+            "R[1]C[-3] + R{2}C{0}"
+        );
+        assert_eq!(
+            formula_code(&gc, other_sheet_id, other_pos),
+            r#""Sheet 1"!R[1]C[0] * 2"#
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(formula_pos),
+            Some(CellValue::Number(BigDecimal::from(12)))
+        );
+
+        // absolute references in a moved formula follow the cells moved with it
+        set_formula_code_cell(&mut gc, sheet_id, "$A$1 + A2", 1, 1);
+        gc.move_cells(
+            SheetRect::new_pos_span(Pos { x: 0, y: 1 }, Pos { x: 1, y: 2 }, sheet_id),
+            (5, 1, sheet_id).into(),
+            None,
+        );
+        let moved_pos = Pos { x: 6, y: 1 };
+        assert_eq!(
+            formula_code(&gc, sheet_id, moved_pos),
+            "R{1}C{5} + R[1]C[-1]"
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).display_value(moved_pos),
+            Some(CellValue::Number(BigDecimal::from(12)))
+        );
+    }
+
     #[test]
     fn copy_cell_formats() {
         let mut gc = GridController::test();
@@ -29,6 +29,19 @@ impl GridController {
         self.start_user_transaction(ops, cursor, TransactionName::RunCode);
     }
 
+    /// Recalculates formulas that use volatile functions, such as `NOW()`,
+    /// and the cells that depend on them. This is not added to the undo stack.
+    pub fn recalculate(&mut self, cursor: Option<String>) {
+        let ops = self.recalculate_operations();
+        self.start_user_transaction(ops, cursor, TransactionName::Recalculate);
+    }
+
+    /// Recalculates all formulas in grid. This is not added to the undo stack.
+    pub fn recalculate_all_formulas(&mut self, cursor: Option<String>) {
+        let ops = self.recalculate_all_formulas_operations();
+        self.start_user_transaction(ops, cursor, TransactionName::Recalculate);
+    }
+
     /// Reruns one code cell
     pub fn rerun_code_cell(&mut self, sheet_pos: SheetPos, cursor: Option<String>) {
         let ops = self.rerun_code_cell_operations(sheet_pos);
@@ -0,0 +1,22 @@
+use anyhow::Result;
+
+use crate::{
+    controller::{active_transactions::transaction_name::TransactionName, GridController},
+    grid::SheetId,
+};
+
+impl GridController {
+    /// Sets a defined name, or removes it if `refers_to` is `None`. Returns an
+    /// error if the name or the formula it refers to is invalid.
+    pub fn set_defined_name(
+        &mut self,
+        name: String,
+        sheet_id: Option<SheetId>,
+        refers_to: Option<String>,
+        cursor: Option<String>,
+    ) -> Result<()> {
+        let ops = self.set_defined_name_operations(name, sheet_id, refers_to)?;
+        self.start_user_transaction(ops, cursor, TransactionName::SetDefinedName);
+        Ok(())
+    }
+}
@@ -5,6 +5,7 @@ pub mod borders;
 pub mod cells;
 pub mod clipboard;
 pub mod code;
+pub mod defined_names;
 pub mod formats;
 pub mod formatting;
 pub mod import;
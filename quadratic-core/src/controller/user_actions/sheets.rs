@@ -50,7 +50,14 @@ impl GridController {
 
 #[cfg(test)]
 mod test {
-    use crate::{controller::GridController, grid::SheetId};
+    use crate::{
+        controller::{
+            test_util::{formula_code, test_with_two_sheets},
+            GridController,
+        },
+        grid::{CodeCellLanguage, SheetId},
+        CellValue, CodeCellValue, Pos, SheetPos,
+    };
 
     #[test]
     fn test_set_sheet_name() {
@@ -75,6 +82,51 @@ mod test {
         assert_eq!(sheet.name, "Nice Name");
     }
 
+    #[test]
+    fn test_set_sheet_name_updates_formulas() {
+        let (mut g, s1, s2) = test_with_two_sheets();
+        g.set_cell_value(SheetPos::new(s2, 0, 1), "5".into(), None);
+        g.set_code_cell(
+            SheetPos::new(s1, 0, 0),
+            CodeCellLanguage::Formula,
+            "'Sheet 2'!A1 * 2".into(),
+            None,
+        );
+        g.set_defined_name("Input".into(), None, Some("'Sheet 2'!$A$1".into()), None)
+            .unwrap();
+        let pos = Pos { x: 0, y: 0 };
+        assert_eq!(formula_code(&g, s1, pos), r#""Sheet 2"!R[1]C[0] * 2"#);
+
+        // a formula that was never run
+        let not_run_pos = Pos { x: 1, y: 0 };
+        g.sheet_mut(s1).set_cell_value(
+            not_run_pos,
+            CellValue::Code(CodeCellValue {
+                language: CodeCellLanguage::Formula,
+                code: r#""Sheet 2"!R[1]C[-1]"#.into(),
+            }),
+        );
+
+        g.set_sheet_name(s2, String::from("Data"), None);
+        assert_eq!(formula_code(&g, s1, pos), r#""Data"!R[1]C[0] * 2"#);
+        assert_eq!(formula_code(&g, s1, not_run_pos), r#""Data"!R[1]C[-1]"#);
+        assert_eq!(g.grid().defined_names()[0].refers_to, r#""Data"!$A$1"#);
+        assert_eq!(
+            g.sheet(s1).display_value(pos),
+            Some(CellValue::Number(10.into()))
+        );
+
+        // the rename and the rewrite are undone together
+        g.undo(None);
+        assert_eq!(g.sheet(s2).name, "Sheet 2");
+        assert_eq!(formula_code(&g, s1, pos), r#""Sheet 2"!R[1]C[0] * 2"#);
+        assert_eq!(g.grid().defined_names()[0].refers_to, "'Sheet 2'!$A$1");
+
+        g.redo(None);
+        assert_eq!(g.sheet(s2).name, "Data");
+        assert_eq!(formula_code(&g, s1, pos), r#""Data"!R[1]C[0] * 2"#);
+    }
+
     #[test]
     fn test_set_sheet_color() {
         let mut g = GridController::test();
@@ -132,6 +184,83 @@ mod test {
         assert_ne!(g.sheet_ids()[0], s1);
     }
 
+    #[test]
+    fn test_delete_sheet_updates_formulas() {
+        let (mut g, s1, s2) = test_with_two_sheets();
+        g.set_cell_value(SheetPos::new(s2, 0, 1), "5".into(), None);
+        g.set_code_cell(
+            SheetPos::new(s1, 0, 0),
+            CodeCellLanguage::Formula,
+            "'Sheet 2'!A1 * 2".into(),
+            None,
+        );
+        g.set_defined_name("Local".into(), Some(s2), Some("$A$1".into()), None)
+            .unwrap();
+        let pos = Pos { x: 0, y: 0 };
+
+        g.delete_sheet(s2, None);
+        assert_eq!(formula_code(&g, s1, pos), "#REF! * 2");
+        assert_eq!(g.sheet(s1).display_value(pos), None);
+        assert!(g.grid().defined_names().is_empty());
+
+        // the deletion and the rewrite are undone together
+        g.undo(None);
+        assert_eq!(formula_code(&g, s1, pos), r#""Sheet 2"!R[1]C[0] * 2"#);
+        assert_eq!(
+            g.sheet(s1).display_value(pos),
+            Some(CellValue::Number(10.into()))
+        );
+        assert_eq!(g.grid().defined_names().len(), 1);
+    }
+
+    #[test]
+    fn test_sheet_span_updates_formulas() {
+        let mut g = GridController::test();
+        let s1 = g.sheet_ids()[0];
+        let mut ids = vec![];
+        for (i, name) in ["Jan", "Feb", "Mar", "Apr"].into_iter().enumerate() {
+            g.add_sheet_with_name(name.into(), None);
+            let id = g.sheet_ids()[i + 1];
+            let value = 10_i64.pow(i as u32).to_string();
+            g.set_cell_value(SheetPos::new(id, 0, 1), value, None);
+            ids.push(id);
+        }
+        let [jan, feb, mar, apr] = ids[..] else {
+            panic!("expected four sheets");
+        };
+        g.set_code_cell(
+            SheetPos::new(s1, 0, 0),
+            CodeCellLanguage::Formula,
+            "SUM(Jan:Mar!A1)".into(),
+            None,
+        );
+        let pos = Pos { x: 0, y: 0 };
+        let sum = |g: &GridController| g.sheet(s1).display_value(pos);
+        assert_eq!(sum(&g), Some(CellValue::Number(111.into())));
+
+        // deleting a sheet in the middle of the span
+        g.delete_sheet(feb, None);
+        assert_eq!(formula_code(&g, s1, pos), r#"SUM("Jan":"Mar"!R[1]C[0])"#);
+        assert_eq!(sum(&g), Some(CellValue::Number(101.into())));
+        g.undo(None);
+        assert_eq!(sum(&g), Some(CellValue::Number(111.into())));
+
+        // moving a sheet into the span
+        g.move_sheet(apr, Some(mar), None);
+        assert_eq!(sum(&g), Some(CellValue::Number(1111.into())));
+        g.undo(None);
+        assert_eq!(sum(&g), Some(CellValue::Number(111.into())));
+
+        // deleting the first sheet of the span moves it to the next sheet
+        g.delete_sheet(jan, None);
+        assert_eq!(formula_code(&g, s1, pos), r#"SUM("Feb":"Mar"!R[1]C[0])"#);
+        assert_eq!(sum(&g), Some(CellValue::Number(110.into())));
+
+        // adding a sheet inside the span
+        g.duplicate_sheet(feb, None);
+        assert_eq!(sum(&g), Some(CellValue::Number(120.into())));
+    }
+
     #[test]
     fn test_move_sheet_sheet_does_not_exist() {
         let mut g = GridController::test();
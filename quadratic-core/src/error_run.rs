@@ -100,6 +100,10 @@ pub enum RunErrorMsg {
     IndexOutOfBounds,
     NoMatch,
     InvalidArgument,
+    NoConvergence,
+    SingularMatrix,
+    UnknownName(Cow<'static, str>),
+    EmptyIntersection,
 }
 
 impl fmt::Display for RunErrorMsg {
@@ -237,10 +241,59 @@ impl fmt::Display for RunErrorMsg {
             Self::InvalidArgument => {
                 write!(f, "Invalid argument")
             }
+            Self::NoConvergence => {
+                write!(f, "Calculation did not converge")
+            }
+            Self::SingularMatrix => {
+                write!(f, "Matrix is singular")
+            }
+            Self::UnknownName(name) => {
+                write!(f, "There is no name `{name}`")
+            }
+            Self::EmptyIntersection => {
+                write!(f, "Ranges do not intersect")
+            }
         }
     }
 }
 impl RunErrorMsg {
+    /// Returns the Excel error code for this error, such as `#DIV/0!` or
+    /// `#N/A`.
+    pub fn excel_code(&self) -> &'static str {
+        match self {
+            Self::DivideByZero => "#DIV/0!",
+            Self::BadCellReference | Self::IndexOutOfBounds => "#REF!",
+            Self::BadFunctionName | Self::UnknownName(_) => "#NAME?",
+            Self::Overflow
+            | Self::NegativeExponent
+            | Self::NotANumber
+            | Self::Infinity
+            | Self::NoConvergence
+            | Self::SingularMatrix => "#NUM!",
+            Self::NoMatch => "#N/A",
+            Self::Spill => "#SPILL!",
+            Self::EmptyArray => "#CALC!",
+            Self::EmptyIntersection => "#NULL!",
+            _ => "#VALUE!",
+        }
+    }
+    /// Returns the error for an Excel error code, such as `#DIV/0!` or `#N/A`,
+    /// ignoring case.
+    pub fn from_excel_code(code: &str) -> Option<Self> {
+        match code.to_ascii_uppercase().as_str() {
+            "#DIV/0!" => Some(Self::DivideByZero),
+            "#VALUE!" => Some(Self::InvalidArgument),
+            "#REF!" => Some(Self::BadCellReference),
+            "#NAME?" => Some(Self::BadFunctionName),
+            "#NUM!" => Some(Self::NotANumber),
+            "#N/A" => Some(Self::NoMatch),
+            "#SPILL!" => Some(Self::Spill),
+            "#CALC!" => Some(Self::EmptyArray),
+            "#NULL!" => Some(Self::EmptyIntersection),
+            _ => None,
+        }
+    }
+
     /// Attaches a span to this error message, returning a Error.
     pub fn with_span(self, span: impl Into<Span>) -> RunError {
         RunError {
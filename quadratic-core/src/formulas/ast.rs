@@ -1,12 +1,13 @@
 use std::fmt;
 
+use bigdecimal::BigDecimal;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use smallvec::smallvec;
 
 use super::*;
 use crate::{
-    Array, ArraySize, CellValue, CodeResult, CoerceInto, Pos, RunErrorMsg, Spanned, Value,
+    Array, ArraySize, CellValue, CodeResult, CoerceInto, Rect, RunErrorMsg, Spanned, Value,
 };
 
 /// Abstract syntax tree of a formula expression.
@@ -32,9 +33,12 @@ pub enum AstNodeContents {
     Paren(Box<AstNode>),
     Array(Vec<Vec<AstNode>>),
     CellRef(CellRef),
+    RangeRef(RangeRef),
+    Name(String),
     String(String),
-    Number(f64),
+    Number(BigDecimal),
     Bool(bool),
+    Error(RunErrorMsg),
 }
 impl fmt::Display for AstNodeContents {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -58,15 +62,18 @@ impl fmt::Display for AstNodeContents {
                 a.iter().map(|row| row.iter().join(", ")).join("; "),
             ),
             AstNodeContents::CellRef(cellref) => write!(f, "{cellref}"),
+            AstNodeContents::RangeRef(range_ref) => write!(f, "{range_ref}"),
+            AstNodeContents::Name(name) => write!(f, "{name}"),
             AstNodeContents::String(s) => write!(f, "{s:?}"),
-            AstNodeContents::Number(n) => write!(f, "{n:?}"),
+            AstNodeContents::Number(n) => write!(f, "{n}"),
             AstNodeContents::Bool(false) => write!(f, "FALSE"),
             AstNodeContents::Bool(true) => write!(f, "TRUE"),
+            AstNodeContents::Error(msg) => write!(f, "{}", msg.excel_code()),
         }
     }
 }
 impl AstNodeContents {
-    fn type_string(&self) -> &'static str {
+    pub(super) fn type_string(&self) -> &'static str {
         match self {
             AstNodeContents::Empty => "empty expression",
             AstNodeContents::FunctionCall { func, .. } => match func.inner.as_str() {
@@ -77,9 +84,12 @@ impl AstNodeContents {
             AstNodeContents::Paren(contents) => contents.inner.type_string(),
             AstNodeContents::Array(_) => "array literal",
             AstNodeContents::CellRef(_) => "cell reference",
+            AstNodeContents::RangeRef(_) => "range reference",
+            AstNodeContents::Name(_) => "name",
             AstNodeContents::String(_) => "string literal",
             AstNodeContents::Number(_) => "numeric literal",
             AstNodeContents::Bool(_) => "boolean literal",
+            AstNodeContents::Error(_) => "error literal",
         }
     }
 }
@@ -95,6 +105,102 @@ impl Spanned<AstNodeContents> {
             .with_span(self.span)),
         }
     }
+
+    /// Returns the sheet name and the region of cells referenced by a cell
+    /// reference, cell range, or defined name, resolved relative to the
+    /// position of `ctx`.
+    pub fn to_range(&self, ctx: &mut Ctx<'_>) -> CodeResult<(Option<String>, Rect)> {
+        match &self.inner {
+            AstNodeContents::FunctionCall { func, args } if func.inner == ":" => {
+                if args.len() != 2 {
+                    internal_error!("invalid arguments to cell range operator");
+                }
+                let ref1 = args[0].to_cell_ref()?;
+                let ref2 = args[1].to_cell_ref()?;
+                let corner1 = ref1.resolve_from(ctx.sheet_pos.into());
+                let corner2 = ref2.resolve_from(ctx.sheet_pos.into());
+                Ok((ref1.sheet, Rect::new_span(corner1, corner2)))
+            }
+            AstNodeContents::FunctionCall { func, .. } if is_area_op(&func.inner) => {
+                let mut areas = self.to_ranges(ctx)?;
+                if areas.len() != 1 {
+                    return Err(RunErrorMsg::Expected {
+                        expected: "single-area reference".into(),
+                        got: Some("multi-area reference".into()),
+                    }
+                    .with_span(self.span));
+                }
+                Ok(areas.remove(0))
+            }
+            AstNodeContents::FunctionCall { func, args }
+                if func.inner == IMPLICIT_INTERSECTION_OP =>
+            {
+                let [reference] = args.as_slice() else {
+                    internal_error!("invalid arguments to implicit intersection operator");
+                };
+                references::implicit_intersection(ctx, reference, self.span)
+            }
+            AstNodeContents::RangeRef(range_ref) => ctx.resolve_range_ref(range_ref, self.span),
+            AstNodeContents::Paren(contents) => contents.to_range(ctx),
+            AstNodeContents::Name(name) if ctx.lookup(name).is_none() => ctx
+                .with_defined_name(name, self.span, |ctx, formula| formula.ast.to_range(ctx))
+                .unwrap_or_else(|| {
+                    Err(RunErrorMsg::UnknownName(name.clone().into()).with_span(self.span))
+                }),
+            _ => {
+                let cell_ref = self.to_cell_ref()?;
+                let pos = cell_ref.resolve_from(ctx.sheet_pos.into());
+                Ok((cell_ref.sheet, Rect::single_pos(pos)))
+            }
+        }
+    }
+
+    /// Returns the sheet name and the region of cells for each area of a
+    /// reference. A union such as `(A1:B2, D4)` has several areas, and so may
+    /// an intersection such as `A1:C3 B2:D4`.
+    pub fn to_ranges(&self, ctx: &mut Ctx<'_>) -> CodeResult<Vec<(Option<String>, Rect)>> {
+        match &self.inner {
+            AstNodeContents::FunctionCall { func, args } if func.inner == UNION_OP => {
+                let mut areas = vec![];
+                for arg in args {
+                    areas.extend(arg.to_ranges(ctx)?);
+                }
+                Ok(areas)
+            }
+            AstNodeContents::FunctionCall { func, args } if func.inner == INTERSECTION_OP => {
+                if args.len() != 2 {
+                    internal_error!("invalid arguments to intersection operator");
+                }
+                let areas1 = args[0].to_ranges(ctx)?;
+                let areas2 = args[1].to_ranges(ctx)?;
+                ctx.intersect_areas(&areas1, &areas2, self.span)
+            }
+            AstNodeContents::Paren(contents) => contents.to_ranges(ctx),
+            AstNodeContents::Name(name) if ctx.lookup(name).is_none() => ctx
+                .with_defined_name(name, self.span, |ctx, formula| formula.ast.to_ranges(ctx))
+                .unwrap_or_else(|| {
+                    Err(RunErrorMsg::UnknownName(name.clone().into()).with_span(self.span))
+                }),
+            _ => Ok(vec![self.to_range(ctx)?]),
+        }
+    }
+}
+
+/// Name of the function call produced by the intersection operator, which is
+/// written as whitespace between two references.
+pub(super) const INTERSECTION_OP: &str = " ";
+/// Name of the function call produced by the union operator, which is written
+/// as a comma between references in parentheses.
+pub(super) const UNION_OP: &str = ",";
+
+/// Name of the function call produced by the implicit intersection operator
+/// `@`.
+pub(super) const IMPLICIT_INTERSECTION_OP: &str = "@";
+
+/// Returns whether `func_name` is the intersection or union operator, which
+/// combine references into references with any number of areas.
+pub(super) fn is_area_op(func_name: &str) -> bool {
+    func_name == INTERSECTION_OP || func_name == UNION_OP
 }
 
 impl Formula {
@@ -105,56 +211,85 @@ impl Formula {
 }
 
 impl AstNode {
-    fn eval<'ctx: 'a, 'a>(&'a self, ctx: &'a mut Ctx<'ctx>, only_parse: bool) -> CodeResult {
+    /// Evaluates an expression. Errors are returned as error values so that
+    /// functions such as `IFERROR()` can handle them, except when only parsing
+    /// the formula.
+    pub(super) fn eval<'ctx: 'a, 'a>(
+        &'a self,
+        ctx: &'a mut Ctx<'ctx>,
+        only_parse: bool,
+    ) -> CodeResult {
+        match self.eval_inner(ctx, only_parse) {
+            Err(e) if !only_parse => Ok(Spanned {
+                span: self.span,
+                inner: CellValue::Error(Box::new(e)).into(),
+            }),
+            other => other,
+        }
+    }
+
+    fn eval_inner<'ctx: 'a, 'a>(&'a self, ctx: &'a mut Ctx<'ctx>, only_parse: bool) -> CodeResult {
         let value = match &self.inner {
             AstNodeContents::Empty => CellValue::Blank.into(),
 
             // Cell range
-            AstNodeContents::FunctionCall { func, args } if func.inner == ":" => {
-                if args.len() != 2 {
-                    internal_error!("invalid arguments to cell range operator");
-                }
-                let ref1 = args[0].to_cell_ref()?;
-                let ref2 = args[1].to_cell_ref()?;
-                let sheet_name = ref1.sheet.clone();
-                let corner1 = ref1.resolve_from(ctx.sheet_pos.into());
-                let corner2 = ref2.resolve_from(ctx.sheet_pos.into());
+            AstNodeContents::FunctionCall { func, .. } if func.inner == ":" => {
+                let (sheet_name, rect) = self.to_range(ctx)?;
+                ctx.get_cell_array(sheet_name, rect, self.span)?
+                    .inner
+                    .into()
+            }
 
-                let x1 = std::cmp::min(corner1.x, corner2.x);
-                let y1 = std::cmp::min(corner1.y, corner2.y);
-
-                let x2 = std::cmp::max(corner1.x, corner2.x);
-                let y2 = std::cmp::max(corner1.y, corner2.y);
-
-                let width = x2
-                    .saturating_sub(x1)
-                    .saturating_add(1)
-                    .try_into()
-                    .unwrap_or(u32::MAX);
-                let height = y2
-                    .saturating_sub(y1)
-                    .saturating_add(1)
-                    .try_into()
-                    .unwrap_or(u32::MAX);
-                if std::cmp::max(width, height) > crate::limits::CELL_RANGE_LIMIT {
-                    return Err(RunErrorMsg::ArrayTooBig.with_span(self.span));
-                }
+            // Intersection or union, such as `A1:C3 B2:D4` or `(A1, C3)`
+            AstNodeContents::FunctionCall { func, .. } if is_area_op(&func.inner) => {
+                let areas = self.to_ranges(ctx)?;
+                ctx.get_areas_array(areas, self.span)?.inner.into()
+            }
 
-                // todo: this should call a new ctx.get_cells to push a full SheetRect to cells_accessed instead of an array of SheetPos
-                let mut flat_array = smallvec![];
-                // Reuse the same `CellRef` object so that we don't have to
-                // clone `sheet_name.`
-                let mut cell_ref = CellRef::absolute(sheet_name, Pos::ORIGIN); // We'll overwrite the position.
-                for y in y1..=y2 {
-                    cell_ref.y = CellRefCoord::Absolute(y);
-                    for x in x1..=x2 {
-                        cell_ref.x = CellRefCoord::Absolute(x);
-                        flat_array.push(ctx.get_cell(&cell_ref, self.span)?.inner);
-                    }
-                }
+            // Implicit intersection, such as `@A:A`
+            AstNodeContents::FunctionCall { func, args }
+                if func.inner == IMPLICIT_INTERSECTION_OP =>
+            {
+                let [arg] = args.as_slice() else {
+                    internal_error!("invalid arguments to implicit intersection operator");
+                };
+                references::eval_implicit_intersection(ctx, only_parse, self.span, arg)?
+            }
 
-                let size = ArraySize::new_or_err(width, height)?;
-                Array::new_row_major(size, flat_array)?.into()
+            // Three-dimensional reference, such as `Jan:Dec!C10`
+            AstNodeContents::RangeRef(RangeRef::SheetRange {
+                first_sheet,
+                last_sheet,
+                range,
+            }) => ctx
+                .get_sheet_range_array(first_sheet, last_sheet, range, self.span)?
+                .inner
+                .into(),
+
+            // Whole-column or whole-row range, or spill range
+            AstNodeContents::RangeRef(_) => {
+                let (sheet_name, rect) = self.to_range(ctx)?;
+                ctx.get_cell_array(sheet_name, rect, self.span)?
+                    .inner
+                    .into()
+            }
+
+            // Lambda call or function that takes unevaluated arguments, such
+            // as `LET()`
+            AstNodeContents::FunctionCall { func, args }
+                if lambda::is_special_form(ctx, &func.inner) =>
+            {
+                lambda::eval_special_form(ctx, only_parse, self.span, func, args)?
+            }
+
+            // Function that takes cell references as arguments, such as
+            // `ROW()`
+            AstNodeContents::FunctionCall { func, args }
+                if references::is_reference_function(&func.inner) =>
+            {
+                ctx.is_volatile |=
+                    functions::lookup_function(&func.inner).is_some_and(|f| f.is_volatile);
+                references::eval_reference_function(ctx, only_parse, self.span, func, args)?
             }
 
             // Other operator/function
@@ -167,6 +302,7 @@ impl AstNode {
                 let func_name = &func.inner;
                 match functions::lookup_function(func_name) {
                     Some(f) => {
+                        ctx.is_volatile |= f.is_volatile;
                         let args = FormulaFnArgs::new(arg_values, self.span, f.name);
                         (f.eval)(&mut *ctx, only_parse, args)?
                     }
@@ -210,9 +346,15 @@ impl AstNode {
                 Array::from(ctx.get_cell(cell_ref, self.span)?.inner).into()
             }
 
+            AstNodeContents::Name(name) => lambda::eval_name(ctx, only_parse, name, self.span)?,
+
             AstNodeContents::String(s) => Value::from(s.to_string()),
-            AstNodeContents::Number(n) => Value::from(*n),
+            AstNodeContents::Number(n) => Value::from(n.clone()),
             AstNodeContents::Bool(b) => Value::from(*b),
+
+            AstNodeContents::Error(msg) => {
+                CellValue::Error(Box::new(msg.clone().with_span(self.span))).into()
+            }
         };
 
         Ok(Spanned {
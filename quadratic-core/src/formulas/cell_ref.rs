@@ -12,13 +12,13 @@ use crate::Pos;
 #[cfg_attr(feature = "js", derive(ts_rs::TS))]
 #[serde(tag = "type")]
 pub enum RangeRef {
-    // this is not yet used...
+    /// Whole-row range, such as `3:5`.
     RowRange {
         start: CellRefCoord,
         end: CellRefCoord,
         sheet: Option<String>,
     },
-    // this is not yet used...
+    /// Whole-column range, such as `A:C`.
     ColRange {
         start: CellRefCoord,
         end: CellRefCoord,
@@ -31,14 +31,37 @@ pub enum RangeRef {
     Cell {
         pos: CellRef,
     },
+    /// Reference to the same range on every sheet from `first_sheet` to
+    /// `last_sheet` in sheet order, such as `Jan:Dec!C10`. The sheet of
+    /// `range` is not used.
+    SheetRange {
+        first_sheet: String,
+        last_sheet: String,
+        range: Box<RangeRef>,
+    },
+    /// Reference to the whole output of the code cell at `pos`, such as
+    /// `B2#`. The size of the range depends on the output of the code cell.
+    Spill {
+        pos: CellRef,
+    },
 }
 impl fmt::Display for RangeRef {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            RangeRef::RowRange { start, end, .. } => write!(f, "R{start}:R{end}"),
-            RangeRef::ColRange { start, end, .. } => write!(f, "C{start}:C{end}"),
+            RangeRef::RowRange { start, end, sheet } => {
+                write!(f, "{}R{start}:R{end}", sheet_prefix(sheet))
+            }
+            RangeRef::ColRange { start, end, sheet } => {
+                write!(f, "{}C{start}:C{end}", sheet_prefix(sheet))
+            }
             RangeRef::CellRange { start, end } => write!(f, "{start}:{end}"),
             RangeRef::Cell { pos } => write!(f, "{pos}"),
+            RangeRef::SheetRange {
+                first_sheet,
+                last_sheet,
+                range,
+            } => write!(f, "{}{range}", sheet_range_prefix(first_sheet, last_sheet)),
+            RangeRef::Spill { pos } => write!(f, "{pos}#"),
         }
     }
 }
@@ -52,20 +75,123 @@ impl RangeRef {
     /// A1-style notation.
     pub fn a1_string(self, base: Pos) -> String {
         match self {
-            RangeRef::RowRange { start, end, .. } => {
-                format!("{}:{}", start.row_string(base.y), end.row_string(base.y))
+            RangeRef::RowRange { start, end, sheet } => {
+                let sheet_str = sheet_prefix(&sheet);
+                let start = start.row_string(base.y);
+                let end = end.row_string(base.y);
+                format!("{sheet_str}{start}:{end}")
             }
-            RangeRef::ColRange { start, end, .. } => {
-                format!("{}:{}", start.col_string(base.x), end.col_string(base.x))
+            RangeRef::ColRange { start, end, sheet } => {
+                let sheet_str = sheet_prefix(&sheet);
+                let start = start.col_string(base.x);
+                let end = end.col_string(base.x);
+                format!("{sheet_str}{start}:{end}")
             }
             RangeRef::CellRange { start, end } => {
                 format!("{}:{}", start.a1_string(base), end.a1_string(base))
             }
             RangeRef::Cell { pos } => pos.a1_string(base),
+            RangeRef::SheetRange {
+                first_sheet,
+                last_sheet,
+                range,
+            } => {
+                let sheet_str = sheet_range_prefix(&first_sheet, &last_sheet);
+                format!("{sheet_str}{}", range.a1_string(base))
+            }
+            RangeRef::Spill { pos } => format!("{}#", pos.a1_string(base)),
+        }
+    }
+
+    /// Returns the same range reference on the sheet `sheet`. For a
+    /// three-dimensional reference, this is the range on a single sheet.
+    pub fn with_sheet(self, sheet: Option<String>) -> RangeRef {
+        match self {
+            RangeRef::RowRange { start, end, .. } => RangeRef::RowRange { start, end, sheet },
+            RangeRef::ColRange { start, end, .. } => RangeRef::ColRange { start, end, sheet },
+            RangeRef::CellRange { start, end } => RangeRef::CellRange {
+                start: CellRef { sheet, ..start },
+                end: CellRef { sheet: None, ..end },
+            },
+            RangeRef::Cell { pos } => RangeRef::Cell {
+                pos: CellRef { sheet, ..pos },
+            },
+            RangeRef::SheetRange { range, .. } => range.with_sheet(sheet),
+            RangeRef::Spill { pos } => RangeRef::Spill {
+                pos: CellRef { sheet, ..pos },
+            },
+        }
+    }
+
+    /// Parses an A1-style whole-column or whole-row range reference, such as
+    /// `A:C` or `$3:5`, relative to a given location. The sheet is not
+    /// included.
+    pub fn parse_a1_row_or_col_range(s: &str, base: Pos) -> Option<RangeRef> {
+        let (start, end) = s.split_once(':')?;
+        let cols = (
+            CellRefCoord::parse_a1_col(start, base.x),
+            CellRefCoord::parse_a1_col(end, base.x),
+        );
+        if let (Some(start), Some(end)) = cols {
+            return Some(RangeRef::ColRange {
+                start,
+                end,
+                sheet: None,
+            });
         }
+        Some(RangeRef::RowRange {
+            start: CellRefCoord::parse_a1_row(start, base.y)?,
+            end: CellRefCoord::parse_a1_row(end, base.y)?,
+            sheet: None,
+        })
+    }
+
+    /// Parses a whole-column or whole-row range reference in the internal
+    /// notation, such as `C[0]:C{2}` or `R[-1]:R[1]`. The sheet is not
+    /// included.
+    pub fn parse_internal_row_or_col_range(s: &str) -> Option<RangeRef> {
+        let (start, end) = s.split_once(':')?;
+        let coords = |prefix: char| -> Option<(CellRefCoord, CellRefCoord)> {
+            Some((
+                CellRefCoord::from_str(start.strip_prefix(prefix)?).ok()?,
+                CellRefCoord::from_str(end.strip_prefix(prefix)?).ok()?,
+            ))
+        };
+        if let Some((start, end)) = coords('C') {
+            return Some(RangeRef::ColRange {
+                start,
+                end,
+                sheet: None,
+            });
+        }
+        let (start, end) = coords('R')?;
+        Some(RangeRef::RowRange {
+            start,
+            end,
+            sheet: None,
+        })
+    }
+}
+
+/// Returns the sheet prefix for a reference, such as `"Sheet 2"!`, or the
+/// empty string if there is no sheet.
+fn sheet_prefix(sheet: &Option<String>) -> String {
+    match sheet {
+        Some(sheet_name) => format!("{}!", escape_string(sheet_name)),
+        None => String::new(),
     }
 }
 
+/// Returns the sheet prefix for a three-dimensional reference, such as
+/// `"Jan":"Dec"!`.
+fn sheet_range_prefix(first_sheet: &str, last_sheet: &str) -> String {
+    format!(
+        "{}:{}!",
+        escape_string(first_sheet),
+        escape_string(last_sheet)
+    )
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "js", derive(ts_rs::TS))]
 pub struct CellRef {
@@ -131,10 +257,7 @@ impl CellRef {
     /// Returns the human-friendly string representing this cell reference in
     /// A1-style notation.
     pub fn a1_string(&self, base: Pos) -> String {
-        let sheet_str = match &self.sheet {
-            Some(sheet_name) => format!("{}!", escape_string(sheet_name)),
-            None => String::new(),
-        };
+        let sheet_str = sheet_prefix(&self.sheet);
         let col = self.x.col_string(base.x);
         let row = self.y.row_string(base.y);
         format!("{sheet_str}{col}{row}")
@@ -234,6 +357,33 @@ impl CellRefCoord {
             CellRefCoord::Absolute(coord) => coord,
         }
     }
+    /// Parses an A1-style column coordinate, such as `C` or `$nB`, relative
+    /// to the column `base_x`.
+    fn parse_a1_col(s: &str, base_x: i64) -> Option<Self> {
+        match s.strip_prefix('$') {
+            Some(name) => Some(CellRefCoord::Absolute(crate::util::column_from_name(name)?)),
+            None => Some(CellRefCoord::Relative(
+                crate::util::column_from_name(s)? - base_x,
+            )),
+        }
+    }
+    /// Parses an A1-style row coordinate, such as `3` or `$n2`, relative to
+    /// the row `base_y`.
+    fn parse_a1_row(s: &str, base_y: i64) -> Option<Self> {
+        let (is_absolute, s) = match s.strip_prefix('$') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let row = match s.strip_prefix('n') {
+            Some(digits) => -digits.parse::<i64>().ok()?,
+            None => s.parse::<i64>().ok()?,
+        };
+        match is_absolute {
+            true => Some(CellRefCoord::Absolute(row)),
+            false => Some(CellRefCoord::Relative(row - base_y)),
+        }
+    }
+
     /// Returns the `$` prefix if this is an absolute reference, or the empty
     /// string if it is a relative reference.
     fn prefix(self) -> &'static str {
@@ -312,4 +462,64 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_a1_row_or_col_range_parsing() {
+        let base_pos = pos![E8];
+
+        for (s, expected) in [
+            (
+                "B:$D",
+                RangeRef::ColRange {
+                    start: CellRefCoord::Relative(-3),
+                    end: CellRefCoord::Absolute(3),
+                    sheet: None,
+                },
+            ),
+            (
+                "nA:A",
+                RangeRef::ColRange {
+                    start: CellRefCoord::Relative(-5),
+                    end: CellRefCoord::Relative(-4),
+                    sheet: None,
+                },
+            ),
+            (
+                "$3:10",
+                RangeRef::RowRange {
+                    start: CellRefCoord::Absolute(3),
+                    end: CellRefCoord::Relative(2),
+                    sheet: None,
+                },
+            ),
+            (
+                "n2:$n1",
+                RangeRef::RowRange {
+                    start: CellRefCoord::Relative(-10),
+                    end: CellRefCoord::Absolute(-1),
+                    sheet: None,
+                },
+            ),
+        ] {
+            let range_ref = RangeRef::parse_a1_row_or_col_range(s, base_pos);
+            assert_eq!(Some(expected.clone()), range_ref);
+            assert_eq!(s, expected.clone().a1_string(base_pos));
+
+            let internal = expected.to_string();
+            let range_ref = RangeRef::parse_internal_row_or_col_range(&internal);
+            assert_eq!(Some(expected), range_ref);
+        }
+
+        for s in ["A", "A:", ":3", "A:3", "$:$", "a:b", "3:C"] {
+            assert_eq!(None, RangeRef::parse_a1_row_or_col_range(s, base_pos));
+        }
+
+        let range_ref = RangeRef::ColRange {
+            start: CellRefCoord::Relative(0),
+            end: CellRefCoord::Relative(0),
+            sheet: Some("Sheet 2".to_string()),
+        };
+        assert_eq!("\"Sheet 2\"!E:E", range_ref.clone().a1_string(base_pos));
+        assert_eq!("\"Sheet 2\"!C[0]:C[0]", range_ref.to_string());
+    }
 }
@@ -152,6 +152,129 @@ impl Criterion {
             // Ignore blank values
             .filter_map_ok(|v| v.coerce_nonblank::<T>()))
     }
+    /// Iterates over values in `output_values_range`, excluding those where
+    /// any criterion does not match the corresponding value in its range.
+    pub fn iter_matching_all<'a>(
+        criteria: &'a [(Spanned<Array>, Criterion)],
+        output_values_range: &'a Spanned<Array>,
+    ) -> CodeResult<impl 'a + Iterator<Item = Spanned<&'a CellValue>>> {
+        for (eval_range, _criterion) in criteria {
+            if eval_range.inner.size() != output_values_range.inner.size() {
+                return Err(RunErrorMsg::ExactArraySizeMismatch {
+                    expected: output_values_range.inner.size(),
+                    got: eval_range.inner.size(),
+                }
+                .with_span(eval_range.span));
+            }
+        }
+
+        Ok(output_values_range
+            .inner
+            .cell_values_slice()
+            .iter()
+            .enumerate()
+            .filter(move |(i, _output_value)| {
+                criteria.iter().all(|(eval_range, criterion)| {
+                    criterion.matches(&eval_range.inner.cell_values_slice()[*i])
+                })
+            })
+            .map(|(_i, output_value)| output_value)
+            .with_all_same_span(output_values_range.span))
+    }
+    /// Iterates over values in `output_values_range` and coerces each one,
+    /// excluding those where any criterion does not match or where coercion
+    /// fails.
+    pub fn iter_matching_all_coerced<'a, T>(
+        criteria: &'a [(Spanned<Array>, Criterion)],
+        output_values_range: &'a Spanned<Array>,
+    ) -> CodeResult<impl 'a + Iterator<Item = CodeResult<T>>>
+    where
+        &'a CellValue: TryInto<T>,
+    {
+        Ok(Self::iter_matching_all(criteria, output_values_range)?
+            // Propogate errors
+            .map(|v| v.into_non_error_value())
+            // Ignore blank values
+            .filter_map_ok(|v| v.coerce_nonblank::<T>()))
+    }
+}
+
+/// Criteria for selecting records from a database in functions such as
+/// `DSUM`, where a database is a range whose first row contains field names
+/// and each following row is a record.
+///
+/// The first row of the criteria range also contains field names, and each
+/// following row contains criteria for those fields. A record matches a row of
+/// criteria if it meets every criterion in that row, ignoring blank cells and
+/// empty strings, and it matches the whole range if it matches any row.
+#[derive(Debug, Clone)]
+pub struct DatabaseCriteria {
+    /// Criteria in each row, along with the index of the database column they
+    /// apply to, or `None` if the database has no such field.
+    rows: Vec<Vec<(Option<usize>, Criterion)>>,
+}
+impl DatabaseCriteria {
+    /// Parses a criteria range for a database.
+    pub fn new(database: &Array, criteria: &Spanned<Array>) -> CodeResult<Self> {
+        let mut criteria_rows = criteria.inner.rows();
+        let fields = criteria_rows
+            .next()
+            .unwrap_or_default()
+            .iter()
+            .map(|label| Self::field_index(database, label))
+            .collect_vec();
+        let rows = criteria_rows
+            .map(|row| {
+                std::iter::zip(&fields, row)
+                    .filter(|(_field, value)| !value.is_blank_or_empty_string())
+                    .map(|(&field, value)| {
+                        let criterion = Criterion::try_from(Spanned {
+                            span: criteria.span,
+                            inner: value,
+                        })?;
+                        Ok((field, criterion))
+                    })
+                    .collect::<CodeResult<Vec<_>>>()
+            })
+            .collect::<CodeResult<_>>()?;
+        Ok(Self { rows })
+    }
+
+    /// Returns the index of the column in `database` whose field name matches
+    /// `label`, ignoring case.
+    pub fn field_index(database: &Array, label: &CellValue) -> Option<usize> {
+        if label.is_blank_or_empty_string() {
+            return None;
+        }
+        let label = label.to_string();
+        database
+            .rows()
+            .next()?
+            .iter()
+            .position(|field| field.to_string().eq_ignore_ascii_case(&label))
+    }
+
+    /// Evaluates the criteria on a record and returns whether it matches. If
+    /// there are no rows of criteria, then every record matches.
+    pub fn matches(&self, record: &[CellValue]) -> bool {
+        self.rows.is_empty()
+            || self.rows.iter().any(|row| {
+                row.iter()
+                    .all(|(field, criterion)| field.is_some_and(|i| criterion.matches(&record[i])))
+            })
+    }
+
+    /// Iterates over the records in `database`, excluding the first row and
+    /// those that do not match.
+    pub fn iter_matching_records<'a: 'b, 'b>(
+        &'b self,
+        database: &'a Array,
+    ) -> impl 'b + Iterator<Item = &'a [CellValue]> {
+        database
+            .rows()
+            .skip(1)
+            .filter(|record| self.matches(record))
+    }
 }
 
 fn strip_compare_fn_prefix(s: &str) -> Option<(CompareFn, &str)> {
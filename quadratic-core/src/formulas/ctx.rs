@@ -4,8 +4,9 @@ use smallvec::SmallVec;
 
 use super::*;
 use crate::{
-    grid::Grid, Array, CellValue, CodeResult, RunErrorMsg, SheetPos, SheetRect, Span, Spanned,
-    Value,
+    grid::{CodeCellLanguage, Grid, GridBounds, Sheet, SheetLines},
+    Array, ArraySize, CellValue, CodeResult, Pos, Rect, RunErrorMsg, SheetPos, SheetRect, Span,
+    Spanned, Value,
 };
 
 /// Formula execution context.
@@ -16,6 +17,18 @@ pub struct Ctx<'ctx> {
     pub sheet_pos: SheetPos,
     /// Cells that have been accessed in evaluating the formula.
     pub cells_accessed: HashSet<SheetRect>,
+    /// Whole columns and rows that have been accessed in evaluating the
+    /// formula.
+    pub lines_accessed: HashSet<SheetLines>,
+    /// Names defined using `LET()` or bound to lambda parameters, from
+    /// outermost to innermost.
+    pub bindings: Vec<(String, Binding)>,
+    /// Defined names that are currently being evaluated, from outermost to
+    /// innermost, used to detect names that refer to themselves.
+    pub evaluating_names: Vec<String>,
+    /// Whether a volatile function, such as `NOW()`, has been called in
+    /// evaluating the formula.
+    pub is_volatile: bool,
 }
 impl<'ctx> Ctx<'ctx> {
     /// Constructs a context for evaluating a formula at `pos` in `grid`.
@@ -24,22 +37,64 @@ impl<'ctx> Ctx<'ctx> {
             grid,
             sheet_pos,
             cells_accessed: HashSet::new(),
+            lines_accessed: HashSet::new(),
+            bindings: vec![],
+            evaluating_names: vec![],
+            is_volatile: false,
         }
     }
 
+    /// Returns the innermost binding for a name, if there is one. Names are
+    /// case-insensitive.
+    pub fn lookup(&self, name: &str) -> Option<&Binding> {
+        self.bindings
+            .iter()
+            .rev()
+            .find(|(bound_name, _)| bound_name.eq_ignore_ascii_case(name))
+            .map(|(_, binding)| binding)
+    }
+
+    /// Returns the formula that the defined name `name` refers to, parsed
+    /// relative to the position of the formula, or `None` if the name is not
+    /// defined on the sheet containing the formula or on the workbook.
+    pub fn defined_name_formula(&self, name: &str) -> Option<CodeResult<Formula>> {
+        let defined_name = self.grid.defined_name(name, self.sheet_pos.sheet_id)?;
+        Some(parse_formula(
+            &defined_name.refers_to,
+            self.sheet_pos.into(),
+        ))
+    }
+
+    /// Calls `f` with the formula that the defined name `name` refers to, or
+    /// returns `None` if there is no such name. Errors are reported at `span`,
+    /// since the formula for the name is not part of the source being
+    /// evaluated.
+    pub fn with_defined_name<T>(
+        &mut self,
+        name: &str,
+        span: Span,
+        f: impl FnOnce(&mut Self, &Formula) -> CodeResult<T>,
+    ) -> Option<CodeResult<T>> {
+        let formula = self.defined_name_formula(name)?;
+        let is_circular = self
+            .evaluating_names
+            .iter()
+            .any(|evaluating| evaluating.eq_ignore_ascii_case(name));
+        let result = if is_circular {
+            Err(RunErrorMsg::CircularReference.with_span(span))
+        } else {
+            self.evaluating_names.push(name.to_string());
+            let result = formula.and_then(|formula| f(self, &formula));
+            self.evaluating_names.pop();
+            result
+        };
+        Some(result.map_err(|e| e.msg.with_span(span)))
+    }
+
     /// Fetches the contents of the cell at `ref_pos` evaluated at `base_pos`,
     /// or returns an error in the case of a circular reference.
     pub fn get_cell(&mut self, ref_pos: &CellRef, span: Span) -> CodeResult<Spanned<CellValue>> {
-        let sheet = match &ref_pos.sheet {
-            Some(sheet_name) => self
-                .grid
-                .try_sheet_from_name(sheet_name.clone())
-                .ok_or(RunErrorMsg::BadCellReference.with_span(span))?,
-            None => self
-                .grid
-                .try_sheet(self.sheet_pos.sheet_id)
-                .ok_or(RunErrorMsg::BadCellReference.with_span(span))?,
-        };
+        let sheet = self.get_sheet(&ref_pos.sheet, span)?;
         let ref_pos = ref_pos.resolve_from(self.sheet_pos.into());
         let ref_pos_with_sheet = ref_pos.to_sheet_pos(sheet.id);
         if ref_pos_with_sheet == self.sheet_pos {
@@ -52,6 +107,241 @@ impl<'ctx> Ctx<'ctx> {
         Ok(Spanned { inner: value, span })
     }
 
+    /// Returns whether the cell at `ref_pos` contains a formula. A formula may
+    /// check whether its own cell contains a formula, so this is never a
+    /// circular reference.
+    pub fn is_formula_cell(&mut self, ref_pos: &CellRef, span: Span) -> CodeResult<bool> {
+        let sheet = self.get_sheet(&ref_pos.sheet, span)?;
+        let ref_pos = ref_pos.resolve_from(self.sheet_pos.into());
+        let ref_pos_with_sheet = ref_pos.to_sheet_pos(sheet.id);
+        if ref_pos_with_sheet != self.sheet_pos {
+            self.cells_accessed.insert(ref_pos_with_sheet.into());
+        }
+
+        Ok(matches!(
+            sheet.cell_value_ref(ref_pos),
+            Some(CellValue::Code(code)) if code.language == CodeCellLanguage::Formula,
+        ))
+    }
+
+    /// Returns the sheet with the name `sheet_name`, or the sheet containing
+    /// the formula if `sheet_name` is `None`.
+    fn get_sheet(&self, sheet_name: &Option<String>, span: Span) -> CodeResult<&'ctx Sheet> {
+        match sheet_name {
+            Some(sheet_name) => self.grid.try_sheet_from_name(sheet_name.clone()),
+            None => self.grid.try_sheet(self.sheet_pos.sheet_id),
+        }
+        .ok_or(RunErrorMsg::BadCellReference.with_span(span))
+    }
+
+    /// Returns the sheet name and the region of cells referenced by a range
+    /// reference, resolved relative to the position of the formula.
+    ///
+    /// Whole-column and whole-row ranges are limited to the data bounds of the
+    /// sheet, or to the row or column of the formula if the sheet is empty.
+    /// The whole columns or rows are recorded as accessed, so that the formula
+    /// is recomputed when data is added outside the current bounds.
+    ///
+    /// Spill range references are resolved to the output of the code cell at
+    /// the anchor. The anchor is recorded as accessed, so that the formula is
+    /// recomputed when the size of the output changes.
+    pub fn resolve_range_ref(
+        &mut self,
+        range_ref: &RangeRef,
+        span: Span,
+    ) -> CodeResult<(Option<String>, Rect)> {
+        let base = Pos::from(self.sheet_pos);
+        match range_ref {
+            RangeRef::RowRange { start, end, sheet } => {
+                let grid_sheet = self.get_sheet(sheet, span)?;
+                let (y1, y2) = (start.resolve_from(base.y), end.resolve_from(base.y));
+                let (x1, x2) = match grid_sheet.bounds(true) {
+                    GridBounds::NonEmpty(rect) => (rect.min.x, rect.max.x),
+                    GridBounds::Empty => (base.x, base.x),
+                };
+                self.lines_accessed.insert(SheetLines::Rows {
+                    sheet_id: grid_sheet.id,
+                    min: y1.min(y2),
+                    max: y1.max(y2),
+                });
+                let rect = Rect::new_span(Pos { x: x1, y: y1 }, Pos { x: x2, y: y2 });
+                Ok((sheet.clone(), rect))
+            }
+            RangeRef::ColRange { start, end, sheet } => {
+                let grid_sheet = self.get_sheet(sheet, span)?;
+                let (x1, x2) = (start.resolve_from(base.x), end.resolve_from(base.x));
+                let (y1, y2) = match grid_sheet.bounds(true) {
+                    GridBounds::NonEmpty(rect) => (rect.min.y, rect.max.y),
+                    GridBounds::Empty => (base.y, base.y),
+                };
+                self.lines_accessed.insert(SheetLines::Columns {
+                    sheet_id: grid_sheet.id,
+                    min: x1.min(x2),
+                    max: x1.max(x2),
+                });
+                let rect = Rect::new_span(Pos { x: x1, y: y1 }, Pos { x: x2, y: y2 });
+                Ok((sheet.clone(), rect))
+            }
+            RangeRef::CellRange { start, end } => {
+                let corner1 = start.resolve_from(base);
+                let corner2 = end.resolve_from(base);
+                Ok((start.sheet.clone(), Rect::new_span(corner1, corner2)))
+            }
+            RangeRef::Cell { pos } => {
+                let rect = Rect::single_pos(pos.resolve_from(base));
+                Ok((pos.sheet.clone(), rect))
+            }
+            RangeRef::Spill { pos } => {
+                let grid_sheet = self.get_sheet(&pos.sheet, span)?;
+                let anchor = pos.resolve_from(base);
+                let anchor_with_sheet = anchor.to_sheet_pos(grid_sheet.id);
+                if anchor_with_sheet == self.sheet_pos {
+                    return Err(RunErrorMsg::CircularReference.with_span(span));
+                }
+                self.cells_accessed.insert(anchor_with_sheet.into());
+
+                let Some(code_run) = grid_sheet.code_run(anchor) else {
+                    return Err(RunErrorMsg::BadCellReference.with_span(span));
+                };
+                if code_run.spill_error {
+                    return Err(RunErrorMsg::Spill.with_span(span));
+                }
+                if let Some(error) = code_run.get_error() {
+                    return Err(error.msg.with_span(span));
+                }
+                Ok((pos.sheet.clone(), code_run.output_rect(anchor, false)))
+            }
+            RangeRef::SheetRange { .. } => Err(RunErrorMsg::Expected {
+                expected: "reference on a single sheet".into(),
+                got: Some("three-dimensional reference".into()),
+            }
+            .with_span(span)),
+        }
+    }
+
+    /// Fetches the contents of the cells in `rect`, or returns an error in the
+    /// case of a circular reference.
+    pub fn get_cell_array(
+        &mut self,
+        sheet_name: Option<String>,
+        rect: Rect,
+        span: Span,
+    ) -> CodeResult<Spanned<Array>> {
+        let width = rect
+            .max
+            .x
+            .saturating_sub(rect.min.x)
+            .saturating_add(1)
+            .try_into()
+            .unwrap_or(u32::MAX);
+        let height = rect
+            .max
+            .y
+            .saturating_sub(rect.min.y)
+            .saturating_add(1)
+            .try_into()
+            .unwrap_or(u32::MAX);
+        if std::cmp::max(width, height) > crate::limits::CELL_RANGE_LIMIT {
+            return Err(RunErrorMsg::ArrayTooBig.with_span(span));
+        }
+
+        // todo: this should push a full SheetRect to cells_accessed instead of
+        // each SheetPos
+        let mut flat_array = SmallVec::new();
+        // Reuse the same `CellRef` object so that we don't have to
+        // clone `sheet_name`. We'll overwrite the position.
+        let mut cell_ref = CellRef::absolute(sheet_name, Pos::ORIGIN);
+        for y in rect.y_range() {
+            cell_ref.y = CellRefCoord::Absolute(y);
+            for x in rect.x_range() {
+                cell_ref.x = CellRefCoord::Absolute(x);
+                flat_array.push(self.get_cell(&cell_ref, span)?.inner);
+            }
+        }
+
+        let size = ArraySize::new_or_err(width, height)?;
+        Ok(Spanned {
+            span,
+            inner: Array::new_row_major(size, flat_array)?,
+        })
+    }
+
+    /// Fetches the contents of the cells referenced by `range` on every sheet
+    /// from `first_sheet` to `last_sheet` in sheet order. See
+    /// [`Self::get_areas_array()`] for how the cells are arranged.
+    pub fn get_sheet_range_array(
+        &mut self,
+        first_sheet: &str,
+        last_sheet: &str,
+        range: &RangeRef,
+        span: Span,
+    ) -> CodeResult<Spanned<Array>> {
+        let grid = self.grid;
+        let sheets = grid
+            .sheets_between(first_sheet, last_sheet)
+            .ok_or(RunErrorMsg::BadCellReference.with_span(span))?;
+
+        let mut areas = vec![];
+        for sheet in sheets {
+            let range = range.clone().with_sheet(Some(sheet.name.clone()));
+            areas.push(self.resolve_range_ref(&range, span)?);
+        }
+        self.get_areas_array(areas, span)
+    }
+
+    /// Fetches the contents of the cells in each of the regions in `areas`.
+    /// The cells of a single region keep their shape, but the cells of several
+    /// regions are placed one after another in a single column, which is what
+    /// aggregate functions such as `SUM()` need.
+    pub fn get_areas_array(
+        &mut self,
+        areas: Vec<(Option<String>, Rect)>,
+        span: Span,
+    ) -> CodeResult<Spanned<Array>> {
+        if let [(sheet_name, rect)] = areas.as_slice() {
+            return self.get_cell_array(sheet_name.clone(), *rect, span);
+        }
+
+        let mut values = SmallVec::new();
+        for (sheet_name, rect) in areas {
+            let array = self.get_cell_array(sheet_name, rect, span)?.inner;
+            values.extend(array.into_cell_values_vec());
+        }
+
+        let height = values.len().try_into().unwrap_or(u32::MAX);
+        let size = ArraySize::new_or_err(1, height)?;
+        Ok(Spanned {
+            span,
+            inner: Array::new_row_major(size, values)?,
+        })
+    }
+
+    /// Returns the regions of cells that are in both `areas1` and `areas2`, or
+    /// an error if there are none.
+    pub fn intersect_areas(
+        &self,
+        areas1: &[(Option<String>, Rect)],
+        areas2: &[(Option<String>, Rect)],
+        span: Span,
+    ) -> CodeResult<Vec<(Option<String>, Rect)>> {
+        let mut intersection = vec![];
+        for (sheet_name1, rect1) in areas1 {
+            let sheet_id = self.get_sheet(sheet_name1, span)?.id;
+            for (sheet_name2, rect2) in areas2 {
+                if self.get_sheet(sheet_name2, span)?.id != sheet_id {
+                    continue;
+                }
+                if let Some(rect) = rect1.intersection(*rect2) {
+                    intersection.push((sheet_name1.clone(), rect));
+                }
+            }
+        }
+        if intersection.is_empty() {
+            return Err(RunErrorMsg::EmptyIntersection.with_span(span));
+        }
+        Ok(intersection)
+    }
+
     /// Evaluates a function once for each corresponding set of values from
     /// `arrays`.
     ///
@@ -63,6 +353,9 @@ impl<'ctx> Ctx<'ctx> {
     /// 2-dimensionally: if one argument is a 1x3 array and the other argument
     /// is a 3x1 array, then both arguments are first expanded to 3x3 arrays. If
     /// arrays cannot be expanded like this, then an error is returned.
+    ///
+    /// If the result is an array, then errors for individual elements are
+    /// stored in the array as error values instead of being returned.
     pub fn zip_map<'a, I: Copy + IntoIterator<Item = &'a Spanned<Value>>>(
         &mut self,
         arrays: I,
@@ -92,7 +385,7 @@ impl<'ctx> Ctx<'ctx> {
                 args_buffer.push(array.get(x, y)?);
             }
 
-            values.push(f(self, &args_buffer)?);
+            values.push(f(self, &args_buffer).unwrap_or_else(|e| CellValue::Error(Box::new(e))));
         }
 
         let result = Array::new_row_major(size, values)?;
@@ -0,0 +1,991 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use rand::Rng;
+use smallvec::SmallVec;
+
+use super::*;
+use crate::ArraySize;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Array functions",
+    docs: "",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        formula_fn!(
+            /// Returns the rows of `array` for which the corresponding value in
+            /// `include` is `TRUE`.
+            ///
+            /// If `include` is a single row instead of a single column, then
+            /// columns are filtered instead of rows.
+            ///
+            /// If no rows match, then `if_empty` is returned. If `if_empty` is
+            /// omitted, then an error is returned instead.
+            #[examples(
+                "FILTER(A1:C10, B1:B10 > 5)",
+                "FILTER(A1:C10, A1:A10 = \"apple\", \"no apples\")"
+            )]
+            fn FILTER(
+                span: Span,
+                array: (Spanned<Array>),
+                include: (Spanned<Array>),
+                if_empty: (Option<Value>),
+            ) {
+                let axis = include
+                    .array_linear_axis()?
+                    .unwrap_or(if array.inner.height() == 1 {
+                        Axis::X
+                    } else {
+                        Axis::Y
+                    });
+                include.check_array_size_on(axis, array.inner.size()[axis].get())?;
+
+                let mut indices = vec![];
+                for (i, value) in include.inner.cell_values_slice().iter().enumerate() {
+                    let value = Spanned {
+                        span: include.span,
+                        inner: value,
+                    };
+                    if value.try_coerce::<bool>()?.inner {
+                        indices.push(i as u32);
+                    }
+                }
+
+                if indices.is_empty() {
+                    return match if_empty {
+                        Some(value) => Ok(value),
+                        None => Err(RunErrorMsg::EmptyArray.with_span(span)),
+                    };
+                }
+                select_along(&array.inner, axis, &indices)?
+            }
+        ),
+        formula_fn!(
+            /// Sorts the rows of an array by the values in one of its columns.
+            ///
+            /// `sort_index` is the column to sort by, starting from 1. If
+            /// omitted, the array is sorted by its first column.
+            ///
+            /// `sort_order` is `1` to sort in ascending order or `-1` to sort
+            /// in descending order. If omitted, it is assumed to be `1`.
+            ///
+            /// If `by_col` is `TRUE`, then columns are sorted by the values in
+            /// one of the rows instead.
+            #[examples("SORT(A1:C10)", "SORT(A1:C10, 2, -1)", "SORT(A1:E2, 1, 1, TRUE)")]
+            fn SORT(
+                array: Array,
+                sort_index: (Option<Spanned<i64>>),
+                sort_order: (Option<Spanned<i64>>),
+                by_col: (Option<bool>),
+            ) {
+                let axis = if by_col.unwrap_or(false) {
+                    Axis::X
+                } else {
+                    Axis::Y
+                };
+                let key_count = array.size()[axis.other_axis()].get();
+                let key_index = match sort_index {
+                    None => 0,
+                    Some(Spanned { span, inner: i }) => {
+                        if !(1..=key_count as i64).contains(&i) {
+                            return Err(RunErrorMsg::IndexOutOfBounds.with_span(span));
+                        }
+                        i as u32 - 1
+                    }
+                };
+                let keys = [(
+                    values_along(&array, axis, key_index),
+                    is_descending(sort_order)?,
+                )];
+                let indices = sorted_indices(array.size()[axis].get(), &keys)?;
+                select_along(&array, axis, &indices)?
+            }
+        ),
+        formula_fn!(
+            /// Sorts the rows of an array by the values in one or more other
+            /// columns.
+            ///
+            /// Each `by_array` must be a single column with the same number of
+            /// rows as `array`, and may be followed by a sort order, which is
+            /// `1` to sort in ascending order or `-1` to sort in descending
+            /// order. If a sort order is omitted, it is assumed to be `1`. Rows
+            /// that are equal according to the first `by_array` are sorted by
+            /// the next one, and so on.
+            ///
+            /// If each `by_array` is a single row, then columns are sorted
+            /// instead.
+            #[examples("SORTBY(A1:B10, C1:C10)", "SORTBY(A1:B10, C1:C10, -1, D1:D10, 1)")]
+            fn SORTBY(
+                array: Array,
+                by_array1: (Spanned<Array>),
+                sort_order1: (Option<Spanned<i64>>),
+                more_by_arrays_and_orders: (Iter<Spanned<Value>>),
+            ) {
+                let axis = by_array1.array_linear_axis()?.unwrap_or(Axis::Y);
+                let len = array.size()[axis].get();
+
+                let mut by_arrays = vec![(by_array1, sort_order1)];
+                while let Some(by_array) = more_by_arrays_and_orders.next() {
+                    let by_array = by_array?.map(Array::from);
+                    let sort_order = match more_by_arrays_and_orders.next() {
+                        Some(sort_order) => Some(sort_order?.try_coerce::<i64>()?),
+                        None => None,
+                    };
+                    by_arrays.push((by_array, sort_order));
+                }
+
+                let mut keys = vec![];
+                for (by_array, sort_order) in &by_arrays {
+                    by_array.check_array_size_on(axis.other_axis(), 1)?;
+                    by_array.check_array_size_on(axis, len)?;
+                    let values = by_array.inner.cell_values_slice().iter().collect_vec();
+                    keys.push((values, is_descending(*sort_order)?));
+                }
+                let indices = sorted_indices(len, &keys)?;
+                select_along(&array, axis, &indices)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the unique rows of an array, in the order in which they
+            /// first appear. Text is compared case-insensitively.
+            ///
+            /// If `by_col` is `TRUE`, then unique columns are returned instead.
+            ///
+            /// If `exactly_once` is `TRUE`, then only rows that appear exactly
+            /// once are returned.
+            #[examples("UNIQUE(A1:A10)", "UNIQUE(A1:C10, FALSE, TRUE)")]
+            fn UNIQUE(array: Array, by_col: (Option<bool>), exactly_once: (Option<bool>)) {
+                let axis = if by_col.unwrap_or(false) {
+                    Axis::X
+                } else {
+                    Axis::Y
+                };
+                let exactly_once = exactly_once.unwrap_or(false);
+
+                let keys = (0..array.size()[axis].get())
+                    .map(|i| {
+                        values_along(&array, axis.other_axis(), i)
+                            .into_iter()
+                            .map(unique_key)
+                            .collect_vec()
+                    })
+                    .collect_vec();
+                let counts = keys.iter().counts();
+                let mut seen = HashSet::new();
+                let indices = (0..keys.len() as u32)
+                    .filter(|&i| {
+                        let key = &keys[i as usize];
+                        if exactly_once {
+                            counts[key] == 1
+                        } else {
+                            seen.insert(key)
+                        }
+                    })
+                    .collect_vec();
+                select_along(&array, axis, &indices)?
+            }
+        ),
+        formula_fn!(
+            /// Returns an array of sequential numbers, filled row by row.
+            ///
+            /// `start` is the first number in the sequence and `step` is the
+            /// amount to increase each subsequent number by. Both default to
+            /// `1`.
+            #[examples("SEQUENCE(10)", "SEQUENCE(3, 4, 0, 5)")]
+            fn SEQUENCE(
+                span: Span,
+                rows: i64,
+                columns: (Option<i64>),
+                start: (Option<f64>),
+                step: (Option<f64>),
+            ) {
+                let size = array_size(span, columns.unwrap_or(1), rows)?;
+                let start = start.unwrap_or(1.0);
+                let step = step.unwrap_or(1.0);
+                let values = (0..size.len())
+                    .map(|i| CellValue::from(start + step * i as f64))
+                    .collect();
+                Array::new_row_major(size, values)?
+            }
+        ),
+        formula_fn!(
+            /// Returns an array of random numbers between `min` and `max`.
+            ///
+            /// `rows` and `columns` default to `1`, `min` defaults to `0`, and
+            /// `max` defaults to `1`. If `integer` is `TRUE`, then the numbers
+            /// are whole numbers between `min` and `max` inclusive, and `min`
+            /// and `max` must both be whole numbers.
+            #[volatile]
+            #[examples("RANDARRAY(5)", "RANDARRAY(3, 3, 1, 6, TRUE)")]
+            fn RANDARRAY(
+                span: Span,
+                rows: (Option<i64>),
+                columns: (Option<i64>),
+                min: (Option<f64>),
+                max: (Option<f64>),
+                integer: (Option<bool>),
+            ) {
+                let size = array_size(span, columns.unwrap_or(1), rows.unwrap_or(1))?;
+                let min = min.unwrap_or(0.0);
+                let max = max.unwrap_or(1.0);
+                let integer = integer.unwrap_or(false);
+                if min > max || (integer && (min.fract() != 0.0 || max.fract() != 0.0)) {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+
+                let mut rng = rand::thread_rng();
+                let values = (0..size.len())
+                    .map(|_| {
+                        if integer {
+                            CellValue::from(rng.gen_range(min as i64..=max as i64))
+                        } else {
+                            CellValue::from(min + rng.gen::<f64>() * (max - min))
+                        }
+                    })
+                    .collect();
+                Array::new_row_major(size, values)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the first `rows` rows and first `columns` columns of an
+            /// array.
+            ///
+            /// If `rows` or `columns` is negative, then rows or columns are
+            /// taken from the end of the array instead. If either is omitted,
+            /// then all rows or columns are included.
+            #[examples("TAKE(A1:C10, 3)", "TAKE(A1:C10, -2, 1)", "TAKE(A1:C10, , 2)")]
+            fn TAKE(array: Array, rows: (Option<i64>), columns: (Option<i64>)) {
+                let xs = take_indices(array.width(), columns);
+                let ys = take_indices(array.height(), rows);
+                select(&array, &xs, &ys)?
+            }
+        ),
+        formula_fn!(
+            /// Returns an array without its first `rows` rows and first
+            /// `columns` columns.
+            ///
+            /// If `rows` or `columns` is negative, then rows or columns are
+            /// removed from the end of the array instead. If either is omitted,
+            /// then no rows or columns are removed.
+            #[examples("DROP(A1:C10, 1)", "DROP(A1:C10, -2, 1)", "DROP(A1:C10, , 2)")]
+            fn DROP(array: Array, rows: (Option<i64>), columns: (Option<i64>)) {
+                let xs = drop_indices(array.width(), columns);
+                let ys = drop_indices(array.height(), rows);
+                select(&array, &xs, &ys)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the specified rows of an array, in the order given.
+            ///
+            /// Rows are numbered starting from 1. Negative numbers count
+            /// backwards from the last row.
+            #[examples("CHOOSEROWS(A1:C10, 1, 3)", "CHOOSEROWS(A1:C10, -1)")]
+            fn CHOOSEROWS(array: Array, row_nums: (Iter<Spanned<i64>>)) {
+                let indices = row_nums
+                    .map(|i| resolve_index(array.height(), i?))
+                    .collect::<CodeResult<Vec<_>>>()?;
+                select_along(&array, Axis::Y, &indices)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the specified columns of an array, in the order given.
+            ///
+            /// Columns are numbered starting from 1. Negative numbers count
+            /// backwards from the last column.
+            #[examples("CHOOSECOLS(A1:C10, 1, 3)", "CHOOSECOLS(A1:C10, -1)")]
+            fn CHOOSECOLS(array: Array, col_nums: (Iter<Spanned<i64>>)) {
+                let indices = col_nums
+                    .map(|i| resolve_index(array.width(), i?))
+                    .collect::<CodeResult<Vec<_>>>()?;
+                select_along(&array, Axis::X, &indices)?
+            }
+        ),
+        formula_fn!(
+            /// Stacks arrays vertically.
+            ///
+            /// Arrays narrower than the widest array are padded with `#N/A`
+            /// errors.
+            #[examples("VSTACK(A1:C3, E1:G5)", "VSTACK({1, 2}, {3, 4})")]
+            fn VSTACK(span: Span, arrays: (Iter<Spanned<Array>>)) {
+                let arrays = arrays
+                    .map_ok(|array| array.inner)
+                    .collect::<CodeResult<Vec<_>>>()?;
+                vstack(span, arrays)?
+            }
+        ),
+        formula_fn!(
+            /// Stacks arrays horizontally.
+            ///
+            /// Arrays shorter than the tallest array are padded with `#N/A`
+            /// errors.
+            #[examples("HSTACK(A1:A10, C1:D10)", "HSTACK({1; 2}, {3; 4})")]
+            fn HSTACK(span: Span, arrays: (Iter<Spanned<Array>>)) {
+                let arrays = arrays
+                    .map_ok(|array| array.inner.transpose())
+                    .collect::<CodeResult<Vec<_>>>()?;
+                vstack(span, arrays)?.transpose()
+            }
+        ),
+        formula_fn!(
+            /// Returns the values of an array as a single column.
+            ///
+            /// `ignore` is `0` to keep all values, `1` to ignore blanks, `2` to
+            /// ignore errors, or `3` to ignore blanks and errors. If omitted,
+            /// it is assumed to be `0`.
+            ///
+            /// If `scan_by_column` is `TRUE`, then values are read column by
+            /// column instead of row by row.
+            #[examples("TOCOL(A1:C10)", "TOCOL(A1:C10, 1, TRUE)")]
+            fn TOCOL(array: Array, ignore: (Option<Spanned<i64>>), scan_by_column: (Option<bool>)) {
+                let values = flatten(array, ignore, scan_by_column)?;
+                Array::new_row_major(ArraySize::new_or_err(1, values.len() as u32)?, values)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the values of an array as a single row.
+            ///
+            /// `ignore` is `0` to keep all values, `1` to ignore blanks, `2` to
+            /// ignore errors, or `3` to ignore blanks and errors. If omitted,
+            /// it is assumed to be `0`.
+            ///
+            /// If `scan_by_column` is `TRUE`, then values are read column by
+            /// column instead of row by row.
+            #[examples("TOROW(A1:C10)", "TOROW(A1:C10, 1, TRUE)")]
+            fn TOROW(array: Array, ignore: (Option<Spanned<i64>>), scan_by_column: (Option<bool>)) {
+                let values = flatten(array, ignore, scan_by_column)?;
+                Array::new_row_major(ArraySize::new_or_err(values.len() as u32, 1)?, values)?
+            }
+        ),
+        formula_fn!(
+            /// Wraps a single row or column into rows of `wrap_count` values
+            /// each.
+            ///
+            /// If there are not enough values to fill the last row, it is
+            /// padded with `pad_with`, which defaults to a `#N/A` error.
+            #[examples("WRAPROWS(A1:A12, 4)", "WRAPROWS(A1:A10, 4, 0)")]
+            fn WRAPROWS(
+                span: Span,
+                vector: (Spanned<Array>),
+                wrap_count: (Spanned<i64>),
+                pad_with: (Option<CellValue>),
+            ) {
+                vector.array_linear_axis()?;
+                wrap_rows(span, vector.inner, wrap_count, pad_with)?
+            }
+        ),
+        formula_fn!(
+            /// Wraps a single row or column into columns of `wrap_count`
+            /// values each.
+            ///
+            /// If there are not enough values to fill the last column, it is
+            /// padded with `pad_with`, which defaults to a `#N/A` error.
+            #[examples("WRAPCOLS(A1:A12, 4)", "WRAPCOLS(A1:A10, 4, 0)")]
+            fn WRAPCOLS(
+                span: Span,
+                vector: (Spanned<Array>),
+                wrap_count: (Spanned<i64>),
+                pad_with: (Option<CellValue>),
+            ) {
+                vector.array_linear_axis()?;
+                wrap_rows(span, vector.inner, wrap_count, pad_with)?.transpose()
+            }
+        ),
+        formula_fn!(
+            /// Expands an array to `rows` rows and `columns` columns.
+            ///
+            /// New cells are filled with `pad_with`, which defaults to a `#N/A`
+            /// error. If `rows` or `columns` is omitted, then the array is not
+            /// expanded in that direction. It is an error for `rows` or
+            /// `columns` to be smaller than the array.
+            #[examples("EXPAND(A1:B2, 4, 3)", "EXPAND(A1:B2, 4, , 0)")]
+            fn EXPAND(
+                span: Span,
+                array: Array,
+                rows: (Option<i64>),
+                columns: (Option<i64>),
+                pad_with: (Option<CellValue>),
+            ) {
+                let width = array.width();
+                let height = array.height();
+                let rows = rows.unwrap_or(height as i64);
+                let columns = columns.unwrap_or(width as i64);
+                if rows < height as i64 || columns < width as i64 {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+
+                let size = array_size(span, columns, rows)?;
+                let pad_with = pad_with.unwrap_or_else(|| not_available(span));
+                let values = size
+                    .iter()
+                    .map(|(x, y)| {
+                        if x < width && y < height {
+                            array.get(x, y).cloned()
+                        } else {
+                            Ok(pad_with.clone())
+                        }
+                    })
+                    .collect::<Result<_, _>>()?;
+                Array::new_row_major(size, values)?
+            }
+        ),
+        formula_fn!(
+            /// Swaps the rows and columns of an array.
+            #[examples("TRANSPOSE(A1:C10)", "TRANSPOSE({1, 2, 3})")]
+            fn TRANSPOSE(array: Array) {
+                array.transpose()
+            }
+        ),
+    ]
+}
+
+/// Returns the size of an array with the given dimensions, or an error if
+/// either dimension is zero or negative or if the array is too big.
+fn array_size(span: Span, width: i64, height: i64) -> CodeResult<ArraySize> {
+    if width < 0 || height < 0 {
+        return Err(RunErrorMsg::InvalidArgument.with_span(span));
+    }
+    if width == 0 || height == 0 {
+        return Err(RunErrorMsg::EmptyArray.with_span(span));
+    }
+    if width.saturating_mul(height) > crate::limits::CELL_RANGE_LIMIT as i64 {
+        return Err(RunErrorMsg::ArrayTooBig.with_span(span));
+    }
+    Ok(ArraySize::new_or_err(width as u32, height as u32)?)
+}
+
+/// Returns a `#N/A` error value, which is used to pad arrays.
+pub(super) fn not_available(span: Span) -> CellValue {
+    CellValue::Error(Box::new(RunErrorMsg::NoMatch.with_span(span)))
+}
+
+/// Constructs an array from the values at columns `xs` and rows `ys` of
+/// another array, in the order given. Returns an error if either list is
+/// empty.
+fn select(array: &Array, xs: &[u32], ys: &[u32]) -> CodeResult<Array> {
+    let size = ArraySize::new_or_err(xs.len() as u32, ys.len() as u32)?;
+    let values = size
+        .iter()
+        .map(|(x, y)| array.get(xs[x as usize], ys[y as usize]).cloned())
+        .collect::<Result<_, _>>()?;
+    Array::new_row_major(size, values)
+}
+
+/// Constructs an array from the rows (if `axis` is `Y`) or columns (if `axis`
+/// is `X`) of another array, in the order given.
+fn select_along(array: &Array, axis: Axis, indices: &[u32]) -> CodeResult<Array> {
+    let all = (0..array.size()[axis.other_axis()].get()).collect_vec();
+    match axis {
+        Axis::X => select(array, indices, &all),
+        Axis::Y => select(array, &all, indices),
+    }
+}
+
+/// Returns the values in column `index` (if `axis` is `Y`) or row `index` (if
+/// `axis` is `X`) of an array.
+fn values_along(array: &Array, axis: Axis, index: u32) -> Vec<&CellValue> {
+    let len = array.size()[axis].get();
+    let values = array.cell_values_slice();
+    let width = array.width() as usize;
+    (0..len as usize)
+        .map(|i| match axis {
+            Axis::X => &values[index as usize * width + i],
+            Axis::Y => &values[i * width + index as usize],
+        })
+        .collect()
+}
+
+/// Returns whether a sort order is descending. The sort order must be `1`
+/// (ascending) or `-1` (descending), and defaults to ascending.
+fn is_descending(sort_order: Option<Spanned<i64>>) -> CodeResult<bool> {
+    match sort_order {
+        None | Some(Spanned { inner: 1, .. }) => Ok(false),
+        Some(Spanned { inner: -1, .. }) => Ok(true),
+        Some(Spanned { span, .. }) => Err(RunErrorMsg::InvalidArgument.with_span(span)),
+    }
+}
+
+/// Returns the indices `0..len` sorted by a list of keys. Each key consists of
+/// one value per index and whether to sort in descending order. Later keys are
+/// only used to break ties in earlier ones, and the sort is stable.
+fn sorted_indices(len: u32, keys: &[(Vec<&CellValue>, bool)]) -> CodeResult<Vec<u32>> {
+    let mut error = None;
+    let mut indices = (0..len).collect_vec();
+    indices.sort_by(|&i, &j| {
+        keys.iter()
+            .map(|(values, descending)| {
+                let ordering = values[i as usize]
+                    .cmp(values[j as usize])
+                    .unwrap_or_else(|e| {
+                        error.get_or_insert(e);
+                        Ordering::Equal
+                    });
+                if *descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+    match error {
+        Some(e) => Err(e),
+        None => Ok(indices),
+    }
+}
+
+/// Returns a key used to compare values in `UNIQUE`, which ignores the case
+/// of text.
+fn unique_key(value: &CellValue) -> String {
+    let s = match value {
+        CellValue::Text(s) => s.to_ascii_uppercase(),
+        CellValue::Number(n) => n.normalized().to_string(),
+        other => other.to_string(),
+    };
+    format!("{}:{s}", value.type_name())
+}
+
+/// Returns the indices to keep along an axis of length `len` for `TAKE`.
+fn take_indices(len: u32, count: Option<i64>) -> Vec<u32> {
+    let len = len as i64;
+    let range = match count {
+        None => 0..len,
+        Some(n) if n >= 0 => 0..n.min(len),
+        Some(n) => (len + n).max(0)..len,
+    };
+    range.map(|i| i as u32).collect()
+}
+
+/// Returns the indices to keep along an axis of length `len` for `DROP`.
+fn drop_indices(len: u32, count: Option<i64>) -> Vec<u32> {
+    let len = len as i64;
+    let range = match count {
+        None => 0..len,
+        Some(n) if n >= 0 => n.min(len)..len,
+        Some(n) => 0..(len + n).max(0),
+    };
+    range.map(|i| i as u32).collect()
+}
+
+/// Converts a 1-based index, where negative numbers count backwards from the
+/// end, into a 0-based index.
+fn resolve_index(len: u32, index: Spanned<i64>) -> CodeResult<u32> {
+    let len = len as i64;
+    let i = if index.inner < 0 {
+        len + index.inner
+    } else {
+        index.inner - 1
+    };
+    if (0..len).contains(&i) {
+        Ok(i as u32)
+    } else {
+        Err(RunErrorMsg::IndexOutOfBounds.with_span(index.span))
+    }
+}
+
+/// Stacks arrays vertically, padding narrower arrays with `#N/A`.
+fn vstack(span: Span, arrays: Vec<Array>) -> CodeResult<Array> {
+    let width = arrays.iter().map(|array| array.width()).max().unwrap_or(0);
+    let height = arrays.iter().map(|array| array.height()).sum();
+    let size = ArraySize::new_or_err(width, height).map_err(|e| e.with_span(span))?;
+    let values = arrays
+        .iter()
+        .flat_map(|array| array.rows())
+        .flat_map(|row| {
+            let padding = std::iter::repeat_with(move || not_available(span));
+            row.iter()
+                .cloned()
+                .chain(padding.take(width as usize - row.len()))
+        })
+        .collect();
+    Array::new_row_major(size, values)
+}
+
+/// Returns the values of an array in a single list for `TOCOL` and `TOROW`.
+fn flatten(
+    array: Array,
+    ignore: Option<Spanned<i64>>,
+    scan_by_column: Option<bool>,
+) -> CodeResult<SmallVec<[CellValue; 1]>> {
+    let (ignore_blanks, ignore_errors) = match ignore {
+        None | Some(Spanned { inner: 0, .. }) => (false, false),
+        Some(Spanned { inner: 1, .. }) => (true, false),
+        Some(Spanned { inner: 2, .. }) => (false, true),
+        Some(Spanned { inner: 3, .. }) => (true, true),
+        Some(Spanned { span, .. }) => return Err(RunErrorMsg::InvalidArgument.with_span(span)),
+    };
+    let array = if scan_by_column.unwrap_or(false) {
+        array.transpose()
+    } else {
+        array
+    };
+    Ok(array
+        .into_cell_values_vec()
+        .into_iter()
+        .filter(|value| {
+            let is_ignored_blank = ignore_blanks && value.is_blank();
+            let is_ignored_error = ignore_errors && matches!(value, CellValue::Error(_));
+            !is_ignored_blank && !is_ignored_error
+        })
+        .collect())
+}
+
+/// Wraps the values of an array into rows of `wrap_count` values each, padding
+/// the last row with `pad_with` (or `#N/A` if it is `None`).
+fn wrap_rows(
+    span: Span,
+    array: Array,
+    wrap_count: Spanned<i64>,
+    pad_with: Option<CellValue>,
+) -> CodeResult<Array> {
+    if wrap_count.inner < 1 {
+        return Err(RunErrorMsg::InvalidArgument.with_span(wrap_count.span));
+    }
+    let len = array.size().len() as i64;
+    let height = (len + wrap_count.inner - 1) / wrap_count.inner;
+    let size = array_size(span, wrap_count.inner, height)?;
+    let pad_with = pad_with.unwrap_or_else(|| not_available(span));
+    let mut values = array.into_cell_values_vec();
+    values.resize(size.len(), pad_with);
+    Array::new_row_major(size, values)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formulas::tests::*;
+
+    #[test]
+    fn test_filter() {
+        let g = Grid::new();
+        let array = "{1, \"a\"; 2, \"b\"; 3, \"c\"; 4, \"d\"}";
+        assert_eq!(
+            "{2, b; 4, d}",
+            eval_to_string(&g, &format!("FILTER({array}, {{0; 1; 0; 1}})")),
+        );
+        assert_eq!(
+            "{3, c; 4, d}",
+            eval_to_string(&g, &format!("FILTER({array}, {{1; 2; 3; 4}} > 2)")),
+        );
+        assert_eq!(
+            "{a; b; c; d}",
+            eval_to_string(&g, &format!("FILTER({array}, {{0, 1}})")),
+        );
+        assert_eq!(
+            "none",
+            eval_to_string(&g, &format!("FILTER({array}, {{0; 0; 0; 0}}, \"none\")")),
+        );
+        expect_err(
+            &RunErrorMsg::EmptyArray,
+            &g,
+            &format!("FILTER({array}, {{0; 0; 0; 0}})"),
+        );
+        assert_eq!(
+            RunErrorMsg::ExactArrayAxisMismatch {
+                axis: Axis::Y,
+                expected: 4,
+                got: 3,
+            },
+            eval_to_err(&g, &format!("FILTER({array}, {{1; 0; 1}})")).msg,
+        );
+    }
+
+    #[test]
+    fn test_sort() {
+        let g = Grid::new();
+        let array = "{3, \"c\"; 1, \"a\"; 2, \"B\"}";
+        assert_eq!(
+            "{1, a; 2, B; 3, c}",
+            eval_to_string(&g, &format!("SORT({array})")),
+        );
+        assert_eq!(
+            "{3, c; 2, B; 1, a}",
+            eval_to_string(&g, &format!("SORT({array}, 2, -1)")),
+        );
+        assert_eq!(
+            "{1, 2, 3; a, B, c}",
+            eval_to_string(&g, "SORT({3, 1, 2; \"c\", \"a\", \"B\"}, 1, 1, TRUE)"),
+        );
+        // Numbers sort before text, which sorts before logical values.
+        assert_eq!(
+            "{2; 10; x; FALSE; TRUE}",
+            eval_to_string(&g, "SORT({TRUE; \"x\"; 10; FALSE; 2})"),
+        );
+        expect_err(
+            &RunErrorMsg::IndexOutOfBounds,
+            &g,
+            &format!("SORT({array}, 3)"),
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            &format!("SORT({array}, 1, 0)"),
+        );
+    }
+
+    #[test]
+    fn test_sortby() {
+        let g = Grid::new();
+        let array = "{\"a\"; \"b\"; \"c\"; \"d\"}";
+        assert_eq!(
+            "{c; a; d; b}",
+            eval_to_string(&g, &format!("SORTBY({array}, {{2; 4; 1; 3}})")),
+        );
+        assert_eq!(
+            "{b; d; a; c}",
+            eval_to_string(&g, &format!("SORTBY({array}, {{2; 4; 1; 3}}, -1)")),
+        );
+        assert_eq!(
+            "{d; b; c; a}",
+            eval_to_string(
+                &g,
+                &format!("SORTBY({array}, {{2; 1; 2; 1}}, 1, {{1; 2; 3; 4}}, -1)"),
+            ),
+        );
+        assert_eq!(
+            "{2, 3, 1}",
+            eval_to_string(&g, "SORTBY({1, 2, 3}, {\"z\", \"x\", \"y\"})"),
+        );
+        assert_eq!(
+            RunErrorMsg::ExactArrayAxisMismatch {
+                axis: Axis::Y,
+                expected: 4,
+                got: 3,
+            },
+            eval_to_err(&g, &format!("SORTBY({array}, {{1; 2; 3}})")).msg,
+        );
+    }
+
+    #[test]
+    fn test_unique() {
+        let g = Grid::new();
+        assert_eq!(
+            "{1; 2; a; 3}",
+            eval_to_string(&g, "UNIQUE({1; 2; 1; \"a\"; \"A\"; 3; 2})"),
+        );
+        assert_eq!(
+            "{a; 3}",
+            eval_to_string(&g, "UNIQUE({1; 2; 1; \"a\"; 3; 2}, FALSE, TRUE)"),
+        );
+        assert_eq!(
+            "{1, 2; 3, 4}",
+            eval_to_string(&g, "UNIQUE({1, 2; 3, 4; 1, 2})"),
+        );
+        assert_eq!(
+            "{1, 2; 1, 4}",
+            eval_to_string(&g, "UNIQUE({1, 2, 1; 1, 4, 1}, TRUE)"),
+        );
+        expect_err(&RunErrorMsg::EmptyArray, &g, "UNIQUE({1; 1}, FALSE, TRUE)");
+    }
+
+    #[test]
+    fn test_sequence() {
+        let g = Grid::new();
+        assert_eq!("{1; 2; 3}", eval_to_string(&g, "SEQUENCE(3)"));
+        assert_eq!(
+            "{0, 5, 10; 15, 20, 25}",
+            eval_to_string(&g, "SEQUENCE(2, 3, 0, 5)"),
+        );
+        assert_eq!("{10, 8, 6}", eval_to_string(&g, "SEQUENCE(1, 3, 10, -2)"));
+        expect_err(&RunErrorMsg::EmptyArray, &g, "SEQUENCE(0)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "SEQUENCE(-1)");
+        expect_err(&RunErrorMsg::ArrayTooBig, &g, "SEQUENCE(100000, 100000)");
+    }
+
+    #[test]
+    fn test_randarray() {
+        let g = Grid::new();
+        let Value::Array(array) = eval(&g, "RANDARRAY(4, 5, 1, 6, TRUE)") else {
+            panic!("expected array");
+        };
+        assert_eq!(5, array.width());
+        assert_eq!(4, array.height());
+        for value in array.cell_values_slice() {
+            let n = f64::try_from(value).unwrap();
+            assert!((1.0..=6.0).contains(&n));
+            assert_eq!(0.0, n.fract());
+        }
+
+        let Value::Array(array) = eval(&g, "RANDARRAY(10, 1, -2, -1)") else {
+            panic!("expected array");
+        };
+        for value in array.cell_values_slice() {
+            let n = f64::try_from(value).unwrap();
+            assert!((-2.0..=-1.0).contains(&n));
+        }
+
+        assert_eq!("{0}", eval_to_string(&g, "RANDARRAY(1, 1, 0, 0)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "RANDARRAY(1, 1, 2, 1)");
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "RANDARRAY(1, 1, 0.5, 2, TRUE)",
+        );
+    }
+
+    #[test]
+    fn test_take_drop() {
+        let g = Grid::new();
+        let array = "{1, 2, 3; 4, 5, 6; 7, 8, 9}";
+        assert_eq!(
+            "{1, 2, 3; 4, 5, 6}",
+            eval_to_string(&g, &format!("TAKE({array}, 2)")),
+        );
+        assert_eq!(
+            "{8, 9}",
+            eval_to_string(&g, &format!("TAKE({array}, -1, -2)")),
+        );
+        assert_eq!(
+            "{1; 4; 7}",
+            eval_to_string(&g, &format!("TAKE({array}, , 1)")),
+        );
+        assert_eq!(
+            "{1, 2, 3; 4, 5, 6; 7, 8, 9}",
+            eval_to_string(&g, &format!("TAKE({array}, 10)")),
+        );
+        expect_err(&RunErrorMsg::EmptyArray, &g, &format!("TAKE({array}, 0)"));
+
+        assert_eq!(
+            "{7, 8, 9}",
+            eval_to_string(&g, &format!("DROP({array}, 2)")),
+        );
+        assert_eq!(
+            "{1; 4}",
+            eval_to_string(&g, &format!("DROP({array}, -1, -2)")),
+        );
+        assert_eq!(
+            "{3; 6; 9}",
+            eval_to_string(&g, &format!("DROP({array}, , 2)")),
+        );
+        expect_err(&RunErrorMsg::EmptyArray, &g, &format!("DROP({array}, 3)"));
+    }
+
+    #[test]
+    fn test_chooserows_choosecols() {
+        let g = Grid::new();
+        let array = "{1, 2, 3; 4, 5, 6; 7, 8, 9}";
+        assert_eq!(
+            "{7, 8, 9; 1, 2, 3; 7, 8, 9}",
+            eval_to_string(&g, &format!("CHOOSEROWS({array}, 3, 1, -1)")),
+        );
+        assert_eq!(
+            "{2, 1; 5, 4; 8, 7}",
+            eval_to_string(&g, &format!("CHOOSECOLS({array}, {{2, 1}})")),
+        );
+        expect_err(
+            &RunErrorMsg::IndexOutOfBounds,
+            &g,
+            &format!("CHOOSEROWS({array}, 0)"),
+        );
+        expect_err(
+            &RunErrorMsg::IndexOutOfBounds,
+            &g,
+            &format!("CHOOSECOLS({array}, -4)"),
+        );
+        expect_err(
+            &RunErrorMsg::MissingRequiredArgument {
+                func_name: "CHOOSEROWS".into(),
+                arg_name: "row_nums".into(),
+            },
+            &g,
+            &format!("CHOOSEROWS({array})"),
+        );
+    }
+
+    #[test]
+    fn test_vstack_hstack() {
+        let g = Grid::new();
+        assert_eq!(
+            "{1, 2; 3, 4; 5, 6}",
+            eval_to_string(&g, "VSTACK({1, 2}, {3, 4; 5, 6})"),
+        );
+        assert_eq!(
+            "{1, 2, 3; 4, #N/A, #N/A}",
+            eval_to_string(&g, "VSTACK({1, 2, 3}, 4)"),
+        );
+        assert_eq!(
+            "{1, 3, 5; 2, 4, 6}",
+            eval_to_string(&g, "HSTACK({1; 2}, {3, 5; 4, 6})"),
+        );
+        assert_eq!("{1, 3; 2, #N/A}", eval_to_string(&g, "HSTACK({1; 2}, 3)"));
+    }
+
+    #[test]
+    fn test_tocol_torow() {
+        let g = Grid::new();
+        assert_eq!("{1; 2; 3; 4}", eval_to_string(&g, "TOCOL({1, 2; 3, 4})"));
+        assert_eq!(
+            "{1; 3; 2; 4}",
+            eval_to_string(&g, "TOCOL({1, 2; 3, 4}, 0, TRUE)"),
+        );
+        assert_eq!("{1, 2, 3, 4}", eval_to_string(&g, "TOROW({1, 2; 3, 4})"));
+        // `A1` is blank, and `EXPAND()` pads with errors.
+        let array = "EXPAND(HSTACK(1, A1, 3), 2)";
+        assert_eq!(
+            "{1, 3, #N/A, #N/A, #N/A}",
+            eval_to_string(&g, &format!("TOROW({array}, 1)")),
+        );
+        assert_eq!(
+            "{1, , 3}",
+            eval_to_string(&g, &format!("TOROW({array}, 2)"))
+        );
+        assert_eq!("{1, 3}", eval_to_string(&g, &format!("TOROW({array}, 3)")));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "TOROW({1, 2; 3, 4}, 4)");
+    }
+
+    #[test]
+    fn test_wraprows_wrapcols() {
+        let g = Grid::new();
+        assert_eq!(
+            "{1, 2; 3, 4; 5, 6}",
+            eval_to_string(&g, "WRAPROWS({1, 2, 3, 4, 5, 6}, 2)"),
+        );
+        assert_eq!(
+            "{1, 2, 3; 4, 5, 0}",
+            eval_to_string(&g, "WRAPROWS({1; 2; 3; 4; 5}, 3, 0)"),
+        );
+        assert_eq!(
+            "{1, 3, 5; 2, 4, #N/A}",
+            eval_to_string(&g, "WRAPCOLS({1, 2, 3, 4, 5}, 2)"),
+        );
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "WRAPROWS({1, 2, 3}, 0)");
+        expect_err(
+            &RunErrorMsg::NonLinearArray,
+            &g,
+            "WRAPROWS({1, 2; 3, 4}, 2)",
+        );
+    }
+
+    #[test]
+    fn test_expand() {
+        let g = Grid::new();
+        assert_eq!(
+            "{1, 2, 0; 3, 4, 0; 0, 0, 0}",
+            eval_to_string(&g, "EXPAND({1, 2; 3, 4}, 3, 3, 0)"),
+        );
+        assert_eq!(
+            "{1, 2; 3, 4; #N/A, #N/A}",
+            eval_to_string(&g, "EXPAND({1, 2; 3, 4}, 3)"),
+        );
+        assert_eq!(
+            "{1, 2, x; 3, 4, x}",
+            eval_to_string(&g, "EXPAND({1, 2; 3, 4}, , 3, \"x\")"),
+        );
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "EXPAND({1, 2; 3, 4}, 1)");
+    }
+
+    #[test]
+    fn test_transpose() {
+        let g = Grid::new();
+        assert_eq!(
+            "{1, 4; 2, 5; 3, 6}",
+            eval_to_string(&g, "TRANSPOSE({1, 2, 3; 4, 5, 6})"),
+        );
+        assert_eq!("{1, 2, 3}", eval_to_string(&g, "TRANSPOSE({1; 2; 3})"));
+        assert_eq!("{5}", eval_to_string(&g, "TRANSPOSE(5)"));
+    }
+}
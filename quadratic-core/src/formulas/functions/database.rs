@@ -0,0 +1,429 @@
+use bigdecimal::BigDecimal;
+
+use super::statistics::variance;
+use super::*;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Database functions",
+    docs: concat!(
+        "A database is a range whose first row contains field names, and \
+         each following row is a record. `field` is either the name of a \
+         field or the number of a column in the database, starting from 1.\
+         \n\n\
+         `criteria` is a range whose first row also contains field names, \
+         and each following row contains criteria for those fields. A record \
+         is included if it meets every criterion in any row of `criteria`. \
+         Blank criteria are ignored.",
+        see_docs_for_more_about_criteria!(),
+    ),
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        formula_fn!(
+            /// Adds the values of `field` in each record that meets the
+            /// criteria.
+            #[examples("DSUM(A1:E10, \"Profit\", G1:H2)")]
+            fn DSUM(
+                database: (Spanned<Array>),
+                field: (Spanned<CellValue>),
+                criteria: (Spanned<Array>),
+            ) {
+                let values = matching_values(&database, &field, &criteria)?;
+                let sum = coerce_numbers::<BigDecimal>(values)?.into_iter().sum();
+                util::trim_zeros(sum)
+            }
+        ),
+        formula_fn!(
+            /// Multiplies the values of `field` in each record that meets the
+            /// criteria. Returns `0` if no records meet the criteria.
+            #[examples("DPRODUCT(A1:E10, \"Yield\", G1:H2)")]
+            fn DPRODUCT(
+                database: (Spanned<Array>),
+                field: (Spanned<CellValue>),
+                criteria: (Spanned<Array>),
+            ) {
+                let values = matching_values(&database, &field, &criteria)?;
+                let numbers = coerce_numbers::<BigDecimal>(values)?;
+                if numbers.is_empty() {
+                    BigDecimal::from(0)
+                } else {
+                    util::trim_zeros(numbers.into_iter().product())
+                }
+            }
+        ),
+        formula_fn!(
+            /// Counts the numbers in `field` in each record that meets the
+            /// criteria.
+            ///
+            /// If `field` is blank, then counts the records that meet the
+            /// criteria.
+            #[examples("DCOUNT(A1:E10, \"Age\", G1:H2)", "DCOUNT(A1:E10, , G1:H2)")]
+            fn DCOUNT(
+                database: (Spanned<Array>),
+                field: (Spanned<CellValue>),
+                criteria: (Spanned<Array>),
+            ) {
+                match field.inner.is_blank() {
+                    false => matching_values(&database, &field, &criteria)?
+                        .into_iter()
+                        .filter(|value| matches!(value, CellValue::Number(_)))
+                        .count(),
+                    true => matching_records(&database, &criteria)?.len(),
+                } as f64
+            }
+        ),
+        formula_fn!(
+            /// Counts the non-blank values in `field` in each record that
+            /// meets the criteria.
+            ///
+            /// If `field` is blank, then counts the records that meet the
+            /// criteria.
+            #[examples("DCOUNTA(A1:E10, \"Tree\", G1:H2)", "DCOUNTA(A1:E10, , G1:H2)")]
+            fn DCOUNTA(
+                database: (Spanned<Array>),
+                field: (Spanned<CellValue>),
+                criteria: (Spanned<Array>),
+            ) {
+                match field.inner.is_blank() {
+                    false => matching_values(&database, &field, &criteria)?
+                        .into_iter()
+                        .filter(|value| !value.is_blank())
+                        .count(),
+                    true => matching_records(&database, &criteria)?.len(),
+                } as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the arithmetic mean of the values of `field` in each
+            /// record that meets the criteria.
+            #[examples("DAVERAGE(A1:E10, \"Yield\", G1:H2)")]
+            fn DAVERAGE(
+                span: Span,
+                database: (Spanned<Array>),
+                field: (Spanned<CellValue>),
+                criteria: (Spanned<Array>),
+            ) {
+                let values = matching_values(&database, &field, &criteria)?;
+                util::average(span, coerce_numbers::<f64>(values)?.into_iter().map(Ok))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the largest value of `field` in any record that meets
+            /// the criteria. Returns `0` if no records meet the criteria.
+            #[examples("DMAX(A1:E10, \"Profit\", G1:H2)")]
+            fn DMAX(
+                database: (Spanned<Array>),
+                field: (Spanned<CellValue>),
+                criteria: (Spanned<Array>),
+            ) {
+                let values = matching_values(&database, &field, &criteria)?;
+                coerce_numbers::<f64>(values)?
+                    .into_iter()
+                    .reduce(f64::max)
+                    .unwrap_or(0.0)
+            }
+        ),
+        formula_fn!(
+            /// Returns the smallest value of `field` in any record that meets
+            /// the criteria. Returns `0` if no records meet the criteria.
+            #[examples("DMIN(A1:E10, \"Profit\", G1:H2)")]
+            fn DMIN(
+                database: (Spanned<Array>),
+                field: (Spanned<CellValue>),
+                criteria: (Spanned<Array>),
+            ) {
+                let values = matching_values(&database, &field, &criteria)?;
+                coerce_numbers::<f64>(values)?
+                    .into_iter()
+                    .reduce(f64::min)
+                    .unwrap_or(0.0)
+            }
+        ),
+        formula_fn!(
+            /// Returns the value of `field` in the only record that meets the
+            /// criteria.
+            ///
+            /// Returns an error if no records or more than one record meet the
+            /// criteria.
+            #[examples("DGET(A1:E10, \"Yield\", G1:H2)")]
+            fn DGET(
+                database: (Spanned<Array>),
+                field: (Spanned<CellValue>),
+                criteria: (Spanned<Array>),
+            ) {
+                match matching_values(&database, &field, &criteria)?.as_slice() {
+                    [value] => (*value).clone(),
+                    [] => return Err(RunErrorMsg::NoMatch.with_span(criteria.span)),
+                    _ => return Err(RunErrorMsg::InvalidArgument.with_span(criteria.span)),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the standard deviation of the values of `field` in each
+            /// record that meets the criteria, treating them as a sample of a
+            /// larger population.
+            #[examples("DSTDEV(A1:E10, \"Yield\", G1:H2)")]
+            fn DSTDEV(
+                span: Span,
+                database: (Spanned<Array>),
+                field: (Spanned<CellValue>),
+                criteria: (Spanned<Array>),
+            ) {
+                let values = matching_values(&database, &field, &criteria)?;
+                variance(span, &coerce_numbers::<f64>(values)?, 1)?.sqrt()
+            }
+        ),
+        formula_fn!(
+            /// Returns the standard deviation of the values of `field` in each
+            /// record that meets the criteria, treating them as the entire
+            /// population.
+            #[examples("DSTDEVP(A1:E10, \"Yield\", G1:H2)")]
+            fn DSTDEVP(
+                span: Span,
+                database: (Spanned<Array>),
+                field: (Spanned<CellValue>),
+                criteria: (Spanned<Array>),
+            ) {
+                let values = matching_values(&database, &field, &criteria)?;
+                variance(span, &coerce_numbers::<f64>(values)?, 0)?.sqrt()
+            }
+        ),
+        formula_fn!(
+            /// Returns the variance of the values of `field` in each record
+            /// that meets the criteria, treating them as a sample of a larger
+            /// population.
+            #[examples("DVAR(A1:E10, \"Yield\", G1:H2)")]
+            fn DVAR(
+                span: Span,
+                database: (Spanned<Array>),
+                field: (Spanned<CellValue>),
+                criteria: (Spanned<Array>),
+            ) {
+                let values = matching_values(&database, &field, &criteria)?;
+                variance(span, &coerce_numbers::<f64>(values)?, 1)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the variance of the values of `field` in each record
+            /// that meets the criteria, treating them as the entire
+            /// population.
+            #[examples("DVARP(A1:E10, \"Yield\", G1:H2)")]
+            fn DVARP(
+                span: Span,
+                database: (Spanned<Array>),
+                field: (Spanned<CellValue>),
+                criteria: (Spanned<Array>),
+            ) {
+                let values = matching_values(&database, &field, &criteria)?;
+                variance(span, &coerce_numbers::<f64>(values)?, 0)?
+            }
+        ),
+    ]
+}
+
+/// Returns the records in `database` that meet `criteria`.
+fn matching_records<'a>(
+    database: &'a Spanned<Array>,
+    criteria: &Spanned<Array>,
+) -> CodeResult<Vec<&'a [CellValue]>> {
+    let criteria = DatabaseCriteria::new(&database.inner, criteria)?;
+    Ok(criteria.iter_matching_records(&database.inner).collect())
+}
+
+/// Returns the value of `field` in each record in `database` that meets
+/// `criteria`.
+fn matching_values<'a>(
+    database: &'a Spanned<Array>,
+    field: &Spanned<CellValue>,
+    criteria: &Spanned<Array>,
+) -> CodeResult<Vec<&'a CellValue>> {
+    let column = field_index(database, field)?;
+    let records = matching_records(database, criteria)?;
+    Ok(records.into_iter().map(|record| &record[column]).collect())
+}
+
+/// Returns the index of the column in `database` given by `field`, which is
+/// either a field name or a column number starting from 1.
+fn field_index(database: &Spanned<Array>, field: &Spanned<CellValue>) -> CodeResult<usize> {
+    match &field.inner {
+        CellValue::Error(e) => Err((**e).clone()),
+        CellValue::Number(_) => {
+            let column = field.as_ref().try_coerce::<f64>()?.inner.trunc();
+            let width = database.inner.width() as f64;
+            util::ensure(field.span, (1.0..=width).contains(&column))?;
+            Ok(column as usize - 1)
+        }
+        label => DatabaseCriteria::field_index(&database.inner, label)
+            .ok_or_else(|| RunErrorMsg::InvalidArgument.with_span(field.span)),
+    }
+}
+
+/// Coerces each value to a number, ignoring blank values and values that
+/// cannot be coerced. Returns an error if any value is an error.
+fn coerce_numbers<'a, T>(values: Vec<&'a CellValue>) -> CodeResult<Vec<T>>
+where
+    &'a CellValue: TryInto<T>,
+{
+    values
+        .into_iter()
+        .filter_map(|value| match value {
+            CellValue::Error(e) => Some(Err((**e).clone())),
+            _ => value.coerce_nonblank::<T>().map(Ok),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formulas::tests::*;
+    use crate::util::assert_f64_approx_eq;
+
+    const DATABASE: &str = "{
+        \"Tree\", \"Height\", \"Age\", \"Yield\", \"Profit\";
+        \"Apple\", 18, 20, 14, 105;
+        \"Pear\", 12, 12, 10, 96;
+        \"Cherry\", 13, 14, 9, 105;
+        \"Apple\", 14, 15, 10, 75;
+        \"Pear\", 9, 8, 8, 76.8;
+        \"Apple\", 8, 9, 6, 45
+    }";
+
+    /// Apple trees between 10 and 16 feet tall, or any pear tree.
+    const CRITERIA: &str = "{
+        \"Tree\", \"Height\", \"Height\";
+        \"=Apple\", \">10\", \"<16\";
+        \"=Pear\", \"\", \"\"
+    }";
+
+    /// Apple or pear trees.
+    const APPLE_OR_PEAR: &str = "{\"Tree\"; \"Apple\"; \"Pear\"}";
+
+    fn eval_db(g: &Grid, func: &str, field: &str, criteria: &str) -> String {
+        eval_to_string(g, &format!("{func}({DATABASE}, {field}, {criteria})"))
+    }
+
+    #[test]
+    fn test_dsum_dproduct() {
+        let g = Grid::new();
+        let apple = "{\"Tree\"; \"Apple\"}";
+        assert_eq!("225", eval_db(&g, "DSUM", "\"Profit\"", apple));
+        assert_eq!("247.8", eval_db(&g, "DSUM", "\"profit\"", CRITERIA));
+        assert_eq!("247.8", eval_db(&g, "DSUM", "5", CRITERIA));
+        assert_eq!("800", eval_db(&g, "DPRODUCT", "\"Yield\"", CRITERIA));
+        assert_eq!(
+            "0",
+            eval_db(&g, "DSUM", "\"Yield\"", "{\"Tree\"; \"Plum\"}")
+        );
+        assert_eq!(
+            "0",
+            eval_db(&g, "DPRODUCT", "\"Yield\"", "{\"Tree\"; \"Plum\"}")
+        );
+
+        // Every record matches a criteria range with no criteria.
+        assert_eq!("74", eval_db(&g, "DSUM", "\"Height\"", "{\"Tree\"; \"\"}"));
+        assert_eq!("74", eval_db(&g, "DSUM", "\"Height\"", "{\"Tree\"}"));
+
+        // Text values are ignored.
+        assert_eq!("0", eval_db(&g, "DSUM", "\"Tree\"", apple));
+    }
+
+    #[test]
+    fn test_dcount() {
+        let g = Grid::new();
+        assert_eq!("3", eval_db(&g, "DCOUNT", "\"Age\"", CRITERIA));
+        assert_eq!("3", eval_db(&g, "DCOUNT", "", CRITERIA));
+        assert_eq!("0", eval_db(&g, "DCOUNT", "\"Tree\"", CRITERIA));
+        assert_eq!("3", eval_db(&g, "DCOUNTA", "\"Tree\"", CRITERIA));
+        assert_eq!("5", eval_db(&g, "DCOUNTA", "", APPLE_OR_PEAR));
+        assert_eq!(
+            "1",
+            eval_db(
+                &g,
+                "DCOUNT",
+                "\"Age\"",
+                "{\"Tree\", \"Age\"; \"Pear\", \">10\"}"
+            ),
+        );
+    }
+
+    #[test]
+    fn test_daverage_dmax_dmin() {
+        let g = Grid::new();
+        let tall_apple = "{\"Tree\", \"Height\"; \"Apple\", \">10\"}";
+        assert_eq!("12", eval_db(&g, "DAVERAGE", "\"Yield\"", tall_apple));
+        // Every record matches its own row when the database is used as the
+        // criteria.
+        assert_eq!("13", eval_db(&g, "DAVERAGE", "3", DATABASE));
+        expect_err(
+            &RunErrorMsg::DivideByZero,
+            &g,
+            &format!("DAVERAGE({DATABASE}, \"Yield\", {{\"Tree\"; \"Plum\"}})"),
+        );
+
+        assert_eq!("105", eval_db(&g, "DMAX", "\"Profit\"", APPLE_OR_PEAR));
+        assert_eq!("75", eval_db(&g, "DMIN", "\"Profit\"", tall_apple));
+        assert_eq!(
+            "0",
+            eval_db(&g, "DMAX", "\"Profit\"", "{\"Tree\"; \"Plum\"}")
+        );
+    }
+
+    #[test]
+    fn test_dget() {
+        let g = Grid::new();
+        assert_eq!(
+            "9",
+            eval_db(&g, "DGET", "\"Yield\"", "{\"Tree\"; \"Cherry\"}")
+        );
+        assert_eq!("Cherry", eval_db(&g, "DGET", "1", "{\"Age\"; 14}"),);
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            &format!("DGET({DATABASE}, \"Yield\", {APPLE_OR_PEAR})"),
+        );
+        expect_err(
+            &RunErrorMsg::NoMatch,
+            &g,
+            &format!("DGET({DATABASE}, \"Yield\", {{\"Tree\"; \"Plum\"}})"),
+        );
+    }
+
+    #[test]
+    fn test_dstdev_dvar() {
+        let g = Grid::new();
+        assert_f64_approx_eq(
+            2.966479395,
+            &eval_db(&g, "DSTDEV", "\"Yield\"", APPLE_OR_PEAR),
+        );
+        assert_f64_approx_eq(
+            2.653299832,
+            &eval_db(&g, "DSTDEVP", "\"Yield\"", APPLE_OR_PEAR),
+        );
+        assert_f64_approx_eq(8.8, &eval_db(&g, "DVAR", "\"Yield\"", APPLE_OR_PEAR));
+        assert_f64_approx_eq(7.04, &eval_db(&g, "DVARP", "\"Yield\"", APPLE_OR_PEAR));
+    }
+
+    #[test]
+    fn test_database_errors() {
+        let g = Grid::new();
+        for field in ["\"Weight\"", "0", "6"] {
+            expect_err(
+                &RunErrorMsg::InvalidArgument,
+                &g,
+                &format!("DSUM({DATABASE}, {field}, {APPLE_OR_PEAR})"),
+            );
+        }
+        expect_err(
+            &RunErrorMsg::DivideByZero,
+            &g,
+            &format!("DSUM({DATABASE}, 1/0, {APPLE_OR_PEAR})"),
+        );
+
+        // Criteria on a field that isn't in the database never match.
+        assert_eq!("0", eval_db(&g, "DCOUNT", "", "{\"Weight\"; \">0\"}"),);
+    }
+}
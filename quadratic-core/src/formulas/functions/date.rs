@@ -0,0 +1,1035 @@
+use std::collections::HashSet;
+
+use chrono::{Datelike, NaiveDate, Utc};
+
+use super::*;
+use crate::{Duration, Instant, SECONDS_PER_DAY};
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Date & time functions",
+    docs: "Dates and times are in UTC.\
+           \n\n\
+           Wherever a date is expected, text such as `\"2024-12-25\"` or \
+           `\"12/25/2024\"` may be used instead. Numbers are interpreted as \
+           spreadsheet serial numbers, which count days since December 30, \
+           1899.\
+           \n\n\
+           Subtracting one date from another gives a duration, and adding a \
+           duration (such as one returned by `TIME`) to a date gives another \
+           date.\
+           \n\n",
+    get_functions,
+};
+
+/// Days of the week (starting from Monday) that are weekend days by default.
+const SATURDAY_SUNDAY: [bool; 7] = [false, false, false, false, false, true, true];
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        formula_fn!(
+            /// Returns a date from a year, month, and day.
+            ///
+            /// If `month` or `day` is out of range, then the date rolls over
+            /// into the neighboring months or years. For example,
+            /// `DATE(2024, 14, 1)` is February 1, 2025, and `DATE(2024, 3, 0)`
+            /// is the last day of February 2024.
+            ///
+            /// For compatibility with other spreadsheet applications, years
+            /// from 0 to 1899 are added to 1900.
+            #[examples("DATE(2024, 12, 25)", "DATE(A1, B1, C1)")]
+            #[zip_map]
+            fn DATE(span: Span, [year]: i64, [month]: i64, [day]: i64) {
+                let year = if (0..1900).contains(&year) {
+                    year + 1900
+                } else {
+                    year
+                };
+                date_from_ymd(year, month, day)
+                    .map(Instant::from_date)
+                    .ok_or(RunErrorMsg::Overflow.with_span(span))?
+            }
+        ),
+        formula_fn!(
+            /// Returns a time of day from an hour, minute, and second.
+            ///
+            /// If `minute` or `second` is out of range, then the time rolls
+            /// over into the neighboring hours or minutes. The result is always
+            /// less than 24 hours; any whole days are dropped. Returns an error
+            /// if the time is negative.
+            ///
+            /// Add the result to a date to get a date with a time.
+            #[examples("TIME(9, 30, 0)", "DATE(2024, 12, 25) + TIME(18, 0, 0)")]
+            #[zip_map]
+            fn TIME(span: Span, [hour]: f64, [minute]: f64, [second]: f64) {
+                let seconds = hour.trunc() * 3600.0 + minute.trunc() * 60.0 + second.trunc();
+                if seconds < 0.0 {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                Duration::from_seconds(seconds.rem_euclid(SECONDS_PER_DAY))
+            }
+        ),
+        formula_fn!(
+            /// Returns the current date and time.
+            #[volatile]
+            #[include_args_in_completion(false)]
+            #[examples("NOW()")]
+            fn NOW() {
+                Instant::from(Utc::now().naive_utc())
+            }
+        ),
+        formula_fn!(
+            /// Returns the current date.
+            #[volatile]
+            #[include_args_in_completion(false)]
+            #[examples("TODAY()")]
+            fn TODAY() {
+                Instant::from_date(Utc::now().date_naive())
+            }
+        ),
+        formula_fn!(
+            /// Returns the year of a date.
+            #[examples("YEAR(A1)", "YEAR(\"2024-12-25\") = 2024")]
+            #[zip_map]
+            fn YEAR([date]: (Spanned<Instant>)) {
+                to_date(date)?.year()
+            }
+        ),
+        formula_fn!(
+            /// Returns the month of a date, from 1 (January) to 12 (December).
+            #[examples("MONTH(A1)", "MONTH(\"2024-12-25\") = 12")]
+            #[zip_map]
+            fn MONTH([date]: (Spanned<Instant>)) {
+                to_date(date)?.month()
+            }
+        ),
+        formula_fn!(
+            /// Returns the day of the month of a date, from 1 to 31.
+            #[examples("DAY(A1)", "DAY(\"2024-12-25\") = 25")]
+            #[zip_map]
+            fn DAY([date]: (Spanned<Instant>)) {
+                to_date(date)?.day()
+            }
+        ),
+        formula_fn!(
+            /// Returns the hour of a time or date, from 0 to 23.
+            #[examples("HOUR(A1)", "HOUR(\"18:45:30\") = 18")]
+            #[zip_map]
+            fn HOUR([time]: (Spanned<CellValue>)) {
+                (seconds_of_day(*time)? / 3600.0).floor() as i64
+            }
+        ),
+        formula_fn!(
+            /// Returns the minute of a time or date, from 0 to 59.
+            #[examples("MINUTE(A1)", "MINUTE(\"18:45:30\") = 45")]
+            #[zip_map]
+            fn MINUTE([time]: (Spanned<CellValue>)) {
+                (seconds_of_day(*time)? / 60.0).floor() as i64 % 60
+            }
+        ),
+        formula_fn!(
+            /// Returns the second of a time or date, from 0 to 59.
+            #[examples("SECOND(A1)", "SECOND(\"18:45:30\") = 30")]
+            #[zip_map]
+            fn SECOND([time]: (Spanned<CellValue>)) {
+                seconds_of_day(*time)? as i64 % 60
+            }
+        ),
+        formula_fn!(
+            /// Returns the day of the week of a date, as a number.
+            ///
+            /// `return_type` determines how days are numbered:
+            ///
+            /// | `return_type` | Numbering                          |
+            /// | ------------- | ---------------------------------- |
+            /// | `1` (default) | Sunday = 1 through Saturday = 7    |
+            /// | `2`           | Monday = 1 through Sunday = 7      |
+            /// | `3`           | Monday = 0 through Sunday = 6      |
+            /// | `11`          | Monday = 1 through Sunday = 7      |
+            /// | `12`          | Tuesday = 1 through Monday = 7     |
+            /// | `13`          | Wednesday = 1 through Tuesday = 7  |
+            /// | `14`          | Thursday = 1 through Wednesday = 7 |
+            /// | `15`          | Friday = 1 through Thursday = 7    |
+            /// | `16`          | Saturday = 1 through Friday = 7    |
+            /// | `17`          | Sunday = 1 through Saturday = 7    |
+            #[examples("WEEKDAY(A1)", "WEEKDAY(\"2024-12-25\", 2) = 3")]
+            #[zip_map]
+            fn WEEKDAY([date]: (Spanned<Instant>), [return_type]: (Option<Spanned<i64>>)) {
+                let day = to_date(date)?.weekday().num_days_from_monday() as i64;
+                match return_type {
+                    None => (day + 1) % 7 + 1,
+                    Some(rt) => match rt.inner {
+                        1 => (day + 1) % 7 + 1,
+                        2 => day + 1,
+                        3 => day,
+                        11..=17 => (day - (rt.inner - 11)).rem_euclid(7) + 1,
+                        _ => return Err(RunErrorMsg::InvalidArgument.with_span(rt.span)),
+                    },
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the week number of a date within its year. The week
+            /// containing January 1 is week 1.
+            ///
+            /// `return_type` determines which day weeks start on: `1` or `17`
+            /// (default) for Sunday, `2` or `11` for Monday, and `12` through
+            /// `16` for Tuesday through Saturday. If `return_type` is `21`,
+            /// then [ISO week numbering](https://en.wikipedia.org/wiki/ISO_week_date)
+            /// is used instead, as in `ISOWEEKNUM`.
+            #[examples("WEEKNUM(A1)", "WEEKNUM(\"2024-12-25\", 2) = 52")]
+            #[zip_map]
+            fn WEEKNUM([date]: (Spanned<Instant>), [return_type]: (Option<Spanned<i64>>)) {
+                let date = to_date(date)?;
+                // Day that weeks start on, as a number of days from Monday.
+                let week_start = match return_type {
+                    None => 6,
+                    Some(rt) => match rt.inner {
+                        1 | 17 => 6,
+                        2 | 11 => 0,
+                        12..=16 => rt.inner - 11,
+                        21 => return Ok(CellValue::from(date.iso_week().week())),
+                        _ => return Err(RunErrorMsg::InvalidArgument.with_span(rt.span)),
+                    },
+                };
+                let day = date.weekday().num_days_from_monday() as i64;
+                let days_since_jan_1 = date.ordinal0() as i64;
+                let jan_1_offset = (day - days_since_jan_1 - week_start).rem_euclid(7);
+                (days_since_jan_1 + jan_1_offset) / 7 + 1
+            }
+        ),
+        formula_fn!(
+            /// Returns the [ISO week number](https://en.wikipedia.org/wiki/ISO_week_date)
+            /// of a date. Weeks start on Monday, and week 1 is the week
+            /// containing the first Thursday of the year.
+            #[examples("ISOWEEKNUM(A1)", "ISOWEEKNUM(\"2021-01-01\") = 53")]
+            #[zip_map]
+            fn ISOWEEKNUM([date]: (Spanned<Instant>)) {
+                to_date(date)?.iso_week().week()
+            }
+        ),
+        formula_fn!(
+            /// Returns the date that is `months` months after `start_date`.
+            /// `months` may be negative.
+            ///
+            /// If the day of the month does not exist in the resulting month,
+            /// then the last day of that month is used instead.
+            #[examples("EDATE(A1, 6)", "EDATE(\"2024-01-31\", 1) = DATE(2024, 2, 29)")]
+            #[zip_map]
+            fn EDATE(span: Span, [start_date]: (Spanned<Instant>), [months]: i64) {
+                let date = to_date(start_date)?;
+                add_months(date, months)
+                    .map(Instant::from_date)
+                    .ok_or(RunErrorMsg::Overflow.with_span(span))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the last day of the month that is `months` months after
+            /// `start_date`. `months` may be negative.
+            #[examples("EOMONTH(A1, 0)", "EOMONTH(\"2024-01-15\", 1) = DATE(2024, 2, 29)")]
+            #[zip_map]
+            fn EOMONTH(span: Span, [start_date]: (Spanned<Instant>), [months]: i64) {
+                let date = to_date(start_date)?;
+                date.with_day(1)
+                    .and_then(|first_of_month| add_months(first_of_month, months.checked_add(1)?))
+                    .and_then(|first_of_next_month| add_days(first_of_next_month, -1))
+                    .map(Instant::from_date)
+                    .ok_or(RunErrorMsg::Overflow.with_span(span))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the difference between two dates in the given `unit`.
+            ///
+            /// | `unit` | Result                                             |
+            /// | ------ | -------------------------------------------------- |
+            /// | `"Y"`  | Complete years                                     |
+            /// | `"M"`  | Complete months                                    |
+            /// | `"D"`  | Days                                               |
+            /// | `"MD"` | Days, ignoring complete months                     |
+            /// | `"YM"` | Complete months, ignoring complete years           |
+            /// | `"YD"` | Days, ignoring complete years                      |
+            ///
+            /// Returns an error if `start_date` is after `end_date`.
+            #[examples(
+                "DATEDIF(A1, B1, \"Y\")",
+                "DATEDIF(\"2020-01-15\", \"2024-03-10\", \"M\") = 49"
+            )]
+            #[zip_map]
+            fn DATEDIF(
+                span: Span,
+                [start_date]: (Spanned<Instant>),
+                [end_date]: (Spanned<Instant>),
+                [unit]: (Spanned<String>),
+            ) {
+                let start = to_date(start_date)?;
+                let end = to_date(end_date)?;
+                if start > end {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                let months = complete_months_between(start, end);
+                let overflow = || RunErrorMsg::Overflow.with_span(span);
+                match unit.inner.to_ascii_uppercase().as_str() {
+                    "Y" => months / 12,
+                    "M" => months,
+                    "D" => days_between(start, end),
+                    "MD" => days_between(add_months(start, months).ok_or_else(overflow)?, end),
+                    "YM" => months % 12,
+                    "YD" => {
+                        let years = months / 12;
+                        days_between(add_months(start, years * 12).ok_or_else(overflow)?, end)
+                    }
+                    _ => return Err(RunErrorMsg::InvalidArgument.with_span(unit.span)),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of days from `start_date` to `end_date`.
+            #[examples("DAYS(B1, A1)", "DAYS(\"2024-03-01\", \"2024-02-01\") = 29")]
+            #[zip_map]
+            fn DAYS([end_date]: (Spanned<Instant>), [start_date]: (Spanned<Instant>)) {
+                days_between(to_date(start_date)?, to_date(end_date)?)
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of days from `start_date` to `end_date`,
+            /// counting every month as 30 days and every year as 360 days.
+            ///
+            /// If `method` is `FALSE` (default), then the U.S. (NASD) method is
+            /// used: if `start_date` is the last day of a month, it is treated
+            /// as the 30th; and if `end_date` is the 31st, it is treated as the
+            /// 30th only when `start_date` is the 30th or 31st.
+            ///
+            /// If `method` is `TRUE`, then the European method is used: the
+            /// 31st of any month is treated as the 30th.
+            #[examples("DAYS360(A1, B1)", "DAYS360(\"2024-01-30\", \"2024-12-31\") = 330")]
+            #[zip_map]
+            fn DAYS360(
+                [start_date]: (Spanned<Instant>),
+                [end_date]: (Spanned<Instant>),
+                [method]: (Option<bool>),
+            ) {
+                let start = to_date(start_date)?;
+                let end = to_date(end_date)?;
+                days_360(start, end, method.unwrap_or(false))
+            }
+        ),
+        formula_fn!(
+            /// Returns the fraction of a year between two dates.
+            ///
+            /// `basis` determines how days are counted:
+            ///
+            /// | `basis`       | Day count                  |
+            /// | ------------- | -------------------------- |
+            /// | `0` (default) | U.S. (NASD) 30/360         |
+            /// | `1`           | Actual days / actual year  |
+            /// | `2`           | Actual days / 360          |
+            /// | `3`           | Actual days / 365          |
+            /// | `4`           | European 30/360            |
+            #[examples("YEARFRAC(A1, B1)", "YEARFRAC(\"2024-01-01\", \"2024-07-01\") = 0.5")]
+            #[zip_map]
+            fn YEARFRAC(
+                [start_date]: (Spanned<Instant>),
+                [end_date]: (Spanned<Instant>),
+                [basis]: (Option<Spanned<i64>>),
+            ) {
+                let mut start = to_date(start_date)?;
+                let mut end = to_date(end_date)?;
+                if start > end {
+                    std::mem::swap(&mut start, &mut end);
+                }
+                let days = days_between(start, end) as f64;
+                match basis.map(|b| (b.inner, b.span)) {
+                    None | Some((0, _)) => days_360_nasd_yearfrac(start, end) as f64 / 360.0,
+                    Some((1, _)) => days / average_year_length(start, end),
+                    Some((2, _)) => days / 360.0,
+                    Some((3, _)) => days / 365.0,
+                    Some((4, _)) => days_360(start, end, true) as f64 / 360.0,
+                    Some((_, span)) => return Err(RunErrorMsg::InvalidArgument.with_span(span)),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the date represented by a string, ignoring any time.
+            #[examples("DATEVALUE(\"2024-12-25\")", "DATEVALUE(\"12/25/2024\")")]
+            #[zip_map]
+            fn DATEVALUE([date_text]: (Spanned<Instant>)) {
+                Instant::from_date(to_date(date_text)?)
+            }
+        ),
+        formula_fn!(
+            /// Returns the time of day represented by a string, ignoring any
+            /// date.
+            #[examples("TIMEVALUE(\"18:45\")", "TIMEVALUE(\"6:45 PM\")")]
+            #[zip_map]
+            fn TIMEVALUE([time_text]: (Spanned<CellValue>)) {
+                Duration::from_seconds(seconds_of_day(*time_text)?)
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of working days from `start_date` to
+            /// `end_date`, including both. Saturdays, Sundays, and any dates in
+            /// `holidays` are not counted.
+            ///
+            /// If `start_date` is after `end_date`, then the result is
+            /// negative.
+            #[examples("NETWORKDAYS(A1, B1)", "NETWORKDAYS(A1, B1, C1:C10)")]
+            #[zip_map]
+            fn NETWORKDAYS(
+                [start_date]: (Spanned<Instant>),
+                [end_date]: (Spanned<Instant>),
+                holidays: (Option<Spanned<Array>>),
+            ) {
+                let start = to_date(start_date)?;
+                let end = to_date(end_date)?;
+                let holidays = holiday_set(holidays)?;
+                count_workdays(start, end, &SATURDAY_SUNDAY, &holidays)
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of working days from `start_date` to
+            /// `end_date`, including both. Weekend days and any dates in
+            /// `holidays` are not counted.
+            ///
+            /// `weekend` is either a number or a string. A number selects one
+            /// of these sets of weekend days:
+            ///
+            /// | `weekend`     | Weekend days       |
+            /// | ------------- | ------------------ |
+            /// | `1` (default) | Saturday, Sunday   |
+            /// | `2`           | Sunday, Monday     |
+            /// | `3`           | Monday, Tuesday    |
+            /// | `4`           | Tuesday, Wednesday |
+            /// | `5`           | Wednesday, Thursday|
+            /// | `6`           | Thursday, Friday   |
+            /// | `7`           | Friday, Saturday   |
+            /// | `11`          | Sunday only        |
+            /// | `12`          | Monday only        |
+            /// | `13`          | Tuesday only       |
+            /// | `14`          | Wednesday only     |
+            /// | `15`          | Thursday only      |
+            /// | `16`          | Friday only        |
+            /// | `17`          | Saturday only      |
+            ///
+            /// A string must have seven characters, one for each day from
+            /// Monday to Sunday, where `1` is a weekend day and `0` is a
+            /// working day. For example, `"0000011"` means Saturday and Sunday.
+            ///
+            /// If `start_date` is after `end_date`, then the result is
+            /// negative.
+            #[examples(
+                "NETWORKDAYS.INTL(A1, B1, 7)",
+                "NETWORKDAYS.INTL(A1, B1, \"0000011\", C1:C10)"
+            )]
+            #[zip_map]
+            fn "NETWORKDAYS.INTL"(
+                [start_date]: (Spanned<Instant>),
+                [end_date]: (Spanned<Instant>),
+                [weekend]: (Option<Spanned<CellValue>>),
+                holidays: (Option<Spanned<Array>>),
+            ) {
+                let start = to_date(start_date)?;
+                let end = to_date(end_date)?;
+                let weekend = parse_weekend(weekend.copied())?;
+                let holidays = holiday_set(holidays)?;
+                count_workdays(start, end, &weekend, &holidays)
+            }
+        ),
+        formula_fn!(
+            /// Returns the date that is `days` working days after
+            /// `start_date`. Saturdays, Sundays, and any dates in `holidays`
+            /// are skipped. `days` may be negative.
+            #[examples("WORKDAY(A1, 10)", "WORKDAY(A1, 10, C1:C10)")]
+            #[zip_map]
+            fn WORKDAY(
+                span: Span,
+                [start_date]: (Spanned<Instant>),
+                [days]: i64,
+                holidays: (Option<Spanned<Array>>),
+            ) {
+                let start = to_date(start_date)?;
+                let holidays = holiday_set(holidays)?;
+                add_workdays(start, days, &SATURDAY_SUNDAY, &holidays)
+                    .map(Instant::from_date)
+                    .ok_or(RunErrorMsg::Overflow.with_span(span))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the date that is `days` working days after
+            /// `start_date`. Weekend days and any dates in `holidays` are
+            /// skipped. `days` may be negative.
+            ///
+            /// `weekend` has the same meaning as in `NETWORKDAYS.INTL`.
+            #[examples("WORKDAY.INTL(A1, 10, 7)", "WORKDAY.INTL(A1, 10, \"0000011\", C1:C10)")]
+            #[zip_map]
+            fn "WORKDAY.INTL"(
+                span: Span,
+                [start_date]: (Spanned<Instant>),
+                [days]: i64,
+                [weekend]: (Option<Spanned<CellValue>>),
+                holidays: (Option<Spanned<Array>>),
+            ) {
+                let start = to_date(start_date)?;
+                let weekend = parse_weekend(weekend.copied())?;
+                let holidays = holiday_set(holidays)?;
+                add_workdays(start, days, &weekend, &holidays)
+                    .map(Instant::from_date)
+                    .ok_or(RunErrorMsg::Overflow.with_span(span))?
+            }
+        ),
+    ]
+}
+
+/// Returns the date of an instant, or an error if it is out of range.
+fn to_date(instant: Spanned<Instant>) -> CodeResult<NaiveDate> {
+    instant
+        .inner
+        .to_date()
+        .ok_or(RunErrorMsg::Overflow.with_span(instant.span))
+}
+
+/// Returns the number of seconds since midnight of a time or date, rounded to
+/// the nearest second.
+fn seconds_of_day(value: Spanned<&CellValue>) -> CodeResult<f64> {
+    let seconds = match value.try_coerce::<Duration>() {
+        Ok(duration) => duration.inner.seconds,
+        Err(_) => value.try_coerce::<Instant>()?.inner.seconds,
+    };
+    Ok(seconds.round().rem_euclid(SECONDS_PER_DAY))
+}
+
+/// Returns the date from a year, month, and day, rolling over out-of-range
+/// months and days. Returns `None` if the result is out of range.
+fn date_from_ymd(year: i64, month: i64, day: i64) -> Option<NaiveDate> {
+    let total_months = year.checked_mul(12)?.checked_add(month.checked_sub(1)?)?;
+    let year = i32::try_from(total_months.div_euclid(12)).ok()?;
+    let month = total_months.rem_euclid(12) as u32 + 1;
+    add_days(
+        NaiveDate::from_ymd_opt(year, month, 1)?,
+        day.checked_sub(1)?,
+    )
+}
+
+/// Adds a number of days to a date. Returns `None` if the result is out of
+/// range.
+fn add_days(date: NaiveDate, days: i64) -> Option<NaiveDate> {
+    let days_from_ce = i64::from(date.num_days_from_ce()).checked_add(days)?;
+    NaiveDate::from_num_days_from_ce_opt(i32::try_from(days_from_ce).ok()?)
+}
+
+/// Adds a number of months to a date. If the day of the month does not exist
+/// in the resulting month, then the last day of that month is used instead.
+/// Returns `None` if the result is out of range.
+fn add_months(date: NaiveDate, months: i64) -> Option<NaiveDate> {
+    Instant::from_date(date)
+        .checked_add_months(i32::try_from(months).ok()?)?
+        .to_date()
+}
+
+/// Returns the number of days from `start` to `end`.
+fn days_between(start: NaiveDate, end: NaiveDate) -> i64 {
+    i64::from(end.num_days_from_ce()) - i64::from(start.num_days_from_ce())
+}
+
+/// Returns the number of complete months from `start` to `end`.
+fn complete_months_between(start: NaiveDate, end: NaiveDate) -> i64 {
+    let months = (i64::from(end.year()) - i64::from(start.year())) * 12
+        + (i64::from(end.month()) - i64::from(start.month()));
+    if end.day() < start.day() {
+        months - 1
+    } else {
+        months
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    NaiveDate::from_ymd_opt(year, 2, 29).is_some()
+}
+
+fn is_last_day_of_february(date: NaiveDate) -> bool {
+    date.month() == 2 && add_days(date, 1).is_some_and(|next| next.month() == 3)
+}
+
+/// Returns the number of days between two dates using a 360-day year, as in
+/// `DAYS360`.
+fn days_360(start: NaiveDate, end: NaiveDate, european: bool) -> i64 {
+    let mut start_day = i64::from(start.day());
+    let mut end_day = i64::from(end.day());
+    if european {
+        start_day = start_day.min(30);
+        end_day = end_day.min(30);
+    } else {
+        if start_day == 31 || is_last_day_of_february(start) {
+            start_day = 30;
+        }
+        if end_day == 31 && start_day == 30 {
+            end_day = 30;
+        }
+    }
+    days_360_from_parts(start, start_day, end, end_day)
+}
+
+/// Returns the number of days between two dates using a 360-day year, as in
+/// `YEARFRAC` with basis `0`. This differs slightly from `DAYS360` in how the
+/// end of February is handled.
+fn days_360_nasd_yearfrac(start: NaiveDate, end: NaiveDate) -> i64 {
+    let mut start_day = i64::from(start.day());
+    let mut end_day = i64::from(end.day());
+    if start_day == 31 && end_day == 31 {
+        start_day = 30;
+        end_day = 30;
+    } else if start_day == 31 {
+        start_day = 30;
+    } else if start_day == 30 && end_day == 31 {
+        end_day = 30;
+    } else if is_last_day_of_february(start) {
+        start_day = 30;
+        if is_last_day_of_february(end) {
+            end_day = 30;
+        }
+    }
+    days_360_from_parts(start, start_day, end, end_day)
+}
+
+fn days_360_from_parts(start: NaiveDate, start_day: i64, end: NaiveDate, end_day: i64) -> i64 {
+    (i64::from(end.year()) - i64::from(start.year())) * 360
+        + (i64::from(end.month()) - i64::from(start.month())) * 30
+        + (end_day - start_day)
+}
+
+/// Returns the length of a year, in days, for the actual/actual day count
+/// used by `YEARFRAC` with basis `1`. `start` must not be after `end`.
+fn average_year_length(start: NaiveDate, end: NaiveDate) -> f64 {
+    let within_one_year = start.year() == end.year()
+        || (start.year() + 1 == end.year()
+            && (start.month(), start.day()) >= (end.month(), end.day()));
+
+    if within_one_year {
+        // Does the range include February 29?
+        let includes_leap_day = (start.year()..=end.year()).any(|year| {
+            NaiveDate::from_ymd_opt(year, 2, 29)
+                .is_some_and(|leap_day| (start..=end).contains(&leap_day))
+        });
+        if includes_leap_day || (start.year() == end.year() && is_leap_year(start.year())) {
+            366.0
+        } else {
+            365.0
+        }
+    } else {
+        let years = (start.year()..=end.year()).collect_vec();
+        let total_days: i64 = years
+            .iter()
+            .map(|&year| if is_leap_year(year) { 366 } else { 365 })
+            .sum();
+        total_days as f64 / years.len() as f64
+    }
+}
+
+/// Parses the `weekend` argument to `NETWORKDAYS.INTL` or `WORKDAY.INTL` into
+/// a list of whether each day of the week (starting from Monday) is a weekend
+/// day.
+fn parse_weekend(weekend: Option<Spanned<&CellValue>>) -> CodeResult<[bool; 7]> {
+    let Some(weekend) = weekend else {
+        return Ok(SATURDAY_SUNDAY);
+    };
+    let invalid = || RunErrorMsg::InvalidArgument.with_span(weekend.span);
+
+    let mut ret = [false; 7];
+    match weekend.inner {
+        CellValue::Text(s) => {
+            if s.chars().count() != 7 {
+                return Err(invalid());
+            }
+            for (is_weekend_day, c) in ret.iter_mut().zip(s.chars()) {
+                *is_weekend_day = match c {
+                    '0' => false,
+                    '1' => true,
+                    _ => return Err(invalid()),
+                };
+            }
+        }
+        _ => match weekend.try_coerce::<i64>()?.inner {
+            code @ 1..=7 => {
+                ret[((code + 4) % 7) as usize] = true;
+                ret[((code + 5) % 7) as usize] = true;
+            }
+            code @ 11..=17 => ret[((code - 5) % 7) as usize] = true,
+            _ => return Err(invalid()),
+        },
+    }
+
+    if ret.iter().all(|&is_weekend_day| is_weekend_day) {
+        return Err(invalid());
+    }
+    Ok(ret)
+}
+
+/// Collects the dates in the `holidays` argument to `NETWORKDAYS` or
+/// `WORKDAY`. Blank values are ignored.
+fn holiday_set(holidays: &Option<Spanned<Array>>) -> CodeResult<HashSet<NaiveDate>> {
+    let mut ret = HashSet::new();
+    if let Some(holidays) = holidays {
+        for value in holidays.inner.cell_values_slice() {
+            if value.is_blank() {
+                continue;
+            }
+            let value = Spanned {
+                inner: value,
+                span: holidays.span,
+            };
+            ret.insert(to_date(value.try_coerce::<Instant>()?)?);
+        }
+    }
+    Ok(ret)
+}
+
+fn is_weekend(date: NaiveDate, weekend: &[bool; 7]) -> bool {
+    weekend[date.weekday().num_days_from_monday() as usize]
+}
+
+/// Counts the working days from `start` to `end`, including both. If `start`
+/// is after `end`, then the result is negative.
+fn count_workdays(
+    start: NaiveDate,
+    end: NaiveDate,
+    weekend: &[bool; 7],
+    holidays: &HashSet<NaiveDate>,
+) -> i64 {
+    if start > end {
+        return -count_workdays(end, start, weekend, holidays);
+    }
+
+    // Count full weeks all at once, then count the remaining days
+    // individually.
+    let total_days = days_between(start, end) + 1;
+    let workdays_per_week = weekend
+        .iter()
+        .filter(|&&is_weekend_day| !is_weekend_day)
+        .count() as i64;
+    let mut count = total_days / 7 * workdays_per_week;
+    count += (0..total_days % 7)
+        .filter_map(|i| add_days(end, -i))
+        .filter(|&date| !is_weekend(date, weekend))
+        .count() as i64;
+
+    // Subtract holidays that would otherwise be working days.
+    count -= holidays
+        .iter()
+        .filter(|&&date| (start..=end).contains(&date) && !is_weekend(date, weekend))
+        .count() as i64;
+
+    count
+}
+
+/// Returns the date that is `days` working days after `start`. Returns `None`
+/// if the result is out of range.
+fn add_workdays(
+    start: NaiveDate,
+    days: i64,
+    weekend: &[bool; 7],
+    holidays: &HashSet<NaiveDate>,
+) -> Option<NaiveDate> {
+    let step = days.signum();
+    let mut remaining = days.unsigned_abs();
+    let mut date = start;
+    while remaining > 0 {
+        date = add_days(date, step)?;
+        if !is_weekend(date, weekend) && !holidays.contains(&date) {
+            remaining -= 1;
+        }
+    }
+    Some(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formulas::tests::*;
+
+    #[test]
+    fn test_formula_date_time() {
+        let g = Grid::new();
+
+        assert_eq!("2024-12-25", eval_to_string(&g, "DATE(2024, 12, 25)"));
+        assert_eq!("2025-02-01", eval_to_string(&g, "DATE(2024, 14, 1)"));
+        assert_eq!("2024-02-29", eval_to_string(&g, "DATE(2024, 3, 0)"));
+        assert_eq!("2023-12-31", eval_to_string(&g, "DATE(2024, 1, 0)"));
+        assert_eq!("2024-01-01", eval_to_string(&g, "DATE(124, 1, 1)"));
+
+        assert_eq!("09:30:00", eval_to_string(&g, "TIME(9, 30, 0)"));
+        assert_eq!("10:00:30", eval_to_string(&g, "TIME(9, 59, 90)"));
+        assert_eq!("01:00:00", eval_to_string(&g, "TIME(25, 0, 0)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "TIME(-1, 0, 0)");
+
+        assert_eq!("2024", eval_to_string(&g, "YEAR(\"2024-07-04\")"));
+        assert_eq!("7", eval_to_string(&g, "MONTH(\"07/04/2024\")"));
+        assert_eq!("4", eval_to_string(&g, "DAY(DATE(2024, 7, 4))"));
+        // Excel serial number
+        assert_eq!("2024-07-04", eval_to_string(&g, "DATEVALUE(45477)"));
+
+        let datetime = "\"2024-07-04 15:45:30\"";
+        assert_eq!("15", eval_to_string(&g, &format!("HOUR({datetime})")));
+        assert_eq!("45", eval_to_string(&g, &format!("MINUTE({datetime})")));
+        assert_eq!("30", eval_to_string(&g, &format!("SECOND({datetime})")));
+        assert_eq!("9", eval_to_string(&g, "HOUR(TIME(9, 30, 0))"));
+        assert_eq!("18", eval_to_string(&g, "HOUR(0.75)"));
+        assert_eq!("30", eval_to_string(&g, "MINUTE(\"6:30 PM\")"));
+
+        let now = eval(&g, "NOW()");
+        assert!(matches!(now, Value::Single(CellValue::Instant(_))));
+        let today = eval(&g, "TODAY()");
+        assert!(matches!(today, Value::Single(CellValue::Instant(_))));
+        assert_eq!("0", eval_to_string(&g, "HOUR(TODAY())"));
+    }
+
+    #[test]
+    fn test_formula_weekday_weeknum() {
+        let g = Grid::new();
+
+        // 2024-01-01 is a Monday.
+        assert_eq!("2", eval_to_string(&g, "WEEKDAY(DATE(2024, 1, 1))"));
+        assert_eq!("2", eval_to_string(&g, "WEEKDAY(DATE(2024, 1, 1), 1)"));
+        assert_eq!("1", eval_to_string(&g, "WEEKDAY(DATE(2024, 1, 1), 2)"));
+        assert_eq!("0", eval_to_string(&g, "WEEKDAY(DATE(2024, 1, 1), 3)"));
+        assert_eq!("1", eval_to_string(&g, "WEEKDAY(DATE(2024, 1, 1), 11)"));
+        assert_eq!("7", eval_to_string(&g, "WEEKDAY(DATE(2024, 1, 1), 12)"));
+        assert_eq!("2", eval_to_string(&g, "WEEKDAY(DATE(2024, 1, 1), 17)"));
+        assert_eq!("1", eval_to_string(&g, "WEEKDAY(DATE(2024, 1, 7))"));
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "WEEKDAY(DATE(2024, 1, 1), 4)",
+        );
+
+        assert_eq!("1", eval_to_string(&g, "WEEKNUM(DATE(2024, 1, 6))"));
+        assert_eq!("2", eval_to_string(&g, "WEEKNUM(DATE(2024, 1, 7))"));
+        assert_eq!("1", eval_to_string(&g, "WEEKNUM(DATE(2024, 1, 7), 2)"));
+        assert_eq!("2", eval_to_string(&g, "WEEKNUM(DATE(2024, 1, 8), 2)"));
+        assert_eq!("53", eval_to_string(&g, "WEEKNUM(DATE(2024, 12, 31))"));
+        assert_eq!("1", eval_to_string(&g, "WEEKNUM(DATE(2024, 12, 31), 21)"));
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "WEEKNUM(DATE(2024, 1, 1), 3)",
+        );
+
+        assert_eq!("53", eval_to_string(&g, "ISOWEEKNUM(DATE(2021, 1, 1))"));
+        assert_eq!("1", eval_to_string(&g, "ISOWEEKNUM(DATE(2024, 1, 1))"));
+    }
+
+    #[test]
+    fn test_formula_edate_eomonth() {
+        let g = Grid::new();
+
+        assert_eq!(
+            "2024-02-29",
+            eval_to_string(&g, "EDATE(DATE(2024, 1, 31), 1)")
+        );
+        assert_eq!(
+            "2024-02-29",
+            eval_to_string(&g, "EDATE(DATE(2024, 3, 31), -1)")
+        );
+        assert_eq!(
+            "2025-01-15",
+            eval_to_string(&g, "EDATE(\"2024-01-15\", 12)")
+        );
+
+        assert_eq!(
+            "2024-01-31",
+            eval_to_string(&g, "EOMONTH(DATE(2024, 1, 15), 0)")
+        );
+        assert_eq!(
+            "2024-02-29",
+            eval_to_string(&g, "EOMONTH(DATE(2024, 1, 15), 1)")
+        );
+        assert_eq!(
+            "2023-12-31",
+            eval_to_string(&g, "EOMONTH(DATE(2024, 1, 15), -1)")
+        );
+    }
+
+    #[test]
+    fn test_formula_date_differences() {
+        let g = Grid::new();
+
+        let (start, end) = ("\"2020-01-15\"", "\"2024-03-10\"");
+        for (unit, expected) in [
+            ("Y", "4"),
+            ("M", "49"),
+            ("D", "1516"),
+            ("MD", "24"),
+            ("YM", "1"),
+            ("YD", "55"),
+            ("yd", "55"),
+        ] {
+            let formula = format!("DATEDIF({start}, {end}, \"{unit}\")");
+            assert_eq!(expected, eval_to_string(&g, &formula));
+        }
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            &format!("DATEDIF({end}, {start}, \"D\")"),
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            &format!("DATEDIF({start}, {end}, \"X\")"),
+        );
+
+        assert_eq!(
+            "29",
+            eval_to_string(&g, "DAYS(\"2024-03-01\", \"2024-02-01\")")
+        );
+        assert_eq!(
+            "-29",
+            eval_to_string(&g, "DAYS(\"2024-02-01\", \"2024-03-01\")")
+        );
+
+        assert_eq!(
+            "330",
+            eval_to_string(&g, "DAYS360(\"2011-01-30\", \"2011-12-31\")")
+        );
+        assert_eq!(
+            "76",
+            eval_to_string(&g, "DAYS360(\"2024-01-15\", \"2024-03-31\")")
+        );
+        let formula = "DAYS360(\"2024-01-15\", \"2024-03-31\", TRUE)";
+        assert_eq!("75", eval_to_string(&g, formula));
+        assert_eq!(
+            "30",
+            eval_to_string(&g, "DAYS360(\"2023-02-28\", \"2023-03-31\")")
+        );
+
+        let (start, end) = ("DATE(2024, 1, 1)", "DATE(2024, 7, 1)");
+        assert_eq!(
+            "0.5",
+            eval_to_string(&g, &format!("YEARFRAC({start}, {end})"))
+        );
+        assert_eq!(
+            "0.5",
+            eval_to_string(&g, &format!("YEARFRAC({end}, {start})"))
+        );
+        assert_eq!(
+            "0.5",
+            eval_to_string(&g, &format!("YEARFRAC({start}, {end}, 4)"))
+        );
+        for (basis, expected) in [(1, 182.0 / 366.0), (2, 182.0 / 360.0), (3, 182.0 / 365.0)] {
+            let formula = format!("YEARFRAC({start}, {end}, {basis})");
+            let result = eval_to_string(&g, &formula).parse::<f64>().unwrap();
+            assert!((result - expected).abs() < 1e-12);
+        }
+        let formula = "YEARFRAC(DATE(2023, 1, 1), DATE(2025, 1, 1), 1)";
+        let result = eval_to_string(&g, formula).parse::<f64>().unwrap();
+        assert!((result - 731.0 / (1096.0 / 3.0)).abs() < 1e-12);
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            &format!("YEARFRAC({start}, {end}, 5)"),
+        );
+    }
+
+    #[test]
+    fn test_formula_datevalue_timevalue() {
+        let g = Grid::new();
+
+        assert_eq!(
+            "2024-12-25",
+            eval_to_string(&g, "DATEVALUE(\"2024-12-25\")")
+        );
+        assert_eq!(
+            "2024-12-25",
+            eval_to_string(&g, "DATEVALUE(\"12/25/2024\")")
+        );
+        assert_eq!(
+            "2024-12-25",
+            eval_to_string(&g, "DATEVALUE(\"December 25, 2024\")")
+        );
+        assert_eq!(
+            "2024-12-25",
+            eval_to_string(&g, "DATEVALUE(\"2024-12-25 18:00\")")
+        );
+        assert_eq!(
+            RunErrorMsg::Expected {
+                expected: "time instant".into(),
+                got: Some("text".into()),
+            },
+            eval_to_err(&g, "DATEVALUE(\"not a date\")").msg,
+        );
+
+        assert_eq!("18:45:00", eval_to_string(&g, "TIMEVALUE(\"18:45\")"));
+        assert_eq!("18:45:00", eval_to_string(&g, "TIMEVALUE(\"6:45 PM\")"));
+        assert_eq!(
+            "18:45:30",
+            eval_to_string(&g, "TIMEVALUE(\"2024-12-25 18:45:30\")")
+        );
+    }
+
+    #[test]
+    fn test_formula_networkdays_workday() {
+        let g = Grid::new();
+
+        let (start, end) = ("DATE(2024, 1, 1)", "DATE(2024, 1, 31)");
+        let networkdays = |args: &str| eval_to_string(&g, &format!("NETWORKDAYS({args})"));
+        assert_eq!("23", networkdays(&format!("{start}, {end}")));
+        assert_eq!("-23", networkdays(&format!("{end}, {start}")));
+        assert_eq!(
+            "22",
+            networkdays(&format!("{start}, {end}, \"2024-01-15\""))
+        );
+        // Holidays on weekends don't count twice.
+        assert_eq!(
+            "23",
+            networkdays(&format!("{start}, {end}, \"2024-01-13\""))
+        );
+        assert_eq!("1", networkdays(&format!("{start}, {start}")));
+
+        let networkdays_intl =
+            |args: &str| eval_to_string(&g, &format!("NETWORKDAYS.INTL({args})"));
+        assert_eq!("23", networkdays_intl(&format!("{start}, {end}")));
+        assert_eq!("27", networkdays_intl(&format!("{start}, {end}, 11")));
+        assert_eq!(
+            "23",
+            networkdays_intl(&format!("{start}, {end}, \"0000011\""))
+        );
+        assert_eq!(
+            "22",
+            networkdays_intl(&format!("{start}, {end}, 7, \"2024-01-15\""))
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            &format!("NETWORKDAYS.INTL({start}, {end}, \"1111111\")"),
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            &format!("NETWORKDAYS.INTL({start}, {end}, 8)"),
+        );
+
+        // 2024-01-05 is a Friday.
+        let friday = "DATE(2024, 1, 5)";
+        assert_eq!(
+            "2024-01-08",
+            eval_to_string(&g, &format!("WORKDAY({friday}, 1)"))
+        );
+        assert_eq!(
+            "2024-01-04",
+            eval_to_string(&g, &format!("WORKDAY({friday}, -1)"))
+        );
+        assert_eq!(
+            "2024-01-19",
+            eval_to_string(&g, &format!("WORKDAY({friday}, 10)"))
+        );
+        assert_eq!(
+            "2024-01-09",
+            eval_to_string(&g, &format!("WORKDAY({friday}, 1, \"2024-01-08\")")),
+        );
+        assert_eq!(
+            "2024-01-07",
+            eval_to_string(&g, &format!("WORKDAY.INTL({friday}, 1, 7)")),
+        );
+        assert_eq!(
+            "2024-01-06",
+            eval_to_string(&g, &format!("WORKDAY.INTL({friday}, 1, \"0000001\")")),
+        );
+    }
+}
@@ -0,0 +1,1022 @@
+use super::special::{self, bisect, std_normal_cdf, std_normal_inv, std_normal_pdf};
+use super::statistics::{mean, numbers_in, variance, Pairs};
+use super::util::ensure;
+use super::*;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Probability distribution functions",
+    docs: "Where `cumulative` is an argument, `TRUE` returns the cumulative \
+           distribution function (the probability of a value less than or \
+           equal to `x`) and `FALSE` returns the probability density function \
+           (or, for discrete distributions, the probability of exactly `x`).\
+           \n\n\
+           Degrees of freedom and other counts are truncated to integers.\
+           \n\n",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        // Normal distribution
+        formula_fn!(
+            /// Returns the normal distribution with mean `mean` and standard
+            /// deviation `standard_dev`.
+            #[examples("NORM.DIST(42, 40, 1.5, TRUE)", "NORM.DIST(A1, 0, 1, FALSE)")]
+            #[zip_map]
+            fn "NORM.DIST"(
+                span: Span,
+                [x]: f64,
+                [mean]: f64,
+                [standard_dev]: f64,
+                [cumulative]: bool,
+            ) {
+                ensure(*span, standard_dev > 0.0)?;
+                let z = (x - mean) / standard_dev;
+                if cumulative {
+                    std_normal_cdf(z)
+                } else {
+                    std_normal_pdf(z) / standard_dev
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the inverse of the cumulative normal distribution with
+            /// mean `mean` and standard deviation `standard_dev`.
+            #[examples("NORM.INV(0.9, 40, 1.5)")]
+            #[zip_map]
+            fn "NORM.INV"(span: Span, [probability]: f64, [mean]: f64, [standard_dev]: f64) {
+                ensure(*span, is_open_probability(probability) && standard_dev > 0.0)?;
+                mean + standard_dev * std_normal_inv(probability)
+            }
+        ),
+        formula_fn!(
+            /// Returns the standard normal distribution, which has a mean of
+            /// zero and a standard deviation of one.
+            #[examples("NORM.S.DIST(1.5, TRUE)", "NORM.S.DIST(A1, FALSE)")]
+            #[zip_map]
+            fn "NORM.S.DIST"([z]: f64, [cumulative]: bool) {
+                if cumulative {
+                    std_normal_cdf(z)
+                } else {
+                    std_normal_pdf(z)
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the inverse of the cumulative standard normal
+            /// distribution, which has a mean of zero and a standard deviation
+            /// of one.
+            #[examples("NORM.S.INV(0.9)")]
+            #[zip_map]
+            fn "NORM.S.INV"(span: Span, [probability]: f64) {
+                ensure(*span, is_open_probability(probability))?;
+                std_normal_inv(probability)
+            }
+        ),
+        formula_fn!(
+            /// Returns the lognormal distribution of `x`, where `ln(x)` is
+            /// normally distributed with mean `mean` and standard deviation
+            /// `standard_dev`.
+            #[examples("LOGNORM.DIST(4, 3.5, 1.2, TRUE)")]
+            #[zip_map]
+            fn "LOGNORM.DIST"(
+                span: Span,
+                [x]: f64,
+                [mean]: f64,
+                [standard_dev]: f64,
+                [cumulative]: bool,
+            ) {
+                ensure(*span, x > 0.0 && standard_dev > 0.0)?;
+                let z = (x.ln() - mean) / standard_dev;
+                if cumulative {
+                    std_normal_cdf(z)
+                } else {
+                    std_normal_pdf(z) / (x * standard_dev)
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the inverse of the cumulative lognormal distribution,
+            /// where `ln(x)` is normally distributed with mean `mean` and
+            /// standard deviation `standard_dev`.
+            #[examples("LOGNORM.INV(0.04, 3.5, 1.2)")]
+            #[zip_map]
+            fn "LOGNORM.INV"(span: Span, [probability]: f64, [mean]: f64, [standard_dev]: f64) {
+                ensure(*span, is_open_probability(probability) && standard_dev > 0.0)?;
+                util::finite(span, (mean + standard_dev * std_normal_inv(probability)).exp())?
+            }
+        ),
+        // Student's t-distribution
+        formula_fn!(
+            /// Returns the left-tailed Student's t-distribution.
+            #[examples("T.DIST(1.5, 10, TRUE)", "T.DIST(A1, B1, FALSE)")]
+            #[zip_map]
+            fn "T.DIST"(span: Span, [x]: f64, [deg_freedom]: f64, [cumulative]: bool) {
+                let df = degrees_of_freedom(*span, deg_freedom)?;
+                if cumulative {
+                    t_upper_tail(-x, df)
+                } else {
+                    t_pdf(x, df)
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the two-tailed Student's t-distribution.
+            ///
+            /// `x` must not be negative.
+            #[examples("T.DIST.2T(1.96, 60)")]
+            #[zip_map]
+            fn "T.DIST.2T"(span: Span, [x]: f64, [deg_freedom]: f64) {
+                let df = degrees_of_freedom(*span, deg_freedom)?;
+                ensure(*span, x >= 0.0)?;
+                2.0 * t_upper_tail(x, df)
+            }
+        ),
+        formula_fn!(
+            /// Returns the right-tailed Student's t-distribution.
+            #[examples("T.DIST.RT(1.96, 60)")]
+            #[zip_map]
+            fn "T.DIST.RT"(span: Span, [x]: f64, [deg_freedom]: f64) {
+                let df = degrees_of_freedom(*span, deg_freedom)?;
+                t_upper_tail(x, df)
+            }
+        ),
+        formula_fn!(
+            /// Returns the inverse of the left-tailed Student's
+            /// t-distribution.
+            #[examples("T.INV(0.75, 2)")]
+            #[zip_map]
+            fn "T.INV"(span: Span, [probability]: f64, [deg_freedom]: f64) {
+                let df = degrees_of_freedom(*span, deg_freedom)?;
+                ensure(*span, is_open_probability(probability))?;
+                if probability < 0.5 {
+                    -t_inv_upper_tail(probability, df)
+                } else {
+                    t_inv_upper_tail(1.0 - probability, df)
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the inverse of the two-tailed Student's
+            /// t-distribution.
+            #[examples("T.INV.2T(0.05, 60)")]
+            #[zip_map]
+            fn "T.INV.2T"(span: Span, [probability]: f64, [deg_freedom]: f64) {
+                let df = degrees_of_freedom(*span, deg_freedom)?;
+                ensure(*span, probability > 0.0 && probability <= 1.0)?;
+                t_inv_upper_tail(probability / 2.0, df)
+            }
+        ),
+        formula_fn!(
+            /// Returns the probability associated with a Student's t-test,
+            /// which indicates how likely it is that two samples come from
+            /// populations with the same mean.
+            ///
+            /// `tails` is the number of distribution tails to use (`1` or
+            /// `2`). `test_type` is the kind of t-test to perform:
+            ///
+            /// - `1` = paired, where `array1` and `array2` must be the same
+            ///   size
+            /// - `2` = two-sample with equal variance
+            /// - `3` = two-sample with unequal variance
+            ///
+            /// Blank and non-numeric values are ignored. For a paired test,
+            /// pairs where either value is blank or not a number are ignored.
+            #[examples("T.TEST(A1:A10, B1:B10, 2, 1)")]
+            fn "T.TEST"(
+                span: Span,
+                array1: (Spanned<Array>),
+                array2: (Spanned<Array>),
+                tails: i64,
+                test_type: i64,
+            ) {
+                ensure(span, (1..=2).contains(&tails))?;
+                let (t, df) = match test_type {
+                    1 => paired_t_statistic(span, &Pairs::new(&array1, &array2)?)?,
+                    2 | 3 => {
+                        let xs = numbers_in(&array1.map(Value::from))?;
+                        let ys = numbers_in(&array2.map(Value::from))?;
+                        two_sample_t_statistic(span, &xs, &ys, test_type == 3)?
+                    }
+                    _ => return Err(RunErrorMsg::InvalidArgument.with_span(span)),
+                };
+                tails as f64 * t_upper_tail(t.abs(), df)
+            }
+        ),
+        // Chi-squared distribution
+        formula_fn!(
+            /// Returns the left-tailed chi-squared distribution.
+            #[examples("CHISQ.DIST(0.5, 1, TRUE)", "CHISQ.DIST(A1, B1, FALSE)")]
+            #[zip_map]
+            fn "CHISQ.DIST"(span: Span, [x]: f64, [deg_freedom]: f64, [cumulative]: bool) {
+                let df = degrees_of_freedom(*span, deg_freedom)?;
+                ensure(*span, x >= 0.0)?;
+                if cumulative {
+                    special::gamma_p(df / 2.0, x / 2.0)
+                } else {
+                    util::finite(span, gamma_pdf(x, df / 2.0, 2.0))?
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the right-tailed chi-squared distribution.
+            #[examples("CHISQ.DIST.RT(18.307, 10)")]
+            #[zip_map]
+            fn "CHISQ.DIST.RT"(span: Span, [x]: f64, [deg_freedom]: f64) {
+                let df = degrees_of_freedom(*span, deg_freedom)?;
+                ensure(*span, x >= 0.0)?;
+                special::gamma_q(df / 2.0, x / 2.0)
+            }
+        ),
+        formula_fn!(
+            /// Returns the inverse of the left-tailed chi-squared
+            /// distribution.
+            #[examples("CHISQ.INV(0.93, 1)")]
+            #[zip_map]
+            fn "CHISQ.INV"(span: Span, [probability]: f64, [deg_freedom]: f64) {
+                let df = degrees_of_freedom(*span, deg_freedom)?;
+                ensure(*span, (0.0..1.0).contains(&probability))?;
+                bisect(0.0, f64::INFINITY, |x| {
+                    special::gamma_p(df / 2.0, x / 2.0) < probability
+                })
+            }
+        ),
+        formula_fn!(
+            /// Returns the inverse of the right-tailed chi-squared
+            /// distribution.
+            #[examples("CHISQ.INV.RT(0.05, 10)")]
+            #[zip_map]
+            fn "CHISQ.INV.RT"(span: Span, [probability]: f64, [deg_freedom]: f64) {
+                let df = degrees_of_freedom(*span, deg_freedom)?;
+                ensure(*span, probability > 0.0 && probability <= 1.0)?;
+                bisect(0.0, f64::INFINITY, |x| {
+                    special::gamma_q(df / 2.0, x / 2.0) > probability
+                })
+            }
+        ),
+        formula_fn!(
+            /// Returns the probability associated with a chi-squared test of
+            /// independence between observed values in `actual_range` and
+            /// expected values in `expected_range`, which must be the same
+            /// size.
+            ///
+            /// If the ranges have a single row or column, the test has one
+            /// fewer degree of freedom than the number of values. Otherwise,
+            /// it has `(rows - 1) * (columns - 1)` degrees of freedom.
+            #[examples("CHISQ.TEST(A1:B3, D1:E3)")]
+            fn "CHISQ.TEST"(
+                span: Span,
+                actual_range: (Spanned<Array>),
+                expected_range: (Spanned<Array>),
+            ) {
+                let pairs = Pairs::new(&actual_range, &expected_range)?;
+                let size = actual_range.inner.size();
+                let (w, h) = (size.w.get() as f64, size.h.get() as f64);
+                let df = if w == 1.0 || h == 1.0 {
+                    w * h - 1.0
+                } else {
+                    (w - 1.0) * (h - 1.0)
+                };
+                ensure(span, df >= 1.0)?;
+                let statistic = std::iter::zip(&pairs.xs, &pairs.ys)
+                    .map(|(actual, expected)| {
+                        util::checked_div(span, (actual - expected).powi(2), *expected)
+                    })
+                    .sum::<CodeResult<f64>>()?;
+                special::gamma_q(df / 2.0, statistic / 2.0)
+            }
+        ),
+        // F-distribution
+        formula_fn!(
+            /// Returns the left-tailed F-distribution.
+            #[examples("F.DIST(15.2, 6, 4, TRUE)", "F.DIST(A1, B1, C1, FALSE)")]
+            #[zip_map]
+            fn "F.DIST"(
+                span: Span,
+                [x]: f64,
+                [deg_freedom1]: f64,
+                [deg_freedom2]: f64,
+                [cumulative]: bool,
+            ) {
+                let d1 = degrees_of_freedom(*span, deg_freedom1)?;
+                let d2 = degrees_of_freedom(*span, deg_freedom2)?;
+                ensure(*span, x >= 0.0)?;
+                if cumulative {
+                    f_cdf(x, d1, d2)
+                } else {
+                    util::finite(span, f_pdf(x, d1, d2))?
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the right-tailed F-distribution.
+            #[examples("F.DIST.RT(15.2, 6, 4)")]
+            #[zip_map]
+            fn "F.DIST.RT"(span: Span, [x]: f64, [deg_freedom1]: f64, [deg_freedom2]: f64) {
+                let d1 = degrees_of_freedom(*span, deg_freedom1)?;
+                let d2 = degrees_of_freedom(*span, deg_freedom2)?;
+                ensure(*span, x >= 0.0)?;
+                f_upper_tail(x, d1, d2)
+            }
+        ),
+        formula_fn!(
+            /// Returns the inverse of the left-tailed F-distribution.
+            #[examples("F.INV(0.01, 6, 4)")]
+            #[zip_map]
+            fn "F.INV"(
+                span: Span,
+                [probability]: f64,
+                [deg_freedom1]: f64,
+                [deg_freedom2]: f64,
+            ) {
+                let d1 = degrees_of_freedom(*span, deg_freedom1)?;
+                let d2 = degrees_of_freedom(*span, deg_freedom2)?;
+                ensure(*span, (0.0..1.0).contains(&probability))?;
+                bisect(0.0, f64::INFINITY, |x| f_cdf(x, d1, d2) < probability)
+            }
+        ),
+        formula_fn!(
+            /// Returns the inverse of the right-tailed F-distribution.
+            #[examples("F.INV.RT(0.01, 6, 4)")]
+            #[zip_map]
+            fn "F.INV.RT"(
+                span: Span,
+                [probability]: f64,
+                [deg_freedom1]: f64,
+                [deg_freedom2]: f64,
+            ) {
+                let d1 = degrees_of_freedom(*span, deg_freedom1)?;
+                let d2 = degrees_of_freedom(*span, deg_freedom2)?;
+                ensure(*span, probability > 0.0 && probability <= 1.0)?;
+                bisect(0.0, f64::INFINITY, |x| {
+                    f_upper_tail(x, d1, d2) > probability
+                })
+            }
+        ),
+        formula_fn!(
+            /// Returns the probability associated with an F-test, which
+            /// indicates how likely it is that two samples come from
+            /// populations with the same variance.
+            ///
+            /// Blank and non-numeric values are ignored.
+            #[examples("F.TEST(A1:A10, B1:B10)")]
+            fn "F.TEST"(span: Span, array1: (Spanned<Value>), array2: (Spanned<Value>)) {
+                let xs = numbers_in(&array1)?;
+                let ys = numbers_in(&array2)?;
+                let f = util::checked_div(span, variance(span, &xs, 1)?, variance(span, &ys, 1)?)?;
+                let d1 = xs.len() as f64 - 1.0;
+                let d2 = ys.len() as f64 - 1.0;
+                2.0 * f_cdf(f, d1, d2).min(f_upper_tail(f, d1, d2))
+            }
+        ),
+        // Discrete distributions
+        formula_fn!(
+            /// Returns the binomial distribution, which is the probability of
+            /// `number_s` successes in `trials` independent trials that each
+            /// succeed with probability `probability_s`.
+            #[examples("BINOM.DIST(6, 10, 0.5, FALSE)", "BINOM.DIST(A1, 10, 0.3, TRUE)")]
+            #[zip_map]
+            fn "BINOM.DIST"(
+                span: Span,
+                [number_s]: f64,
+                [trials]: f64,
+                [probability_s]: f64,
+                [cumulative]: bool,
+            ) {
+                let (k, n) = (number_s.trunc(), trials.trunc());
+                ensure(
+                    *span,
+                    (0.0..=n).contains(&k) && (0.0..=1.0).contains(&probability_s),
+                )?;
+                if cumulative {
+                    binomial_cdf(k, n, probability_s)
+                } else {
+                    binomial_pmf(k, n, probability_s)
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the smallest number of successes for which the
+            /// cumulative binomial distribution is greater than or equal to
+            /// `alpha`, given `trials` independent trials that each succeed
+            /// with probability `probability_s`.
+            #[examples("BINOM.INV(6, 0.5, 0.75)")]
+            #[zip_map]
+            fn "BINOM.INV"(span: Span, [trials]: f64, [probability_s]: f64, [alpha]: f64) {
+                let n = trials.trunc();
+                ensure(
+                    *span,
+                    n >= 0.0
+                        && (0.0..=1.0).contains(&probability_s)
+                        && (0.0..=1.0).contains(&alpha),
+                )?;
+                // Binary search for the smallest `k` where the CDF is at
+                // least `alpha`.
+                let (mut lo, mut hi) = (0.0, n);
+                while lo < hi {
+                    let mid = ((lo + hi) / 2.0).floor();
+                    if binomial_cdf(mid, n, probability_s) >= alpha {
+                        hi = mid;
+                    } else {
+                        lo = mid + 1.0;
+                    }
+                }
+                lo
+            }
+        ),
+        formula_fn!(
+            /// Returns the Poisson distribution, which is the probability of
+            /// `x` events occurring when `mean` events are expected.
+            #[examples("POISSON.DIST(2, 5, TRUE)", "POISSON.DIST(A1, B1, FALSE)")]
+            #[zip_map]
+            fn "POISSON.DIST"(span: Span, [x]: f64, [mean]: f64, [cumulative]: bool) {
+                let k = x.trunc();
+                ensure(*span, k >= 0.0 && mean >= 0.0)?;
+                if cumulative {
+                    special::gamma_q(k + 1.0, mean)
+                } else {
+                    (special::x_ln_y(k, mean) - mean - special::ln_gamma(k + 1.0)).exp()
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the hypergeometric distribution, which is the
+            /// probability of `sample_s` successes in a sample of
+            /// `number_sample` items drawn without replacement from a
+            /// population of `number_pop` items containing `population_s`
+            /// successes.
+            #[examples("HYPGEOM.DIST(1, 4, 8, 20, TRUE)")]
+            #[zip_map]
+            fn "HYPGEOM.DIST"(
+                span: Span,
+                [sample_s]: f64,
+                [number_sample]: f64,
+                [population_s]: f64,
+                [number_pop]: f64,
+                [cumulative]: bool,
+            ) {
+                let k = sample_s.trunc();
+                let n = number_sample.trunc();
+                let successes = population_s.trunc();
+                let population = number_pop.trunc();
+                let min_k = (n + successes - population).max(0.0);
+                ensure(
+                    *span,
+                    (1.0..=population).contains(&n)
+                        && (1.0..=population).contains(&successes)
+                        && (min_k..=n.min(successes)).contains(&k),
+                )?;
+                let pmf = |i: f64| {
+                    (special::ln_choose(successes, i)
+                        + special::ln_choose(population - successes, n - i)
+                        - special::ln_choose(population, n))
+                    .exp()
+                };
+                if cumulative {
+                    let mut sum = 0.0;
+                    let mut i = min_k;
+                    while i <= k {
+                        sum += pmf(i);
+                        i += 1.0;
+                    }
+                    sum.min(1.0)
+                } else {
+                    pmf(k)
+                }
+            }
+        ),
+        // Continuous distributions
+        formula_fn!(
+            /// Returns the exponential distribution with rate `lambda`.
+            #[examples("EXPON.DIST(0.2, 10, TRUE)", "EXPON.DIST(A1, B1, FALSE)")]
+            #[zip_map]
+            fn "EXPON.DIST"(span: Span, [x]: f64, [lambda]: f64, [cumulative]: bool) {
+                ensure(*span, x >= 0.0 && lambda > 0.0)?;
+                if cumulative {
+                    -(-lambda * x).exp_m1()
+                } else {
+                    lambda * (-lambda * x).exp()
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the gamma distribution with shape `alpha` and scale
+            /// `beta`.
+            #[examples("GAMMA.DIST(10, 9, 2, TRUE)", "GAMMA.DIST(A1, B1, C1, FALSE)")]
+            #[zip_map]
+            fn "GAMMA.DIST"(
+                span: Span,
+                [x]: f64,
+                [alpha]: f64,
+                [beta]: f64,
+                [cumulative]: bool,
+            ) {
+                ensure(*span, x >= 0.0 && alpha > 0.0 && beta > 0.0)?;
+                if cumulative {
+                    special::gamma_p(alpha, x / beta)
+                } else {
+                    util::finite(span, gamma_pdf(x, alpha, beta))?
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the inverse of the cumulative gamma distribution with
+            /// shape `alpha` and scale `beta`.
+            #[examples("GAMMA.INV(0.068, 9, 2)")]
+            #[zip_map]
+            fn "GAMMA.INV"(span: Span, [probability]: f64, [alpha]: f64, [beta]: f64) {
+                ensure(
+                    *span,
+                    (0.0..1.0).contains(&probability) && alpha > 0.0 && beta > 0.0,
+                )?;
+                bisect(0.0, f64::INFINITY, |x| {
+                    special::gamma_p(alpha, x / beta) < probability
+                })
+            }
+        ),
+        formula_fn!(
+            /// Returns the natural logarithm of the gamma function.
+            ///
+            /// `x` must be positive.
+            #[examples("GAMMALN(4)")]
+            #[zip_map]
+            fn GAMMALN(span: Span, [x]: f64) {
+                ensure(*span, x > 0.0)?;
+                special::ln_gamma(x)
+            }
+        ),
+        formula_fn!(
+            /// Returns the beta distribution with shape parameters `alpha`
+            /// and `beta`.
+            ///
+            /// If `lower_bound` and `upper_bound` are given, the distribution
+            /// is rescaled from the interval between 0 and 1 to the interval
+            /// between `lower_bound` and `upper_bound`.
+            #[examples("BETA.DIST(0.2, 0.5, 2, TRUE)", "BETA.DIST(2, 8, 10, FALSE, 1, 3)")]
+            #[zip_map]
+            fn "BETA.DIST"(
+                span: Span,
+                [x]: f64,
+                [alpha]: f64,
+                [beta]: f64,
+                [cumulative]: bool,
+                [lower_bound]: (Option<f64>),
+                [upper_bound]: (Option<f64>),
+            ) {
+                let lower_bound = lower_bound.unwrap_or(0.0);
+                let upper_bound = upper_bound.unwrap_or(1.0);
+                ensure(
+                    *span,
+                    alpha > 0.0
+                        && beta > 0.0
+                        && lower_bound < upper_bound
+                        && (lower_bound..=upper_bound).contains(&x),
+                )?;
+                let width = upper_bound - lower_bound;
+                let z = (x - lower_bound) / width;
+                if cumulative {
+                    special::beta_regularized(alpha, beta, z)
+                } else {
+                    let ln_density = special::x_ln_y(alpha - 1.0, z)
+                        + special::x_ln_y(beta - 1.0, 1.0 - z)
+                        - special::ln_beta(alpha, beta);
+                    util::finite(span, ln_density.exp() / width)?
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the inverse of the cumulative beta distribution with
+            /// shape parameters `alpha` and `beta`.
+            ///
+            /// If `lower_bound` and `upper_bound` are given, the distribution
+            /// is rescaled from the interval between 0 and 1 to the interval
+            /// between `lower_bound` and `upper_bound`.
+            #[examples("BETA.INV(0.6, 0.5, 2)", "BETA.INV(0.685, 8, 10, 1, 3)")]
+            #[zip_map]
+            fn "BETA.INV"(
+                span: Span,
+                [probability]: f64,
+                [alpha]: f64,
+                [beta]: f64,
+                [lower_bound]: (Option<f64>),
+                [upper_bound]: (Option<f64>),
+            ) {
+                let lower_bound = lower_bound.unwrap_or(0.0);
+                let upper_bound = upper_bound.unwrap_or(1.0);
+                ensure(
+                    *span,
+                    (0.0..=1.0).contains(&probability)
+                        && alpha > 0.0
+                        && beta > 0.0
+                        && lower_bound < upper_bound,
+                )?;
+                let z = bisect(0.0, 1.0, |z| {
+                    special::beta_regularized(alpha, beta, z) < probability
+                });
+                lower_bound + z * (upper_bound - lower_bound)
+            }
+        ),
+        formula_fn!(
+            /// Returns the Weibull distribution with shape `alpha` and scale
+            /// `beta`.
+            #[examples("WEIBULL.DIST(105, 20, 100, TRUE)")]
+            #[zip_map]
+            fn "WEIBULL.DIST"(
+                span: Span,
+                [x]: f64,
+                [alpha]: f64,
+                [beta]: f64,
+                [cumulative]: bool,
+            ) {
+                ensure(*span, x >= 0.0 && alpha > 0.0 && beta > 0.0)?;
+                let scaled = (x / beta).powf(alpha);
+                if cumulative {
+                    -(-scaled).exp_m1()
+                } else {
+                    let density = alpha / beta * (x / beta).powf(alpha - 1.0) * (-scaled).exp();
+                    util::finite(span, density)?
+                }
+            }
+        ),
+        // Confidence intervals and tests
+        formula_fn!(
+            /// Returns the half-width of a confidence interval for a
+            /// population mean, using the normal distribution.
+            ///
+            /// `alpha` is the significance level (such as `0.05` for a 95%
+            /// confidence interval), `standard_dev` is the population standard
+            /// deviation, and `size` is the sample size.
+            #[examples("CONFIDENCE.NORM(0.05, 2.5, 50)")]
+            #[zip_map]
+            fn "CONFIDENCE.NORM"(span: Span, [alpha]: f64, [standard_dev]: f64, [size]: f64) {
+                let n = size.trunc();
+                ensure(
+                    *span,
+                    is_open_probability(alpha) && standard_dev > 0.0 && n >= 1.0,
+                )?;
+                -std_normal_inv(alpha / 2.0) * standard_dev / n.sqrt()
+            }
+        ),
+        formula_fn!(
+            /// Returns the half-width of a confidence interval for a
+            /// population mean, using the Student's t-distribution.
+            ///
+            /// `alpha` is the significance level (such as `0.05` for a 95%
+            /// confidence interval), `standard_dev` is the sample standard
+            /// deviation, and `size` is the sample size.
+            #[examples("CONFIDENCE.T(0.05, 1, 50)")]
+            #[zip_map]
+            fn "CONFIDENCE.T"(span: Span, [alpha]: f64, [standard_dev]: f64, [size]: f64) {
+                let n = size.trunc();
+                ensure(
+                    *span,
+                    is_open_probability(alpha) && standard_dev > 0.0 && n >= 1.0,
+                )?;
+                if n == 1.0 {
+                    return Err(RunErrorMsg::DivideByZero.with_span(span));
+                }
+                t_inv_upper_tail(alpha / 2.0, n - 1.0) * standard_dev / n.sqrt()
+            }
+        ),
+        formula_fn!(
+            /// Returns the one-tailed probability associated with a z-test,
+            /// which indicates how likely it is that the mean of the
+            /// population that `array` was sampled from is greater than `x`.
+            ///
+            /// If `sigma` is omitted, the sample standard deviation of
+            /// `array` is used. Blank and non-numeric values are ignored.
+            #[examples("Z.TEST(A1:A10, 4)", "Z.TEST(A1:A10, 4, 2)")]
+            fn "Z.TEST"(span: Span, array: (Spanned<Value>), x: f64, sigma: (Option<f64>)) {
+                let numbers = numbers_in(&array)?;
+                let sigma = match sigma {
+                    Some(sigma) => sigma,
+                    None => variance(span, &numbers, 1)?.sqrt(),
+                };
+                let standard_error = sigma / (numbers.len() as f64).sqrt();
+                let z = util::checked_div(span, mean(span, &numbers)? - x, standard_error)?;
+                std_normal_cdf(-z)
+            }
+        ),
+    ]
+}
+
+/// Returns whether `p` is a probability strictly between 0 and 1.
+fn is_open_probability(p: f64) -> bool {
+    p > 0.0 && p < 1.0
+}
+
+/// Truncates a number of degrees of freedom to an integer, returning an error
+/// if it is less than 1.
+fn degrees_of_freedom(span: Span, df: f64) -> CodeResult<f64> {
+    let df = df.trunc();
+    ensure(span, df >= 1.0)?;
+    Ok(df)
+}
+
+/// Returns the probability density function of the gamma distribution with
+/// shape `alpha` and scale `beta`.
+fn gamma_pdf(x: f64, alpha: f64, beta: f64) -> f64 {
+    (special::x_ln_y(alpha - 1.0, x) - x / beta - special::ln_gamma(alpha) - alpha * beta.ln())
+        .exp()
+}
+
+/// Returns the probability density function of the Student's t-distribution.
+fn t_pdf(t: f64, df: f64) -> f64 {
+    let ln_density = special::ln_gamma((df + 1.0) / 2.0)
+        - special::ln_gamma(df / 2.0)
+        - 0.5 * (df * std::f64::consts::PI).ln()
+        - (df + 1.0) / 2.0 * (t * t / df).ln_1p();
+    ln_density.exp()
+}
+
+/// Returns the probability that a value from the Student's t-distribution is
+/// greater than `t`.
+fn t_upper_tail(t: f64, df: f64) -> f64 {
+    let tail = 0.5 * special::beta_regularized(df / 2.0, 0.5, df / (df + t * t));
+    if t >= 0.0 {
+        tail
+    } else {
+        1.0 - tail
+    }
+}
+
+/// Returns the value `t` for which the probability that a value from the
+/// Student's t-distribution is greater than `t` is `p`, for `0 < p <= 0.5`.
+fn t_inv_upper_tail(p: f64, df: f64) -> f64 {
+    bisect(0.0, f64::INFINITY, |t| t_upper_tail(t, df) > p)
+}
+
+/// Returns the t-statistic and degrees of freedom for a paired t-test.
+fn paired_t_statistic(span: Span, pairs: &Pairs) -> CodeResult<(f64, f64)> {
+    let differences = std::iter::zip(&pairs.xs, &pairs.ys)
+        .map(|(x, y)| x - y)
+        .collect_vec();
+    let n = differences.len() as f64;
+    let standard_error = (variance(span, &differences, 1)? / n).sqrt();
+    let t = util::checked_div(span, mean(span, &differences)?, standard_error)?;
+    Ok((t, n - 1.0))
+}
+
+/// Returns the t-statistic and degrees of freedom for a two-sample t-test,
+/// using the Welch-Satterthwaite approximation for the degrees of freedom if
+/// `unequal_variance` is true.
+fn two_sample_t_statistic(
+    span: Span,
+    xs: &[f64],
+    ys: &[f64],
+    unequal_variance: bool,
+) -> CodeResult<(f64, f64)> {
+    let (n1, n2) = (xs.len() as f64, ys.len() as f64);
+    let (v1, v2) = (variance(span, xs, 1)?, variance(span, ys, 1)?);
+    let difference = mean(span, xs)? - mean(span, ys)?;
+    if unequal_variance {
+        let (s1, s2) = (v1 / n1, v2 / n2);
+        let t = util::checked_div(span, difference, (s1 + s2).sqrt())?;
+        let df_denominator = s1 * s1 / (n1 - 1.0) + s2 * s2 / (n2 - 1.0);
+        let df = util::checked_div(span, (s1 + s2).powi(2), df_denominator)?;
+        Ok((t, df))
+    } else {
+        let df = n1 + n2 - 2.0;
+        let pooled_variance = ((n1 - 1.0) * v1 + (n2 - 1.0) * v2) / df;
+        let standard_error = (pooled_variance * (1.0 / n1 + 1.0 / n2)).sqrt();
+        Ok((util::checked_div(span, difference, standard_error)?, df))
+    }
+}
+
+/// Returns the probability density function of the F-distribution.
+fn f_pdf(x: f64, d1: f64, d2: f64) -> f64 {
+    let ln_density = special::x_ln_y(d1 / 2.0 - 1.0, x) + d1 / 2.0 * d1.ln() + d2 / 2.0 * d2.ln()
+        - (d1 + d2) / 2.0 * (d1 * x + d2).ln()
+        - special::ln_beta(d1 / 2.0, d2 / 2.0);
+    ln_density.exp()
+}
+
+/// Returns the cumulative distribution function of the F-distribution.
+fn f_cdf(x: f64, d1: f64, d2: f64) -> f64 {
+    special::beta_regularized(d1 / 2.0, d2 / 2.0, d1 * x / (d1 * x + d2))
+}
+
+/// Returns the probability that a value from the F-distribution is greater
+/// than `x`.
+fn f_upper_tail(x: f64, d1: f64, d2: f64) -> f64 {
+    special::beta_regularized(d2 / 2.0, d1 / 2.0, d2 / (d2 + d1 * x))
+}
+
+/// Returns the probability of exactly `k` successes in `n` trials.
+fn binomial_pmf(k: f64, n: f64, p: f64) -> f64 {
+    let ln_probability =
+        special::ln_choose(n, k) + special::x_ln_y(k, p) + special::x_ln_y(n - k, 1.0 - p);
+    ln_probability.exp()
+}
+
+/// Returns the probability of at most `k` successes in `n` trials.
+fn binomial_cdf(k: f64, n: f64, p: f64) -> f64 {
+    if k >= n {
+        1.0
+    } else {
+        special::beta_regularized(n - k, k + 1.0, 1.0 - p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formulas::tests::*;
+
+    /// Asserts that `actual` is within a relative tolerance of 1e-9 of
+    /// `expected`.
+    #[track_caller]
+    fn assert_close(expected: f64, actual: &str) {
+        let actual = actual.parse::<f64>().unwrap();
+        assert!(
+            (expected - actual).abs() <= 1e-9 * expected.abs(),
+            "expected {expected} but got {actual}",
+        );
+    }
+
+    #[track_caller]
+    fn check(g: &Grid, expected: f64, formula: &str) {
+        assert_close(expected, &eval_to_string(g, formula));
+    }
+
+    #[test]
+    fn test_formula_normal_distribution() {
+        let g = Grid::new();
+
+        check(&g, 0.9087887802741321, "NORM.DIST(42, 40, 1.5, TRUE)");
+        check(&g, 0.10934004978399575, "NORM.DIST(42, 40, 1.5, FALSE)");
+        check(&g, 42.00000200956616, "NORM.INV(0.908789, 40, 1.5)");
+        check(&g, 0.9087887256040951, "NORM.S.DIST(1.333333, TRUE)");
+        check(&g, 0.16401014756936724, "NORM.S.DIST(1.333333, FALSE)");
+        check(&g, 7.619853024160525e-24, "NORM.S.DIST(-10, TRUE)");
+        check(&g, 1.3333346730441076, "NORM.S.INV(0.908789)");
+        check(&g, -4.753424308822899, "NORM.S.INV(0.000001)");
+        check(&g, 0.03908355570680047, "LOGNORM.DIST(4, 3.5, 1.2, TRUE)");
+        check(&g, 0.017617596681819225, "LOGNORM.DIST(4, 3.5, 1.2, FALSE)");
+        check(&g, 4.000025218680635, "LOGNORM.INV(0.039084, 3.5, 1.2)");
+
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "NORM.DIST(42, 40, 0, TRUE)",
+        );
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "NORM.S.INV(0)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "NORM.INV(1, 0, 1)");
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "LOGNORM.DIST(0, 0, 1, TRUE)",
+        );
+    }
+
+    #[test]
+    fn test_formula_t_distribution() {
+        let g = Grid::new();
+
+        check(&g, 0.9946953263673767, "T.DIST(60, 1, TRUE)");
+        check(&g, 0.0007369065209469263, "T.DIST(8, 3, FALSE)");
+        // Degrees of freedom are truncated.
+        check(&g, 0.020496109292876447, "T.DIST(-2.5, 7.9, TRUE)");
+        check(&g, 0.054644929975920874, "T.DIST.2T(1.959999998, 60)");
+        check(&g, 0.027322464987960437, "T.DIST.RT(1.959999998, 60)");
+        check(&g, 0.816496580927726, "T.INV(0.75, 2)");
+        check(&g, -1.3721836411103356, "T.INV(0.1, 10)");
+        check(&g, 0.606533075825755, "T.INV.2T(0.546449, 60)");
+        check(&g, 0.2841968554957299, "CONFIDENCE.T(0.05, 1, 50)");
+        check(&g, 0.692951912174839, "CONFIDENCE.NORM(0.05, 2.5, 50)");
+
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "T.DIST(1, 0.5, TRUE)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "T.DIST.2T(-1, 5)");
+        expect_err(&RunErrorMsg::DivideByZero, &g, "CONFIDENCE.T(0.05, 1, 1)");
+    }
+
+    #[test]
+    fn test_formula_chisq_f_distributions() {
+        let g = Grid::new();
+
+        check(&g, 0.5204998778130465, "CHISQ.DIST(0.5, 1, TRUE)");
+        check(&g, 0.20755374871029736, "CHISQ.DIST(2, 3, FALSE)");
+        check(&g, 0.0500005890913981, "CHISQ.DIST.RT(18.307, 10)");
+        check(&g, 3.2830202867595357, "CHISQ.INV(0.93, 1)");
+        check(&g, 1.83258146374831, "CHISQ.INV(0.6, 2)");
+        check(&g, 18.306973456961057, "CHISQ.INV.RT(0.050001, 10)");
+
+        check(&g, 0.9900000430027627, "F.DIST(15.2069, 6, 4, TRUE)");
+        check(&g, 0.0012237917087831731, "F.DIST(15.2069, 6, 4, FALSE)");
+        check(&g, 0.009999956997237311, "F.DIST.RT(15.2069, 6, 4)");
+        check(&g, 0.10930991412457854, "F.INV(0.01, 6, 4)");
+        check(&g, 15.20686486115753, "F.INV.RT(0.01, 6, 4)");
+
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "CHISQ.DIST(-1, 2, TRUE)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "F.INV(1, 6, 4)");
+    }
+
+    #[test]
+    fn test_formula_discrete_distributions() {
+        let g = Grid::new();
+
+        check(&g, 0.205078125, "BINOM.DIST(6, 10, 0.5, FALSE)");
+        check(&g, 0.9894079216, "BINOM.DIST(6, 10, 0.3, TRUE)");
+        assert_eq!("4", eval_to_string(&g, "BINOM.INV(6, 0.5, 0.75)"));
+        assert_eq!("0", eval_to_string(&g, "BINOM.INV(6, 0.5, 0)"));
+        assert_eq!("6", eval_to_string(&g, "BINOM.INV(6, 0.5, 1)"));
+        check(&g, 0.12465201948308113, "POISSON.DIST(2, 5, TRUE)");
+        check(&g, 0.08422433748856833, "POISSON.DIST(2, 5, FALSE)");
+        check(&g, 0.46542827657378744, "HYPGEOM.DIST(1, 4, 8, 20, TRUE)");
+        check(&g, 0.3632610939112487, "HYPGEOM.DIST(1, 4, 8, 20, FALSE)");
+
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "BINOM.DIST(11, 10, 0.5, FALSE)",
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "POISSON.DIST(-1, 5, TRUE)",
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "HYPGEOM.DIST(5, 4, 8, 20, TRUE)",
+        );
+    }
+
+    #[test]
+    fn test_formula_continuous_distributions() {
+        let g = Grid::new();
+
+        check(&g, 0.8646647167633873, "EXPON.DIST(0.2, 10, TRUE)");
+        check(&g, 1.3533528323661268, "EXPON.DIST(0.2, 10, FALSE)");
+        check(
+            &g,
+            0.032639130418294,
+            "GAMMA.DIST(10.00001131, 9, 2, FALSE)",
+        );
+        check(
+            &g,
+            0.06809400386978733,
+            "GAMMA.DIST(10.00001131, 9, 2, TRUE)",
+        );
+        check(&g, 10.000011191437178, "GAMMA.INV(0.068094, 9, 2)");
+        check(&g, 1.791759469228055, "GAMMALN(4)");
+        check(&g, 0.5723649429247001, "GAMMALN(0.5)");
+        check(&g, 0.6854705810546875, "BETA.DIST(2, 8, 10, TRUE, 1, 3)");
+        check(&g, 1.4837646484375, "BETA.DIST(2, 8, 10, FALSE, 1, 3)");
+        check(&g, 0.6260990336999411, "BETA.DIST(0.2, 0.5, 2, TRUE)");
+        check(&g, 1.9999999999631426, "BETA.INV(0.685470581, 8, 10, 1, 3)");
+        check(&g, 0.9295813900692768, "WEIBULL.DIST(105, 20, 100, TRUE)");
+        check(&g, 0.03558886402450438, "WEIBULL.DIST(105, 20, 100, FALSE)");
+
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "GAMMALN(0)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "EXPON.DIST(1, 0, TRUE)");
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "BETA.DIST(4, 8, 10, TRUE, 1, 3)",
+        );
+    }
+
+    #[test]
+    fn test_formula_hypothesis_tests() {
+        let g = Grid::new();
+
+        let (a, b) = (
+            "{3, 4, 5, 8, 9, 1, 2, 4, 5}",
+            "{6, 19, 3, 2, 14, 4, 5, 17, 1}",
+        );
+        check(&g, 0.1960157849252821, &format!("T.TEST({a}, {b}, 2, 1)"));
+        check(&g, 0.09800789246264105, &format!("T.TEST({a}, {b}, 1, 1)"));
+        check(&g, 0.19199588676039622, &format!("T.TEST({a}, {b}, 2, 2)"));
+        check(&g, 0.20229392336867788, &format!("T.TEST({a}, {b}, 2, 3)"));
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            &format!("T.TEST({a}, {b}, 3, 1)"),
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            &format!("T.TEST({a}, {b}, 2, 4)"),
+        );
+
+        check(
+            &g,
+            0.6483178467861745,
+            "F.TEST({6, 7, 9, 15, 21}, {20, 28, 31, 38, 40})",
+        );
+        check(
+            &g,
+            0.0003081920170083089,
+            "CHISQ.TEST({58, 35; 11, 25; 10, 23}, \
+             {45.35, 47.65; 17.56, 18.44; 16.09, 16.91})",
+        );
+
+        let z = "{3, 6, 7, 8, 6, 5, 4, 2, 1, 9}";
+        check(&g, 0.09057419685136377, &format!("Z.TEST({z}, 4)"));
+        check(&g, 0.8630433891295299, &format!("Z.TEST({z}, 6)"));
+        check(&g, 0.04099516050019149, &format!("Z.TEST({z}, 4, 2)"));
+        expect_err(&RunErrorMsg::DivideByZero, &g, "Z.TEST({5, 5, 5}, 4)");
+    }
+}
@@ -0,0 +1,1145 @@
+use std::f64::consts::{LN_10, LN_2};
+use std::ops::{Add, Mul, Sub};
+
+use bigdecimal::BigDecimal;
+
+use super::mathematics::MAX_EXACT_INTEGER;
+use super::special::{bessel_i, bessel_j, bessel_k, bessel_y, erf, erfc};
+use super::*;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Engineering functions",
+    docs: "Binary, octal, and hexadecimal numbers are written as text with at \
+           most 10 digits. Negative numbers are written with exactly 10 \
+           digits using two's complement, so `DEC2BIN(-1)` is \
+           `\"1111111111\"`.\
+           \n\n\
+           Complex numbers are written as text in the form `a+bi` or `a+bj`, \
+           such as `\"3+4i\"`, `\"-2.5j\"`, or `\"7\"`. Functions that \
+           return a complex number use the same suffix as their arguments.\
+           \n\n",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        // Base conversion
+        formula_fn!(
+            /// Converts a binary number to decimal.
+            #[examples("BIN2DEC(\"1100100\")", "BIN2DEC(1111111111)")]
+            #[zip_map]
+            fn BIN2DEC(span: Span, [number]: String) {
+                parse_signed(*span, &number, 2)?
+            }
+        ),
+        formula_fn!(
+            /// Converts a binary number to hexadecimal.
+            ///
+            /// If `places` is given, then the result is padded with zeros to
+            /// that many digits. `places` is ignored for negative numbers.
+            #[examples("BIN2HEX(\"11111011\", 4)", "BIN2HEX(1110)")]
+            #[zip_map]
+            fn BIN2HEX(span: Span, [number]: String, [places]: (Option<f64>)) {
+                let n = parse_signed(*span, &number, 2)?;
+                format_signed(*span, n as f64, 16, places)?
+            }
+        ),
+        formula_fn!(
+            /// Converts a binary number to octal.
+            ///
+            /// If `places` is given, then the result is padded with zeros to
+            /// that many digits. `places` is ignored for negative numbers.
+            #[examples("BIN2OCT(\"1001\", 3)", "BIN2OCT(1100100)")]
+            #[zip_map]
+            fn BIN2OCT(span: Span, [number]: String, [places]: (Option<f64>)) {
+                let n = parse_signed(*span, &number, 2)?;
+                format_signed(*span, n as f64, 8, places)?
+            }
+        ),
+        formula_fn!(
+            /// Converts a decimal number from -512 to 511 to binary.
+            ///
+            /// If `places` is given, then the result is padded with zeros to
+            /// that many digits. `places` is ignored for negative numbers.
+            #[examples("DEC2BIN(9, 4)", "DEC2BIN(-100)")]
+            #[zip_map]
+            fn DEC2BIN(span: Span, [number]: f64, [places]: (Option<f64>)) {
+                format_signed(*span, number, 2, places)?
+            }
+        ),
+        formula_fn!(
+            /// Converts a decimal number from -2^39 to 2^39-1 to hexadecimal.
+            ///
+            /// If `places` is given, then the result is padded with zeros to
+            /// that many digits. `places` is ignored for negative numbers.
+            #[examples("DEC2HEX(100, 4)", "DEC2HEX(-54)")]
+            #[zip_map]
+            fn DEC2HEX(span: Span, [number]: f64, [places]: (Option<f64>)) {
+                format_signed(*span, number, 16, places)?
+            }
+        ),
+        formula_fn!(
+            /// Converts a decimal number from -2^29 to 2^29-1 to octal.
+            ///
+            /// If `places` is given, then the result is padded with zeros to
+            /// that many digits. `places` is ignored for negative numbers.
+            #[examples("DEC2OCT(58, 3)", "DEC2OCT(-100)")]
+            #[zip_map]
+            fn DEC2OCT(span: Span, [number]: f64, [places]: (Option<f64>)) {
+                format_signed(*span, number, 8, places)?
+            }
+        ),
+        formula_fn!(
+            /// Converts a hexadecimal number to binary.
+            ///
+            /// If `places` is given, then the result is padded with zeros to
+            /// that many digits. `places` is ignored for negative numbers.
+            #[examples("HEX2BIN(\"F\", 8)", "HEX2BIN(\"B7\")")]
+            #[zip_map]
+            fn HEX2BIN(span: Span, [number]: String, [places]: (Option<f64>)) {
+                let n = parse_signed(*span, &number, 16)?;
+                format_signed(*span, n as f64, 2, places)?
+            }
+        ),
+        formula_fn!(
+            /// Converts a hexadecimal number to decimal.
+            #[examples("HEX2DEC(\"A5\")", "HEX2DEC(\"FFFFFFFF5B\")")]
+            #[zip_map]
+            fn HEX2DEC(span: Span, [number]: String) {
+                parse_signed(*span, &number, 16)?
+            }
+        ),
+        formula_fn!(
+            /// Converts a hexadecimal number to octal.
+            ///
+            /// If `places` is given, then the result is padded with zeros to
+            /// that many digits. `places` is ignored for negative numbers.
+            #[examples("HEX2OCT(\"F\", 3)", "HEX2OCT(\"3B4E\")")]
+            #[zip_map]
+            fn HEX2OCT(span: Span, [number]: String, [places]: (Option<f64>)) {
+                let n = parse_signed(*span, &number, 16)?;
+                format_signed(*span, n as f64, 8, places)?
+            }
+        ),
+        formula_fn!(
+            /// Converts an octal number to binary.
+            ///
+            /// If `places` is given, then the result is padded with zeros to
+            /// that many digits. `places` is ignored for negative numbers.
+            #[examples("OCT2BIN(\"3\", 3)", "OCT2BIN(7777777000)")]
+            #[zip_map]
+            fn OCT2BIN(span: Span, [number]: String, [places]: (Option<f64>)) {
+                let n = parse_signed(*span, &number, 8)?;
+                format_signed(*span, n as f64, 2, places)?
+            }
+        ),
+        formula_fn!(
+            /// Converts an octal number to decimal.
+            #[examples("OCT2DEC(\"54\")", "OCT2DEC(7777777533)")]
+            #[zip_map]
+            fn OCT2DEC(span: Span, [number]: String) {
+                parse_signed(*span, &number, 8)?
+            }
+        ),
+        formula_fn!(
+            /// Converts an octal number to hexadecimal.
+            ///
+            /// If `places` is given, then the result is padded with zeros to
+            /// that many digits. `places` is ignored for negative numbers.
+            #[examples("OCT2HEX(\"100\", 4)", "OCT2HEX(7777777533)")]
+            #[zip_map]
+            fn OCT2HEX(span: Span, [number]: String, [places]: (Option<f64>)) {
+                let n = parse_signed(*span, &number, 8)?;
+                format_signed(*span, n as f64, 16, places)?
+            }
+        ),
+        formula_fn!(
+            /// Converts a nonnegative integer to text in the given `radix`,
+            /// which must be from 2 to 36.
+            ///
+            /// If `min_length` is given, then the result is padded with zeros
+            /// to at least that many digits.
+            #[examples("BASE(7, 2)", "BASE(255, 16, 4)")]
+            #[zip_map]
+            fn BASE(span: Span, [number]: f64, [radix]: f64, [min_length]: (Option<f64>)) {
+                let number = number.trunc();
+                let radix = radix.trunc();
+                let min_length = min_length.unwrap_or(0.0).trunc();
+                util::ensure(*span, (0.0..MAX_EXACT_INTEGER).contains(&number))?;
+                util::ensure(*span, (2.0..=36.0).contains(&radix))?;
+                util::ensure(*span, (0.0..=255.0).contains(&min_length))?;
+                let digits = format_radix(number as u64, radix as u32);
+                format!("{digits:0>width$}", width = min_length as usize)
+            }
+        ),
+        formula_fn!(
+            /// Converts text in the given `radix` to a number. `radix` must be
+            /// from 2 to 36.
+            ///
+            /// Letters are case-insensitive.
+            #[examples("DECIMAL(\"FF\", 16)", "DECIMAL(111, 2)")]
+            #[zip_map]
+            fn DECIMAL(span: Span, [text]: String, [radix]: f64) {
+                let radix = radix.trunc();
+                util::ensure(*span, (2.0..=36.0).contains(&radix))?;
+                let mut number = 0.0;
+                for c in text.chars() {
+                    let digit = c
+                        .to_digit(radix as u32)
+                        .ok_or(RunErrorMsg::InvalidArgument.with_span(*span))?;
+                    number = number * radix + digit as f64;
+                }
+                util::ensure(*span, number < MAX_EXACT_INTEGER)?;
+                number
+            }
+        ),
+        // Bitwise operations
+        formula_fn!(
+            /// Returns the bitwise AND of two numbers.
+            ///
+            /// Both numbers must be integers from 0 to 2^48-1.
+            #[examples("BITAND(13, 25)")]
+            #[zip_map]
+            fn BITAND(span: Span, [number1]: f64, [number2]: f64) {
+                (bit_operand(*span, number1)? & bit_operand(*span, number2)?) as i64
+            }
+        ),
+        formula_fn!(
+            /// Returns the bitwise OR of two numbers.
+            ///
+            /// Both numbers must be integers from 0 to 2^48-1.
+            #[examples("BITOR(23, 10)")]
+            #[zip_map]
+            fn BITOR(span: Span, [number1]: f64, [number2]: f64) {
+                (bit_operand(*span, number1)? | bit_operand(*span, number2)?) as i64
+            }
+        ),
+        formula_fn!(
+            /// Returns the bitwise XOR of two numbers.
+            ///
+            /// Both numbers must be integers from 0 to 2^48-1.
+            #[examples("BITXOR(5, 3)")]
+            #[zip_map]
+            fn BITXOR(span: Span, [number1]: f64, [number2]: f64) {
+                (bit_operand(*span, number1)? ^ bit_operand(*span, number2)?) as i64
+            }
+        ),
+        formula_fn!(
+            /// Shifts the bits of a number left by `shift_amount`, or right if
+            /// `shift_amount` is negative.
+            ///
+            /// `number` must be an integer from 0 to 2^48-1, and so must the
+            /// result. `shift_amount` must be from -53 to 53.
+            #[examples("BITLSHIFT(4, 2)")]
+            #[zip_map]
+            fn BITLSHIFT(span: Span, [number]: f64, [shift_amount]: f64) {
+                bit_shift_left(*span, number, shift_amount.trunc())?
+            }
+        ),
+        formula_fn!(
+            /// Shifts the bits of a number right by `shift_amount`, or left if
+            /// `shift_amount` is negative.
+            ///
+            /// `number` must be an integer from 0 to 2^48-1, and so must the
+            /// result. `shift_amount` must be from -53 to 53.
+            #[examples("BITRSHIFT(13, 2)")]
+            #[zip_map]
+            fn BITRSHIFT(span: Span, [number]: f64, [shift_amount]: f64) {
+                bit_shift_left(*span, number, -shift_amount.trunc())?
+            }
+        ),
+        // Comparison
+        formula_fn!(
+            /// Returns 1 if two numbers are equal, and 0 otherwise.
+            ///
+            /// If `number2` is omitted, then `number1` is compared to zero.
+            #[examples("DELTA(5, 4)", "SUM(DELTA(A1:A10, 3))")]
+            #[zip_map]
+            fn DELTA([number1]: f64, [number2]: (Option<f64>)) {
+                i64::from(number1 == number2.unwrap_or(0.0))
+            }
+        ),
+        formula_fn!(
+            /// Returns 1 if `number` is greater than or equal to `step`, and 0
+            /// otherwise.
+            ///
+            /// If `step` is omitted, then `number` is compared to zero.
+            #[examples("GESTEP(5, 4)", "SUM(GESTEP(A1:A10, 100))")]
+            #[zip_map]
+            fn GESTEP([number]: f64, [step]: (Option<f64>)) {
+                i64::from(number >= step.unwrap_or(0.0))
+            }
+        ),
+        // Error function
+        formula_fn!(
+            /// Returns the error function integrated between `lower_limit` and
+            /// `upper_limit`.
+            ///
+            /// If `upper_limit` is omitted, then the error function is
+            /// integrated between zero and `lower_limit`.
+            #[examples("ERF(0.745)", "ERF(1, 2)")]
+            #[zip_map]
+            fn ERF([lower_limit]: f64, [upper_limit]: (Option<f64>)) {
+                match upper_limit {
+                    Some(upper_limit) => erf(upper_limit) - erf(lower_limit),
+                    None => erf(lower_limit),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the error function integrated between zero and `x`.
+            #[examples("ERF.PRECISE(0.745)")]
+            #[zip_map]
+            fn "ERF.PRECISE"([x]: f64) {
+                erf(x)
+            }
+        ),
+        formula_fn!(
+            /// Returns the complementary error function integrated between `x`
+            /// and infinity.
+            #[examples("ERFC(1)")]
+            #[zip_map]
+            fn ERFC([x]: f64) {
+                erfc(x)
+            }
+        ),
+        formula_fn!(
+            /// Returns the complementary error function integrated between `x`
+            /// and infinity.
+            #[examples("ERFC.PRECISE(1)")]
+            #[zip_map]
+            fn "ERFC.PRECISE"([x]: f64) {
+                erfc(x)
+            }
+        ),
+        // Bessel functions
+        formula_fn!(
+            /// Returns the Bessel function Jₙ(x).
+            ///
+            /// `n` is truncated to an integer and must be from 0 to 1000.
+            #[examples("BESSELJ(1.9, 2)")]
+            #[zip_map]
+            fn BESSELJ(span: Span, [x]: f64, [n]: f64) {
+                let n = bessel_order(*span, n)?;
+                util::finite(*span, bessel_j(n, x))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the Bessel function Yₙ(x), also called the Weber
+            /// function or the Neumann function.
+            ///
+            /// `x` must be positive. `n` is truncated to an integer and must
+            /// be from 0 to 1000.
+            #[examples("BESSELY(2.5, 1)")]
+            #[zip_map]
+            fn BESSELY(span: Span, [x]: f64, [n]: f64) {
+                let n = bessel_order(*span, n)?;
+                util::ensure(*span, x > 0.0)?;
+                util::finite(*span, bessel_y(n, x))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the modified Bessel function Iₙ(x).
+            ///
+            /// `n` is truncated to an integer and must be from 0 to 1000.
+            #[examples("BESSELI(1.5, 1)")]
+            #[zip_map]
+            fn BESSELI(span: Span, [x]: f64, [n]: f64) {
+                let n = bessel_order(*span, n)?;
+                util::finite(*span, bessel_i(n, x))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the modified Bessel function Kₙ(x).
+            ///
+            /// `x` must be positive. `n` is truncated to an integer and must
+            /// be from 0 to 1000.
+            #[examples("BESSELK(1.5, 1)")]
+            #[zip_map]
+            fn BESSELK(span: Span, [x]: f64, [n]: f64) {
+                let n = bessel_order(*span, n)?;
+                util::ensure(*span, x > 0.0)?;
+                util::finite(*span, bessel_k(n, x))?
+            }
+        ),
+        // Complex numbers
+        formula_fn!(
+            /// Returns the complex number `real_num + i_num*i` as text.
+            ///
+            /// `suffix` is the letter used for the imaginary unit, which must
+            /// be `"i"` (the default) or `"j"`.
+            #[examples("COMPLEX(3, 4)", "COMPLEX(0, 1, \"j\")")]
+            #[zip_map]
+            fn COMPLEX(span: Span, [real_num]: f64, [i_num]: f64, [suffix]: (Option<String>)) {
+                let suffix = match suffix.as_deref() {
+                    None | Some("" | "i") => 'i',
+                    Some("j") => 'j',
+                    Some(_) => return Err(RunErrorMsg::InvalidArgument.with_span(*span)),
+                };
+                format_complex(*span, Complex::new(real_num, i_num), suffix)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the real part of a complex number.
+            #[examples("IMREAL(\"6-9i\")")]
+            #[zip_map]
+            fn IMREAL(span: Span, [inumber]: String) {
+                parse_complex(*span, &inumber)?.0.re
+            }
+        ),
+        formula_fn!(
+            /// Returns the imaginary part of a complex number.
+            #[examples("IMAGINARY(\"3+4i\")")]
+            #[zip_map]
+            fn IMAGINARY(span: Span, [inumber]: String) {
+                parse_complex(*span, &inumber)?.0.im
+            }
+        ),
+        formula_fn!(
+            /// Returns the absolute value of a complex number.
+            #[examples("IMABS(\"5+12i\")")]
+            #[zip_map]
+            fn IMABS(span: Span, [inumber]: String) {
+                parse_complex(*span, &inumber)?.0.abs()
+            }
+        ),
+        formula_fn!(
+            /// Returns the argument of a complex number, which is the angle in
+            /// radians from the positive real axis, from -π to π.
+            #[examples("IMARGUMENT(\"3+4i\")")]
+            #[zip_map]
+            fn IMARGUMENT(span: Span, [inumber]: String) {
+                let z = parse_complex(*span, &inumber)?.0;
+                if z == Complex::default() {
+                    return Err(RunErrorMsg::DivideByZero.with_span(*span));
+                }
+                z.arg()
+            }
+        ),
+        formula_fn!(
+            /// Returns the complex conjugate of a complex number.
+            #[examples("IMCONJUGATE(\"3+4i\")")]
+            #[zip_map]
+            fn IMCONJUGATE(span: Span, [inumber]: String) {
+                map_complex(*span, &inumber, |z| Ok(Complex::new(z.re, -z.im)))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the sum of complex numbers.
+            #[examples("IMSUM(\"3+4i\", \"5-3i\")", "IMSUM(A1:A10)")]
+            fn IMSUM(span: Span, inumbers: (Iter<String>)) {
+                let (numbers, suffix) = parse_all_complex(span, inumbers)?;
+                let sum = numbers.into_iter().fold(Complex::default(), |a, b| a + b);
+                format_complex(span, sum, suffix)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the difference of two complex numbers.
+            #[examples("IMSUB(\"13+4i\", \"5+3i\")")]
+            #[zip_map]
+            fn IMSUB(span: Span, [inumber1]: String, [inumber2]: String) {
+                let (z1, suffix1) = parse_complex(*span, &inumber1)?;
+                let (z2, suffix2) = parse_complex(*span, &inumber2)?;
+                format_complex(*span, z1 - z2, common_suffix(*span, [suffix1, suffix2])?)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the product of complex numbers.
+            #[examples("IMPRODUCT(\"3+4i\", \"5-3i\")", "IMPRODUCT(A1:A10)")]
+            fn IMPRODUCT(span: Span, inumbers: (Iter<String>)) {
+                let (numbers, suffix) = parse_all_complex(span, inumbers)?;
+                let product = numbers
+                    .into_iter()
+                    .fold(Complex::new(1.0, 0.0), |a, b| a * b);
+                format_complex(span, product, suffix)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the quotient of two complex numbers.
+            #[examples("IMDIV(\"-238+240i\", \"10+24i\")")]
+            #[zip_map]
+            fn IMDIV(span: Span, [inumber1]: String, [inumber2]: String) {
+                let (z1, suffix1) = parse_complex(*span, &inumber1)?;
+                let (z2, suffix2) = parse_complex(*span, &inumber2)?;
+                let quotient = z1.checked_div(*span, z2)?;
+                format_complex(*span, quotient, common_suffix(*span, [suffix1, suffix2])?)?
+            }
+        ),
+        formula_fn!(
+            /// Raises a complex number to a real power.
+            #[examples("IMPOWER(\"2+3i\", 3)")]
+            #[zip_map]
+            fn IMPOWER(span: Span, [inumber]: String, [number]: f64) {
+                map_complex(*span, &inumber, |z| Ok(z.powf(number)))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the square root of a complex number.
+            #[examples("IMSQRT(\"1+i\")")]
+            #[zip_map]
+            fn IMSQRT(span: Span, [inumber]: String) {
+                map_complex(*span, &inumber, |z| Ok(z.powf(0.5)))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the exponential of a complex number.
+            #[examples("IMEXP(\"1+i\")")]
+            #[zip_map]
+            fn IMEXP(span: Span, [inumber]: String) {
+                map_complex(*span, &inumber, |z| Ok(z.exp()))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the natural logarithm of a complex number.
+            #[examples("IMLN(\"3+4i\")")]
+            #[zip_map]
+            fn IMLN(span: Span, [inumber]: String) {
+                map_complex(*span, &inumber, |z| Ok(z.ln()))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the base-10 logarithm of a complex number.
+            #[examples("IMLOG10(\"3+4i\")")]
+            #[zip_map]
+            fn IMLOG10(span: Span, [inumber]: String) {
+                map_complex(*span, &inumber, |z| Ok(z.ln().scale(1.0 / LN_10)))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the base-2 logarithm of a complex number.
+            #[examples("IMLOG2(\"3+4i\")")]
+            #[zip_map]
+            fn IMLOG2(span: Span, [inumber]: String) {
+                map_complex(*span, &inumber, |z| Ok(z.ln().scale(1.0 / LN_2)))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the sine of a complex number.
+            #[examples("IMSIN(\"4+3i\")")]
+            #[zip_map]
+            fn IMSIN(span: Span, [inumber]: String) {
+                map_complex(*span, &inumber, |z| Ok(z.sin()))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the cosine of a complex number.
+            #[examples("IMCOS(\"1+i\")")]
+            #[zip_map]
+            fn IMCOS(span: Span, [inumber]: String) {
+                map_complex(*span, &inumber, |z| Ok(z.cos()))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the tangent of a complex number.
+            #[examples("IMTAN(\"4+3i\")")]
+            #[zip_map]
+            fn IMTAN(span: Span, [inumber]: String) {
+                map_complex(*span, &inumber, |z| z.sin().checked_div(*span, z.cos()))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the secant of a complex number.
+            #[examples("IMSEC(\"4+3i\")")]
+            #[zip_map]
+            fn IMSEC(span: Span, [inumber]: String) {
+                map_complex(*span, &inumber, |z| z.cos().recip(*span))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the cosecant of a complex number.
+            #[examples("IMCSC(\"4+3i\")")]
+            #[zip_map]
+            fn IMCSC(span: Span, [inumber]: String) {
+                map_complex(*span, &inumber, |z| z.sin().recip(*span))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the cotangent of a complex number.
+            #[examples("IMCOT(\"4+3i\")")]
+            #[zip_map]
+            fn IMCOT(span: Span, [inumber]: String) {
+                map_complex(*span, &inumber, |z| z.cos().checked_div(*span, z.sin()))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the hyperbolic sine of a complex number.
+            #[examples("IMSINH(\"4+3i\")")]
+            #[zip_map]
+            fn IMSINH(span: Span, [inumber]: String) {
+                map_complex(*span, &inumber, |z| Ok(z.sinh()))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the hyperbolic cosine of a complex number.
+            #[examples("IMCOSH(\"4+3i\")")]
+            #[zip_map]
+            fn IMCOSH(span: Span, [inumber]: String) {
+                map_complex(*span, &inumber, |z| Ok(z.cosh()))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the hyperbolic secant of a complex number.
+            #[examples("IMSECH(\"4+3i\")")]
+            #[zip_map]
+            fn IMSECH(span: Span, [inumber]: String) {
+                map_complex(*span, &inumber, |z| z.cosh().recip(*span))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the hyperbolic cosecant of a complex number.
+            #[examples("IMCSCH(\"4+3i\")")]
+            #[zip_map]
+            fn IMCSCH(span: Span, [inumber]: String) {
+                map_complex(*span, &inumber, |z| z.sinh().recip(*span))?
+            }
+        ),
+    ]
+}
+
+/// Maximum number of digits in a binary, octal, or hexadecimal number.
+const MAX_DIGITS: u32 = 10;
+
+/// Largest number allowed in bitwise functions, plus one.
+const BIT_LIMIT: u64 = 1 << 48;
+
+/// Largest order allowed in Bessel functions.
+const MAX_BESSEL_ORDER: f64 = 1000.0;
+
+/// Number of significant digits in each part of a complex number formatted
+/// as text.
+const COMPLEX_PRECISION: u64 = 15;
+
+/// Parses a binary, octal, or hexadecimal number with at most 10 digits.
+/// Numbers with 10 digits whose first digit is at least half of `radix` are
+/// negative, using two's complement.
+fn parse_signed(span: Span, text: &str, radix: u32) -> CodeResult<i64> {
+    util::ensure(span, text.len() <= MAX_DIGITS as usize)?;
+    util::ensure(span, text.chars().all(|c| c.is_digit(radix)))?;
+    if text.is_empty() {
+        return Ok(0);
+    }
+    let n = i64::from_str_radix(text, radix)
+        .map_err(|_| RunErrorMsg::InvalidArgument.with_span(span))?;
+    let modulus = (radix as i64).pow(MAX_DIGITS);
+    if n >= modulus / 2 {
+        Ok(n - modulus)
+    } else {
+        Ok(n)
+    }
+}
+
+/// Formats a number in binary, octal, or hexadecimal. Negative numbers are
+/// formatted with 10 digits using two's complement, and other numbers are
+/// padded with zeros to `places` digits.
+fn format_signed(span: Span, number: f64, radix: u32, places: Option<f64>) -> CodeResult<String> {
+    let modulus = (radix as i64).pow(MAX_DIGITS);
+    let n = number.trunc();
+    util::ensure(
+        span,
+        (-(modulus / 2) as f64..(modulus / 2) as f64).contains(&n),
+    )?;
+    let n = n as i64;
+    if n < 0 {
+        return Ok(format_radix((n + modulus) as u64, radix));
+    }
+    let digits = format_radix(n as u64, radix);
+    match places {
+        None => Ok(digits),
+        Some(places) => {
+            let places = places.trunc();
+            util::ensure(span, (1.0..=MAX_DIGITS as f64).contains(&places))?;
+            util::ensure(span, digits.len() <= places as usize)?;
+            Ok(format!("{digits:0>width$}", width = places as usize))
+        }
+    }
+}
+
+/// Formats a nonnegative integer in `radix` using uppercase letters.
+fn format_radix(mut n: u64, radix: u32) -> String {
+    let mut digits = vec![];
+    loop {
+        let digit = (n % radix as u64) as u32;
+        digits.push(std::char::from_digit(digit, radix).unwrap_or('0'));
+        n /= radix as u64;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect::<String>().to_ascii_uppercase()
+}
+
+/// Returns an argument to a bitwise function, which must be an integer from
+/// 0 to 2^48-1.
+fn bit_operand(span: Span, number: f64) -> CodeResult<u64> {
+    util::ensure(span, number.fract() == 0.0)?;
+    util::ensure(span, (0.0..BIT_LIMIT as f64).contains(&number))?;
+    Ok(number as u64)
+}
+
+/// Shifts the bits of a number left by `shift`, or right if `shift` is
+/// negative.
+fn bit_shift_left(span: Span, number: f64, shift: f64) -> CodeResult<i64> {
+    let n = bit_operand(span, number)?;
+    util::ensure(span, shift.abs() <= 53.0)?;
+    let result = if shift >= 0.0 {
+        (n as u128) << shift as u32
+    } else {
+        (n >> -shift as u32) as u128
+    };
+    util::ensure(span, result < BIT_LIMIT as u128)?;
+    Ok(result as i64)
+}
+
+/// Returns the order of a Bessel function, truncated to an integer.
+fn bessel_order(span: Span, n: f64) -> CodeResult<u32> {
+    let n = n.trunc();
+    util::ensure(span, (0.0..=MAX_BESSEL_ORDER).contains(&n))?;
+    Ok(n as u32)
+}
+
+/// Complex number `re + im*i`.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+impl Add for Complex {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.re + other.re, self.im + other.im)
+    }
+}
+impl Sub for Complex {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.re - other.re, self.im - other.im)
+    }
+}
+impl Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+impl Complex {
+    fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+    fn from_polar(r: f64, theta: f64) -> Self {
+        Self::new(r * theta.cos(), r * theta.sin())
+    }
+
+    fn abs(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+    fn arg(self) -> f64 {
+        self.im.atan2(self.re)
+    }
+    fn scale(self, factor: f64) -> Self {
+        Self::new(self.re * factor, self.im * factor)
+    }
+
+    /// Divides two complex numbers, returning an error if `other` is zero.
+    fn checked_div(self, span: Span, other: Self) -> CodeResult<Self> {
+        let denominator = other.re * other.re + other.im * other.im;
+        if denominator == 0.0 {
+            return Err(RunErrorMsg::DivideByZero.with_span(span));
+        }
+        Ok(Self::new(
+            (self.re * other.re + self.im * other.im) / denominator,
+            (self.im * other.re - self.re * other.im) / denominator,
+        ))
+    }
+    fn recip(self, span: Span) -> CodeResult<Self> {
+        Self::new(1.0, 0.0).checked_div(span, self)
+    }
+
+    fn powf(self, n: f64) -> Self {
+        Self::from_polar(self.abs().powf(n), self.arg() * n)
+    }
+    fn exp(self) -> Self {
+        Self::from_polar(self.re.exp(), self.im)
+    }
+    fn ln(self) -> Self {
+        Self::new(self.abs().ln(), self.arg())
+    }
+
+    fn sin(self) -> Self {
+        Self::new(
+            self.re.sin() * self.im.cosh(),
+            self.re.cos() * self.im.sinh(),
+        )
+    }
+    fn cos(self) -> Self {
+        Self::new(
+            self.re.cos() * self.im.cosh(),
+            -self.re.sin() * self.im.sinh(),
+        )
+    }
+    fn sinh(self) -> Self {
+        Self::new(
+            self.re.sinh() * self.im.cos(),
+            self.re.cosh() * self.im.sin(),
+        )
+    }
+    fn cosh(self) -> Self {
+        Self::new(
+            self.re.cosh() * self.im.cos(),
+            self.re.sinh() * self.im.sin(),
+        )
+    }
+}
+
+/// Parses a complex number written as text, such as `3+4i`, `-2.5j`, or `7`,
+/// and returns the number along with the suffix used for the imaginary unit.
+/// An empty string is zero.
+fn parse_complex(span: Span, text: &str) -> CodeResult<(Complex, Option<char>)> {
+    let invalid = || RunErrorMsg::InvalidArgument.with_span(span);
+
+    let Some(body) = text.strip_suffix(['i', 'j']) else {
+        let re = match text {
+            "" => 0.0,
+            _ => parse_real(text).ok_or_else(invalid)?,
+        };
+        return Ok((Complex::new(re, 0.0), None));
+    };
+    let suffix = text.chars().last();
+
+    // The imaginary part starts at the last sign that isn't part of an
+    // exponent.
+    let split = body
+        .char_indices()
+        .rev()
+        .find(|&(i, c)| matches!(c, '+' | '-') && !body[..i].ends_with(['e', 'E']))
+        .map_or(0, |(i, _)| i);
+    let (re, im) = body.split_at(split);
+    let re = match re {
+        "" => 0.0,
+        _ => parse_real(re).ok_or_else(invalid)?,
+    };
+    let im = match im {
+        "" | "+" => 1.0,
+        "-" => -1.0,
+        _ => parse_real(im).ok_or_else(invalid)?,
+    };
+    Ok((Complex::new(re, im), suffix))
+}
+
+/// Parses a real number, such as `-2.5` or `1e-3`.
+fn parse_real(text: &str) -> Option<f64> {
+    let is_valid_char = |c: char| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-');
+    if !text.chars().all(is_valid_char) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+/// Parses complex numbers, and returns them along with the suffix to use for
+/// the result.
+fn parse_all_complex(
+    span: Span,
+    inumbers: impl IntoIterator<Item = CodeResult<String>>,
+) -> CodeResult<(Vec<Complex>, char)> {
+    let mut numbers = vec![];
+    let mut suffixes = vec![];
+    for inumber in inumbers {
+        let (z, suffix) = parse_complex(span, &inumber?)?;
+        numbers.push(z);
+        suffixes.push(suffix);
+    }
+    Ok((numbers, common_suffix(span, suffixes)?))
+}
+
+/// Returns the suffix for the imaginary unit in a result, which is `i` unless
+/// the arguments use `j`. Returns an error if the arguments use different
+/// suffixes.
+fn common_suffix(span: Span, suffixes: impl IntoIterator<Item = Option<char>>) -> CodeResult<char> {
+    let mut result = None;
+    for suffix in suffixes.into_iter().flatten() {
+        match result {
+            Some(existing) if existing != suffix => {
+                return Err(RunErrorMsg::InvalidArgument.with_span(span));
+            }
+            _ => result = Some(suffix),
+        }
+    }
+    Ok(result.unwrap_or('i'))
+}
+
+/// Applies a function to a complex number written as text, and formats the
+/// result using the same suffix.
+fn map_complex(
+    span: Span,
+    inumber: &str,
+    f: impl FnOnce(Complex) -> CodeResult<Complex>,
+) -> CodeResult<String> {
+    let (z, suffix) = parse_complex(span, inumber)?;
+    format_complex(span, f(z)?, suffix.unwrap_or('i'))
+}
+
+/// Formats a complex number as text, such as `3+4i`.
+fn format_complex(span: Span, z: Complex, suffix: char) -> CodeResult<String> {
+    let re = format_real(span, z.re)?;
+    let im = format_real(span, z.im)?;
+    Ok(match (re.as_str(), im.as_str()) {
+        (_, "0") => re,
+        ("0", "1") => suffix.to_string(),
+        ("0", "-1") => format!("-{suffix}"),
+        ("0", _) => format!("{im}{suffix}"),
+        (_, "1") => format!("{re}+{suffix}"),
+        (_, "-1") => format!("{re}-{suffix}"),
+        (_, _) if im.starts_with('-') => format!("{re}{im}{suffix}"),
+        (_, _) => format!("{re}+{im}{suffix}"),
+    })
+}
+
+/// Formats one part of a complex number, rounded to 15 significant digits.
+fn format_real(span: Span, x: f64) -> CodeResult<String> {
+    let x = util::finite(span, x)?;
+    let decimal = BigDecimal::try_from(x).map_err(|_| RunErrorMsg::NotANumber.with_span(span))?;
+    Ok(util::round_to_precision(decimal, COMPLEX_PRECISION).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formulas::tests::*;
+    use crate::util::assert_f64_approx_eq;
+
+    #[test]
+    fn test_base_conversion() {
+        let g = Grid::new();
+        assert_eq!("100", eval_to_string(&g, "BIN2DEC(1100100)"));
+        assert_eq!("-1", eval_to_string(&g, "BIN2DEC(\"1111111111\")"));
+        assert_eq!("0", eval_to_string(&g, "BIN2DEC(\"\")"));
+        assert_eq!("00FB", eval_to_string(&g, "BIN2HEX(11111011, 4)"));
+        assert_eq!("E", eval_to_string(&g, "BIN2HEX(1110)"));
+        assert_eq!("FFFFFFFFFF", eval_to_string(&g, "BIN2HEX(1111111111)"));
+        assert_eq!("011", eval_to_string(&g, "BIN2OCT(1001, 3)"));
+        assert_eq!("7777777777", eval_to_string(&g, "BIN2OCT(1111111111)"));
+
+        assert_eq!("1001", eval_to_string(&g, "DEC2BIN(9, 4)"));
+        assert_eq!("1110011100", eval_to_string(&g, "DEC2BIN(-100)"));
+        assert_eq!("0064", eval_to_string(&g, "DEC2HEX(100, 4)"));
+        assert_eq!("FFFFFFFFCA", eval_to_string(&g, "DEC2HEX(-54)"));
+        assert_eq!("1C", eval_to_string(&g, "DEC2HEX(28.9)"));
+        assert_eq!("072", eval_to_string(&g, "DEC2OCT(58, 3)"));
+        assert_eq!("7777777634", eval_to_string(&g, "DEC2OCT(-100)"));
+
+        assert_eq!("00001111", eval_to_string(&g, "HEX2BIN(\"F\", 8)"));
+        assert_eq!("1000000000", eval_to_string(&g, "HEX2BIN(\"FFFFFFFE00\")"));
+        assert_eq!("165", eval_to_string(&g, "HEX2DEC(\"a5\")"));
+        assert_eq!("-165", eval_to_string(&g, "HEX2DEC(\"FFFFFFFF5B\")"));
+        assert_eq!("1034160313", eval_to_string(&g, "HEX2DEC(\"3DA408B9\")"));
+        assert_eq!("35516", eval_to_string(&g, "HEX2OCT(\"3B4E\")"));
+        assert_eq!("7777777400", eval_to_string(&g, "HEX2OCT(\"FFFFFFFF00\")"));
+
+        assert_eq!("011", eval_to_string(&g, "OCT2BIN(3, 3)"));
+        assert_eq!("1000000000", eval_to_string(&g, "OCT2BIN(7777777000)"));
+        assert_eq!("44", eval_to_string(&g, "OCT2DEC(54)"));
+        assert_eq!("-165", eval_to_string(&g, "OCT2DEC(7777777533)"));
+        assert_eq!("0040", eval_to_string(&g, "OCT2HEX(100, 4)"));
+        assert_eq!("FFFFFFFF5B", eval_to_string(&g, "OCT2HEX(7777777533)"));
+
+        for formula in [
+            "BIN2DEC(12)",
+            "BIN2DEC(\"11111111111\")",
+            "HEX2DEC(\" A5\")",
+            "HEX2BIN(\"200\")",
+            "DEC2BIN(512)",
+            "DEC2BIN(-513)",
+            "DEC2HEX(64, 1)",
+            "DEC2HEX(64, 11)",
+            "OCT2BIN(\"-7\")",
+        ] {
+            expect_err(&RunErrorMsg::InvalidArgument, &g, formula);
+        }
+    }
+
+    #[test]
+    fn test_base_decimal() {
+        let g = Grid::new();
+        assert_eq!("111", eval_to_string(&g, "BASE(7, 2)"));
+        assert_eq!("64", eval_to_string(&g, "BASE(100, 16)"));
+        assert_eq!("0000001111", eval_to_string(&g, "BASE(15, 2, 10)"));
+        assert_eq!("ZZ", eval_to_string(&g, "BASE(1295, 36)"));
+        assert_eq!("0", eval_to_string(&g, "BASE(0, 10)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "BASE(-1, 2)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "BASE(7, 37)");
+
+        assert_eq!("255", eval_to_string(&g, "DECIMAL(\"FF\", 16)"));
+        assert_eq!("7", eval_to_string(&g, "DECIMAL(111, 2)"));
+        assert_eq!("45745", eval_to_string(&g, "DECIMAL(\"zap\", 36)"));
+        assert_eq!("0", eval_to_string(&g, "DECIMAL(\"\", 2)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "DECIMAL(12, 2)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "DECIMAL(\"1\", 1)");
+    }
+
+    #[test]
+    fn test_bitwise() {
+        let g = Grid::new();
+        assert_eq!("9", eval_to_string(&g, "BITAND(13, 25)"));
+        assert_eq!("31", eval_to_string(&g, "BITOR(23, 10)"));
+        assert_eq!("6", eval_to_string(&g, "BITXOR(5, 3)"));
+        assert_eq!("16", eval_to_string(&g, "BITLSHIFT(4, 2)"));
+        assert_eq!("1", eval_to_string(&g, "BITLSHIFT(4, -2)"));
+        assert_eq!("3", eval_to_string(&g, "BITRSHIFT(13, 2)"));
+        assert_eq!("52", eval_to_string(&g, "BITRSHIFT(13, -2)"));
+        assert_eq!("0", eval_to_string(&g, "BITRSHIFT(13, 53)"));
+        assert_eq!("281474976710655", eval_to_string(&g, "BITOR(2^48-1, 0)"),);
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "BITAND(1.5, 1)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "BITAND(-1, 1)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "BITXOR(2^48, 1)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "BITLSHIFT(2^47, 1)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "BITLSHIFT(1, 54)");
+    }
+
+    #[test]
+    fn test_delta_gestep() {
+        let g = Grid::new();
+        assert_eq!("0", eval_to_string(&g, "DELTA(5, 4)"));
+        assert_eq!("1", eval_to_string(&g, "DELTA(5, 5)"));
+        assert_eq!("1", eval_to_string(&g, "DELTA(0)"));
+        assert_eq!("0", eval_to_string(&g, "DELTA(0.5)"));
+        assert_eq!("1", eval_to_string(&g, "GESTEP(5, 4)"));
+        assert_eq!("1", eval_to_string(&g, "GESTEP(5, 5)"));
+        assert_eq!("1", eval_to_string(&g, "GESTEP(-4, -5)"));
+        assert_eq!("0", eval_to_string(&g, "GESTEP(-1)"));
+        assert_eq!("{0, 1, 1}", eval_to_string(&g, "GESTEP({1, 2, 3}, 2)"));
+    }
+
+    #[test]
+    fn test_erf() {
+        let g = Grid::new();
+        assert_f64_approx_eq(0.707928920, &eval_to_string(&g, "ERF(0.745)"));
+        assert_f64_approx_eq(0.842700793, &eval_to_string(&g, "ERF(1)"));
+        assert_f64_approx_eq(-0.842700793, &eval_to_string(&g, "ERF(-1)"));
+        assert_f64_approx_eq(0.152621472, &eval_to_string(&g, "ERF(1, 2)"));
+        assert_f64_approx_eq(0.707928920, &eval_to_string(&g, "ERF.PRECISE(0.745)"));
+        assert_f64_approx_eq(0.157299207, &eval_to_string(&g, "ERFC(1)"));
+        assert_f64_approx_eq(1.842700793, &eval_to_string(&g, "ERFC.PRECISE(-1)"));
+        assert_eq!("0", eval_to_string(&g, "ERF(0)"));
+    }
+
+    #[test]
+    fn test_bessel() {
+        let g = Grid::new();
+        assert_f64_approx_eq(0.329925728, &eval_to_string(&g, "BESSELJ(1.9, 2)"));
+        assert_f64_approx_eq(-0.113423407, &eval_to_string(&g, "BESSELJ(-1.9, 3)"));
+        assert_f64_approx_eq(0.145918138, &eval_to_string(&g, "BESSELY(2.5, 1)"));
+        assert_f64_approx_eq(0.981666428, &eval_to_string(&g, "BESSELI(1.5, 1)"));
+        assert_f64_approx_eq(0.277387804, &eval_to_string(&g, "BESSELK(1.5, 1)"));
+        assert_f64_approx_eq(0.765197687, &eval_to_string(&g, "BESSELJ(1, 0)"));
+        assert_f64_approx_eq(0.261140546, &eval_to_string(&g, "BESSELJ(5, 5)"));
+        assert_f64_approx_eq(-0.219602686, &eval_to_string(&g, "BESSELJ(10, 4)"));
+        assert_f64_approx_eq(0.215720776, &eval_to_string(&g, "BESSELY(12, 2)"));
+        assert_f64_approx_eq(17.50561497, &eval_to_string(&g, "BESSELI(5, 2)"));
+        assert_f64_approx_eq(0.012483499, &eval_to_string(&g, "BESSELK(4, 1)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "BESSELJ(1, -1)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "BESSELY(0, 1)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "BESSELK(-1, 1)");
+    }
+
+    #[test]
+    fn test_complex_parts() {
+        let g = Grid::new();
+        assert_eq!("3+4i", eval_to_string(&g, "COMPLEX(3, 4)"));
+        assert_eq!("3+4j", eval_to_string(&g, "COMPLEX(3, 4, \"j\")"));
+        assert_eq!("1.5-2.25i", eval_to_string(&g, "COMPLEX(1.5, -2.25)"));
+        assert_eq!("i", eval_to_string(&g, "COMPLEX(0, 1)"));
+        assert_eq!("-i", eval_to_string(&g, "COMPLEX(0, -1)"));
+        assert_eq!("2-i", eval_to_string(&g, "COMPLEX(2, -1)"));
+        assert_eq!("1", eval_to_string(&g, "COMPLEX(1, 0)"));
+        assert_eq!("0", eval_to_string(&g, "COMPLEX(0, 0)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "COMPLEX(1, 1, \"k\")");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "COMPLEX(1, 1, \"I\")");
+
+        assert_eq!("6", eval_to_string(&g, "IMREAL(\"6-9i\")"));
+        assert_eq!("-9", eval_to_string(&g, "IMAGINARY(\"6-9i\")"));
+        assert_eq!("-1", eval_to_string(&g, "IMAGINARY(\"0-j\")"));
+        assert_eq!("1", eval_to_string(&g, "IMAGINARY(\"i\")"));
+        assert_eq!("0", eval_to_string(&g, "IMAGINARY(4)"));
+        assert_eq!("1000", eval_to_string(&g, "IMREAL(\"1e3+2i\")"));
+        assert_eq!("-0.001", eval_to_string(&g, "IMAGINARY(\"1-1E-3i\")"));
+        assert_eq!("200000", eval_to_string(&g, "IMAGINARY(\"1e+5+2e+5i\")"));
+        assert_eq!("13", eval_to_string(&g, "IMABS(\"5+12i\")"));
+        assert_f64_approx_eq(0.927295218, &eval_to_string(&g, "IMARGUMENT(\"3+4i\")"));
+        expect_err(&RunErrorMsg::DivideByZero, &g, "IMARGUMENT(\"0\")");
+        assert_eq!("3-4i", eval_to_string(&g, "IMCONJUGATE(\"3+4i\")"));
+        assert_eq!("-3+4j", eval_to_string(&g, "IMCONJUGATE(\"-3-4j\")"));
+
+        for formula in [
+            "IMREAL(\"abc\")",
+            "IMREAL(\"3+4k\")",
+            "IMREAL(\"3+4ii\")",
+            "IMREAL(\"3 + 4i\")",
+            "IMREAL(\"inf\")",
+            "IMREAL(TRUE)",
+        ] {
+            expect_err(&RunErrorMsg::InvalidArgument, &g, formula);
+        }
+    }
+
+    #[test]
+    fn test_complex_arithmetic() {
+        let g = Grid::new();
+        assert_eq!("8+i", eval_to_string(&g, "IMSUM(\"3+4i\", \"5-3i\")"));
+        assert_eq!("8+j", eval_to_string(&g, "IMSUM(\"3+4j\", \"5-3j\", 0)"));
+        assert_eq!("8+i", eval_to_string(&g, "IMSUB(\"13+4i\", \"5+3i\")"));
+        assert_eq!(
+            "27+11i",
+            eval_to_string(&g, "IMPRODUCT(\"3+4i\", \"5-3i\")")
+        );
+        assert_eq!("30+60i", eval_to_string(&g, "IMPRODUCT(\"1+2i\", 30)"));
+        assert_eq!(
+            "5+12i",
+            eval_to_string(&g, "IMDIV(\"-238+240i\", \"10+24i\")")
+        );
+        assert_eq!("0.3", eval_to_string(&g, "IMSUM(\"0.1\", \"0.2\")"));
+        expect_err(&RunErrorMsg::DivideByZero, &g, "IMDIV(\"1+i\", \"0\")");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "IMSUM(\"1+i\", \"1+j\")");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "IMSUB(\"1+i\", \"1+j\")");
+
+        assert_eq!("3-2i", eval_to_string(&g, "IMSUM({\"1+i\"; \"2-3i\"})"));
+        assert_eq!("{1, 2}", eval_to_string(&g, "IMREAL({\"1+i\", \"2-3i\"})"));
+    }
+
+    #[test]
+    fn test_complex_functions() {
+        let g = Grid::new();
+        let check = |formula: &str, re: f64, im: f64| {
+            let z = format!("\"{}\"", eval_to_string(&g, formula));
+            assert_f64_approx_eq(re, &eval_to_string(&g, &format!("IMREAL({z})")));
+            assert_f64_approx_eq(im, &eval_to_string(&g, &format!("IMAGINARY({z})")));
+        };
+        check("IMPOWER(\"2+3i\", 3)", -46.0, 9.0);
+        check("IMPOWER(\"4\", 0.5)", 2.0, 0.0);
+        check("IMSQRT(\"1+i\")", 1.098684113, 0.455089861);
+        check("IMSQRT(\"-4\")", 0.0, 2.0);
+        check("IMEXP(\"1+i\")", 1.46869394, 2.287355287);
+        check("IMLN(\"3+4i\")", 1.609437912, 0.927295218);
+        check("IMLOG10(\"3+4i\")", 0.698970004, 0.402719196);
+        check("IMLOG2(\"3+4i\")", 2.321928095, 1.337804212);
+        check("IMSIN(\"4+3i\")", -7.61923172, -6.548120041);
+        check("IMCOS(\"1+i\")", 0.833730025, -0.988897706);
+        check("IMTAN(\"4+3i\")", 0.004908258, 1.000709536);
+        check("IMSEC(\"4+3i\")", -0.065294028, -0.07522496);
+        check("IMCSC(\"4+3i\")", -0.075489833, 0.064877471);
+        check("IMCOT(\"4+3i\")", 0.004901182, -0.999266928);
+        check("IMSINH(\"4+3i\")", -27.01681326, 3.853738038);
+        check("IMCOSH(\"4+3i\")", -27.0349456, 3.851153335);
+        check("IMSECH(\"4+3i\")", -0.036253497, -0.005164345);
+        check("IMCSCH(\"4+3i\")", -0.03627589, -0.005174473);
+
+        assert_eq!("-1.5j", eval_to_string(&g, "IMCONJUGATE(\"1.5j\")"));
+        expect_err(&RunErrorMsg::Infinity, &g, "IMLN(\"0\")");
+        expect_err(&RunErrorMsg::DivideByZero, &g, "IMCSC(\"0\")");
+    }
+}
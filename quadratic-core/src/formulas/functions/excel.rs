@@ -20,8 +20,8 @@ lazy_static! {
       EXCEL_FUNCTIONS_LIST.iter().cloned().collect::<HashSet<&'static str>>()
   };
 
-  // regex to remove _xlfn. _xludf. prefix from the function name
-  static ref PREFIX_RE: regex::Regex = regex::Regex::new(r"^_xl(?:fn|udf)\.").unwrap();
+  // regex to remove _xlfn. _xludf. _xlws. prefixes from the function name
+  static ref PREFIX_RE: regex::Regex = regex::Regex::new(r"^(?:_xl(?:fn|udf|ws)\.)+").unwrap();
 }
 
 const EXCEL_FUNCTIONS_LIST: [&str; 512] = [
@@ -0,0 +1,1029 @@
+use super::*;
+use crate::{Instant, SECONDS_PER_DAY};
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Financial functions",
+    docs: "Money paid out (such as a loan payment or an investment) is \
+           negative, and money received (such as a loan or a dividend) is \
+           positive. Interest rates are per period, so an annual rate of 6% \
+           with monthly payments is `6%/12`.\
+           \n\n\
+           Where `payment_type` is an argument, `FALSE` or `0` (the default) \
+           means that payments are due at the end of each period, and `TRUE` \
+           or `1` means that they are due at the beginning.\
+           \n\n\
+           `IRR`, `XIRR`, and `RATE` are computed by iteration starting from \
+           `guess`. If no solution is found, they return an error; try a \
+           different `guess`.\
+           \n\n",
+    get_functions,
+};
+
+/// Maximum number of iterations for solvers such as `IRR`.
+const MAX_ITERATIONS: usize = 100;
+/// Relative tolerance at which solvers such as `IRR` stop iterating.
+const TOLERANCE: f64 = 1e-12;
+/// Initial guess for solvers such as `IRR`.
+const DEFAULT_GUESS: f64 = 0.1;
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        // Time value of money
+        formula_fn!(
+            /// Returns the present value of an investment or loan with periodic
+            /// constant payments and a constant interest rate.
+            #[examples("PV(5%/12, 60, -500)", "PV(A1, B1, C1, D1, TRUE)")]
+            #[zip_map]
+            fn PV(
+                span: Span,
+                [rate]: f64,
+                [periods]: f64,
+                [payment]: f64,
+                [future_value]: (Option<f64>),
+                [payment_type]: (Option<bool>),
+            ) {
+                let fv = future_value.unwrap_or(0.0);
+                let at_start = payment_type.unwrap_or(false);
+                util::finite(*span, present_value(rate, periods, payment, fv, at_start))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the future value of an investment or loan with periodic
+            /// constant payments and a constant interest rate.
+            #[examples("FV(5%/12, 60, -500)", "FV(A1, B1, C1, D1, TRUE)")]
+            #[zip_map]
+            fn FV(
+                span: Span,
+                [rate]: f64,
+                [periods]: f64,
+                [payment]: f64,
+                [present_value]: (Option<f64>),
+                [payment_type]: (Option<bool>),
+            ) {
+                let pv = present_value.unwrap_or(0.0);
+                let at_start = payment_type.unwrap_or(false);
+                util::finite(*span, future_value(rate, periods, payment, pv, at_start))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the periodic payment for a loan or investment with a
+            /// constant interest rate.
+            #[examples("PMT(5%/12, 60, 25000)", "PMT(A1, B1, C1, D1, TRUE)")]
+            #[zip_map]
+            fn PMT(
+                span: Span,
+                [rate]: f64,
+                [periods]: f64,
+                [present_value]: f64,
+                [future_value]: (Option<f64>),
+                [payment_type]: (Option<bool>),
+            ) {
+                let fv = future_value.unwrap_or(0.0);
+                let at_start = payment_type.unwrap_or(false);
+                util::finite(*span, payment(rate, periods, present_value, fv, at_start))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the interest portion of the payment for period `period`
+            /// of a loan or investment with periodic constant payments and a
+            /// constant interest rate.
+            ///
+            /// `period` ranges from 1 to `periods`.
+            #[examples("IPMT(5%/12, 1, 60, 25000)", "IPMT(A1, B1, C1, D1)")]
+            #[zip_map]
+            fn IPMT(
+                span: Span,
+                [rate]: f64,
+                [period]: f64,
+                [periods]: f64,
+                [present_value]: f64,
+                [future_value]: (Option<f64>),
+                [payment_type]: (Option<bool>),
+            ) {
+                if !(1.0..=periods).contains(&period) {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                let fv = future_value.unwrap_or(0.0);
+                let at_start = payment_type.unwrap_or(false);
+                let interest = interest_payment(rate, period, periods, present_value, fv, at_start);
+                util::finite(*span, interest)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the principal portion of the payment for period `period`
+            /// of a loan or investment with periodic constant payments and a
+            /// constant interest rate.
+            ///
+            /// `period` ranges from 1 to `periods`.
+            #[examples("PPMT(5%/12, 1, 60, 25000)", "PPMT(A1, B1, C1, D1)")]
+            #[zip_map]
+            fn PPMT(
+                span: Span,
+                [rate]: f64,
+                [period]: f64,
+                [periods]: f64,
+                [present_value]: f64,
+                [future_value]: (Option<f64>),
+                [payment_type]: (Option<bool>),
+            ) {
+                if !(1.0..=periods).contains(&period) {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                let fv = future_value.unwrap_or(0.0);
+                let at_start = payment_type.unwrap_or(false);
+                let total = payment(rate, periods, present_value, fv, at_start);
+                let interest = interest_payment(rate, period, periods, present_value, fv, at_start);
+                util::finite(*span, total - interest)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of periods for a loan or investment with
+            /// periodic constant payments and a constant interest rate.
+            #[examples("NPER(5%/12, -500, 25000)", "NPER(A1, B1, C1, D1, TRUE)")]
+            #[zip_map]
+            fn NPER(
+                span: Span,
+                [rate]: f64,
+                [payment]: f64,
+                [present_value]: f64,
+                [future_value]: (Option<f64>),
+                [payment_type]: (Option<bool>),
+            ) {
+                let fv = future_value.unwrap_or(0.0);
+                let at_start = payment_type.unwrap_or(false);
+                let periods = if rate == 0.0 {
+                    util::checked_div(span, -(present_value + fv), payment)?
+                } else {
+                    let z = payment * (1.0 + rate * start_factor(at_start)) / rate;
+                    ((z - fv) / (present_value + z)).ln() / rate.ln_1p()
+                };
+                util::finite(*span, periods)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the interest rate per period of a loan or investment
+            /// with periodic constant payments.
+            ///
+            /// The rate is found by iteration starting from `guess`, which
+            /// defaults to 10%. Returns an error if no rate is found.
+            #[examples("RATE(60, -500, 25000)", "RATE(A1, B1, C1, D1, TRUE, 1%)")]
+            #[zip_map]
+            fn RATE(
+                span: Span,
+                [periods]: f64,
+                [payment]: f64,
+                [present_value]: f64,
+                [future_value]: (Option<f64>),
+                [payment_type]: (Option<bool>),
+                [guess]: (Option<f64>),
+            ) {
+                let fv = future_value.unwrap_or(0.0);
+                let at_start = payment_type.unwrap_or(false);
+                // The balance at the end of the last period, which should be
+                // zero.
+                let balance = |rate: f64| {
+                    -self::future_value(rate, periods, payment, present_value, at_start) + fv
+                };
+                solve(*span, guess.unwrap_or(DEFAULT_GUESS), |rate| {
+                    // Use a central difference for the derivative.
+                    let h = 1e-7 * rate.abs().max(1.0);
+                    let derivative = (balance(rate + h) - balance(rate - h)) / (2.0 * h);
+                    (balance(rate), derivative)
+                })?
+            }
+        ),
+        formula_fn!(
+            /// Returns the cumulative interest paid on a loan between periods
+            /// `start_period` and `end_period`, including both.
+            ///
+            /// `rate`, `periods`, and `present_value` must be positive, and
+            /// periods range from 1 to `periods`.
+            #[examples("CUMIPMT(5%/12, 60, 25000, 1, 12, 0)")]
+            #[zip_map]
+            fn CUMIPMT(
+                span: Span,
+                [rate]: f64,
+                [periods]: f64,
+                [present_value]: f64,
+                [start_period]: i64,
+                [end_period]: i64,
+                [payment_type]: bool,
+            ) {
+                let range = cumulative_periods(
+                    *span,
+                    rate,
+                    periods,
+                    present_value,
+                    start_period,
+                    end_period,
+                )?;
+                let interest = range
+                    .map(|period| {
+                        interest_payment(
+                            rate,
+                            period as f64,
+                            periods,
+                            present_value,
+                            0.0,
+                            payment_type,
+                        )
+                    })
+                    .sum::<f64>();
+                util::finite(*span, interest)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the cumulative principal paid on a loan between periods
+            /// `start_period` and `end_period`, including both.
+            ///
+            /// `rate`, `periods`, and `present_value` must be positive, and
+            /// periods range from 1 to `periods`.
+            #[examples("CUMPRINC(5%/12, 60, 25000, 1, 12, 0)")]
+            #[zip_map]
+            fn CUMPRINC(
+                span: Span,
+                [rate]: f64,
+                [periods]: f64,
+                [present_value]: f64,
+                [start_period]: i64,
+                [end_period]: i64,
+                [payment_type]: bool,
+            ) {
+                let range = cumulative_periods(
+                    *span,
+                    rate,
+                    periods,
+                    present_value,
+                    start_period,
+                    end_period,
+                )?;
+                let total = payment(rate, periods, present_value, 0.0, payment_type);
+                let principal = range
+                    .map(|period| {
+                        total
+                            - interest_payment(
+                                rate,
+                                period as f64,
+                                periods,
+                                present_value,
+                                0.0,
+                                payment_type,
+                            )
+                    })
+                    .sum::<f64>();
+                util::finite(*span, principal)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the effective annual interest rate from a nominal annual
+            /// interest rate compounded `periods_per_year` times per year.
+            #[examples("EFFECT(5%, 12)")]
+            #[zip_map]
+            fn EFFECT(span: Span, [nominal_rate]: f64, [periods_per_year]: i64) {
+                if nominal_rate <= 0.0 || periods_per_year < 1 {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                let n = periods_per_year as f64;
+                ((nominal_rate / n).ln_1p() * n).exp_m1()
+            }
+        ),
+        formula_fn!(
+            /// Returns the nominal annual interest rate compounded
+            /// `periods_per_year` times per year from an effective annual
+            /// interest rate.
+            #[examples("NOMINAL(5%, 12)")]
+            #[zip_map]
+            fn NOMINAL(span: Span, [effective_rate]: f64, [periods_per_year]: i64) {
+                if effective_rate <= 0.0 || periods_per_year < 1 {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                let n = periods_per_year as f64;
+                (effective_rate.ln_1p() / n).exp_m1() * n
+            }
+        ),
+        // Cash flows
+        formula_fn!(
+            /// Returns the net present value of a series of cash flows, given a
+            /// discount rate per period.
+            ///
+            /// The first cash flow is discounted by one period; to include a
+            /// cash flow at the start of the first period, add it to the
+            /// result.
+            #[examples("NPV(8%, B1:B10)", "A1 + NPV(8%, B1:B10)")]
+            fn NPV(span: Span, rate: f64, cash_flows: (Iter<f64>)) {
+                let mut npv = 0.0;
+                let mut discount = 1.0;
+                for cash_flow in cash_flows {
+                    discount *= 1.0 + rate;
+                    npv += util::checked_div(span, cash_flow?, discount)?;
+                }
+                npv
+            }
+        ),
+        formula_fn!(
+            /// Returns the net present value of a series of cash flows at
+            /// irregular dates, given an annual discount rate.
+            ///
+            /// `cash_flows` and `dates` must be the same size. Cash flows are
+            /// discounted from the first date, assuming a 365-day year.
+            #[examples("XNPV(8%, B1:B10, A1:A10)")]
+            fn XNPV(span: Span, rate: f64, cash_flows: (Spanned<Array>), dates: (Spanned<Array>)) {
+                if rate <= -1.0 {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                let cash_flows = dated_cash_flows(&cash_flows, &dates)?;
+                util::finite(span, xnpv(rate, &cash_flows).0)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the internal rate of return of a series of cash flows at
+            /// regular intervals: the discount rate at which their net present
+            /// value is zero.
+            ///
+            /// `cash_flows` must contain at least one positive and one negative
+            /// value. Blank cells and text in `cash_flows` are ignored.
+            ///
+            /// The rate is found by iteration starting from `guess`, which
+            /// defaults to 10%. Returns an error if no rate is found.
+            #[examples("IRR(A1:A10)", "IRR(A1:A10, -10%)")]
+            fn IRR(span: Span, cash_flows: (Spanned<Value>), guess: (Option<f64>)) {
+                let cash_flows = cash_flows
+                    .into_iter::<f64>()
+                    .without_spans()
+                    .collect::<CodeResult<Vec<f64>>>()?;
+                check_signs(span, cash_flows.iter().copied())?;
+                solve(span, guess.unwrap_or(DEFAULT_GUESS), |rate| {
+                    let mut npv = 0.0;
+                    let mut derivative = 0.0;
+                    for (i, cash_flow) in cash_flows.iter().enumerate() {
+                        let discount = (1.0 + rate).powi(i as i32);
+                        npv += cash_flow / discount;
+                        derivative -= i as f64 * cash_flow / (discount * (1.0 + rate));
+                    }
+                    (npv, derivative)
+                })?
+            }
+        ),
+        formula_fn!(
+            /// Returns the internal rate of return of a series of cash flows at
+            /// irregular dates: the annual discount rate at which their net
+            /// present value is zero.
+            ///
+            /// `cash_flows` and `dates` must be the same size, and
+            /// `cash_flows` must contain at least one positive and one negative
+            /// value.
+            ///
+            /// The rate is found by iteration starting from `guess`, which
+            /// defaults to 10%. Returns an error if no rate is found.
+            #[examples("XIRR(B1:B10, A1:A10)", "XIRR(B1:B10, A1:A10, 50%)")]
+            fn XIRR(
+                span: Span,
+                cash_flows: (Spanned<Array>),
+                dates: (Spanned<Array>),
+                guess: (Option<f64>),
+            ) {
+                let cash_flows = dated_cash_flows(&cash_flows, &dates)?;
+                check_signs(span, cash_flows.iter().map(|&(cash_flow, _)| cash_flow))?;
+                solve(span, guess.unwrap_or(DEFAULT_GUESS), |rate| {
+                    xnpv(rate, &cash_flows)
+                })?
+            }
+        ),
+        formula_fn!(
+            /// Returns the modified internal rate of return of a series of cash
+            /// flows at regular intervals, where negative cash flows are
+            /// financed at `finance_rate` and positive cash flows are
+            /// reinvested at `reinvest_rate`.
+            ///
+            /// `cash_flows` must contain at least one positive and one negative
+            /// value. Blank cells and text in `cash_flows` are ignored.
+            #[examples("MIRR(A1:A10, 8%, 5%)")]
+            fn MIRR(
+                span: Span,
+                cash_flows: (Spanned<Value>),
+                finance_rate: f64,
+                reinvest_rate: f64,
+            ) {
+                let cash_flows = cash_flows
+                    .into_iter::<f64>()
+                    .without_spans()
+                    .collect::<CodeResult<Vec<f64>>>()?;
+                let mut positive = 0.0;
+                let mut negative = 0.0;
+                for (i, &cash_flow) in cash_flows.iter().enumerate() {
+                    if cash_flow > 0.0 {
+                        positive += cash_flow / (1.0 + reinvest_rate).powi(i as i32);
+                    } else {
+                        negative += cash_flow / (1.0 + finance_rate).powi(i as i32);
+                    }
+                }
+                let n = cash_flows.len() as f64 - 1.0;
+                let ratio =
+                    util::checked_div(span, -positive * (1.0 + reinvest_rate).powf(n), negative)?;
+                if ratio == 0.0 {
+                    return Err(RunErrorMsg::DivideByZero.with_span(span));
+                }
+                util::finite(span, ratio.powf(n.recip()) - 1.0)?
+            }
+        ),
+        // Depreciation
+        formula_fn!(
+            /// Returns the straight-line depreciation of an asset for one
+            /// period.
+            #[examples("SLN(10000, 1000, 5)")]
+            #[zip_map]
+            fn SLN(span: Span, [cost]: f64, [salvage]: f64, [life]: f64) {
+                util::checked_div(span, cost - salvage, life)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the sum-of-years'-digits depreciation of an asset for
+            /// period `period`, which ranges from 1 to `life`.
+            #[examples("SYD(10000, 1000, 5, 1)")]
+            #[zip_map]
+            fn SYD(span: Span, [cost]: f64, [salvage]: f64, [life]: f64, [period]: f64) {
+                if life <= 0.0 || !(1.0..=life).contains(&period) {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                (cost - salvage) * (life - period + 1.0) * 2.0 / (life * (life + 1.0))
+            }
+        ),
+        formula_fn!(
+            /// Returns the fixed-declining-balance depreciation of an asset for
+            /// period `period`.
+            ///
+            /// `months` is the number of months in the first year, from 1 to 12
+            /// (the default). If `months` is less than 12, then the asset is
+            /// depreciated over `life + 1` periods.
+            #[examples("DB(10000, 1000, 5, 1)", "DB(10000, 1000, 5, 6, 3)")]
+            #[zip_map]
+            fn DB(
+                span: Span,
+                [cost]: f64,
+                [salvage]: f64,
+                [life]: i64,
+                [period]: i64,
+                [months]: (Option<i64>),
+            ) {
+                let months = months.unwrap_or(12);
+                let last_period = if months < 12 { life + 1 } else { life };
+                if cost <= 0.0
+                    || salvage < 0.0
+                    || life < 1
+                    || !(1..=12).contains(&months)
+                    || !(1..=last_period).contains(&period)
+                {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                // The rate is rounded to three decimal places, for
+                // compatibility with other spreadsheet applications.
+                let rate = ((1.0 - (salvage / cost).powf((life as f64).recip())) * 1000.0).round()
+                    / 1000.0;
+                let first_year_fraction = months as f64 / 12.0;
+                let mut total = 0.0;
+                let mut depreciation = 0.0;
+                for p in 1..=period {
+                    depreciation = if p == 1 {
+                        cost * rate * first_year_fraction
+                    } else if p == life + 1 {
+                        (cost - total) * rate * (1.0 - first_year_fraction)
+                    } else {
+                        (cost - total) * rate
+                    };
+                    total += depreciation;
+                }
+                depreciation
+            }
+        ),
+        formula_fn!(
+            /// Returns the declining-balance depreciation of an asset for
+            /// period `period`, which ranges from 1 to `life`.
+            ///
+            /// `factor` is the rate at which the balance declines, and defaults
+            /// to 2 (double-declining balance).
+            #[examples("DDB(10000, 1000, 5, 1)", "DDB(10000, 1000, 5, 1, 1.5)")]
+            #[zip_map]
+            fn DDB(
+                span: Span,
+                [cost]: f64,
+                [salvage]: f64,
+                [life]: f64,
+                [period]: f64,
+                [factor]: (Option<f64>),
+            ) {
+                let factor = factor.unwrap_or(2.0);
+                if cost < 0.0
+                    || salvage < 0.0
+                    || factor <= 0.0
+                    || life <= 0.0
+                    || !(1.0..=life).contains(&period)
+                {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                declining_balance(cost, salvage, life, period, factor)
+            }
+        ),
+        formula_fn!(
+            /// Returns the declining-balance depreciation of an asset from
+            /// `start_period` to `end_period`. Periods may be fractional.
+            ///
+            /// `factor` is the rate at which the balance declines, and defaults
+            /// to 2 (double-declining balance). Depreciation switches to
+            /// straight-line once that is greater, unless `no_switch` is
+            /// `TRUE`.
+            #[examples("VDB(10000, 1000, 5, 0, 1)", "VDB(10000, 1000, 5, 1.5, 3, 1.5)")]
+            #[zip_map]
+            fn VDB(
+                span: Span,
+                [cost]: f64,
+                [salvage]: f64,
+                [life]: f64,
+                [start_period]: f64,
+                [end_period]: f64,
+                [factor]: (Option<f64>),
+                [no_switch]: (Option<bool>),
+            ) {
+                let factor = factor.unwrap_or(2.0);
+                if cost < 0.0
+                    || salvage < 0.0
+                    || factor <= 0.0
+                    || start_period < 0.0
+                    || end_period < start_period
+                    || end_period > life
+                {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                let depreciation = if no_switch.unwrap_or(false) {
+                    let int_start = start_period.floor();
+                    let int_end = end_period.ceil();
+                    let mut total = 0.0;
+                    let mut p = int_start + 1.0;
+                    while p <= int_end {
+                        let mut term = declining_balance(cost, salvage, life, p, factor);
+                        if p == int_start + 1.0 {
+                            term *= end_period.min(int_start + 1.0) - start_period;
+                        } else if p == int_end {
+                            term *= end_period + 1.0 - int_end;
+                        }
+                        total += term;
+                        p += 1.0;
+                    }
+                    total
+                } else {
+                    let (mut start, mut end) = (start_period, end_period);
+                    if start.fract() != 0.0 && factor > 1.0 && start >= life / 2.0 {
+                        let past_half_life = start - life / 2.0;
+                        start = life / 2.0;
+                        end -= past_half_life;
+                    }
+                    let remaining_cost =
+                        cost - switching_balance(cost, salvage, life, life, start, factor);
+                    switching_balance(
+                        remaining_cost,
+                        salvage,
+                        life,
+                        life - start,
+                        end - start,
+                        factor,
+                    )
+                };
+                util::finite(*span, depreciation)?
+            }
+        ),
+    ]
+}
+
+/// Returns `1.0` if payments are due at the start of each period, or `0.0` if
+/// they are due at the end.
+fn start_factor(at_start: bool) -> f64 {
+    if at_start {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Returns the sum of the payments over `periods` periods, each compounded to
+/// the end of the last period.
+fn compounded_payments(rate: f64, periods: f64, payment: f64, at_start: bool) -> f64 {
+    if rate == 0.0 {
+        payment * periods
+    } else {
+        payment * (1.0 + rate * start_factor(at_start)) * (rate.ln_1p() * periods).exp_m1() / rate
+    }
+}
+
+fn future_value(rate: f64, periods: f64, payment: f64, present_value: f64, at_start: bool) -> f64 {
+    let growth = (1.0 + rate).powf(periods);
+    -(present_value * growth + compounded_payments(rate, periods, payment, at_start))
+}
+
+fn present_value(rate: f64, periods: f64, payment: f64, future_value: f64, at_start: bool) -> f64 {
+    let growth = (1.0 + rate).powf(periods);
+    -(future_value + compounded_payments(rate, periods, payment, at_start)) / growth
+}
+
+fn payment(rate: f64, periods: f64, present_value: f64, future_value: f64, at_start: bool) -> f64 {
+    let growth = (1.0 + rate).powf(periods);
+    -(future_value + present_value * growth) / compounded_payments(rate, periods, 1.0, at_start)
+}
+
+/// Returns the interest portion of the payment for `period`, which starts at 1.
+fn interest_payment(
+    rate: f64,
+    period: f64,
+    periods: f64,
+    present_value: f64,
+    future_value: f64,
+    at_start: bool,
+) -> f64 {
+    let payment = payment(rate, periods, present_value, future_value, at_start);
+    // Balance owed at the start of `period`, negated.
+    let balance = if period == 1.0 {
+        if at_start {
+            0.0
+        } else {
+            -present_value
+        }
+    } else if at_start {
+        self::future_value(rate, period - 2.0, payment, present_value, true) - payment
+    } else {
+        self::future_value(rate, period - 1.0, payment, present_value, false)
+    };
+    balance * rate
+}
+
+/// Validates the arguments to `CUMIPMT` or `CUMPRINC` and returns the range of
+/// periods to sum over.
+fn cumulative_periods(
+    span: Span,
+    rate: f64,
+    periods: f64,
+    present_value: f64,
+    start_period: i64,
+    end_period: i64,
+) -> CodeResult<std::ops::RangeInclusive<i64>> {
+    if rate <= 0.0
+        || periods <= 0.0
+        || present_value <= 0.0
+        || start_period < 1
+        || end_period < start_period
+        || end_period as f64 > periods
+    {
+        return Err(RunErrorMsg::InvalidArgument.with_span(span));
+    }
+    Ok(start_period..=end_period)
+}
+
+/// Returns an error unless there is at least one positive and one negative
+/// cash flow, since otherwise there is no internal rate of return.
+fn check_signs(span: Span, cash_flows: impl IntoIterator<Item = f64>) -> CodeResult<()> {
+    let (mut any_positive, mut any_negative) = (false, false);
+    for cash_flow in cash_flows {
+        any_positive |= cash_flow > 0.0;
+        any_negative |= cash_flow < 0.0;
+    }
+    match any_positive && any_negative {
+        true => Ok(()),
+        false => Err(RunErrorMsg::InvalidArgument.with_span(span)),
+    }
+}
+
+/// Returns pairs of cash flows and the number of years since the first date,
+/// assuming a 365-day year.
+fn dated_cash_flows(
+    cash_flows: &Spanned<Array>,
+    dates: &Spanned<Array>,
+) -> CodeResult<Vec<(f64, f64)>> {
+    if cash_flows.inner.size() != dates.inner.size() {
+        return Err(RunErrorMsg::ExactArraySizeMismatch {
+            expected: cash_flows.inner.size(),
+            got: dates.inner.size(),
+        }
+        .with_span(dates.span));
+    }
+
+    let mut first_day = None;
+    std::iter::zip(
+        cash_flows.inner.cell_values_slice(),
+        dates.inner.cell_values_slice(),
+    )
+    .map(|(cash_flow, date)| {
+        let cash_flow = Spanned {
+            inner: cash_flow,
+            span: cash_flows.span,
+        }
+        .try_coerce::<f64>()?
+        .inner;
+        let date = Spanned {
+            inner: date,
+            span: dates.span,
+        }
+        .try_coerce::<Instant>()?
+        .inner;
+        let day = (date.seconds / SECONDS_PER_DAY).floor();
+        let first_day = *first_day.get_or_insert(day);
+        Ok((cash_flow, (day - first_day) / 365.0))
+    })
+    .collect()
+}
+
+/// Returns the net present value of dated cash flows and its derivative with
+/// respect to `rate`.
+fn xnpv(rate: f64, cash_flows: &[(f64, f64)]) -> (f64, f64) {
+    let mut npv = 0.0;
+    let mut derivative = 0.0;
+    for &(cash_flow, years) in cash_flows {
+        let discount = (1.0 + rate).powf(years);
+        npv += cash_flow / discount;
+        derivative -= years * cash_flow / (discount * (1.0 + rate));
+    }
+    (npv, derivative)
+}
+
+/// Finds a rate at which `f` is zero using Newton's method, starting from
+/// `guess`. `f` returns the value of the function and its derivative.
+///
+/// Returns an error if the rate does not converge or drops to -100% or below.
+fn solve(span: Span, guess: f64, f: impl Fn(f64) -> (f64, f64)) -> CodeResult<f64> {
+    let mut rate = guess;
+    for _ in 0..MAX_ITERATIONS {
+        let (value, derivative) = f(rate);
+        let next = rate - value / derivative;
+        if !next.is_finite() || next <= -1.0 {
+            break;
+        }
+        if (next - rate).abs() <= TOLERANCE * next.abs().max(1.0) {
+            return Ok(next);
+        }
+        rate = next;
+    }
+    Err(RunErrorMsg::NoConvergence.with_span(span))
+}
+
+/// Returns the declining-balance depreciation for `period`.
+fn declining_balance(cost: f64, salvage: f64, life: f64, period: f64, factor: f64) -> f64 {
+    let rate = factor / life;
+    let (rate, old_value) = if rate >= 1.0 {
+        (1.0, if period == 1.0 { cost } else { 0.0 })
+    } else {
+        (rate, cost * (1.0 - rate).powf(period - 1.0))
+    };
+    let new_value = cost * (1.0 - rate).powf(period);
+    let depreciation = if new_value < salvage {
+        old_value - salvage
+    } else {
+        old_value - new_value
+    };
+    depreciation.max(0.0)
+}
+
+/// Returns the declining-balance depreciation over the first `periods`
+/// periods, switching to straight-line depreciation over the `remaining_life`
+/// once that is greater.
+fn switching_balance(
+    cost: f64,
+    salvage: f64,
+    life: f64,
+    remaining_life: f64,
+    periods: f64,
+    factor: f64,
+) -> f64 {
+    let int_end = periods.ceil();
+    let mut total = 0.0;
+    let mut remaining_value = cost - salvage;
+    let mut straight_line = None;
+    let mut p = 1.0;
+    while p <= int_end {
+        let mut term = match straight_line {
+            Some(straight_line) => straight_line,
+            None => {
+                let declining = declining_balance(cost, salvage, life, p, factor);
+                let straight = remaining_value / (remaining_life - (p - 1.0));
+                if straight > declining {
+                    straight_line = Some(straight);
+                    straight
+                } else {
+                    remaining_value -= declining;
+                    declining
+                }
+            }
+        };
+        if p == int_end {
+            term *= periods + 1.0 - int_end;
+        }
+        total += term;
+        p += 1.0;
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formulas::tests::*;
+    use crate::util::assert_f64_approx_eq;
+
+    #[test]
+    fn test_formula_time_value_of_money() {
+        let g = Grid::new();
+
+        let test_cases = [
+            ("PMT(8%/12, 10, 10000)", -1037.0320893591636),
+            ("PMT(0, 10, 10000)", -1000.0),
+            ("PMT(8%/12, 10, 10000, 0, TRUE)", -1030.1643271779772),
+            ("PV(8%/12, 240, 500)", -59777.14585118777),
+            ("PV(0, 10, -100, -50)", 1050.0),
+            ("FV(6%/12, 10, -200, -500, 1)", 2581.4033740601362),
+            ("FV(0, 10, -100)", 1000.0),
+            ("NPER(12%/12, -100, -1000, 10000, 1)", 59.67386567429457),
+            ("NPER(0, -100, 1000)", 10.0),
+            ("RATE(48, -200, 8000)", 0.007701472488202379),
+            ("RATE(10, 0, -100, 200)", 0.07177346253629317),
+            ("IPMT(10%/12, 1, 36, 8000)", -66.66666666666667),
+            ("IPMT(10%, 3, 3, 8000)", -292.4471299093658),
+            ("IPMT(10%, 1, 3, 8000, 0, 1)", 0.0),
+            ("IPMT(10%, 2, 3, 8000, 0, 1)", -507.5528700906347),
+            ("PPMT(10%/12, 1, 24, 2000)", -75.62318600836664),
+            ("PPMT(8%, 10, 10, 200000)", -27598.053462421365),
+            (
+                "CUMIPMT(9%/12, 360, 125000, 13, 24, 0)",
+                -11135.232130750845,
+            ),
+            ("CUMIPMT(9%/12, 360, 125000, 1, 1, 0)", -937.5),
+            (
+                "CUMPRINC(9%/12, 360, 125000, 13, 24, 0)",
+                -934.1071234208764,
+            ),
+            ("EFFECT(5.25%, 4)", 0.05354266737075819),
+            ("NOMINAL(5.3543%, 4)", 0.052500319868356016),
+        ];
+        for (formula, expected) in test_cases {
+            println!("Testing that {formula} = {expected}");
+            assert_f64_approx_eq(expected, &eval_to_string(&g, formula));
+        }
+
+        // Principal and interest add up to the payment.
+        assert_f64_approx_eq(
+            0.0,
+            &eval_to_string(
+                &g,
+                "PMT(8%/12, 10, 10000) - IPMT(8%/12, 4, 10, 10000) - PPMT(8%/12, 4, 10, 10000)",
+            ),
+        );
+
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "IPMT(10%, 0, 3, 8000)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "PPMT(10%, 4, 3, 8000)");
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "CUMIPMT(0, 10, 1000, 1, 2, 0)",
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "CUMPRINC(1%, 10, 1000, 3, 2, 0)",
+        );
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "EFFECT(5%, 0)");
+        expect_err(&RunErrorMsg::DivideByZero, &g, "NPER(0, 0, 1000)");
+        expect_err(&RunErrorMsg::NoConvergence, &g, "RATE(10, 100, 1000)");
+    }
+
+    #[test]
+    fn test_formula_cash_flows() {
+        let g = Grid::new();
+
+        let cash_flows = "{-70000; 12000; 15000; 18000; 21000; 26000}";
+        let test_cases = [
+            (
+                "NPV(10%, -10000, 3000, 4200, 6800)".to_string(),
+                1188.4434123352216,
+            ),
+            (
+                "NPV(10%, {-10000, 3000, \"\", 4200, 6800})".to_string(),
+                1188.4434123352216,
+            ),
+            (format!("IRR({cash_flows})"), 0.08663094803653162),
+            (
+                "IRR({-70000, 12000, 15000, 18000, 21000})".to_string(),
+                -0.021244848273410943,
+            ),
+            (
+                "IRR({-70000, 12000, 15000}, -10%)".to_string(),
+                -0.44350694133474056,
+            ),
+            (
+                "MIRR({-120000, 39000, 30000, 21000, 37000, 46000}, 10%, 12%)".to_string(),
+                0.1260941303659051,
+            ),
+            (
+                "MIRR({-120000, 39000, 30000, 21000}, 10%, 12%)".to_string(),
+                -0.048044655249980806,
+            ),
+        ];
+        for (formula, expected) in test_cases {
+            println!("Testing that {formula} = {expected}");
+            assert_f64_approx_eq(expected, &eval_to_string(&g, &formula));
+        }
+
+        // NPV at the IRR is zero.
+        assert_f64_approx_eq(
+            0.0,
+            &eval_to_string(&g, &format!("NPV(IRR({cash_flows}), {cash_flows})")),
+        );
+
+        let cash_flows = "{-10000, 2750, 4250, 3250, 2750}";
+        let dates =
+            "{\"2008-01-01\", \"2008-03-01\", \"2008-10-30\", \"2009-02-15\", \"2009-04-01\"}";
+        assert_f64_approx_eq(
+            2086.647602031535,
+            &eval_to_string(&g, &format!("XNPV(9%, {cash_flows}, {dates})")),
+        );
+        assert_f64_approx_eq(
+            0.37336253351883136,
+            &eval_to_string(&g, &format!("XIRR({cash_flows}, {dates})")),
+        );
+
+        // Invalid cash flows
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "IRR({100, 200, 300})");
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            &format!("XIRR({{1, 2, 3, 4, 5}}, {dates})"),
+        );
+        expect_err(
+            &RunErrorMsg::DivideByZero,
+            &g,
+            "MIRR({100, 200, 300}, 10%, 10%)",
+        );
+        assert_eq!(
+            RunErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(5, 1).unwrap(),
+                got: ArraySize::new(4, 1).unwrap(),
+            },
+            eval_to_err(&g, &format!("XNPV(9%, {cash_flows}, {{1, 2, 3, 4}})")).msg,
+        );
+
+        // No solution
+        expect_err(&RunErrorMsg::NoConvergence, &g, "IRR({1, -1, 1})");
+        expect_err(
+            &RunErrorMsg::NoConvergence,
+            &g,
+            "IRR({-70000, 12000, 15000})",
+        );
+    }
+
+    #[test]
+    fn test_formula_depreciation() {
+        let g = Grid::new();
+
+        let test_cases = [
+            ("SLN(30000, 7500, 10)", 2250.0),
+            ("SYD(30000, 7500, 10, 1)", 4090.909090909091),
+            ("SYD(30000, 7500, 10, 10)", 409.0909090909091),
+            ("DB(1000000, 100000, 6, 1, 7)", 186083.33333333334),
+            ("DB(1000000, 100000, 6, 2, 7)", 259639.41666666666),
+            ("DB(1000000, 100000, 6, 7, 7)", 15845.098473848071),
+            ("DDB(2400, 300, 3650, 1)", 1.3150684931506476),
+            ("DDB(2400, 300, 120, 1, 2)", 40.0),
+            ("DDB(2400, 300, 10, 2, 1.5)", 306.0000000000002),
+            ("DDB(2400, 300, 10, 10)", 22.122547200000156),
+            ("VDB(2400, 300, 3650, 0, 1)", 1.3150684931506476),
+            ("VDB(2400, 300, 120, 0, 1)", 40.0),
+            ("VDB(2400, 300, 10, 0, 1)", 480.0),
+            ("VDB(2400, 300, 120, 6, 18)", 396.3060532647519),
+            ("VDB(2400, 300, 120, 6, 18, 1.5)", 311.80893665823305),
+            ("VDB(2400, 300, 10, 0, 0.875, 1.5)", 315.0),
+            ("VDB(2400, 300, 10, 0, 10)", 2100.0),
+            ("VDB(2400, 300, 10, 0, 10, 2, TRUE)", 2100.0),
+        ];
+        for (formula, expected) in test_cases {
+            println!("Testing that {formula} = {expected}");
+            assert_f64_approx_eq(expected, &eval_to_string(&g, formula));
+        }
+
+        expect_err(&RunErrorMsg::DivideByZero, &g, "SLN(30000, 7500, 0)");
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "SYD(30000, 7500, 10, 11)",
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "DB(1000000, 100000, 6, 7)",
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "DB(1000000, 100000, 6, 1, 13)",
+        );
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "DDB(2400, 300, 10, 11)");
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "VDB(2400, 300, 10, 5, 4)",
+        );
+    }
+}
@@ -0,0 +1,362 @@
+use super::*;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Information functions",
+    docs: "These functions return information about a value, such as its type \
+           or whether it is an error. Errors can be handled using `IFERROR` \
+           and `IFNA`.",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        formula_fn!(
+            /// Returns `TRUE` if `value` is an error, and `FALSE` otherwise.
+            #[examples("ISERROR(1/A1)", "ISERROR(MATCH(\"x\", A1:A10, 0))")]
+            #[zip_map]
+            fn ISERROR([value]: CellValue) {
+                value.error().is_some()
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is an error other than `#N/A`, and
+            /// `FALSE` otherwise.
+            #[examples("ISERR(1/A1)")]
+            #[zip_map]
+            fn ISERR([value]: CellValue) {
+                value.error().is_some_and(|e| e.msg != RunErrorMsg::NoMatch)
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is a `#N/A` error, such as when a
+            /// lookup finds no match, and `FALSE` otherwise.
+            #[examples("ISNA(MATCH(\"x\", A1:A10, 0))")]
+            #[zip_map]
+            fn ISNA([value]: CellValue) {
+                value.error().is_some_and(|e| e.msg == RunErrorMsg::NoMatch)
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is blank, and `FALSE` otherwise.
+            ///
+            /// A cell containing an empty string is not blank.
+            #[examples("ISBLANK(A1)")]
+            #[zip_map]
+            fn ISBLANK([value]: CellValue) {
+                value.is_blank()
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is a number, and `FALSE` otherwise.
+            ///
+            /// Text containing a number is not a number.
+            #[examples("ISNUMBER(A1)", "ISNUMBER(\"12\")")]
+            #[zip_map]
+            fn ISNUMBER([value]: CellValue) {
+                matches!(value, CellValue::Number(_))
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is text, and `FALSE` otherwise.
+            #[examples("ISTEXT(A1)")]
+            #[zip_map]
+            fn ISTEXT([value]: CellValue) {
+                matches!(value, CellValue::Text(_))
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is `TRUE` or `FALSE`, and `FALSE`
+            /// otherwise.
+            #[examples("ISLOGICAL(A1)", "ISLOGICAL(A1 > 0)")]
+            #[zip_map]
+            fn ISLOGICAL([value]: CellValue) {
+                matches!(value, CellValue::Logical(_))
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is not text, and `FALSE` otherwise.
+            ///
+            /// Blank values are not text.
+            #[examples("ISNONTEXT(A1)")]
+            #[zip_map]
+            fn ISNONTEXT([value]: CellValue) {
+                !matches!(value, CellValue::Text(_))
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `number` is even, and `FALSE` if it is odd.
+            ///
+            /// The fractional part of `number` is ignored.
+            #[examples("ISEVEN(4)", "ISEVEN(A1)")]
+            #[zip_map]
+            fn ISEVEN([number]: f64) {
+                number.trunc() % 2.0 == 0.0
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `number` is odd, and `FALSE` if it is even.
+            ///
+            /// The fractional part of `number` is ignored.
+            #[examples("ISODD(3)", "ISODD(A1)")]
+            #[zip_map]
+            fn ISODD([number]: f64) {
+                number.trunc() % 2.0 != 0.0
+            }
+        ),
+        special_form!(
+            /// Returns `TRUE` if `reference` is a cell containing a formula,
+            /// and `FALSE` otherwise.
+            ///
+            /// If `reference` is a range, then only the top left cell is
+            /// checked.
+            #[examples("ISFORMULA(A1)")]
+            fn ISFORMULA(reference: CellRef);
+        ),
+        special_form!(
+            /// Returns `TRUE` if `value` is a cell reference or range, and
+            /// `FALSE` otherwise.
+            #[examples("ISREF(A1)", "ISREF(A1:B10)", "ISREF(\"A1\")")]
+            fn ISREF(value: Value);
+        ),
+        formula_fn!(
+            /// Returns a `#N/A` error, which indicates that a value is not
+            /// available.
+            #[include_args_in_completion(false)]
+            #[examples("NA()", "IF(A1 = 0, NA(), A1)")]
+            fn NA(span: Span) {
+                CellValue::Error(Box::new(RunErrorMsg::NoMatch.with_span(span)))
+            }
+        ),
+        formula_fn!(
+            /// Returns a number identifying the kind of error in `error`, or
+            /// a `#N/A` error if `error` is not an error.
+            ///
+            /// | Error     | Number |
+            /// |-----------|--------|
+            /// | `#NULL!`  | 1      |
+            /// | `#DIV/0!` | 2      |
+            /// | `#VALUE!` | 3      |
+            /// | `#REF!`   | 4      |
+            /// | `#NAME?`  | 5      |
+            /// | `#NUM!`   | 6      |
+            /// | `#N/A`    | 7      |
+            /// | `#SPILL!` | 9      |
+            /// | `#CALC!`  | 14     |
+            #[examples("ERROR.TYPE(1/0)", "IF(ERROR.TYPE(A1) = 2, \"divided by zero\")")]
+            #[zip_map]
+            fn "ERROR.TYPE"(span: Span, [error]: CellValue) {
+                match error.error() {
+                    Some(e) => error_type_number(&e.msg),
+                    None => return Err(RunErrorMsg::NoMatch.with_span(*span)),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns a number identifying the type of `value`.
+            ///
+            /// | Type                   | Number |
+            /// |------------------------|--------|
+            /// | number, date, or blank | 1      |
+            /// | text                   | 2      |
+            /// | logical value          | 4      |
+            /// | error                  | 16     |
+            /// | array                  | 64     |
+            #[examples("TYPE(A1)", "TYPE({1, 2, 3})")]
+            fn TYPE(value: Value) {
+                match value.cell_value() {
+                    Ok(value) => match value {
+                        CellValue::Blank
+                        | CellValue::Number(_)
+                        | CellValue::Instant(_)
+                        | CellValue::Duration(_) => 1,
+                        CellValue::Text(_)
+                        | CellValue::Html(_)
+                        | CellValue::Code(_)
+                        | CellValue::Image(_) => 2,
+                        CellValue::Logical(_) => 4,
+                        CellValue::Error(_) => 16,
+                    },
+                    Err(_) => 64,
+                }
+            }
+        ),
+        formula_fn!(
+            /// Converts `value` to a number.
+            ///
+            /// - Numbers are returned unchanged.
+            /// - `TRUE` is converted to `1`.
+            /// - Errors are returned unchanged.
+            /// - All other values are converted to `0`.
+            #[examples("N(A1)", "N(TRUE)")]
+            #[zip_map]
+            fn N([value]: CellValue) {
+                match value {
+                    CellValue::Number(_) | CellValue::Error(_) => value.clone(),
+                    CellValue::Logical(true) => CellValue::from(1),
+                    _ => CellValue::from(0),
+                }
+            }
+        ),
+    ]
+}
+
+/// Returns the number used by `ERROR.TYPE()` for an error, based on its Excel
+/// error code.
+fn error_type_number(msg: &RunErrorMsg) -> i64 {
+    match msg.excel_code() {
+        "#NULL!" => 1,
+        "#DIV/0!" => 2,
+        "#REF!" => 4,
+        "#NAME?" => 5,
+        "#NUM!" => 6,
+        "#N/A" => 7,
+        "#SPILL!" => 9,
+        "#CALC!" => 14,
+        _ => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{formulas::tests::*, grid::CodeCellLanguage};
+
+    #[test]
+    fn test_is_error() {
+        let g = Grid::new();
+        assert_eq!("TRUE", eval_to_string(&g, "ISERROR(1/0)"));
+        assert_eq!("FALSE", eval_to_string(&g, "ISERROR(1/2)"));
+        assert_eq!("TRUE", eval_to_string(&g, "ISERROR(NA())"));
+        assert_eq!("TRUE", eval_to_string(&g, "ISERR(1/0)"));
+        assert_eq!("FALSE", eval_to_string(&g, "ISERR(NA())"));
+        assert_eq!("FALSE", eval_to_string(&g, "ISNA(1/0)"));
+        assert_eq!("TRUE", eval_to_string(&g, "ISNA(NA())"));
+        assert_eq!(
+            "TRUE",
+            eval_to_string(&g, "ISNA(MATCH(\"z\", {\"a\", \"b\"}, 0))"),
+        );
+        assert_eq!(
+            "{FALSE, TRUE, FALSE}",
+            eval_to_string(&g, "ISERROR(1/{1, 0, 2})"),
+        );
+        expect_err(&RunErrorMsg::NoMatch, &g, "NA()");
+    }
+
+    #[test]
+    fn test_is_type() {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        let _ = sheet.set_cell_value(pos![A1], 5);
+        let _ = sheet.set_cell_value(pos![A2], "hello");
+        let _ = sheet.set_cell_value(pos![A3], true);
+
+        let cases = [
+            ("ISBLANK", ["FALSE", "FALSE", "FALSE", "TRUE"]),
+            ("ISNUMBER", ["TRUE", "FALSE", "FALSE", "FALSE"]),
+            ("ISTEXT", ["FALSE", "TRUE", "FALSE", "FALSE"]),
+            ("ISLOGICAL", ["FALSE", "FALSE", "TRUE", "FALSE"]),
+            ("ISNONTEXT", ["TRUE", "FALSE", "TRUE", "TRUE"]),
+        ];
+        for (func, expected) in cases {
+            for (cell, expected) in ["A1", "A2", "A3", "A4"].into_iter().zip(expected) {
+                assert_eq!(expected, eval_to_string(&g, &format!("{func}({cell})")));
+            }
+        }
+        assert_eq!("FALSE", eval_to_string(&g, "ISBLANK(\"\")"));
+        assert_eq!("FALSE", eval_to_string(&g, "ISNUMBER(\"12\")"));
+    }
+
+    #[test]
+    fn test_is_even_odd() {
+        let g = Grid::new();
+        assert_eq!(
+            "{TRUE, FALSE, TRUE}",
+            eval_to_string(&g, "ISEVEN({-2, 3, 4.5})")
+        );
+        assert_eq!(
+            "{FALSE, TRUE, TRUE}",
+            eval_to_string(&g, "ISODD({-2, -3, 5.9})")
+        );
+        assert_eq!("TRUE", eval_to_string(&g, "ISEVEN(A1)"));
+        expect_err(
+            &RunErrorMsg::Expected {
+                expected: "number".into(),
+                got: Some("text".into()),
+            },
+            &g,
+            "ISODD(\"abc\")",
+        );
+    }
+
+    #[test]
+    fn test_is_formula_is_ref() {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        let _ = sheet.set_cell_value(pos![A1], 5);
+        let _ = sheet.set_cell_value(
+            pos![A2],
+            CellValue::Code(CodeCellValue {
+                language: CodeCellLanguage::Formula,
+                code: "1 + 2".to_string(),
+            }),
+        );
+        let _ = sheet.set_cell_value(
+            pos![A3],
+            CellValue::Code(CodeCellValue {
+                language: CodeCellLanguage::Python,
+                code: "1 + 2".to_string(),
+            }),
+        );
+
+        assert_eq!("FALSE", eval_to_string(&g, "ISFORMULA(A1)"));
+        assert_eq!("TRUE", eval_to_string(&g, "ISFORMULA(A2)"));
+        assert_eq!("TRUE", eval_to_string(&g, "ISFORMULA(A2:A3)"));
+        assert_eq!("FALSE", eval_to_string(&g, "ISFORMULA(A3)"));
+        assert_eq!("FALSE", eval_to_string(&g, "ISFORMULA(A4)"));
+        expect_err(
+            &RunErrorMsg::Expected {
+                expected: "cell reference".into(),
+                got: Some("numeric literal".into()),
+            },
+            &g,
+            "ISFORMULA(1)",
+        );
+
+        assert_eq!("TRUE", eval_to_string(&g, "ISREF(A1)"));
+        assert_eq!("TRUE", eval_to_string(&g, "ISREF(A1:B2)"));
+        assert_eq!("FALSE", eval_to_string(&g, "ISREF(\"A1\")"));
+        assert_eq!("FALSE", eval_to_string(&g, "ISREF(1 + 2)"));
+    }
+
+    #[test]
+    fn test_error_type() {
+        let g = Grid::new();
+        assert_eq!("2", eval_to_string(&g, "ERROR.TYPE(1/0)"));
+        assert_eq!("7", eval_to_string(&g, "ERROR.TYPE(NA())"));
+        assert_eq!("5", eval_to_string(&g, "ERROR.TYPE(undefined_name)"));
+        assert_eq!("3", eval_to_string(&g, "ERROR.TYPE(\"a\" + 1)"));
+        expect_err(&RunErrorMsg::NoMatch, &g, "ERROR.TYPE(1)");
+    }
+
+    #[test]
+    fn test_type_n() {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        let _ = sheet.set_cell_value(pos![A1], 5);
+
+        assert_eq!("1", eval_to_string(&g, "TYPE(A1)"));
+        assert_eq!("1", eval_to_string(&g, "TYPE(A2)"));
+        assert_eq!("2", eval_to_string(&g, "TYPE(\"a\")"));
+        assert_eq!("4", eval_to_string(&g, "TYPE(FALSE)"));
+        assert_eq!("16", eval_to_string(&g, "TYPE(1/0)"));
+        assert_eq!("64", eval_to_string(&g, "TYPE({1, 2})"));
+
+        assert_eq!("5", eval_to_string(&g, "N(A1)"));
+        assert_eq!("1", eval_to_string(&g, "N(TRUE)"));
+        assert_eq!("0", eval_to_string(&g, "N(FALSE)"));
+        assert_eq!("0", eval_to_string(&g, "N(\"7\")"));
+        assert_eq!("0", eval_to_string(&g, "N(A2)"));
+        expect_err(&RunErrorMsg::DivideByZero, &g, "N(1/0)");
+    }
+}
@@ -0,0 +1,250 @@
+use super::*;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Lambda functions",
+    docs: "Names defined using `LET` and lambdas defined using `LAMBDA` are \
+           only visible inside the formula that defines them. Names are \
+           case-insensitive.",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        special_form!(
+            /// Assigns names to the results of calculations, then returns the
+            /// result of `calculation`, which may refer to those names.
+            ///
+            /// Each name may refer to names defined before it. A name may also
+            /// be assigned a lambda defined using `LAMBDA`, which can then be
+            /// called like a function.
+            #[include_args_in_completion(false)]
+            #[examples(
+                "LET(x, A1 * 2, x + 1)",
+                "LET(square, LAMBDA(n, n * n), square(3) + square(4))"
+            )]
+            fn LET(name1: Name, value1: Value, names_and_values: (Iter<Value>), calculation: Value);
+        ),
+        special_form!(
+            /// Defines a lambda that takes the named parameters and returns the
+            /// result of `calculation`.
+            ///
+            /// A lambda must be assigned a name using `LET` or passed to a
+            /// function such as `MAP`. Calling a lambda with fewer arguments
+            /// than it has parameters leaves the remaining parameters blank;
+            /// use `ISOMITTED` to check for this.
+            #[examples(
+                "LET(hyp, LAMBDA(a, b, SQRT(a^2 + b^2)), hyp(3, 4))",
+                "MAP(A1:A10, LAMBDA(x, x * 2))"
+            )]
+            fn LAMBDA(parameters: (Iter<Name>), calculation: Value);
+        ),
+        special_form!(
+            /// Returns `TRUE` if no argument was given for the lambda parameter
+            /// `argument`, or `FALSE` otherwise.
+            #[examples("LET(f, LAMBDA(x, y, IF(ISOMITTED(y), x, x + y)), f(1))")]
+            fn ISOMITTED(argument: Name);
+        ),
+        special_form!(
+            /// Calls `function` on each value in the arrays, returning an array
+            /// of the results.
+            ///
+            /// If multiple arrays are given, then they must have the same size
+            /// and `function` is called with one value from each array.
+            #[include_args_in_completion(false)]
+            #[examples(
+                "MAP(A1:C3, LAMBDA(x, x * 2))",
+                "MAP(A1:A10, B1:B10, LAMBDA(a, b, MAX(a, b)))"
+            )]
+            fn MAP(array1: Array, arrays: (Iter<Array>), function: Lambda);
+        ),
+        special_form!(
+            /// Reduces an array to a single value by calling `function` with
+            /// the accumulated value and each value in the array, starting
+            /// with `initial_value`.
+            ///
+            /// Values are visited row by row.
+            #[examples("REDUCE(0, A1:C3, LAMBDA(acc, x, acc + x^2))")]
+            fn REDUCE(initial_value: Value, array: Array, function: Lambda);
+        ),
+        special_form!(
+            /// Same as `REDUCE`, but returns an array containing every
+            /// intermediate accumulated value.
+            #[examples("SCAN(1, A1:A10, LAMBDA(acc, x, acc * x))")]
+            fn SCAN(initial_value: Value, array: Array, function: Lambda);
+        ),
+        special_form!(
+            /// Calls `function` on each row of `array`, returning a column
+            /// containing the results.
+            #[examples("BYROW(A1:C10, LAMBDA(row, SUM(row)))")]
+            fn BYROW(array: Array, function: Lambda);
+        ),
+        special_form!(
+            /// Calls `function` on each column of `array`, returning a row
+            /// containing the results.
+            #[examples("BYCOL(A1:C10, LAMBDA(col, MAX(col)))")]
+            fn BYCOL(array: Array, function: Lambda);
+        ),
+        special_form!(
+            /// Returns an array with the given number of rows and columns,
+            /// where each value is the result of calling `function` with its
+            /// row and column numbers, starting at 1.
+            #[examples("MAKEARRAY(3, 3, LAMBDA(r, c, r * c))")]
+            fn MAKEARRAY(rows: u32, columns: u32, function: Lambda);
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formulas::tests::*;
+
+    #[test]
+    fn test_let() {
+        let g = Grid::new();
+        assert_eq!("6", eval_to_string(&g, "LET(x, 2, x * 3)"));
+        assert_eq!("10", eval_to_string(&g, "LET(x, 2, y, x + 3, x * y)"));
+        assert_eq!("{2, 4, 6}", eval_to_string(&g, "LET(a, {1, 2, 3}, a * 2)"));
+        // Names are case-insensitive, and inner names shadow outer ones.
+        assert_eq!("5", eval_to_string(&g, "LET(x, 2, LET(X, 5, x))"));
+        assert_eq!("7", eval_to_string(&g, "LET(x, 2, LET(y, 5, x + y))"));
+        // Names do not leak out of `LET()`.
+        expect_err(
+            &RunErrorMsg::UnknownName("x".into()),
+            &g,
+            "LET(y, LET(x, 1, x), x)",
+        );
+        expect_err(&RunErrorMsg::UnknownName("z".into()), &g, "z + 1");
+        expect_err(
+            &RunErrorMsg::MissingRequiredArgument {
+                func_name: "LET".into(),
+                arg_name: "calculation".into(),
+            },
+            &g,
+            "LET(x, 2)",
+        );
+        expect_err(
+            &RunErrorMsg::Expected {
+                expected: "name".into(),
+                got: Some("numeric literal".into()),
+            },
+            &g,
+            "LET(1, 2, 3)",
+        );
+    }
+
+    #[test]
+    fn test_lambda() {
+        let g = Grid::new();
+        assert_eq!(
+            "9",
+            eval_to_string(&g, "LET(square, LAMBDA(n, n * n), square(3))"),
+        );
+        // Lambdas capture names defined before them.
+        assert_eq!(
+            "15",
+            eval_to_string(&g, "LET(k, 5, times_k, LAMBDA(n, n * k), times_k(3))"),
+        );
+        // Lambdas can be passed to other lambdas.
+        assert_eq!(
+            "8",
+            eval_to_string(
+                &g,
+                "LET(twice, LAMBDA(f, x, f(f(x))), double, LAMBDA(n, n * 2), twice(double, 2))",
+            ),
+        );
+        assert_eq!(
+            "a",
+            eval_to_string(
+                &g,
+                "LET(f, LAMBDA(a, b, IF(ISOMITTED(b), \"a\", \"ab\")), f(1))",
+            ),
+        );
+        assert_eq!(
+            "ab",
+            eval_to_string(
+                &g,
+                "LET(f, LAMBDA(a, b, IF(ISOMITTED(b), \"a\", \"ab\")), f(1, 2))",
+            ),
+        );
+        expect_err(
+            &RunErrorMsg::Expected {
+                expected: "value".into(),
+                got: Some("lambda".into()),
+            },
+            &g,
+            "LAMBDA(x, x + 1)",
+        );
+        expect_err(
+            &RunErrorMsg::TooManyArguments {
+                func_name: "LAMBDA".into(),
+                max_arg_count: 1,
+            },
+            &g,
+            "LET(f, LAMBDA(x, x), f(1, 2))",
+        );
+    }
+
+    #[test]
+    fn test_map_reduce_scan() {
+        let g = Grid::new();
+        assert_eq!(
+            "{2, 4; 6, 8}",
+            eval_to_string(&g, "MAP({1, 2; 3, 4}, LAMBDA(x, x * 2))"),
+        );
+        assert_eq!(
+            "{11, 22, 33}",
+            eval_to_string(&g, "MAP({1, 2, 3}, {10, 20, 30}, LAMBDA(a, b, a + b))"),
+        );
+        assert_eq!(
+            "10",
+            eval_to_string(&g, "REDUCE(0, {1, 2; 3, 4}, LAMBDA(acc, x, acc + x))"),
+        );
+        assert_eq!(
+            "{1, 3, 6, 10}",
+            eval_to_string(&g, "SCAN(0, {1, 2, 3, 4}, LAMBDA(acc, x, acc + x))"),
+        );
+        assert_eq!(
+            "{a; ab; abc}",
+            eval_to_string(
+                &g,
+                "SCAN(\"\", {\"a\"; \"b\"; \"c\"}, LAMBDA(acc, x, acc & x))"
+            ),
+        );
+        expect_err(
+            &RunErrorMsg::Expected {
+                expected: "lambda".into(),
+                got: Some("numeric literal".into()),
+            },
+            &g,
+            "MAP({1, 2}, 3)",
+        );
+    }
+
+    #[test]
+    fn test_byrow_bycol_makearray() {
+        let g = Grid::new();
+        assert_eq!(
+            "{3; 7}",
+            eval_to_string(&g, "BYROW({1, 2; 3, 4}, LAMBDA(row, SUM(row)))"),
+        );
+        assert_eq!(
+            "{4, 6}",
+            eval_to_string(&g, "BYCOL({1, 2; 3, 4}, LAMBDA(col, SUM(col)))"),
+        );
+        assert_eq!(
+            "{2, 2}",
+            eval_to_string(&g, "BYCOL({1, 2; 3, 4}, LAMBDA(col, COUNT(col)))"),
+        );
+        assert_eq!(
+            "{1, 2, 3; 2, 4, 6}",
+            eval_to_string(&g, "MAKEARRAY(2, 3, LAMBDA(r, c, r * c))"),
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "MAKEARRAY(0, 3, LAMBDA(r, c, r * c))",
+        );
+    }
+}
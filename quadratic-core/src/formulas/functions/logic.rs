@@ -81,6 +81,100 @@ fn get_functions() -> Vec<FormulaFunction> {
                 if condition { t } else { f }.clone()
             }
         ),
+        formula_fn!(
+            /// Returns the value corresponding to the first truthy condition.
+            ///
+            /// Arguments alternate between conditions and values. If no
+            /// condition is truthy, returns an error.
+            #[examples("IFS(A1 < 0, \"negative\", A1 > 0, \"positive\", TRUE, \"zero\")")]
+            fn IFS(span: Span, conditions_and_values: (Iter<Spanned<Value>>)) {
+                let args = conditions_and_values.collect::<CodeResult<Vec<_>>>()?;
+                if args.is_empty() || args.len() % 2 != 0 {
+                    return Err(RunErrorMsg::MissingRequiredArgument {
+                        func_name: "IFS".into(),
+                        arg_name: "value".into(),
+                    }
+                    .with_span(span));
+                }
+                let mut result = None;
+                for (condition, value) in args.into_iter().tuples() {
+                    if condition.try_coerce::<bool>()?.inner {
+                        result = Some(value.inner);
+                        break;
+                    }
+                }
+                result.ok_or(RunErrorMsg::NoMatch.with_span(span))?
+            }
+        ),
+        formula_fn!(
+            /// Compares `expression` to each `case` in turn and returns the
+            /// `value` corresponding to the first one that is equal.
+            ///
+            /// Arguments after `expression` alternate between cases and
+            /// values. If there is an extra argument at the end, it is returned
+            /// when no case is equal; otherwise an error is returned.
+            #[examples(
+                "SWITCH(A1, 1, \"one\", 2, \"two\", \"many\")",
+                "SWITCH(B3, \"r\", \"red\", \"g\", \"green\", \"b\", \"blue\")"
+            )]
+            fn SWITCH(
+                span: Span,
+                expression: (Spanned<CellValue>),
+                cases_and_values: (Iter<Spanned<Value>>),
+            ) {
+                let args = cases_and_values.collect::<CodeResult<Vec<_>>>()?;
+                if args.len() < 2 {
+                    return Err(RunErrorMsg::MissingRequiredArgument {
+                        func_name: "SWITCH".into(),
+                        arg_name: "value".into(),
+                    }
+                    .with_span(span));
+                }
+                let default = if args.len() % 2 != 0 {
+                    args.last()
+                } else {
+                    None
+                };
+                let mut result = None;
+                for (case, value) in args.iter().tuples() {
+                    if expression.inner.eq(case.cell_value()?.inner)? {
+                        result = Some(value);
+                        break;
+                    }
+                }
+                result
+                    .or(default)
+                    .ok_or(RunErrorMsg::NoMatch.with_span(span))?
+                    .inner
+                    .clone()
+            }
+        ),
+        formula_fn!(
+            /// Returns `fallback` if `value` is an error, and `value`
+            /// otherwise.
+            #[examples("IFERROR(1/A1, 0)", "IFERROR(VLOOKUP(\"x\", A1:B10, 2), \"none\")")]
+            #[zip_map]
+            fn IFERROR([value]: CellValue, [fallback]: CellValue) {
+                match value {
+                    CellValue::Error(_) => fallback,
+                    _ => value,
+                }
+                .clone()
+            }
+        ),
+        formula_fn!(
+            /// Returns `fallback` if `value` is a `#N/A` error, such as when
+            /// a lookup finds no match, and `value` otherwise.
+            #[examples("IFNA(MATCH(\"x\", A1:A10, 0), 0)")]
+            #[zip_map]
+            fn IFNA([value]: CellValue, [fallback]: CellValue) {
+                match value.error() {
+                    Some(e) if e.msg == RunErrorMsg::NoMatch => fallback,
+                    _ => value,
+                }
+                .clone()
+            }
+        ),
     ]
 }
 
@@ -109,4 +203,71 @@ mod tests {
             form.eval(&mut ctx, false).unwrap().to_string()
         );
     }
+
+    #[test]
+    fn test_formula_iferror() {
+        let g = Grid::new();
+        assert_eq!("0.5", eval_to_string(&g, "IFERROR(1/2, \"oops\")"));
+        assert_eq!("oops", eval_to_string(&g, "IFERROR(1/0, \"oops\")"));
+        assert_eq!("oops", eval_to_string(&g, "IFERROR(NA(), \"oops\")"));
+        assert_eq!(
+            "oops",
+            eval_to_string(&g, "IFERROR(undefined_name, \"oops\")")
+        );
+        assert_eq!("{1, 0, 0.5}", eval_to_string(&g, "IFERROR(1/{1, 0, 2}, 0)"));
+        assert_eq!("{1, 2}", eval_to_string(&g, "IFERROR({1, 1/0}, {3, 2})"));
+
+        assert_eq!("oops", eval_to_string(&g, "IFNA(NA(), \"oops\")"));
+        assert_eq!(
+            "oops",
+            eval_to_string(&g, "IFNA(MATCH(5, {1, 2, 3}, 0), \"oops\")"),
+        );
+        expect_err(&RunErrorMsg::DivideByZero, &g, "IFNA(1/0, \"oops\")");
+        // Errors that are not handled are still errors.
+        expect_err(&RunErrorMsg::DivideByZero, &g, "1 + IFNA(1/0, 2)");
+    }
+
+    #[test]
+    fn test_formula_ifs() {
+        let g = Grid::new();
+        let formula = |n: i64| format!("IFS({n} < 0, \"neg\", {n} > 0, \"pos\", TRUE, \"zero\")");
+        assert_eq!("neg", eval_to_string(&g, &formula(-3)));
+        assert_eq!("pos", eval_to_string(&g, &formula(3)));
+        assert_eq!("zero", eval_to_string(&g, &formula(0)));
+        expect_err(&RunErrorMsg::NoMatch, &g, "IFS(FALSE, 1, 0, 2)");
+        expect_err(
+            &RunErrorMsg::MissingRequiredArgument {
+                func_name: "IFS".into(),
+                arg_name: "value".into(),
+            },
+            &g,
+            "IFS(TRUE, 1, FALSE)",
+        );
+    }
+
+    #[test]
+    fn test_formula_switch() {
+        let g = Grid::new();
+        let formula = |x: &str| format!("SWITCH({x}, 1, \"one\", \"b\", \"bee\", \"other\")");
+        assert_eq!("one", eval_to_string(&g, &formula("1")));
+        assert_eq!("bee", eval_to_string(&g, &formula("\"B\"")));
+        assert_eq!("other", eval_to_string(&g, &formula("2")));
+        assert_eq!(
+            "two",
+            eval_to_string(&g, "SWITCH(2, 1, \"one\", 2, \"two\")")
+        );
+        expect_err(
+            &RunErrorMsg::NoMatch,
+            &g,
+            "SWITCH(3, 1, \"one\", 2, \"two\")",
+        );
+        expect_err(
+            &RunErrorMsg::MissingRequiredArgument {
+                func_name: "SWITCH".into(),
+                arg_name: "value".into(),
+            },
+            &g,
+            "SWITCH(3, 1)",
+        );
+    }
 }
@@ -17,6 +17,7 @@ fn get_functions() -> Vec<FormulaFunction> {
     vec![
         formula_fn!(
             /// Returns the value of the cell at a given location.
+            #[volatile]
             #[examples("INDIRECT(\"Cn7\")", "INDIRECT(\"F\" & B0)")]
             #[zip_map]
             fn INDIRECT(ctx: Ctx, [cellref_string]: (Spanned<String>)) {
@@ -274,9 +275,343 @@ fn get_functions() -> Vec<FormulaFunction> {
                 Array::new_row_major(result_size, final_output_array)?
             }
         ),
+        formula_fn!(
+            /// Searches for a value in a linear range and returns its position,
+            /// starting at 1.
+            ///
+            /// `match_type` determines which value is returned:
+            ///
+            /// - 1 = largest value less than or equal to `search_key`
+            ///       (default); `search_range` must be sorted in ascending
+            ///       order
+            /// - 0 = value equal to `search_key`
+            /// - -1 = smallest value greater than or equal to `search_key`;
+            ///        `search_range` must be sorted in descending order
+            ///
+            /// If `match_type` is 0 and `search_key` is text, then
+            /// `search_key` may contain wildcards.
+            #[doc = see_docs_for_more_about_wildcards!()]
+            ///
+            /// If no match is found, then an error is returned.
+            #[examples("MATCH(17, A1:A10)", "MATCH(\"b*\", A1:Z1, 0)")]
+            #[zip_map]
+            fn MATCH(
+                span: Span,
+                [search_key]: CellValue,
+                search_range: (Spanned<Array>),
+                match_type: (Option<i64>),
+            ) {
+                search_range.array_linear_axis()?;
+                let haystack = search_range.inner.cell_values_slice();
+                let (match_mode, search_mode) = match match_type.unwrap_or(1).signum() {
+                    1 => (
+                        LookupMatchMode::NextSmaller,
+                        LookupSearchMode::BinaryAscending,
+                    ),
+                    -1 => (
+                        LookupMatchMode::NextLarger,
+                        LookupSearchMode::BinaryDescending,
+                    ),
+                    _ if matches!(search_key, CellValue::Text(_)) => {
+                        (LookupMatchMode::Wildcard, LookupSearchMode::LinearForward)
+                    }
+                    _ => (LookupMatchMode::Exact, LookupSearchMode::LinearForward),
+                };
+
+                let i = lookup(search_key, haystack, match_mode, search_mode)?
+                    .ok_or_else(|| RunErrorMsg::NoMatch.with_span(span))?;
+                i as i64 + 1
+            }
+        ),
+        formula_fn!(
+            /// Searches for a value in a linear range and returns its position,
+            /// starting at 1.
+            ///
+            /// `match_mode` and `search_mode` work the same as in `XLOOKUP`.
+            ///
+            /// If no match is found, then an error is returned.
+            #[examples(
+                "XMATCH(\"zebra\", A1:Z1)",
+                "XMATCH(50, B1:B100, -1)",
+                "XMATCH(\"b*\", A1:A100, 2, -1)"
+            )]
+            #[zip_map]
+            fn XMATCH(
+                span: Span,
+                [search_key]: CellValue,
+                search_range: (Spanned<Array>),
+                match_mode: (Option<Spanned<i64>>),
+                search_mode: (Option<Spanned<i64>>),
+            ) {
+                let search_mode_span = search_mode.map_or(*span, |arg| arg.span);
+                let match_mode = LookupMatchMode::try_from(*match_mode)?;
+                let search_mode = LookupSearchMode::try_from(*search_mode)?;
+                if match_mode == LookupMatchMode::Wildcard
+                    && matches!(
+                        search_mode,
+                        LookupSearchMode::BinaryAscending | LookupSearchMode::BinaryDescending,
+                    )
+                {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(search_mode_span));
+                }
+
+                search_range.array_linear_axis()?;
+                let haystack = search_range.inner.cell_values_slice();
+                let i = lookup(search_key, haystack, match_mode, search_mode)?
+                    .ok_or_else(|| RunErrorMsg::NoMatch.with_span(span))?;
+                i as i64 + 1
+            }
+        ),
+        formula_fn!(
+            /// Searches for a value in a sorted range and returns the
+            /// corresponding value from another range.
+            ///
+            /// Returns the value corresponding to the largest value in
+            /// `search_range` that is less than or equal to `search_key`.
+            /// `search_range` must be sorted in ascending order.
+            ///
+            /// If `result_range` is omitted, then the search is performed on
+            /// the first row or column of `search_range` and the result is
+            /// taken from the last row or column. A row is used if
+            /// `search_range` is wider than it is tall; otherwise a column is
+            /// used.
+            ///
+            /// If no match is found, then an error is returned.
+            #[examples("LOOKUP(17, A1:A10, B1:B10)", "LOOKUP(17, A1:B10)")]
+            #[zip_map]
+            fn LOOKUP(
+                span: Span,
+                [search_key]: CellValue,
+                search_range: Array,
+                result_range: (Option<Array>),
+            ) {
+                let use_rows = search_range.width() > search_range.height();
+                let (haystack, returns) = match result_range {
+                    Some(result_range) => (
+                        first_row_or_column(search_range, use_rows)?,
+                        result_range.cell_values_slice().iter().collect_vec(),
+                    ),
+                    None => (
+                        first_row_or_column(search_range, use_rows)?,
+                        last_row_or_column(search_range, use_rows)?,
+                    ),
+                };
+
+                let i = lookup(
+                    search_key,
+                    &haystack,
+                    LookupMatchMode::NextSmaller,
+                    LookupSearchMode::BinaryAscending,
+                )?
+                .ok_or_else(|| RunErrorMsg::NoMatch.with_span(span))?;
+                returns
+                    .get(i)
+                    .copied()
+                    .ok_or(RunErrorMsg::IndexOutOfBounds)?
+                    .clone()
+            }
+        ),
+        special_form!(
+            /// Returns the value at a given row and column in an array, or the
+            /// cell at a given row and column in a range.
+            ///
+            /// Rows and columns start at 1. If `row` or `column` is 0 or
+            /// omitted, then the whole column or row is returned instead. If
+            /// `array` is a single row, then `row` is used as the column
+            /// instead.
+            ///
+            /// If `array` is a cell range, then the result is a cell
+            /// reference, and only the referenced cells are accessed. If
+            /// `array` is a reference with several areas, then `area_num`
+            /// selects the area, starting at 1.
+            #[examples(
+                "INDEX(A1:C10, 3, 2)",
+                "INDEX(A1:C10, 0, 2)",
+                "INDEX({1, 2, 3}, 2)",
+                "INDEX((A1:B5, D1:E5), 2, 1, 2)"
+            )]
+            fn INDEX(
+                array: Array,
+                row: (Option<i64>),
+                column: (Option<i64>),
+                area_num: (Option<i64>),
+            );
+        ),
+        special_form!(
+            /// Returns a range that is offset from `reference` by the given
+            /// number of rows and columns.
+            ///
+            /// If `height` or `width` is omitted, then the result has the same
+            /// height or width as `reference`.
+            #[volatile]
+            #[examples("OFFSET(A1, 2, 3)", "SUM(OFFSET(A1, 0, 1, 10, 1))")]
+            fn OFFSET(
+                reference: CellRef,
+                rows: i64,
+                columns: i64,
+                height: (Option<i64>),
+                width: (Option<i64>),
+            );
+        ),
+        formula_fn!(
+            /// Returns the value at position `index` in the list of values,
+            /// starting at 1.
+            #[examples("CHOOSE(2, \"red\", \"green\", \"blue\")", "CHOOSE(A1, B1, C1:C10)")]
+            fn CHOOSE(index: (Spanned<i64>), values: (Iter<Spanned<Value>>)) {
+                let n = usize::try_from(index.inner)
+                    .ok()
+                    .and_then(|i| i.checked_sub(1))
+                    .ok_or(RunErrorMsg::IndexOutOfBounds.with_span(index.span))?;
+                values
+                    .nth(n)
+                    .ok_or(RunErrorMsg::IndexOutOfBounds.with_span(index.span))??
+                    .inner
+            }
+        ),
+        special_form!(
+            /// Returns the row number of a cell reference, or of the cell
+            /// containing the formula if `reference` is omitted.
+            ///
+            /// If `reference` is a range, then returns a column containing the
+            /// row number of each row in the range.
+            #[examples("ROW()", "ROW(B7)", "ROW(A1:A10)")]
+            fn ROW(reference: (Option<CellRef>));
+        ),
+        formula_fn!(
+            /// Returns the number of rows in an array or range.
+            #[examples("ROWS(A1:C10)", "ROWS({1, 2; 3, 4; 5, 6})")]
+            fn ROWS(array: Array) {
+                array.height()
+            }
+        ),
+        special_form!(
+            /// Returns the column number of a cell reference, or of the cell
+            /// containing the formula if `reference` is omitted. Column A is
+            /// column 1.
+            ///
+            /// If `reference` is a range, then returns a row containing the
+            /// column number of each column in the range.
+            #[examples("COLUMN()", "COLUMN(B7)", "COLUMN(A1:J1)")]
+            fn COLUMN(reference: (Option<CellRef>));
+        ),
+        formula_fn!(
+            /// Returns the number of columns in an array or range.
+            #[examples("COLUMNS(A1:C10)", "COLUMNS({1, 2; 3, 4; 5, 6})")]
+            fn COLUMNS(array: Array) {
+                array.width()
+            }
+        ),
+        formula_fn!(
+            /// Returns a cell reference as text, given a row and column number.
+            /// Column A is column 1.
+            ///
+            /// `absolute_mode` determines which parts of the reference are
+            /// absolute:
+            ///
+            /// - 1 = absolute row and column (default)
+            /// - 2 = absolute row, relative column
+            /// - 3 = relative row, absolute column
+            /// - 4 = relative row and column
+            ///
+            /// If `use_a1` is `FALSE`, then the reference is returned in R1C1
+            /// notation instead of A1 notation.
+            ///
+            /// If `sheet` is given, then the reference includes the sheet
+            /// name.
+            #[examples(
+                "ADDRESS(2, 3)",
+                "ADDRESS(2, 3, 4)",
+                "ADDRESS(ROW(), COLUMN(), 1, TRUE, \"Sheet 2\")"
+            )]
+            #[zip_map]
+            fn ADDRESS(
+                [row]: i64,
+                [column]: i64,
+                [absolute_mode]: (Option<Spanned<i64>>),
+                [use_a1]: (Option<bool>),
+                [sheet]: (Option<String>),
+            ) {
+                let (row_is_absolute, column_is_absolute) = match absolute_mode {
+                    None => (true, true),
+                    Some(mode) => match mode.inner {
+                        1 => (true, true),
+                        2 => (true, false),
+                        3 => (false, true),
+                        4 => (false, false),
+                        _ => return Err(RunErrorMsg::InvalidArgument.with_span(mode.span)),
+                    },
+                };
+
+                let cell = if use_a1.unwrap_or(true) {
+                    let dollar = |is_absolute: bool| if is_absolute { "$" } else { "" };
+                    format!(
+                        "{}{}{}{}",
+                        dollar(column_is_absolute),
+                        crate::util::column_name(column - 1),
+                        dollar(row_is_absolute),
+                        crate::util::row_name(row),
+                    )
+                } else {
+                    let coord = |n: i64, is_absolute: bool| match is_absolute {
+                        true => n.to_string(),
+                        false => format!("[{n}]"),
+                    };
+                    format!(
+                        "R{}C{}",
+                        coord(row, row_is_absolute),
+                        coord(column, column_is_absolute),
+                    )
+                };
+
+                match sheet {
+                    None => cell,
+                    Some(sheet) if sheet.chars().all(|c| c.is_alphanumeric() || c == '_') => {
+                        format!("{sheet}!{cell}")
+                    }
+                    Some(sheet) => {
+                        let sheet = sheet.replace('\\', "\\\\").replace('\'', "\\'");
+                        format!("'{sheet}'!{cell}")
+                    }
+                }
+            }
+        ),
+        special_form!(
+            /// Returns the number of areas in a reference.
+            ///
+            /// A union of references, such as `(A1:B5, D1:E5)`, has an area
+            /// for each reference.
+            #[examples("AREAS(A1:C10)", "AREAS((A1:B5, D1:E5))")]
+            fn AREAS(reference: CellRef);
+        ),
     ]
 }
 
+/// Returns the first row or column of an array.
+fn first_row_or_column(array: &Array, row: bool) -> CodeResult<Vec<&CellValue>> {
+    row_or_column(array, row, 0)
+}
+
+/// Returns the last row or column of an array.
+fn last_row_or_column(array: &Array, row: bool) -> CodeResult<Vec<&CellValue>> {
+    match row {
+        true => row_or_column(array, row, array.height() - 1),
+        false => row_or_column(array, row, array.width() - 1),
+    }
+}
+
+/// Returns a row or column of an array.
+fn row_or_column(array: &Array, row: bool, index: u32) -> CodeResult<Vec<&CellValue>> {
+    let values = match row {
+        true => (0..array.width())
+            .map(|x| array.get(x, index))
+            .collect::<Result<_, _>>()?,
+        false => (0..array.height())
+            .map(|y| array.get(index, y))
+            .collect::<Result<_, _>>()?,
+    };
+    Ok(values)
+}
+
 /// Performs a `LOOKUP` and returns the index of the best match.
 fn lookup<V: ToString + AsRef<CellValue>>(
     needle: &CellValue,
@@ -459,11 +794,12 @@ impl TryFrom<Option<Spanned<i64>>> for LookupSearchMode {
 #[cfg(test)]
 mod tests {
     use std::borrow::Cow;
+    use std::collections::HashSet;
 
     use lazy_static::lazy_static;
     use smallvec::smallvec;
 
-    use crate::{formulas::tests::*, Pos};
+    use crate::{formulas::tests::*, Pos, SheetRect};
 
     lazy_static! {
         static ref NUMBERS_LOOKUP_ARRAY: Array = array![
@@ -1026,4 +1362,224 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_match() {
+        let g = Grid::from_array(pos![A1], &NUMBERS_LOOKUP_ARRAY);
+
+        assert_eq!("3", eval_to_string(&g, "MATCH(50, A1:A4)"));
+        assert_eq!("3", eval_to_string(&g, "MATCH(60, A1:A4)"));
+        assert_eq!("2", eval_to_string(&g, "MATCH(\"two\", B1:B4, 0)"));
+        assert_eq!("3", eval_to_string(&g, "MATCH(\"f*\", B1:B4, 0)"));
+        assert_eq!("3", eval_to_string(&g, "MATCH(\"wan\", A1:C1, 0)"));
+        assert_eq!("1", eval_to_string(&g, "MATCH(60, {100, 50, 2, 1}, -1)"));
+        assert_eq!("{2, 4}", eval_to_string(&g, "MATCH({2, 100}, A1:A4, 0)"));
+
+        expect_err(&RunErrorMsg::NoMatch, &g, "MATCH(0, A1:A4)");
+        expect_err(&RunErrorMsg::NoMatch, &g, "MATCH(60, A1:A4, 0)");
+        eval_to_err(&g, "MATCH(1, A1:C4)");
+    }
+
+    #[test]
+    fn test_xmatch() {
+        let g = Grid::from_array(pos![A1], &NUMBERS_LOOKUP_ARRAY);
+
+        assert_eq!("3", eval_to_string(&g, "XMATCH(50, A1:A4)"));
+        assert_eq!("4", eval_to_string(&g, "XMATCH(60, A1:A4, 1)"));
+        assert_eq!("3", eval_to_string(&g, "XMATCH(60, A1:A4, -1)"));
+        assert_eq!("3", eval_to_string(&g, "XMATCH(60, A1:A4, -1, 2)"));
+        assert_eq!("3", eval_to_string(&g, "XMATCH(\"*e\", C1:C4, 2)"));
+        assert_eq!("4", eval_to_string(&g, "XMATCH(\"*e\", C1:C4, 2, -1)"));
+        assert_eq!("2", eval_to_string(&g, "XMATCH(\"TWO\", A2:C2)"));
+
+        expect_err(&RunErrorMsg::NoMatch, &g, "XMATCH(60, A1:A4)");
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "XMATCH(\"*e\", C1:C4, 2, 2)",
+        );
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "XMATCH(1, A1:A4, 3)");
+    }
+
+    #[test]
+    fn test_lookup() {
+        let g = Grid::from_array(pos![A1], &NUMBERS_LOOKUP_ARRAY);
+
+        assert_eq!("mute", eval_to_string(&g, "LOOKUP(60, A1:A4, C1:C4)"));
+        assert_eq!("ale", eval_to_string(&g, "LOOKUP(100, A1:A4, C1:C4)"));
+        assert_eq!("mute", eval_to_string(&g, "LOOKUP(60, A1:C4)"));
+        assert_eq!(
+            "b",
+            eval_to_string(&g, "LOOKUP(2, {1, 2, 3; \"a\", \"b\", \"c\"})"),
+        );
+        assert_eq!(
+            "{one, fifty}",
+            eval_to_string(&g, "LOOKUP({1, 99}, A1:A4, B1:B4)"),
+        );
+
+        expect_err(&RunErrorMsg::NoMatch, &g, "LOOKUP(0, A1:A4, B1:B4)");
+    }
+
+    #[test]
+    fn test_index() {
+        let g = Grid::from_array(pos![A1], &NUMBERS_LOOKUP_ARRAY);
+
+        assert_eq!("{tu}", eval_to_string(&g, "INDEX(A1:C4, 2, 3)"));
+        assert_eq!("{50, fifty, mute}", eval_to_string(&g, "INDEX(A1:C4, 3)"));
+        assert_eq!(
+            "{one; two; fifty; hundred}",
+            eval_to_string(&g, "INDEX(A1:C4, 0, 2)"),
+        );
+        assert_eq!("{one}", eval_to_string(&g, "INDEX(A1:C1, 2)"));
+        assert_eq!("153", eval_to_string(&g, "SUM(INDEX(A1:C4, 0, 1))"));
+        assert_eq!("3", eval_to_string(&g, "INDEX({1, 2; 3, 4}, 2, 1)"));
+        assert_eq!("3", eval_to_string(&g, "INDEX({1, 2, 3}, 3)"));
+        assert_eq!("{2; 4}", eval_to_string(&g, "INDEX({1, 2; 3, 4}, , 2)"));
+
+        expect_err(&RunErrorMsg::IndexOutOfBounds, &g, "INDEX(A1:C4, 5, 1)");
+        expect_err(&RunErrorMsg::IndexOutOfBounds, &g, "INDEX(A1:C4, 1, -1)");
+
+        // `area_num` selects an area of a union.
+        assert_eq!(
+            eval_to_string(&g, "INDEX(C1:C4, 2)"),
+            eval_to_string(&g, "INDEX((A1:A4, C1:C4), 2, 1, 2)"),
+        );
+        assert_eq!(
+            eval_to_string(&g, "INDEX(A1:A4, 3)"),
+            eval_to_string(&g, "INDEX((A1:A4, C1:C4), 3)"),
+        );
+        expect_err(
+            &RunErrorMsg::IndexOutOfBounds,
+            &g,
+            "INDEX((A1:A4, C1:C4), 1, 1, 3)",
+        );
+        expect_err(&RunErrorMsg::IndexOutOfBounds, &g, "INDEX({1, 2}, 1, 1, 2)");
+        expect_err(
+            &RunErrorMsg::MissingRequiredArgument {
+                func_name: "INDEX".into(),
+                arg_name: "array".into(),
+            },
+            &g,
+            "INDEX()",
+        );
+
+        // Only the cell returned by `INDEX()` is accessed.
+        let sheet_id = g.sheets()[0].id;
+        let mut ctx = Ctx::new(&g, pos![E1].to_sheet_pos(sheet_id));
+        let form = parse_formula("INDEX($A$1:$C$4, 2, 3)", Pos::ORIGIN).unwrap();
+        assert_eq!("{tu}", form.eval(&mut ctx, false).unwrap().to_string());
+        assert_eq!(
+            HashSet::from([SheetRect::single_pos(pos![C2], sheet_id)]),
+            ctx.cells_accessed,
+        );
+    }
+
+    #[test]
+    fn test_offset() {
+        let g = Grid::from_array(pos![A1], &NUMBERS_LOOKUP_ARRAY);
+
+        assert_eq!("{tu}", eval_to_string(&g, "OFFSET(A1, 1, 2)"));
+        assert_eq!(
+            "{50, fifty; 100, hundred}",
+            eval_to_string(&g, "OFFSET(A1, 2, 0, 2, 2)"),
+        );
+        assert_eq!("150", eval_to_string(&g, "SUM(OFFSET(A1:A2, 2, 0))"));
+        assert_eq!("{two}", eval_to_string(&g, "OFFSET(B3, -1, 0)"));
+
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "OFFSET(A1, 0, 0, 0)");
+        expect_err(
+            &RunErrorMsg::Expected {
+                expected: "cell reference".into(),
+                got: Some("numeric literal".into()),
+            },
+            &g,
+            "OFFSET(1, 1, 1)",
+        );
+
+        // Only the cells returned by `OFFSET()` are accessed.
+        let sheet_id = g.sheets()[0].id;
+        let mut ctx = Ctx::new(&g, pos![E1].to_sheet_pos(sheet_id));
+        let form = parse_formula("OFFSET($A$1, 1, 2)", Pos::ORIGIN).unwrap();
+        assert_eq!("{tu}", form.eval(&mut ctx, false).unwrap().to_string());
+        assert_eq!(
+            HashSet::from([SheetRect::single_pos(pos![C2], sheet_id)]),
+            ctx.cells_accessed,
+        );
+    }
+
+    #[test]
+    fn test_choose() {
+        let g = Grid::new();
+
+        assert_eq!(
+            "green",
+            eval_to_string(&g, "CHOOSE(2, \"red\", \"green\", \"blue\")"),
+        );
+        assert_eq!("{1, 2}", eval_to_string(&g, "CHOOSE(1, {1, 2}, 3)"));
+        expect_err(&RunErrorMsg::IndexOutOfBounds, &g, "CHOOSE(4, 1, 2, 3)");
+        expect_err(&RunErrorMsg::IndexOutOfBounds, &g, "CHOOSE(0, 1)");
+    }
+
+    #[test]
+    fn test_row_column() {
+        let g = Grid::new();
+        let sheet_id = g.sheets()[0].id;
+
+        assert_eq!("7", eval_to_string(&g, "ROW(B7)"));
+        assert_eq!("2", eval_to_string(&g, "COLUMN(B7)"));
+        assert_eq!("{2; 3; 4}", eval_to_string(&g, "ROW(A2:C4)"));
+        assert_eq!("{1, 2, 3}", eval_to_string(&g, "COLUMN(A2:C4)"));
+        assert_eq!(
+            "5",
+            eval_to_string_at(&g, pos![C5].to_sheet_pos(sheet_id), "ROW()")
+        );
+        assert_eq!(
+            "3",
+            eval_to_string_at(&g, pos![C5].to_sheet_pos(sheet_id), "COLUMN()")
+        );
+
+        assert_eq!("4", eval_to_string(&g, "ROWS(A1:C4)"));
+        assert_eq!("3", eval_to_string(&g, "COLUMNS(A1:C4)"));
+        assert_eq!("1", eval_to_string(&g, "ROWS({1, 2})"));
+        assert_eq!("2", eval_to_string(&g, "COLUMNS({1, 2})"));
+
+        assert_eq!("1", eval_to_string(&g, "AREAS(A1:C4)"));
+        assert_eq!("1", eval_to_string(&g, "AREAS(B2)"));
+        assert_eq!("2", eval_to_string(&g, "AREAS((A1:B2, C3))"));
+        assert_eq!("3", eval_to_string(&g, "AREAS((A1:B2, C3, (D4, E5)))"));
+        assert_eq!("1", eval_to_string(&g, "AREAS(A1:C4 B2:D5)"));
+
+        expect_err(
+            &RunErrorMsg::Expected {
+                expected: "cell reference".into(),
+                got: Some("numeric literal".into()),
+            },
+            &g,
+            "ROW(1)",
+        );
+    }
+
+    #[test]
+    fn test_address() {
+        let g = Grid::new();
+
+        assert_eq!("$C$2", eval_to_string(&g, "ADDRESS(2, 3)"));
+        assert_eq!("C$2", eval_to_string(&g, "ADDRESS(2, 3, 2)"));
+        assert_eq!("$C2", eval_to_string(&g, "ADDRESS(2, 3, 3)"));
+        assert_eq!("C2", eval_to_string(&g, "ADDRESS(2, 3, 4)"));
+        assert_eq!("$AB$1", eval_to_string(&g, "ADDRESS(1, 28)"));
+        assert_eq!("R2C3", eval_to_string(&g, "ADDRESS(2, 3, 1, FALSE)"));
+        assert_eq!("R[2]C[3]", eval_to_string(&g, "ADDRESS(2, 3, 4, FALSE)"));
+        assert_eq!(
+            "Sheet2!$C$2",
+            eval_to_string(&g, "ADDRESS(2, 3, 1, TRUE, \"Sheet2\")"),
+        );
+        assert_eq!(
+            "'Sheet 2'!$C$2",
+            eval_to_string(&g, "ADDRESS(2, 3, 1, TRUE, \"Sheet 2\")"),
+        );
+        assert_eq!("{$A$1, $A$2}", eval_to_string(&g, "ADDRESS({1, 2}, 1)"));
+
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "ADDRESS(2, 3, 5)");
+    }
 }
@@ -1,7 +1,7 @@
 //! Macros for defining formula functions.
 //!
-//! `formula_fn!` is the entry point; other macros generally should not be
-//! called by outside code.
+//! `formula_fn!` and `special_form!` are the entry points; other macros
+//! generally should not be called by outside code.
 
 /// Outputs a string containing a sentence linking to the documentation for
 /// user-specification of criteria used in `SUMIF`, `COUNTIF`, and `AVERAGEIF`.
@@ -74,6 +74,8 @@ macro_rules! see_docs_for_more_about_wildcards {
 /// Attributes must be specified in the order listed below.
 ///
 /// - `#[doc = "..."]` (or doc comments using `///`) - user-facing documentation
+/// - `#[volatile]` - marks the function as volatile, so that formulas using
+///                   it are recalculated whenever anything changes
 /// - `#[operator]` - removes the function from documentation
 /// - `#[examples("EXAMPLE()", "EXAMPLE(A, B)")]` - example usages
 /// - `#[zip_map]` - if certain arguments are arrays, **zip** them together
@@ -113,6 +115,18 @@ macro_rules! see_docs_for_more_about_wildcards {
 /// repeating parameter) then the repeating arguments will be zipped together
 /// first. The `#[zip_map]` attribute is required for this to work.
 macro_rules! formula_fn {
+    (
+        #[doc = $doc:expr]
+        $(#[doc = $additional_doc:expr])*
+        #[volatile]
+        $($rest:tt)*
+    ) => {
+        $crate::formulas::functions::FormulaFunction {
+            is_volatile: true,
+            ..formula_fn!(#[doc = $doc] $(#[doc = $additional_doc])* $($rest)*)
+        }
+    };
+
     (
         #[operator]
         $(#[$($attr:tt)*])*
@@ -124,6 +138,8 @@ macro_rules! formula_fn {
             usage: "",
             examples: &[],
             doc: "",
+            is_volatile: false,
+            params: params_list!($($params)*),
             eval: formula_fn_eval!(
                 { $($body)* };
                 $(#[$($attr)*])*
@@ -153,6 +169,8 @@ macro_rules! formula_fn {
             usage: $crate::formulas::params::usage_string(&params_list),
             examples: &[$($example_str),+],
             doc: concat!($doc $(, "\n", $additional_doc)*),
+            is_volatile: false,
+            params: params_list,
             eval: formula_fn_eval!(
                 { $($body)* };
                 $(#[$($attr)*])*
@@ -160,6 +178,86 @@ macro_rules! formula_fn {
             ),
         }
     }};
+
+    // Function names that are not valid identifiers, such as
+    // `NETWORKDAYS.INTL`, are given as string literals.
+    (
+        #[doc = $doc:expr]
+        $(#[doc = $additional_doc:expr])*
+        $(#[include_args_in_completion($include_args_in_completion:expr)])?
+        #[examples($($example_str:expr),+ $(,)?)]
+        $(#[$($attr:tt)*])*
+        fn $fn_name:literal( $($params:tt)* ) { $($body:tt)* }
+    ) => {{
+        let params_list = params_list!($($params)*);
+
+        // Default to `true`
+        let include_args_in_completion = [$($include_args_in_completion, )? true][0];
+
+        $crate::formulas::functions::FormulaFunction {
+            name: $fn_name,
+            arg_completion: include_args_in_completion.then(|| {
+                $crate::formulas::params::arg_completion_string(&params_list)
+            }),
+            usage: $crate::formulas::params::usage_string(&params_list),
+            examples: &[$($example_str),+],
+            doc: concat!($doc $(, "\n", $additional_doc)*),
+            is_volatile: false,
+            params: params_list,
+            eval: formula_fn_eval!(
+                { $($body)* };
+                $(#[$($attr)*])*
+                $($params)*
+            ),
+        }
+    }};
+}
+
+/// Macro to generate a `FormulaFunction` for a special form, such as `LET()` or
+/// `ROW()`, which is evaluated directly by `AstNode::eval()` because it needs
+/// its arguments unevaluated. Only the documentation is generated here;
+/// parameter types are used only to determine whether each parameter is
+/// optional or repeating.
+macro_rules! special_form {
+    (
+        #[doc = $doc:expr]
+        $(#[doc = $additional_doc:expr])*
+        #[volatile]
+        $($rest:tt)*
+    ) => {
+        $crate::formulas::functions::FormulaFunction {
+            is_volatile: true,
+            ..special_form!(#[doc = $doc] $(#[doc = $additional_doc])* $($rest)*)
+        }
+    };
+
+    (
+        #[doc = $doc:expr]
+        $(#[doc = $additional_doc:expr])*
+        $(#[include_args_in_completion($include_args_in_completion:expr)])?
+        #[examples($($example_str:expr),+ $(,)?)]
+        fn $fn_name:ident( $($params:tt)* );
+    ) => {{
+        let params_list = params_list!($($params)*);
+
+        // Default to `true`
+        let include_args_in_completion = [$($include_args_in_completion, )? true][0];
+
+        $crate::formulas::functions::FormulaFunction {
+            name: stringify!($fn_name),
+            arg_completion: include_args_in_completion.then(|| {
+                $crate::formulas::params::arg_completion_string(&params_list)
+            }),
+            usage: $crate::formulas::params::usage_string(&params_list),
+            examples: &[$($example_str),+],
+            doc: concat!($doc $(, "\n", $additional_doc)*),
+            is_volatile: false,
+            params: params_list,
+            eval: |_ctx, _only_parse, args| {
+                internal_error!("{} must be evaluated as a special form", args.func_name)
+            },
+        }
+    }};
 }
 
 /// Constructs the `eval` function for a `FormulaFunction`.
@@ -319,7 +417,7 @@ macro_rules! formula_fn_arg {
     };
     (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< Array > >) => {
         // Do not flatten arrays.
-        let mut $arg_name = $args.take_rest().map(Array::from).map(CodeResult::Ok);
+        let mut $arg_name = $args.take_rest().map(|arg| arg.map(Array::from)).map(CodeResult::Ok);
     };
     (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< $($arg_type:tt)*) => {
         $args.error_if_no_more_args(stringify!($arg_name))?;
@@ -1,4 +1,9 @@
+use bigdecimal::{BigDecimal, RoundingMode, Signed, Zero};
+use rand::Rng;
+
+use super::statistics::Pairs;
 use super::*;
+use crate::ArraySize;
 
 pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
     include_in_docs: true,
@@ -14,8 +19,10 @@ fn get_functions() -> Vec<FormulaFunction> {
             /// Adds all values.
             /// Returns `0` if given no values.
             #[examples("SUM(B2:C6, 15, E1)")]
-            fn SUM(numbers: (Iter<f64>)) {
-                numbers.sum::<CodeResult<f64>>()
+            fn SUM(numbers: (Iter<BigDecimal>)) {
+                numbers
+                    .sum::<CodeResult<BigDecimal>>()
+                    .map(util::trim_zeros)
             }
         ),
         formula_fn!(
@@ -36,17 +43,49 @@ fn get_functions() -> Vec<FormulaFunction> {
                 numbers_range: (Option<Spanned<Array>>),
             ) {
                 let criteria = Criterion::try_from(*criteria)?;
+                let numbers = criteria
+                    .iter_matching_coerced::<BigDecimal>(eval_range, numbers_range.as_ref())?;
+                util::trim_zeros(numbers.sum::<CodeResult<BigDecimal>>()?)
+            }
+        ),
+        formula_fn!(
+            /// Evaluates multiple ranges, each based on some criteria, and then
+            /// adds the values in `sum_range` wherever the corresponding value
+            /// in every `eval_range` meets its criteria.
+            ///
+            /// All ranges must be the same size.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "SUMIFS(C1:C10, A1:A10, \">0\")",
+                "SUMIFS(C1:C10, A1:A10, \">0\", B1:B10, \"<>INVALID\")"
+            )]
+            fn SUMIFS(
+                sum_range: (Spanned<Array>),
+                eval_range1: (Spanned<Array>),
+                criteria1: (Spanned<CellValue>),
+                more_eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                let criteria = util::criteria_pairs(
+                    "SUMIFS",
+                    eval_range1,
+                    criteria1,
+                    more_eval_ranges_and_criteria,
+                )?;
                 let numbers =
-                    criteria.iter_matching_coerced::<f64>(eval_range, numbers_range.as_ref())?;
-                numbers.sum::<CodeResult<f64>>()
+                    Criterion::iter_matching_all_coerced::<BigDecimal>(&criteria, &sum_range)?;
+                util::trim_zeros(numbers.sum::<CodeResult<BigDecimal>>()?)
             }
         ),
         formula_fn!(
             /// Multiplies all values.
             /// Returns `1` if given no values.
             #[examples("PRODUCT(B2:C6, 0.002, E1)")]
-            fn PRODUCT(numbers: (Iter<f64>)) {
-                numbers.product::<CodeResult<f64>>()
+            fn PRODUCT(numbers: (Iter<BigDecimal>)) {
+                let mut product = BigDecimal::from(1);
+                for n in numbers {
+                    product = util::round_to_precision(product * n?, util::INTERMEDIATE_PRECISION);
+                }
+                util::round_to_precision(product, util::DECIMAL_PRECISION)
             }
         ),
         formula_fn!(
@@ -65,6 +104,678 @@ fn get_functions() -> Vec<FormulaFunction> {
                 number.sqrt()
             }
         ),
+        formula_fn!(
+            /// Multiplies corresponding values in the arrays and returns the
+            /// sum of the products.
+            ///
+            /// All arrays must be the same size. Values that are not numbers
+            /// are treated as zero.
+            #[examples("SUMPRODUCT(A1:A10, B1:B10)", "SUMPRODUCT(A1:A10, B1:B10, C1:C10)")]
+            fn SUMPRODUCT(span: Span, arrays: (Iter<Spanned<Array>>)) {
+                let arrays = arrays.collect::<CodeResult<Vec<_>>>()?;
+                let Some(first) = arrays.first() else {
+                    return Err(RunErrorMsg::MissingRequiredArgument {
+                        func_name: "SUMPRODUCT".into(),
+                        arg_name: "arrays".into(),
+                    }
+                    .with_span(span));
+                };
+                let size = first.inner.size();
+                let mut products = vec![1.0; size.len()];
+                for array in &arrays {
+                    array.check_array_size_exact(size)?;
+                    let values = array.inner.cell_values_slice();
+                    for (product, value) in std::iter::zip(&mut products, values) {
+                        *product *= match value {
+                            CellValue::Number(_) | CellValue::Error(_) => {
+                                Spanned {
+                                    span: array.span,
+                                    inner: value,
+                                }
+                                .try_coerce::<f64>()?
+                                .inner
+                            }
+                            _ => 0.0,
+                        };
+                    }
+                }
+                products.into_iter().sum::<f64>()
+            }
+        ),
+        formula_fn!(
+            /// Returns the sum of the squares of all values.
+            #[examples("SUMSQ(A1:A10)", "SUMSQ(3, 4)")]
+            fn SUMSQ(numbers: (Iter<f64>)) {
+                numbers.map(|n| n.map(|n| n * n)).sum::<CodeResult<f64>>()
+            }
+        ),
+        formula_fn!(
+            /// Returns the sum of the differences between the squares of
+            /// corresponding values in two arrays.
+            ///
+            /// Both arrays must be the same size. Pairs where either value is
+            /// blank or not a number are ignored.
+            #[examples("SUMX2MY2(A1:A10, B1:B10)")]
+            fn SUMX2MY2(array_x: (Spanned<Array>), array_y: (Spanned<Array>)) {
+                let pairs = Pairs::new(&array_x, &array_y)?;
+                std::iter::zip(&pairs.xs, &pairs.ys)
+                    .map(|(x, y)| x * x - y * y)
+                    .sum::<f64>()
+            }
+        ),
+        formula_fn!(
+            /// Returns the sum of the sums of the squares of corresponding
+            /// values in two arrays.
+            ///
+            /// Both arrays must be the same size. Pairs where either value is
+            /// blank or not a number are ignored.
+            #[examples("SUMX2PY2(A1:A10, B1:B10)")]
+            fn SUMX2PY2(array_x: (Spanned<Array>), array_y: (Spanned<Array>)) {
+                let pairs = Pairs::new(&array_x, &array_y)?;
+                std::iter::zip(&pairs.xs, &pairs.ys)
+                    .map(|(x, y)| x * x + y * y)
+                    .sum::<f64>()
+            }
+        ),
+        formula_fn!(
+            /// Returns the sum of the squares of the differences between
+            /// corresponding values in two arrays.
+            ///
+            /// Both arrays must be the same size. Pairs where either value is
+            /// blank or not a number are ignored.
+            #[examples("SUMXMY2(A1:A10, B1:B10)")]
+            fn SUMXMY2(array_x: (Spanned<Array>), array_y: (Spanned<Array>)) {
+                let pairs = Pairs::new(&array_x, &array_y)?;
+                std::iter::zip(&pairs.xs, &pairs.ys)
+                    .map(|(x, y)| (x - y) * (x - y))
+                    .sum::<f64>()
+            }
+        ),
+        formula_fn!(
+            /// Returns the sum of a power series, where each coefficient is
+            /// multiplied by `x` raised to a power.
+            ///
+            /// The first coefficient is multiplied by `x^n`, the second by
+            /// `x^(n+m)`, the third by `x^(n+2m)`, and so on.
+            #[examples("SERIESSUM(0.5, 0, 1, 1, 1, 1, 1)", "SERIESSUM(A1, 1, 2, B1:B5)")]
+            fn SERIESSUM(span: Span, x: f64, n: f64, m: f64, coefficients: (Iter<f64>)) {
+                let mut sum = 0.0;
+                for (i, coefficient) in coefficients.enumerate() {
+                    sum += coefficient? * x.powf(n + i as f64 * m);
+                }
+                util::finite(span, sum)?
+            }
+        ),
+        // Rounding
+        formula_fn!(
+            /// Rounds a number to the specified number of digits after the
+            /// decimal point.
+            ///
+            /// - If `digits` is 0, then the number is rounded to the nearest
+            ///   integer.
+            /// - If `digits` is negative, then the number is rounded to the
+            ///   left of the decimal point.
+            /// - Numbers exactly halfway between are rounded away from zero.
+            ///
+            /// Rounding is done in decimal, so `ROUND(2.675, 2)` is `2.68`.
+            #[examples("ROUND(6.553, 2)", "ROUND(1234, -2)")]
+            #[zip_map]
+            fn ROUND(span: Span, [number]: BigDecimal, [digits]: i64) {
+                round_to_digits(*span, &number, digits, RoundingMode::HalfUp)?
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number away from zero to the specified number of digits
+            /// after the decimal point.
+            ///
+            /// If `digits` is negative, then the number is rounded to the left
+            /// of the decimal point.
+            #[examples("ROUNDUP(6.551, 2)", "ROUNDUP(-1234, -2)")]
+            #[zip_map]
+            fn ROUNDUP(span: Span, [number]: BigDecimal, [digits]: i64) {
+                round_to_digits(*span, &number, digits, RoundingMode::Up)?
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number toward zero to the specified number of digits
+            /// after the decimal point.
+            ///
+            /// If `digits` is negative, then the number is rounded to the left
+            /// of the decimal point.
+            #[examples("ROUNDDOWN(6.559, 2)", "ROUNDDOWN(-1299, -2)")]
+            #[zip_map]
+            fn ROUNDDOWN(span: Span, [number]: BigDecimal, [digits]: i64) {
+                round_to_digits(*span, &number, digits, RoundingMode::Down)?
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number to the nearest multiple of `multiple`.
+            ///
+            /// Numbers exactly halfway between are rounded away from zero.
+            /// Returns an error if `number` and `multiple` have different
+            /// signs.
+            #[examples("MROUND(17, 5)", "MROUND(A1, 0.25)")]
+            #[zip_map]
+            fn MROUND(span: Span, [number]: BigDecimal, [multiple]: BigDecimal) {
+                if multiple.is_zero() {
+                    BigDecimal::zero()
+                } else {
+                    util::ensure(
+                        *span,
+                        number.is_zero() || number.is_negative() == multiple.is_negative(),
+                    )?;
+                    round_to_multiple(&number, &multiple, RoundingMode::HalfUp)
+                }
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number up to the nearest multiple of `significance`.
+            ///
+            /// If `number` and `significance` are both negative, then the
+            /// number is rounded away from zero. Returns an error if `number`
+            /// is positive and `significance` is negative.
+            #[examples("CEILING(6.12, 0.5)", "CEILING(-6.12, -1)")]
+            #[zip_map]
+            fn CEILING(span: Span, [number]: BigDecimal, [significance]: BigDecimal) {
+                if significance.is_zero() {
+                    BigDecimal::zero()
+                } else {
+                    util::ensure(*span, !(number.is_positive() && significance.is_negative()))?;
+                    round_to_multiple(&number, &significance, RoundingMode::Ceiling)
+                }
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number up to the nearest multiple of `significance`,
+            /// which defaults to `1`.
+            ///
+            /// The sign of `significance` is ignored. If `number` is negative
+            /// and `mode` is truthy, then the number is rounded away from zero
+            /// instead.
+            #[examples("CEILING.MATH(6.12)", "CEILING.MATH(-6.12, 0.5, 1)")]
+            #[zip_map]
+            fn "CEILING.MATH"(
+                [number]: BigDecimal,
+                [significance]: (Option<BigDecimal>),
+                [mode]: (Option<bool>),
+            ) {
+                let significance = significance.map_or(BigDecimal::from(1), |s| s.abs());
+                if significance.is_zero() {
+                    BigDecimal::zero()
+                } else if number.is_negative() && mode.unwrap_or(false) {
+                    round_to_multiple(&number, &significance, RoundingMode::Floor)
+                } else {
+                    round_to_multiple(&number, &significance, RoundingMode::Ceiling)
+                }
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number up to the nearest multiple of `significance`,
+            /// which defaults to `1`.
+            ///
+            /// The sign of `significance` is ignored.
+            #[examples("CEILING.PRECISE(6.12)", "CEILING.PRECISE(-6.12, 0.5)")]
+            #[zip_map]
+            fn "CEILING.PRECISE"([number]: BigDecimal, [significance]: (Option<BigDecimal>)) {
+                let significance = significance.map_or(BigDecimal::from(1), |s| s.abs());
+                if significance.is_zero() {
+                    BigDecimal::zero()
+                } else {
+                    round_to_multiple(&number, &significance, RoundingMode::Ceiling)
+                }
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number down to the nearest multiple of `significance`.
+            ///
+            /// If `number` and `significance` are both negative, then the
+            /// number is rounded toward zero. Returns an error if `number` is
+            /// positive and `significance` is negative.
+            #[examples("FLOOR(6.12, 0.5)", "FLOOR(-6.12, -1)")]
+            #[zip_map]
+            fn FLOOR(span: Span, [number]: BigDecimal, [significance]: BigDecimal) {
+                if significance.is_zero() {
+                    if !number.is_zero() {
+                        return Err(RunErrorMsg::DivideByZero.with_span(*span));
+                    }
+                    BigDecimal::zero()
+                } else {
+                    util::ensure(*span, !(number.is_positive() && significance.is_negative()))?;
+                    round_to_multiple(&number, &significance, RoundingMode::Floor)
+                }
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number down to the nearest multiple of `significance`,
+            /// which defaults to `1`.
+            ///
+            /// The sign of `significance` is ignored. If `number` is negative
+            /// and `mode` is truthy, then the number is rounded toward zero
+            /// instead.
+            #[examples("FLOOR.MATH(6.12)", "FLOOR.MATH(-6.12, 0.5, 1)")]
+            #[zip_map]
+            fn "FLOOR.MATH"(
+                [number]: BigDecimal,
+                [significance]: (Option<BigDecimal>),
+                [mode]: (Option<bool>),
+            ) {
+                let significance = significance.map_or(BigDecimal::from(1), |s| s.abs());
+                if significance.is_zero() {
+                    BigDecimal::zero()
+                } else if number.is_negative() && mode.unwrap_or(false) {
+                    round_to_multiple(&number, &significance, RoundingMode::Ceiling)
+                } else {
+                    round_to_multiple(&number, &significance, RoundingMode::Floor)
+                }
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number down to the nearest multiple of `significance`,
+            /// which defaults to `1`.
+            ///
+            /// The sign of `significance` is ignored.
+            #[examples("FLOOR.PRECISE(6.12)", "FLOOR.PRECISE(-6.12, 0.5)")]
+            #[zip_map]
+            fn "FLOOR.PRECISE"([number]: BigDecimal, [significance]: (Option<BigDecimal>)) {
+                let significance = significance.map_or(BigDecimal::from(1), |s| s.abs());
+                if significance.is_zero() {
+                    BigDecimal::zero()
+                } else {
+                    round_to_multiple(&number, &significance, RoundingMode::Floor)
+                }
+            }
+        ),
+        formula_fn!(
+            /// Truncates a number toward zero, keeping `digits` digits after
+            /// the decimal point. `digits` defaults to `0`.
+            #[examples("TRUNC(8.97)", "TRUNC(-8.97, 1)")]
+            #[zip_map]
+            fn TRUNC(span: Span, [number]: BigDecimal, [digits]: (Option<i64>)) {
+                round_to_digits(*span, &number, digits.unwrap_or(0), RoundingMode::Down)?
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number down to the nearest integer.
+            ///
+            /// Negative numbers are rounded away from zero, so `INT(-8.9)` is
+            /// `-9`.
+            #[examples("INT(8.9)", "INT(-8.9)")]
+            #[zip_map]
+            fn INT(span: Span, [number]: BigDecimal) {
+                round_to_digits(*span, &number, 0, RoundingMode::Floor)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the remainder after dividing `number` by `divisor`.
+            ///
+            /// The result has the same sign as `divisor`.
+            #[examples("MOD(10, 3)", "MOD(-10, 3)")]
+            #[zip_map]
+            fn MOD(span: Span, [number]: BigDecimal, [divisor]: BigDecimal) {
+                if divisor.is_zero() {
+                    return Err(RunErrorMsg::DivideByZero.with_span(*span));
+                }
+                let quotient = (&number / &divisor).with_scale_round(0, RoundingMode::Floor);
+                util::trim_zeros(number - divisor * quotient)
+            }
+        ),
+        formula_fn!(
+            /// Returns the integer part of `numerator` divided by
+            /// `denominator`, truncated toward zero.
+            #[examples("QUOTIENT(10, 3)", "QUOTIENT(-10, 3)")]
+            #[zip_map]
+            fn QUOTIENT(span: Span, [numerator]: BigDecimal, [denominator]: BigDecimal) {
+                if denominator.is_zero() {
+                    return Err(RunErrorMsg::DivideByZero.with_span(*span));
+                }
+                util::trim_zeros(
+                    (&numerator / &denominator).with_scale_round(0, RoundingMode::Down),
+                )
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number away from zero to the nearest even integer.
+            #[examples("EVEN(3)", "EVEN(-0.5)")]
+            #[zip_map]
+            fn EVEN([number]: f64) {
+                let even = (number.abs() / 2.0).ceil() * 2.0;
+                if number < 0.0 {
+                    -even
+                } else {
+                    even
+                }
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number away from zero to the nearest odd integer.
+            #[examples("ODD(2)", "ODD(-0.5)")]
+            #[zip_map]
+            fn ODD([number]: f64) {
+                let odd = ((number.abs() + 1.0) / 2.0).ceil() * 2.0 - 1.0;
+                if number < 0.0 {
+                    -odd
+                } else {
+                    odd
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns `1` if a number is positive, `-1` if it is negative,
+            /// and `0` if it is zero.
+            #[examples("SIGN(-4)", "SIGN(A1)")]
+            #[zip_map]
+            fn SIGN([number]: f64) {
+                if number > 0.0 {
+                    1
+                } else if number < 0.0 {
+                    -1
+                } else {
+                    0
+                }
+            }
+        ),
+        // Powers and logarithms
+        formula_fn!(
+            /// Returns `base` raised to the power of `exponent`.
+            #[examples("POWER(2, 8)", "POWER(A1, 0.5)")]
+            #[zip_map]
+            fn POWER(span: Span, [base]: f64, [exponent]: f64) {
+                if base == 0.0 && exponent < 0.0 {
+                    return Err(RunErrorMsg::DivideByZero.with_span(*span));
+                }
+                util::finite(*span, base.powf(exponent))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the square root of a number multiplied by π.
+            #[examples("SQRTPI(2)")]
+            #[zip_map]
+            fn SQRTPI(span: Span, [number]: f64) {
+                util::ensure(*span, number >= 0.0)?;
+                (number * std::f64::consts::PI).sqrt()
+            }
+        ),
+        formula_fn!(
+            /// Returns e raised to the power of `exponent`.
+            #[examples("EXP(1)", "EXP(A1)")]
+            #[zip_map]
+            fn EXP(span: Span, [exponent]: f64) {
+                util::finite(*span, exponent.exp())?
+            }
+        ),
+        formula_fn!(
+            /// Returns the natural logarithm of a number.
+            #[examples("LN(50)", "LN(EXP(2))")]
+            #[zip_map]
+            fn LN(span: Span, [number]: f64) {
+                util::finite(*span, number.ln())?
+            }
+        ),
+        formula_fn!(
+            /// Returns the logarithm of a number to the base `base`, which
+            /// defaults to `10`.
+            #[examples("LOG(100)", "LOG(8, 2)")]
+            #[zip_map]
+            fn LOG(span: Span, [number]: f64, [base]: (Option<f64>)) {
+                util::finite(*span, number.log(base.unwrap_or(10.0)))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the base-10 logarithm of a number.
+            #[examples("LOG10(100)")]
+            #[zip_map]
+            fn LOG10(span: Span, [number]: f64) {
+                util::finite(*span, number.log10())?
+            }
+        ),
+        // Number theory
+        formula_fn!(
+            /// Returns the greatest common divisor of all values.
+            ///
+            /// The fractional part of each value is ignored. Returns an error
+            /// if any value is negative.
+            #[examples("GCD(24, 36)", "GCD(A1:A10)")]
+            fn GCD(span: Span, numbers: (Iter<f64>)) {
+                let mut result = 0;
+                for n in numbers {
+                    result = gcd(result, natural_number(span, n?)?);
+                }
+                result as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the least common multiple of all values.
+            ///
+            /// The fractional part of each value is ignored. Returns an error
+            /// if any value is negative.
+            #[examples("LCM(4, 6)", "LCM(A1:A10)")]
+            fn LCM(span: Span, numbers: (Iter<f64>)) {
+                let mut result = 1_u64;
+                for n in numbers {
+                    let n = natural_number(span, n?)?;
+                    if n == 0 || result == 0 {
+                        result = 0;
+                    } else {
+                        result = (result / gcd(result, n))
+                            .checked_mul(n)
+                            .ok_or(RunErrorMsg::Overflow.with_span(span))?;
+                    }
+                }
+                result as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the factorial of a number.
+            ///
+            /// The fractional part of `number` is ignored.
+            #[examples("FACT(5)")]
+            #[zip_map]
+            fn FACT(span: Span, [number]: f64) {
+                let n = number.trunc();
+                util::ensure(*span, n >= 0.0)?;
+                util::finite(*span, product_of_range(n, 1.0, 1.0))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the double factorial of a number, which is the product
+            /// of every integer from `number` down to 1 with the same parity
+            /// as `number`.
+            ///
+            /// The fractional part of `number` is ignored.
+            #[examples("FACTDOUBLE(7)")]
+            #[zip_map]
+            fn FACTDOUBLE(span: Span, [number]: f64) {
+                let n = number.trunc();
+                util::ensure(*span, n >= -1.0)?;
+                util::finite(*span, product_of_range(n, 1.0, 2.0))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of ways to choose `number_chosen` items from
+            /// `number` items, ignoring order.
+            ///
+            /// The fractional parts of the arguments are ignored.
+            #[examples("COMBIN(8, 2)")]
+            #[zip_map]
+            fn COMBIN(span: Span, [number]: f64, [number_chosen]: f64) {
+                let (n, k) = (number.trunc(), number_chosen.trunc());
+                util::ensure(*span, (0.0..=n).contains(&k))?;
+                util::finite(*span, combin(n, k))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of ways to choose `number_chosen` items from
+            /// `number` items, ignoring order and allowing repetition.
+            ///
+            /// The fractional parts of the arguments are ignored.
+            #[examples("COMBINA(4, 3)")]
+            #[zip_map]
+            fn COMBINA(span: Span, [number]: f64, [number_chosen]: f64) {
+                let (n, k) = (number.trunc(), number_chosen.trunc());
+                util::ensure(*span, n >= 0.0 && k >= 0.0)?;
+                if n == 0.0 {
+                    if k == 0.0 {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                } else {
+                    util::finite(*span, combin(n + k - 1.0, k))?
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of ways to choose `number_chosen` items from
+            /// `number` items, where order matters.
+            ///
+            /// The fractional parts of the arguments are ignored.
+            #[examples("PERMUT(8, 2)")]
+            #[zip_map]
+            fn PERMUT(span: Span, [number]: f64, [number_chosen]: f64) {
+                let (n, k) = (number.trunc(), number_chosen.trunc());
+                util::ensure(*span, (0.0..=n).contains(&k))?;
+                util::finite(*span, product_of_range(n, n - k + 1.0, 1.0))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of ways to choose `number_chosen` items from
+            /// `number` items, where order matters and repetition is allowed.
+            ///
+            /// The fractional parts of the arguments are ignored.
+            #[examples("PERMUTATIONA(3, 2)")]
+            #[zip_map]
+            fn PERMUTATIONA(span: Span, [number]: f64, [number_chosen]: f64) {
+                let (n, k) = (number.trunc(), number_chosen.trunc());
+                util::ensure(*span, n >= 0.0 && k >= 0.0)?;
+                util::finite(*span, n.powf(k))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the factorial of the sum of all values divided by the
+            /// product of their factorials.
+            ///
+            /// The fractional part of each value is ignored. Returns an error
+            /// if any value is negative.
+            #[examples("MULTINOMIAL(2, 3, 4)")]
+            fn MULTINOMIAL(span: Span, numbers: (Iter<f64>)) {
+                let mut sum = 0.0;
+                let mut result = 1.0;
+                for n in numbers {
+                    let n = n?.trunc();
+                    util::ensure(span, n >= 0.0)?;
+                    sum += n;
+                    result *= combin(sum, n);
+                }
+                util::finite(span, result)?
+            }
+        ),
+        // Roman numerals
+        formula_fn!(
+            /// Converts a number from 0 to 3999 to Roman numerals.
+            ///
+            /// The fractional part of `number` is ignored. Zero is converted to
+            /// an empty string.
+            #[examples("ROMAN(1999)", "ROMAN(A1)")]
+            #[zip_map]
+            fn ROMAN(span: Span, [number]: f64) {
+                let n = number.trunc();
+                util::ensure(*span, (0.0..=3999.0).contains(&n))?;
+                to_roman(n as u32)
+            }
+        ),
+        formula_fn!(
+            /// Converts Roman numerals to a number.
+            ///
+            /// Letters are case-insensitive. An empty string is converted to
+            /// zero.
+            #[examples("ARABIC(\"MCMXCIX\")", "ARABIC(A1)")]
+            #[zip_map]
+            fn ARABIC(span: Span, [text]: String) {
+                from_roman(&text).ok_or(RunErrorMsg::InvalidArgument.with_span(*span))?
+            }
+        ),
+        // Matrices
+        formula_fn!(
+            /// Returns the matrix product of two arrays.
+            ///
+            /// The width of `array1` must equal the height of `array2`. The
+            /// result has the same height as `array1` and the same width as
+            /// `array2`.
+            #[examples("MMULT(A1:B3, D1:E2)", "MMULT({1, 2; 3, 4}, {5; 6})")]
+            fn MMULT(span: Span, array1: (Spanned<Array>), array2: (Spanned<Array>)) {
+                array2.check_array_size_on(Axis::Y, array1.inner.width())?;
+                let result_size = array1.inner.height().saturating_mul(array2.inner.width());
+                if result_size > crate::limits::CELL_RANGE_LIMIT {
+                    return Err(RunErrorMsg::ArrayTooBig.with_span(span));
+                }
+                let product =
+                    matrix_product(&matrix_from_array(&array1)?, &matrix_from_array(&array2)?);
+                array_from_matrix(span, &product)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the determinant of a square matrix.
+            #[examples("MDETERM(A1:C3)", "MDETERM({3, 1; 4, 2})")]
+            fn MDETERM(span: Span, array: (Spanned<Array>)) {
+                array.check_array_size_on(Axis::Y, array.inner.width())?;
+                util::finite(span, matrix_determinant(&matrix_from_array(&array)?))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the inverse of a square matrix.
+            ///
+            /// Returns an error if the matrix is singular, meaning that it has
+            /// no inverse.
+            #[examples("MINVERSE(A1:C3)", "MINVERSE({4, -1; 2, 0})")]
+            fn MINVERSE(span: Span, array: (Spanned<Array>)) {
+                array.check_array_size_on(Axis::Y, array.inner.width())?;
+                let inverse = matrix_inverse(&matrix_from_array(&array)?)
+                    .ok_or_else(|| RunErrorMsg::SingularMatrix.with_span(array.span))?;
+                array_from_matrix(span, &inverse)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the identity matrix with `dimension` rows and columns.
+            #[examples("MUNIT(3)")]
+            fn MUNIT(span: Span, dimension: (Spanned<i64>)) {
+                let n = dimension.inner;
+                util::ensure(dimension.span, n >= 1)?;
+                if n.saturating_mul(n) > crate::limits::CELL_RANGE_LIMIT as i64 {
+                    return Err(RunErrorMsg::ArrayTooBig.with_span(dimension.span));
+                }
+                array_from_matrix(span, &identity_matrix(n as usize))?
+            }
+        ),
+        // Random numbers
+        formula_fn!(
+            /// Returns a random number between `0` (inclusive) and `1`
+            /// (exclusive).
+            #[volatile]
+            #[include_args_in_completion(false)]
+            #[examples("RAND()", "RAND() * 100")]
+            fn RAND() {
+                rand::thread_rng().gen::<f64>()
+            }
+        ),
+        formula_fn!(
+            /// Returns a random whole number between `bottom` and `top`
+            /// inclusive.
+            ///
+            /// `bottom` is rounded up and `top` is rounded down to whole
+            /// numbers. Returns an error if there are no whole numbers between
+            /// them.
+            #[volatile]
+            #[examples("RANDBETWEEN(1, 6)", "RANDBETWEEN(-10, 10)")]
+            fn RANDBETWEEN(span: Span, bottom: f64, top: f64) {
+                let bottom = bottom.ceil();
+                let top = top.floor();
+                util::ensure(
+                    span,
+                    bottom <= top && bottom > -MAX_EXACT_INTEGER && top < MAX_EXACT_INTEGER,
+                )?;
+                rand::thread_rng().gen_range(bottom as i64..=top as i64)
+            }
+        ),
         // Constants
         formula_fn!(
             /// Returns π, the circle constant.
@@ -83,8 +794,287 @@ fn get_functions() -> Vec<FormulaFunction> {
     ]
 }
 
+/// Limit on how many digits to the left of the decimal point a number may be
+/// rounded to, since rounding constructs a power of ten with that many digits.
+const MAX_ROUNDING_DIGITS: i64 = 1000;
+
+/// Largest integer that an `f64` can represent exactly.
+pub(super) const MAX_EXACT_INTEGER: f64 = (1_u64 << f64::MANTISSA_DIGITS) as f64;
+
+/// Pivots smaller than this fraction of the largest value in a matrix are
+/// treated as zero when inverting the matrix.
+const SINGULAR_MATRIX_TOLERANCE: f64 = 1e-12;
+
+/// Roman numeral symbols and their values, including subtractive pairs, from
+/// largest to smallest.
+const ROMAN_NUMERALS: [(&str, u32); 13] = [
+    ("M", 1000),
+    ("CM", 900),
+    ("D", 500),
+    ("CD", 400),
+    ("C", 100),
+    ("XC", 90),
+    ("L", 50),
+    ("XL", 40),
+    ("X", 10),
+    ("IX", 9),
+    ("V", 5),
+    ("IV", 4),
+    ("I", 1),
+];
+
+/// Rounds `number` to `digits` digits after the decimal point, or to the left
+/// of the decimal point if `digits` is negative.
+fn round_to_digits(
+    span: Span,
+    number: &BigDecimal,
+    digits: i64,
+    mode: RoundingMode,
+) -> CodeResult<BigDecimal> {
+    if digits < -MAX_ROUNDING_DIGITS {
+        return Err(RunErrorMsg::Overflow.with_span(span));
+    }
+    let (_, scale) = number.as_bigint_and_exponent();
+    if digits >= scale {
+        return Ok(number.clone());
+    }
+    Ok(util::trim_zeros(number.with_scale_round(digits, mode)))
+}
+
+/// Rounds `number` to a multiple of `multiple`, which must be nonzero.
+fn round_to_multiple(number: &BigDecimal, multiple: &BigDecimal, mode: RoundingMode) -> BigDecimal {
+    util::trim_zeros((number / multiple).with_scale_round(0, mode) * multiple)
+}
+
+/// Truncates a number to an integer, returning an error if it is negative or
+/// too large to represent exactly.
+fn natural_number(span: Span, n: f64) -> CodeResult<u64> {
+    let n = n.trunc();
+    util::ensure(span, (0.0..=MAX_EXACT_INTEGER).contains(&n))?;
+    Ok(n as u64)
+}
+
+/// Returns the greatest common divisor of two integers.
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Returns the product of `high`, `high - step`, `high - 2 * step`, etc. for
+/// every factor that is at least `low`, or `1` if `high < low`. Stops early if
+/// the product becomes infinite.
+fn product_of_range(high: f64, low: f64, step: f64) -> f64 {
+    let mut product = 1.0;
+    let mut factor = high;
+    while factor >= low && product.is_finite() {
+        product *= factor;
+        factor -= step;
+    }
+    product
+}
+
+/// Returns the binomial coefficient `n` choose `k`, for integers `0 <= k <=
+/// n`.
+fn combin(n: f64, k: f64) -> f64 {
+    let k = k.min(n - k);
+    let mut result = 1.0;
+    let mut i = 1.0;
+    while i <= k && result.is_finite() {
+        result = result * (n - k + i) / i;
+        i += 1.0;
+    }
+    result.round()
+}
+
+/// Converts a number to Roman numerals.
+fn to_roman(mut n: u32) -> String {
+    let mut ret = String::new();
+    for (symbol, value) in ROMAN_NUMERALS {
+        while n >= value {
+            ret.push_str(symbol);
+            n -= value;
+        }
+    }
+    ret
+}
+
+/// Converts Roman numerals to a number, or returns `None` if the string
+/// contains any other characters.
+fn from_roman(s: &str) -> Option<i64> {
+    let s = s.trim();
+    let (sign, s) = match s.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, s),
+    };
+    let values = s
+        .chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            'I' => Some(1),
+            'V' => Some(5),
+            'X' => Some(10),
+            'L' => Some(50),
+            'C' => Some(100),
+            'D' => Some(500),
+            'M' => Some(1000),
+            _ => None,
+        })
+        .collect::<Option<Vec<i64>>>()?;
+
+    let mut total = 0;
+    for (i, &value) in values.iter().enumerate() {
+        // A symbol followed by a larger one is subtracted, as in "IV".
+        match values.get(i + 1) {
+            Some(&next) if next > value => total -= value,
+            _ => total += value,
+        }
+    }
+    Some(sign * total)
+}
+
+/// Returns the values in an array as a matrix stored as a list of rows.
+/// Returns an error if any value is not a number.
+pub(super) fn matrix_from_array(array: &Spanned<Array>) -> CodeResult<Vec<Vec<f64>>> {
+    array
+        .inner
+        .rows()
+        .map(|row| {
+            row.iter()
+                .map(|value| match value {
+                    CellValue::Number(_) | CellValue::Error(_) => Ok(Spanned {
+                        span: array.span,
+                        inner: value,
+                    }
+                    .try_coerce::<f64>()?
+                    .inner),
+                    _ => Err(RunErrorMsg::Expected {
+                        expected: "number".into(),
+                        got: Some(value.type_name().into()),
+                    }
+                    .with_span(array.span)),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Constructs an array from a matrix stored as a list of rows. Returns an
+/// error if any value is NaN or infinite.
+pub(super) fn array_from_matrix(span: Span, matrix: &[Vec<f64>]) -> CodeResult<Array> {
+    let width = matrix.first().map_or(0, |row| row.len());
+    let size = ArraySize::new_or_err(width as u32, matrix.len() as u32)?;
+    let values = matrix
+        .iter()
+        .flatten()
+        .map(|&x| util::finite(span, x).map(CellValue::from))
+        .collect::<CodeResult<_>>()?;
+    Array::new_row_major(size, values)
+}
+
+/// Returns the identity matrix with `n` rows and columns.
+fn identity_matrix(n: usize) -> Vec<Vec<f64>> {
+    (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect()
+}
+
+/// Returns the transpose of a matrix.
+pub(super) fn matrix_transpose(matrix: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let width = matrix.first().map_or(0, |row| row.len());
+    (0..width)
+        .map(|j| matrix.iter().map(|row| row[j]).collect())
+        .collect()
+}
+
+/// Returns the product of two matrices. The width of `a` must equal the
+/// height of `b`.
+pub(super) fn matrix_product(a: &[Vec<f64>], b: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let width = b.first().map_or(0, |row| row.len());
+    a.iter()
+        .map(|a_row| {
+            (0..width)
+                .map(|j| {
+                    std::iter::zip(a_row, b)
+                        .map(|(x, b_row)| x * b_row[j])
+                        .sum()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the index of the row at or below `col` with the largest absolute
+/// value in column `col`, which is used as the pivot for Gaussian elimination.
+fn pivot_row(matrix: &[Vec<f64>], col: usize) -> usize {
+    (col..matrix.len())
+        .max_by(|&i, &j| matrix[i][col].abs().total_cmp(&matrix[j][col].abs()))
+        .unwrap_or(col)
+}
+
+/// Returns the determinant of a square matrix using Gaussian elimination.
+fn matrix_determinant(matrix: &[Vec<f64>]) -> f64 {
+    let mut a = matrix.to_vec();
+    let mut determinant = 1.0;
+    for col in 0..a.len() {
+        let pivot = pivot_row(&a, col);
+        if a[pivot][col] == 0.0 {
+            return 0.0;
+        }
+        if pivot != col {
+            a.swap(col, pivot);
+            determinant = -determinant;
+        }
+        let pivot_values = a[col].clone();
+        determinant *= pivot_values[col];
+        for row in &mut a[col + 1..] {
+            let factor = row[col] / pivot_values[col];
+            for (x, p) in std::iter::zip(row, &pivot_values).skip(col) {
+                *x -= factor * p;
+            }
+        }
+    }
+    determinant
+}
+
+/// Returns the inverse of a square matrix using Gauss-Jordan elimination, or
+/// `None` if the matrix is singular.
+pub(super) fn matrix_inverse(matrix: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let n = matrix.len();
+    let largest = matrix.iter().flatten().fold(0.0_f64, |m, x| m.max(x.abs()));
+    let tolerance = largest * SINGULAR_MATRIX_TOLERANCE;
+
+    let mut a = matrix.to_vec();
+    let mut inverse = identity_matrix(n);
+    for col in 0..n {
+        let pivot = pivot_row(&a, col);
+        let pivot_magnitude = a[pivot][col].abs();
+        if pivot_magnitude.is_nan() || pivot_magnitude <= tolerance {
+            return None;
+        }
+        a.swap(col, pivot);
+        inverse.swap(col, pivot);
+
+        let divisor = a[col][col];
+        a[col].iter_mut().for_each(|x| *x /= divisor);
+        inverse[col].iter_mut().for_each(|x| *x /= divisor);
+        let (pivot_values, inverse_pivot_values) = (a[col].clone(), inverse[col].clone());
+        for row in (0..n).filter(|&row| row != col) {
+            let factor = a[row][col];
+            for (x, p) in std::iter::zip(&mut a[row], &pivot_values) {
+                *x -= factor * p;
+            }
+            for (x, p) in std::iter::zip(&mut inverse[row], &inverse_pivot_values) {
+                *x -= factor * p;
+            }
+        }
+    }
+    Some(inverse)
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::util::assert_f64_approx_eq;
     use crate::{formulas::tests::*, Pos};
 
     #[test]
@@ -119,6 +1109,9 @@ mod tests {
         assert_eq!("27", eval_to_string(&g, "SUM(0..5, {\"\"}, {\"abc\"}, 12)"));
         assert_eq!("0", eval_to_string(&g, "SUM({\"\", \"abc\"})"));
         assert_eq!("12", eval_to_string(&g, "SUM({\"\", \"abc\", 12})"));
+        // Sums are exact in decimal.
+        assert_eq!("0.3", eval_to_string(&g, "SUM(0.1, 0.2)"));
+        assert_eq!("0", eval_to_string(&g, "SUM(0.1, 0.2, -0.3)"));
 
         let mut g = Grid::new();
         let sheet = &mut g.sheets_mut()[0];
@@ -150,6 +1143,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sumifs() {
+        let g = Grid::new();
+        assert_eq!(
+            "56",
+            eval_to_string(&g, "SUMIFS(2^0..10, 0..10, \">2\", 0..10, \"<=5\")"),
+        );
+        assert_eq!(
+            "5",
+            eval_to_string(
+                &g,
+                "SUMIFS({1, 2, 3, 4}, {\"a\", \"b\", \"a\", \"a\"}, \"a\", {1, 1, 0, 1}, 1)",
+            ),
+        );
+
+        // Error on range size mismatch.
+        assert_eq!(
+            RunErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(1, 11).unwrap(),
+                got: ArraySize::new(1, 6).unwrap(),
+            },
+            eval_to_err(&g, "SUMIFS(0..10, 0..5, \">2\")").msg,
+        );
+        // Error on a range without criteria.
+        assert_eq!(
+            RunErrorMsg::MissingRequiredArgument {
+                func_name: "SUMIFS".into(),
+                arg_name: "criteria".into(),
+            },
+            eval_to_err(&g, "SUMIFS(0..10, 0..10, \">2\", 0..10)").msg,
+        );
+    }
+
     #[test]
     fn test_product() {
         let g = Grid::new();
@@ -179,6 +1205,11 @@ mod tests {
         );
         assert_eq!("1", eval_to_string(&g, "PRODUCT({\"_\", \"abc\"})"));
         assert_eq!("12", eval_to_string(&g, "PRODUCT({\"_\", \"abc\", 12})"));
+        assert_eq!("1.21", eval_to_string(&g, "PRODUCT(1.1, 1.1)"));
+        assert_eq!(
+            "0.111111111111111111111111111111",
+            eval_to_string(&g, "PRODUCT(1 / 3, 1 / 3)"),
+        );
         assert_eq!(
             "1440",
             eval_to_string(&g, "PRODUCT(1..5, {\"_\", \"abc\"}, 12)"),
@@ -223,7 +1254,7 @@ mod tests {
     #[test]
     fn test_sqrt() {
         let g = Grid::new();
-        crate::util::assert_f64_approx_eq(3.0_f64.sqrt(), &eval_to_string(&g, "SQRT(3)"));
+        assert_f64_approx_eq(3.0_f64.sqrt(), &eval_to_string(&g, "SQRT(3)"));
         assert_eq!("4", eval_to_string(&g, "SQRT(16)"));
         let mut ctx = Ctx::new(&g, Pos::ORIGIN.to_sheet_pos(g.sheets()[0].id));
         assert_eq!(
@@ -269,6 +1300,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rand() {
+        let g = Grid::new();
+        for _ in 0..100 {
+            let n = eval_to_string(&g, "RAND()").parse::<f64>().unwrap();
+            assert!((0.0..1.0).contains(&n));
+
+            let n = eval_to_string(&g, "RANDBETWEEN(-2.5, 3.5)")
+                .parse::<f64>()
+                .unwrap();
+            assert!((-2.0..=3.0).contains(&n));
+            assert_eq!(0.0, n.fract());
+        }
+        assert_eq!("4", eval_to_string(&g, "RANDBETWEEN(4, 4)"));
+        assert_eq!("4", eval_to_string(&g, "RANDBETWEEN(3.5, 4.5)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "RANDBETWEEN(2, 1)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "RANDBETWEEN(1.2, 1.8)");
+    }
+
     #[test]
     fn test_tau() {
         let g = Grid::new();
@@ -286,4 +1336,265 @@ mod tests {
                 .msg,
         );
     }
+    #[test]
+    fn test_sumproduct() {
+        let g = Grid::new();
+        assert_eq!(
+            "28",
+            eval_to_string(&g, "SUMPRODUCT({1, 2; 3, 4}, {5, 6; 1, 2})"),
+        );
+        assert_eq!("10", eval_to_string(&g, "SUMPRODUCT({1, 2, 3, 4})"));
+        // Values that are not numbers are treated as zero.
+        assert_eq!(
+            "5",
+            eval_to_string(&g, "SUMPRODUCT({1, \"a\", TRUE}, {5, 6, 7})"),
+        );
+        expect_err(
+            &RunErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(3, 1).unwrap(),
+                got: ArraySize::new(2, 1).unwrap(),
+            },
+            &g,
+            "SUMPRODUCT({1, 2, 3}, {1, 2})",
+        );
+        expect_err(&RunErrorMsg::DivideByZero, &g, "SUMPRODUCT({1, 1/0})");
+    }
+
+    #[test]
+    fn test_sum_of_squares() {
+        let g = Grid::new();
+        assert_eq!("25", eval_to_string(&g, "SUMSQ(3, 4)"));
+        assert_eq!("30", eval_to_string(&g, "SUMSQ(1..4)"));
+        let (xs, ys) = ("{2, 3, 9, 1}", "{6, 5, 11, 7}");
+        assert_eq!("-136", eval_to_string(&g, &format!("SUMX2MY2({xs}, {ys})")));
+        assert_eq!("326", eval_to_string(&g, &format!("SUMX2PY2({xs}, {ys})")));
+        assert_eq!("60", eval_to_string(&g, &format!("SUMXMY2({xs}, {ys})")));
+        assert_eq!(
+            "1.875",
+            eval_to_string(&g, "SERIESSUM(0.5, 0, 1, 1, 1, 1, 1)"),
+        );
+        assert_eq!("18", eval_to_string(&g, "SERIESSUM(2, 1, 2, {1, 2})"));
+    }
+
+    #[test]
+    fn test_round() {
+        let g = Grid::new();
+        // Rounding is exact in decimal.
+        assert_eq!("2.68", eval_to_string(&g, "ROUND(2.675, 2)"));
+        assert_eq!("6.55", eval_to_string(&g, "ROUND(6.553, 2)"));
+        assert_eq!("1200", eval_to_string(&g, "ROUND(1234, -2)"));
+        assert_eq!("-3", eval_to_string(&g, "ROUND(-2.5, 0)"));
+        assert_eq!("2.5", eval_to_string(&g, "ROUND(2.5, 3)"));
+        assert_eq!("2", eval_to_string(&g, "ROUND(2.001, 2)"));
+        assert_eq!("6.56", eval_to_string(&g, "ROUNDUP(6.551, 2)"));
+        assert_eq!("-1300", eval_to_string(&g, "ROUNDUP(-1234, -2)"));
+        assert_eq!("6.55", eval_to_string(&g, "ROUNDDOWN(6.559, 2)"));
+        assert_eq!("-1200", eval_to_string(&g, "ROUNDDOWN(-1299, -2)"));
+        assert_eq!("{1, 2, 3}", eval_to_string(&g, "ROUND({1.4, 1.5, 3.1}, 0)"));
+
+        assert_eq!("8", eval_to_string(&g, "TRUNC(8.97)"));
+        assert_eq!("-8.9", eval_to_string(&g, "TRUNC(-8.97, 1)"));
+        assert_eq!("8", eval_to_string(&g, "INT(8.9)"));
+        assert_eq!("-9", eval_to_string(&g, "INT(-8.9)"));
+    }
+
+    #[test]
+    fn test_round_to_multiple() {
+        let g = Grid::new();
+        assert_eq!("15", eval_to_string(&g, "MROUND(17, 5)"));
+        assert_eq!("7.5", eval_to_string(&g, "MROUND(7.3, 0.5)"));
+        assert_eq!("-10", eval_to_string(&g, "MROUND(-7.5, -5)"));
+        assert_eq!("0", eval_to_string(&g, "MROUND(3, 0)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "MROUND(5, -2)");
+
+        assert_eq!("6.5", eval_to_string(&g, "CEILING(6.12, 0.5)"));
+        assert_eq!("-7", eval_to_string(&g, "CEILING(-6.12, -1)"));
+        assert_eq!("-6", eval_to_string(&g, "CEILING(-6.12, 1)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "CEILING(6.12, -1)");
+        assert_eq!("7", eval_to_string(&g, "CEILING.MATH(6.12)"));
+        assert_eq!("-6", eval_to_string(&g, "CEILING.MATH(-6.12)"));
+        assert_eq!("-6.5", eval_to_string(&g, "CEILING.MATH(-6.12, 0.5, 1)"));
+        assert_eq!("7", eval_to_string(&g, "CEILING.PRECISE(6.12, -1)"));
+        assert_eq!("-6", eval_to_string(&g, "CEILING.PRECISE(-6.12)"));
+
+        assert_eq!("6", eval_to_string(&g, "FLOOR(6.12, 0.5)"));
+        assert_eq!("-6", eval_to_string(&g, "FLOOR(-6.12, -1)"));
+        assert_eq!("-7", eval_to_string(&g, "FLOOR(-6.12, 1)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "FLOOR(6.12, -1)");
+        expect_err(&RunErrorMsg::DivideByZero, &g, "FLOOR(6.12, 0)");
+        assert_eq!("6", eval_to_string(&g, "FLOOR.MATH(6.12)"));
+        assert_eq!("-7", eval_to_string(&g, "FLOOR.MATH(-6.12)"));
+        assert_eq!("-6", eval_to_string(&g, "FLOOR.MATH(-6.12, 0.5, 1)"));
+        assert_eq!("6", eval_to_string(&g, "FLOOR.PRECISE(6.12, -1)"));
+        assert_eq!("-7", eval_to_string(&g, "FLOOR.PRECISE(-6.12)"));
+    }
+
+    #[test]
+    fn test_mod_quotient() {
+        let g = Grid::new();
+        assert_eq!("1", eval_to_string(&g, "MOD(10, 3)"));
+        assert_eq!("2", eval_to_string(&g, "MOD(-10, 3)"));
+        assert_eq!("-2", eval_to_string(&g, "MOD(10, -3)"));
+        assert_eq!("0.1", eval_to_string(&g, "MOD(5.1, 1)"));
+        expect_err(&RunErrorMsg::DivideByZero, &g, "MOD(10, 0)");
+        assert_eq!("3", eval_to_string(&g, "QUOTIENT(10, 3)"));
+        assert_eq!("-3", eval_to_string(&g, "QUOTIENT(-10, 3)"));
+        expect_err(&RunErrorMsg::DivideByZero, &g, "QUOTIENT(10, 0)");
+    }
+
+    #[test]
+    fn test_even_odd_sign() {
+        let g = Grid::new();
+        assert_eq!(
+            "{0, 2, 2, 4, -2}",
+            eval_to_string(&g, "EVEN({0, 1, 2, 2.1, -0.5})")
+        );
+        assert_eq!(
+            "{1, 1, 3, 3, -1}",
+            eval_to_string(&g, "ODD({0, 1, 2, 1.5, -0.5})")
+        );
+        assert_eq!("{-1, 0, 1}", eval_to_string(&g, "SIGN({-4, 0, 0.1})"));
+    }
+
+    #[test]
+    fn test_powers_and_logarithms() {
+        let g = Grid::new();
+        assert_eq!("256", eval_to_string(&g, "POWER(2, 8)"));
+        assert_eq!("3", eval_to_string(&g, "POWER(9, 0.5)"));
+        expect_err(&RunErrorMsg::DivideByZero, &g, "POWER(0, -1)");
+        expect_err(&RunErrorMsg::NotANumber, &g, "POWER(-8, 0.5)");
+        assert_f64_approx_eq(std::f64::consts::E, &eval_to_string(&g, "EXP(1)"));
+        assert_f64_approx_eq(2.0, &eval_to_string(&g, "LN(EXP(2))"));
+        expect_err(&RunErrorMsg::Infinity, &g, "LN(0)");
+        expect_err(&RunErrorMsg::NotANumber, &g, "LN(-1)");
+        assert_f64_approx_eq(2.0, &eval_to_string(&g, "LOG(100)"));
+        assert_f64_approx_eq(3.0, &eval_to_string(&g, "LOG(8, 2)"));
+        assert_f64_approx_eq(3.0, &eval_to_string(&g, "LOG10(1000)"));
+        assert_f64_approx_eq(
+            (2.0 * std::f64::consts::PI).sqrt(),
+            &eval_to_string(&g, "SQRTPI(2)"),
+        );
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "SQRTPI(-1)");
+    }
+
+    #[test]
+    fn test_gcd_lcm() {
+        let g = Grid::new();
+        assert_eq!("12", eval_to_string(&g, "GCD(24, 36)"));
+        assert_eq!("6", eval_to_string(&g, "GCD({12, 18}, 24.9)"));
+        assert_eq!("5", eval_to_string(&g, "GCD(0, 5)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "GCD(-2, 4)");
+        assert_eq!("12", eval_to_string(&g, "LCM(4, 6)"));
+        assert_eq!("60", eval_to_string(&g, "LCM(1..5)"));
+        assert_eq!("0", eval_to_string(&g, "LCM(0, 5)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "LCM(-2, 4)");
+    }
+
+    #[test]
+    fn test_combinatorics() {
+        let g = Grid::new();
+        assert_eq!("120", eval_to_string(&g, "FACT(5)"));
+        assert_eq!("1", eval_to_string(&g, "FACT(0)"));
+        assert_eq!("24", eval_to_string(&g, "FACT(4.9)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "FACT(-1)");
+        expect_err(&RunErrorMsg::Infinity, &g, "FACT(200)");
+        assert_eq!("105", eval_to_string(&g, "FACTDOUBLE(7)"));
+        assert_eq!("48", eval_to_string(&g, "FACTDOUBLE(6)"));
+        assert_eq!("1", eval_to_string(&g, "FACTDOUBLE(-1)"));
+
+        assert_eq!("28", eval_to_string(&g, "COMBIN(8, 2)"));
+        assert_eq!("1", eval_to_string(&g, "COMBIN(8, 0)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "COMBIN(2, 3)");
+        assert_eq!("20", eval_to_string(&g, "COMBINA(4, 3)"));
+        assert_eq!("1", eval_to_string(&g, "COMBINA(0, 0)"));
+        assert_eq!("56", eval_to_string(&g, "PERMUT(8, 2)"));
+        assert_eq!("1", eval_to_string(&g, "PERMUT(8, 0)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "PERMUT(2, 3)");
+        assert_eq!("9", eval_to_string(&g, "PERMUTATIONA(3, 2)"));
+        assert_eq!("1260", eval_to_string(&g, "MULTINOMIAL(2, 3, 4)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "MULTINOMIAL(2, -3)");
+    }
+
+    #[test]
+    fn test_roman_arabic() {
+        let g = Grid::new();
+        assert_eq!("MCMXCIX", eval_to_string(&g, "ROMAN(1999)"));
+        assert_eq!("MMMCMXCIX", eval_to_string(&g, "ROMAN(3999)"));
+        assert_eq!("XLIV", eval_to_string(&g, "ROMAN(44.7)"));
+        assert_eq!("", eval_to_string(&g, "ROMAN(0)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "ROMAN(4000)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "ROMAN(-1)");
+
+        assert_eq!("1999", eval_to_string(&g, "ARABIC(\"MCMXCIX\")"));
+        assert_eq!("44", eval_to_string(&g, "ARABIC(\" xliv \")"));
+        assert_eq!("-4", eval_to_string(&g, "ARABIC(\"-IV\")"));
+        assert_eq!("0", eval_to_string(&g, "ARABIC(\"\")"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "ARABIC(\"XIZ\")");
+        for n in [1, 4, 9, 14, 40, 90, 400, 1444, 2024, 3888] {
+            assert_eq!(
+                n.to_string(),
+                eval_to_string(&g, &format!("ARABIC(ROMAN({n}))")),
+            );
+        }
+    }
+
+    #[test]
+    fn test_matrices() {
+        let g = Grid::new();
+        assert_eq!(
+            "{2, 6; 14, 4}",
+            eval_to_string(&g, "MMULT({1, 3; 7, 2}, {2, 0; 0, 2})"),
+        );
+        assert_eq!(
+            "{17; 39}",
+            eval_to_string(&g, "MMULT({1, 2; 3, 4}, {5; 6})")
+        );
+        assert_eq!(
+            RunErrorMsg::ExactArrayAxisMismatch {
+                axis: Axis::Y,
+                expected: 2,
+                got: 3,
+            },
+            eval_to_err(&g, "MMULT({1, 2; 3, 4}, {1; 2; 3})").msg,
+        );
+        expect_err(
+            &RunErrorMsg::ArrayTooBig,
+            &g,
+            "MMULT(SEQUENCE(2000), TRANSPOSE(SEQUENCE(2000)))",
+        );
+
+        assert_f64_approx_eq(
+            88.0,
+            &eval_to_string(
+                &g,
+                "MDETERM({1, 3, 8, 5; 1, 3, 6, 1; 1, 1, 1, 0; 7, 3, 10, 2})",
+            ),
+        );
+        assert_f64_approx_eq(2.0, &eval_to_string(&g, "MDETERM({3, 1; 4, 2})"));
+        assert_eq!(
+            RunErrorMsg::ExactArrayAxisMismatch {
+                axis: Axis::Y,
+                expected: 3,
+                got: 2,
+            },
+            eval_to_err(&g, "MDETERM({1, 2, 3; 4, 5, 6})").msg,
+        );
+
+        let inverse = "MINVERSE({4, -1; 2, 0})";
+        for (row, col, expected) in [(1, 1, 0.0), (1, 2, 0.5), (2, 1, -1.0), (2, 2, 2.0)] {
+            let formula = format!("INDEX({inverse}, {row}, {col})");
+            assert_f64_approx_eq(expected, &eval_to_string(&g, &formula));
+        }
+        assert_eq!(
+            "{1, 0; 0, 1}",
+            eval_to_string(&g, "ROUND(MMULT(MINVERSE({3, 1; 4, 2}), {3, 1; 4, 2}), 9)"),
+        );
+        expect_err(&RunErrorMsg::SingularMatrix, &g, "MINVERSE({1, 2; 2, 4})");
+        expect_err(&RunErrorMsg::SingularMatrix, &g, "MINVERSE({0})");
+
+        assert_eq!("{1, 0; 0, 1}", eval_to_string(&g, "MUNIT(2)"));
+        assert_eq!("{1}", eval_to_string(&g, "MUNIT(1)"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "MUNIT(0)");
+        expect_err(&RunErrorMsg::ArrayTooBig, &g, "MUNIT(100000)");
+    }
 }
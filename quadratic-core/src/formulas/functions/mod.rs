@@ -6,28 +6,38 @@ use lazy_static::lazy_static;
 
 #[macro_use]
 mod macros;
+mod array;
+mod database;
+mod date;
+mod distributions;
+mod engineering;
 pub mod excel;
+mod financial;
+mod information;
+mod lambda;
 mod logic;
 mod lookup;
 mod mathematics;
 mod operators;
+mod special;
 mod statistics;
 mod string;
 mod trigonometry;
 mod util;
 
-use super::{CellRef, Criterion, Ctx, Param, ParamKind};
+use super::{CellRef, Criterion, Ctx, DatabaseCriteria, Param, ParamKind};
 use crate::{
     Array, Axis, CellValue, CodeResult, CoerceInto, IsBlank, RunError, RunErrorMsg, Span, Spanned,
     SpannedIterExt, Value,
 };
 
 pub fn lookup_function(name: &str) -> Option<&'static FormulaFunction> {
-    ALL_FUNCTIONS.get(
-        excel::remove_excel_function_prefix(name)
-            .to_ascii_uppercase()
-            .as_str(),
-    )
+    ALL_FUNCTIONS.get(normalize_function_name(name).as_str())
+}
+
+/// Returns a function name in uppercase without any Excel prefix.
+pub fn normalize_function_name(name: &str) -> String {
+    excel::remove_excel_function_prefix(name).to_ascii_uppercase()
 }
 
 pub const CATEGORIES: &[FormulaFunctionCategory] = &[
@@ -35,9 +45,17 @@ pub const CATEGORIES: &[FormulaFunctionCategory] = &[
     mathematics::CATEGORY,
     trigonometry::CATEGORY,
     statistics::CATEGORY,
+    distributions::CATEGORY,
     logic::CATEGORY,
+    information::CATEGORY,
     string::CATEGORY,
+    date::CATEGORY,
+    financial::CATEGORY,
+    engineering::CATEGORY,
     lookup::CATEGORY,
+    database::CATEGORY,
+    array::CATEGORY,
+    lambda::CATEGORY,
 ];
 
 lazy_static! {
@@ -147,6 +165,11 @@ pub struct FormulaFunction {
     pub usage: &'static str,
     pub examples: &'static [&'static str],
     pub doc: &'static str,
+    /// Whether the function may return a different result each time it is
+    /// evaluated, such as `NOW()` or `RAND()`, so that formulas using it must
+    /// be recalculated even if nothing they depend on has changed.
+    pub is_volatile: bool,
+    pub(super) params: Vec<Param>,
     pub eval: FormulaFn,
 }
 impl FormulaFunction {
@@ -164,6 +187,18 @@ impl FormulaFunction {
         self.doc.replace("\n ", "\n")
     }
 
+    /// Returns whether the argument at `index` is a single value that the
+    /// function is mapped over, rather than a range or array that the function
+    /// takes as a whole.
+    pub(super) fn is_scalar_arg(&self, index: usize) -> bool {
+        let param = self.params.get(index).or_else(|| {
+            self.params
+                .last()
+                .filter(|param| param.kind == ParamKind::Repeating)
+        });
+        param.is_some_and(|param| param.zip_mapped)
+    }
+
     /// Returns the autocomplete snippet for this function.
     pub fn autocomplete_snippet(&self) -> String {
         let name = self.name;
@@ -1,4 +1,6 @@
-use crate::ArraySize;
+use bigdecimal::{BigDecimal, ToPrimitive, Zero};
+
+use crate::{ArraySize, Duration};
 
 use super::*;
 
@@ -10,6 +12,9 @@ pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
     get_functions,
 };
 
+/// Largest integer exponent for which `^` is computed exactly.
+const MAX_EXACT_EXPONENT: u64 = 1000;
+
 fn get_functions() -> Vec<FormulaFunction> {
     vec![
         // Comparison operators
@@ -25,46 +30,55 @@ fn get_functions() -> Vec<FormulaFunction> {
         formula_fn!(
             #[operator]
             #[zip_map]
-            fn "+"([a]: f64, [b]: (Option<f64>)) {
-                a + b.unwrap_or(0.0)
+            fn "+"(span: Span, [a]: (Spanned<CellValue>), [b]: (Option<Spanned<CellValue>>)) {
+                match b {
+                    Some(b) => add(*span, *a, *b)?,
+                    None => match a.inner {
+                        CellValue::Instant(_) | CellValue::Duration(_) => a.inner.clone(),
+                        _ => CellValue::from(a.try_coerce::<BigDecimal>()?.inner),
+                    },
+                }
             }
         ),
         formula_fn!(
             #[operator]
             #[zip_map]
-            fn "-"([a]: f64, [b]: (Option<f64>)) {
+            fn "-"(span: Span, [a]: (Spanned<CellValue>), [b]: (Option<Spanned<CellValue>>)) {
                 match b {
-                    Some(b) => a - b,
-                    None => -a
+                    Some(b) => subtract(*span, *a, *b)?,
+                    None => match a.inner {
+                        CellValue::Duration(d) => CellValue::Duration(-*d),
+                        _ => CellValue::from(-a.try_coerce::<BigDecimal>()?.inner),
+                    },
                 }
             }
         ),
         formula_fn!(
             #[operator]
             #[zip_map]
-            fn "*"([a]: f64, [b]: f64) {
-                a * b
+            fn "*"([a]: BigDecimal, [b]: BigDecimal) {
+                util::round_to_precision(a * b, util::DECIMAL_PRECISION)
             }
         ),
         formula_fn!(
             #[operator]
             #[zip_map]
-            fn "/"(span: Span, [dividend]: f64, [divisor]: f64) {
-                util::checked_div(span, dividend, divisor)
+            fn "/"(span: Span, [dividend]: BigDecimal, [divisor]: BigDecimal) {
+                util::checked_decimal_div(span, &dividend, &divisor)
             }
         ),
         formula_fn!(
             #[operator]
             #[zip_map]
-            fn "^"([base]: f64, [exponent]: f64) {
-                base.powf(exponent)
+            fn "^"(span: Span, [base]: BigDecimal, [exponent]: BigDecimal) {
+                power(*span, &base, &exponent)?
             }
         ),
         formula_fn!(
             #[operator]
             #[zip_map]
-            fn "%"([percentage]: f64) {
-                percentage / 100.0
+            fn "%"([percentage]: BigDecimal) {
+                util::trim_zeros(percentage * BigDecimal::new(1.into(), 2))
             }
         ),
         formula_fn!(
@@ -95,6 +109,110 @@ fn get_functions() -> Vec<FormulaFunction> {
     ]
 }
 
+/// Adds two values, which may be numbers, time instants, or durations. Adding a
+/// number to a time instant adds that many days.
+fn add(span: Span, a: Spanned<&CellValue>, b: Spanned<&CellValue>) -> CodeResult<CellValue> {
+    let overflow = || RunErrorMsg::Overflow.with_span(span);
+    Ok(match (a.inner, b.inner) {
+        (CellValue::Instant(i), CellValue::Duration(d))
+        | (CellValue::Duration(d), CellValue::Instant(i)) => {
+            CellValue::Instant(i.checked_add(*d).ok_or_else(overflow)?)
+        }
+        (CellValue::Duration(d1), CellValue::Duration(d2)) => CellValue::Duration(*d1 + *d2),
+        (CellValue::Instant(i), _) => {
+            let days = Duration::from_days(b.try_coerce::<f64>()?.inner);
+            CellValue::Instant(i.checked_add(days).ok_or_else(overflow)?)
+        }
+        (_, CellValue::Instant(i)) => {
+            let days = Duration::from_days(a.try_coerce::<f64>()?.inner);
+            CellValue::Instant(i.checked_add(days).ok_or_else(overflow)?)
+        }
+        _ => CellValue::from(util::trim_zeros(
+            a.try_coerce::<BigDecimal>()?.inner + b.try_coerce::<BigDecimal>()?.inner,
+        )),
+    })
+}
+
+/// Subtracts one value from another, where each may be a number, time instant,
+/// or duration. Subtracting two time instants gives a duration, and
+/// subtracting a number from a time instant subtracts that many days.
+fn subtract(span: Span, a: Spanned<&CellValue>, b: Spanned<&CellValue>) -> CodeResult<CellValue> {
+    let overflow = || RunErrorMsg::Overflow.with_span(span);
+    Ok(match (a.inner, b.inner) {
+        (CellValue::Instant(i1), CellValue::Instant(i2)) => {
+            CellValue::Duration(Duration::from_seconds(i1.seconds - i2.seconds))
+        }
+        (CellValue::Instant(i), CellValue::Duration(d)) => {
+            CellValue::Instant(i.checked_add(-*d).ok_or_else(overflow)?)
+        }
+        (CellValue::Duration(d1), CellValue::Duration(d2)) => CellValue::Duration(*d1 + -*d2),
+        (CellValue::Instant(i), _) => {
+            let days = Duration::from_days(b.try_coerce::<f64>()?.inner);
+            CellValue::Instant(i.checked_add(-days).ok_or_else(overflow)?)
+        }
+        _ => CellValue::from(util::trim_zeros(
+            a.try_coerce::<BigDecimal>()?.inner - b.try_coerce::<BigDecimal>()?.inner,
+        )),
+    })
+}
+
+/// Raises a number to a power. Integer exponents are computed in decimal and
+/// rounded to [`util::DECIMAL_PRECISION`] significant digits; other exponents
+/// fall back to `f64`.
+fn power(span: Span, base: &BigDecimal, exponent: &BigDecimal) -> CodeResult<BigDecimal> {
+    let integer_exponent = exponent
+        .to_i64()
+        .filter(|n| exponent.is_integer() && n.unsigned_abs() <= MAX_EXACT_EXPONENT);
+    if let Some(n) = integer_exponent {
+        // Exponentiation by squaring
+        let mut result = BigDecimal::from(1);
+        let mut square = base.clone();
+        let mut remaining = n.unsigned_abs();
+        while remaining > 0 {
+            if remaining & 1 == 1 {
+                result = util::round_to_precision(&result * &square, util::INTERMEDIATE_PRECISION);
+            }
+            remaining >>= 1;
+            if remaining > 0 {
+                square = util::round_to_precision(&square * &square, util::INTERMEDIATE_PRECISION);
+            }
+        }
+        // Match the range of `f64`, so that results can still be passed to
+        // other functions. Results too small for it are flushed to zero.
+        if !result.to_f64().is_some_and(f64::is_finite) {
+            return match n < 0 {
+                true => Ok(BigDecimal::zero()),
+                false => Err(RunErrorMsg::Overflow.with_span(span)),
+            };
+        }
+        if n < 0 && is_below_f64_range(&result) {
+            return Err(RunErrorMsg::Overflow.with_span(span));
+        }
+        let result = match n < 0 {
+            true => util::checked_decimal_div(span, &BigDecimal::from(1), &result)?,
+            false => util::round_to_precision(result, util::DECIMAL_PRECISION),
+        };
+        return match is_below_f64_range(&result) {
+            true => Ok(BigDecimal::zero()),
+            false => Ok(result),
+        };
+    }
+
+    let base = base.to_f64().unwrap_or(f64::NAN);
+    let exponent = exponent.to_f64().unwrap_or(f64::NAN);
+    if base == 0.0 && exponent < 0.0 {
+        return Err(RunErrorMsg::DivideByZero.with_span(span));
+    }
+    let result = util::finite(span, base.powf(exponent))?;
+    BigDecimal::try_from(result).map_err(|_| RunErrorMsg::NotANumber.with_span(span))
+}
+
+/// Returns whether a nonzero number is too small to be represented as a
+/// normal `f64`.
+fn is_below_f64_range(n: &BigDecimal) -> bool {
+    !n.is_zero() && n.to_f64().is_some_and(|f| !f.is_normal())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::formulas::tests::*;
@@ -113,6 +231,63 @@ mod tests {
         assert_eq!(RunErrorMsg::DivideByZero, eval_to_err(&g, "0/ 0").msg);
     }
 
+    #[test]
+    fn test_formula_decimal_arithmetic() {
+        let g = Grid::new();
+
+        // Arithmetic is exact in decimal.
+        assert_eq!("0.3", eval_to_string(&g, "0.1 + 0.2"));
+        assert_eq!("TRUE", eval_to_string(&g, "0.1 + 0.2 = 0.3"));
+        assert_eq!("10.25", eval_to_string(&g, "10.50 - 0.25"));
+        assert_eq!("1.21", eval_to_string(&g, "1.1 * 1.1"));
+        assert_eq!("0.125", eval_to_string(&g, "1 / 8"));
+        assert_eq!("0.05", eval_to_string(&g, "5%"));
+
+        // Division is rounded to 30 significant digits.
+        assert_eq!(
+            "0.333333333333333333333333333333",
+            eval_to_string(&g, "1 / 3"),
+        );
+        assert_eq!(
+            "66.6666666666666666666666666667",
+            eval_to_string(&g, "200 / 3"),
+        );
+
+        // Multiplication and integer exponents are rounded to 30 significant
+        // digits.
+        assert_eq!(
+            "0.111111111111111111111111111111",
+            eval_to_string(&g, "(1 / 3) * (1 / 3)"),
+        );
+        assert_eq!(
+            "123456789876543201987654319309000000",
+            eval_to_string(&g, "123456789012345678901234567 * 1000000007"),
+        );
+        assert_eq!("1024", eval_to_string(&g, "2 ^ 10"));
+        assert_eq!("0.25", eval_to_string(&g, "2 ^ -2"));
+        assert_eq!("1.331", eval_to_string(&g, "1.1 ^ 3"));
+        assert_eq!(
+            "1267650600228229401496703205380",
+            eval_to_string(&g, "2 ^ 100"),
+        );
+        assert_eq!(
+            "0.0370370370370370370370370370369",
+            eval_to_string(&g, "(1 / 3) ^ 3"),
+        );
+        assert_eq!("1", eval_to_string(&g, "0 ^ 0"));
+        assert_eq!("0", eval_to_string(&g, "10 ^ -400"));
+        assert_eq!("0", eval_to_string(&g, "0.1 ^ 400"));
+        assert_eq!("0", eval_to_string(&g, "(-0.1) ^ 401"));
+        assert_eq!(RunErrorMsg::Overflow, eval_to_err(&g, "10 ^ 400").msg);
+        assert_eq!(RunErrorMsg::Overflow, eval_to_err(&g, "0.1 ^ -400").msg);
+        assert_eq!(RunErrorMsg::DivideByZero, eval_to_err(&g, "0 ^ -1").msg);
+
+        // Other exponents fall back to floating point.
+        assert_eq!("3", eval_to_string(&g, "9 ^ 0.5"));
+        assert_eq!(RunErrorMsg::NotANumber, eval_to_err(&g, "(-8) ^ 0.5").msg);
+        assert_eq!(RunErrorMsg::DivideByZero, eval_to_err(&g, "0 ^ -0.5").msg);
+    }
+
     #[test]
     fn test_formula_math_operators_on_empty_string() {
         // Empty string should coerce to zero
@@ -138,4 +313,60 @@ mod tests {
         // Test string concatenation
         assert_eq!("apple", eval_to_string(&g, "C6 & \"apple\" & D6"));
     }
+
+    #[test]
+    fn test_formula_time_operators() {
+        let g = Grid::new();
+
+        // Instant - Instant = Duration
+        assert_eq!(
+            "29 days",
+            eval_to_string(&g, "DATE(2024, 3, 1) - DATE(2024, 2, 1)"),
+        );
+        assert_eq!(
+            "-1 day, -12:00:00",
+            eval_to_string(&g, "DATE(2024, 2, 1) - (DATE(2024, 2, 2) + TIME(12, 0, 0))"),
+        );
+
+        // Instant + Duration = Instant
+        assert_eq!(
+            "2024-01-15 09:30:00",
+            eval_to_string(&g, "DATE(2024, 1, 15) + TIME(9, 30, 0)"),
+        );
+        assert_eq!(
+            "2024-01-15 09:30:00",
+            eval_to_string(&g, "TIME(9, 30, 0) + DATE(2024, 1, 15)"),
+        );
+        assert_eq!(
+            "2024-01-14 14:30:00",
+            eval_to_string(&g, "DATE(2024, 1, 15) - TIME(9, 30, 0)"),
+        );
+        assert_eq!(
+            "2024-02-29",
+            eval_to_string(
+                &g,
+                "DATE(2024, 1, 1) + (DATE(2024, 3, 1) - DATE(2024, 1, 2))"
+            ),
+        );
+
+        // Instant + number of days
+        assert_eq!("2024-02-01", eval_to_string(&g, "DATE(2024, 1, 31) + 1"));
+        assert_eq!("2023-12-31", eval_to_string(&g, "DATE(2024, 1, 1) - 1"));
+
+        // Duration arithmetic
+        assert_eq!(
+            "10:30:00",
+            eval_to_string(&g, "TIME(9, 0, 0) + TIME(1, 30, 0)")
+        );
+        assert_eq!("-01:00:00", eval_to_string(&g, "-TIME(1, 0, 0)"));
+
+        // Invalid combinations
+        assert_eq!(
+            RunErrorMsg::Expected {
+                expected: "number".into(),
+                got: Some("time instant".into()),
+            },
+            eval_to_err(&g, "DATE(2024, 1, 1) + DATE(2024, 1, 1)").msg,
+        );
+    }
 }
@@ -1,4 +1,8 @@
+use super::mathematics::{
+    array_from_matrix, matrix_from_array, matrix_inverse, matrix_product, matrix_transpose,
+};
 use super::*;
+use crate::ArraySize;
 
 pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
     include_in_docs: true,
@@ -42,6 +46,36 @@ fn get_functions() -> Vec<FormulaFunction> {
                 util::average(span, numbers)
             }
         ),
+        formula_fn!(
+            /// Evaluates multiple ranges, each based on some criteria, and then
+            /// computes the arithmetic mean of the values in `average_range`
+            /// wherever the corresponding value in every `eval_range` meets its
+            /// criteria.
+            ///
+            /// All ranges must be the same size.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "AVERAGEIFS(C1:C10, A1:A10, \">0\")",
+                "AVERAGEIFS(C1:C10, A1:A10, \">0\", B1:B10, \"<>INVALID\")"
+            )]
+            fn AVERAGEIFS(
+                span: Span,
+                average_range: (Spanned<Array>),
+                eval_range1: (Spanned<Array>),
+                criteria1: (Spanned<CellValue>),
+                more_eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                let criteria = util::criteria_pairs(
+                    "AVERAGEIFS",
+                    eval_range1,
+                    criteria1,
+                    more_eval_ranges_and_criteria,
+                )?;
+                let numbers =
+                    Criterion::iter_matching_all_coerced::<f64>(&criteria, &average_range)?;
+                util::average(span, numbers)?
+            }
+        ),
         formula_fn!(
             /// Returns the number of numeric values.
             ///
@@ -85,6 +119,31 @@ fn get_functions() -> Vec<FormulaFunction> {
                 count as f64
             }
         ),
+        formula_fn!(
+            /// Evaluates multiple ranges, each based on some criteria, and then
+            /// counts how many positions meet the criteria in every range.
+            ///
+            /// All ranges must be the same size.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "COUNTIFS(A1:A10, \">0\")",
+                "COUNTIFS(A1:A10, \">0\", B1:B10, \"<>INVALID\")"
+            )]
+            fn COUNTIFS(
+                eval_range1: (Spanned<Array>),
+                criteria1: (Spanned<CellValue>),
+                more_eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                let criteria = util::criteria_pairs(
+                    "COUNTIFS",
+                    eval_range1,
+                    criteria1,
+                    more_eval_ranges_and_criteria,
+                )?;
+                let count = Criterion::iter_matching_all(&criteria, &criteria[0].0)?.count();
+                count as f64
+            }
+        ),
         formula_fn!(
             /// Counts how many values in the range are empty.
             ///
@@ -108,20 +167,1051 @@ fn get_functions() -> Vec<FormulaFunction> {
             fn MIN(numbers: (Iter<f64>)) {
                 numbers.try_fold(f64::INFINITY, |a, b| Ok(f64::min(a, b?)))
             }
-        ),
-        formula_fn!(
-            /// Returns the largest value.
-            /// Returns -∞ if given no values.
-            #[examples("MAX(A1:A6)", "MAX(0, A1:A6)")]
-            fn MAX(numbers: (Iter<f64>)) {
-                numbers.try_fold(-f64::INFINITY, |a, b| Ok(f64::max(a, b?)))
+        ),
+        formula_fn!(
+            /// Returns the largest value.
+            /// Returns -∞ if given no values.
+            #[examples("MAX(A1:A6)", "MAX(0, A1:A6)")]
+            fn MAX(numbers: (Iter<f64>)) {
+                numbers.try_fold(-f64::INFINITY, |a, b| Ok(f64::max(a, b?)))
+            }
+        ),
+        formula_fn!(
+            /// Evaluates multiple ranges, each based on some criteria, and then
+            /// returns the smallest value in `min_range` wherever the
+            /// corresponding value in every `eval_range` meets its criteria.
+            /// Returns `0` if no values meet the criteria.
+            ///
+            /// All ranges must be the same size.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "MINIFS(C1:C10, A1:A10, \">0\")",
+                "MINIFS(C1:C10, A1:A10, \">0\", B1:B10, \"<>INVALID\")"
+            )]
+            fn MINIFS(
+                min_range: (Spanned<Array>),
+                eval_range1: (Spanned<Array>),
+                criteria1: (Spanned<CellValue>),
+                more_eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                let criteria = util::criteria_pairs(
+                    "MINIFS",
+                    eval_range1,
+                    criteria1,
+                    more_eval_ranges_and_criteria,
+                )?;
+                let numbers = Criterion::iter_matching_all_coerced::<f64>(&criteria, &min_range)?;
+                numbers
+                    .fold_ok(None, |a: Option<f64>, b| Some(a.map_or(b, |a| a.min(b))))?
+                    .unwrap_or(0.0)
+            }
+        ),
+        formula_fn!(
+            /// Evaluates multiple ranges, each based on some criteria, and then
+            /// returns the largest value in `max_range` wherever the
+            /// corresponding value in every `eval_range` meets its criteria.
+            /// Returns `0` if no values meet the criteria.
+            ///
+            /// All ranges must be the same size.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "MAXIFS(C1:C10, A1:A10, \">0\")",
+                "MAXIFS(C1:C10, A1:A10, \">0\", B1:B10, \"<>INVALID\")"
+            )]
+            fn MAXIFS(
+                max_range: (Spanned<Array>),
+                eval_range1: (Spanned<Array>),
+                criteria1: (Spanned<CellValue>),
+                more_eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                let criteria = util::criteria_pairs(
+                    "MAXIFS",
+                    eval_range1,
+                    criteria1,
+                    more_eval_ranges_and_criteria,
+                )?;
+                let numbers = Criterion::iter_matching_all_coerced::<f64>(&criteria, &max_range)?;
+                numbers
+                    .fold_ok(None, |a: Option<f64>, b| Some(a.map_or(b, |a| a.max(b))))?
+                    .unwrap_or(0.0)
+            }
+        ),
+        // Central tendency
+        formula_fn!(
+            /// Returns the median of all values, which is the middle value when
+            /// they are sorted. If there are an even number of values, returns
+            /// the mean of the two middle values.
+            #[examples("MEDIAN(A1:A6)", "MEDIAN(A1, A3, A5, B1:B6)")]
+            fn MEDIAN(span: Span, numbers: (Iter<f64>)) {
+                let sorted = sorted(numbers.collect::<CodeResult<Vec<f64>>>()?);
+                if sorted.is_empty() {
+                    return Err(RunErrorMsg::EmptyArray.with_span(span));
+                }
+                percentile_inclusive(&sorted, 0.5)
+            }
+        ),
+        formula_fn!(
+            /// Returns the most common value. If there is a tie, returns the
+            /// one that appears first.
+            ///
+            /// Returns an error if no value appears more than once.
+            #[examples("MODE.SNGL(A1:A6)", "MODE.SNGL(A1, A3, A5, B1:B6)")]
+            fn "MODE.SNGL"(span: Span, numbers: (Iter<f64>)) {
+                let numbers = numbers.collect::<CodeResult<Vec<f64>>>()?;
+                match modes(&numbers).first() {
+                    Some(&mode) => mode,
+                    None => return Err(RunErrorMsg::NoMatch.with_span(span)),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns a vertical array of the most common values, in the
+            /// order that they first appear.
+            ///
+            /// Returns an error if no value appears more than once.
+            #[examples("MODE.MULT(A1:A6)", "MODE.MULT(A1, A3, A5, B1:B6)")]
+            fn "MODE.MULT"(span: Span, numbers: (Iter<f64>)) {
+                let numbers = numbers.collect::<CodeResult<Vec<f64>>>()?;
+                let modes = modes(&numbers);
+                if modes.is_empty() {
+                    return Err(RunErrorMsg::NoMatch.with_span(span));
+                }
+                let size = ArraySize::new_or_err(1, modes.len() as u32)?;
+                Array::new_row_major(size, modes.into_iter().map(CellValue::from).collect())?
+            }
+        ),
+        formula_fn!(
+            /// Returns the geometric mean of all values, which must all be
+            /// positive.
+            #[examples("GEOMEAN(A1:A6)", "GEOMEAN(A1, A3, A5, B1:B6)")]
+            fn GEOMEAN(span: Span, numbers: (Iter<f64>)) {
+                let numbers = numbers.collect::<CodeResult<Vec<f64>>>()?;
+                if numbers.iter().any(|&x| x <= 0.0) {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                util::average(span, numbers.iter().map(|x| Ok(x.ln())))?.exp()
+            }
+        ),
+        formula_fn!(
+            /// Returns the harmonic mean of all values, which must all be
+            /// positive.
+            #[examples("HARMEAN(A1:A6)", "HARMEAN(A1, A3, A5, B1:B6)")]
+            fn HARMEAN(span: Span, numbers: (Iter<f64>)) {
+                let numbers = numbers.collect::<CodeResult<Vec<f64>>>()?;
+                if numbers.iter().any(|&x| x <= 0.0) {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                util::average(span, numbers.iter().map(|x| Ok(x.recip())))?.recip()
+            }
+        ),
+        formula_fn!(
+            /// Returns the arithmetic mean of all values in `array`, excluding
+            /// a fraction `percent` of the values from the top and bottom.
+            ///
+            /// The number of excluded values is rounded down to a multiple of
+            /// 2, so that the same number are excluded from the top and bottom.
+            #[examples("TRIMMEAN(A1:A10, 0.2)")]
+            fn TRIMMEAN(span: Span, array: (Spanned<Value>), percent: f64) {
+                if !(0.0..1.0).contains(&percent) {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                let sorted = sorted(numbers_in(&array)?);
+                let excluded = (sorted.len() as f64 * percent / 2.0).floor() as usize;
+                let kept = &sorted[excluded..sorted.len() - excluded];
+                util::average(span, kept.iter().copied().map(Ok))?
+            }
+        ),
+        // Dispersion
+        formula_fn!(
+            /// Returns the sample variance of all values, treating them as a
+            /// sample of a larger population.
+            #[examples("VAR.S(A1:A6)", "VAR.S(A1, A3, A5, B1:B6)")]
+            fn "VAR.S"(span: Span, numbers: (Iter<f64>)) {
+                let numbers = numbers.collect::<CodeResult<Vec<f64>>>()?;
+                variance(span, &numbers, 1)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the variance of all values, treating them as the entire
+            /// population.
+            #[examples("VAR.P(A1:A6)", "VAR.P(A1, A3, A5, B1:B6)")]
+            fn "VAR.P"(span: Span, numbers: (Iter<f64>)) {
+                let numbers = numbers.collect::<CodeResult<Vec<f64>>>()?;
+                variance(span, &numbers, 0)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the sample standard deviation of all values, treating
+            /// them as a sample of a larger population.
+            #[examples("STDEV.S(A1:A6)", "STDEV.S(A1, A3, A5, B1:B6)")]
+            fn "STDEV.S"(span: Span, numbers: (Iter<f64>)) {
+                let numbers = numbers.collect::<CodeResult<Vec<f64>>>()?;
+                variance(span, &numbers, 1)?.sqrt()
+            }
+        ),
+        formula_fn!(
+            /// Returns the standard deviation of all values, treating them as
+            /// the entire population.
+            #[examples("STDEV.P(A1:A6)", "STDEV.P(A1, A3, A5, B1:B6)")]
+            fn "STDEV.P"(span: Span, numbers: (Iter<f64>)) {
+                let numbers = numbers.collect::<CodeResult<Vec<f64>>>()?;
+                variance(span, &numbers, 0)?.sqrt()
+            }
+        ),
+        formula_fn!(
+            /// Returns the mean of the absolute deviations of all values from
+            /// their mean.
+            #[examples("AVEDEV(A1:A6)", "AVEDEV(A1, A3, A5, B1:B6)")]
+            fn AVEDEV(span: Span, numbers: (Iter<f64>)) {
+                let numbers = numbers.collect::<CodeResult<Vec<f64>>>()?;
+                let mean = mean(span, &numbers)?;
+                util::average(span, numbers.iter().map(|x| Ok((x - mean).abs())))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the sum of the squares of the deviations of all values
+            /// from their mean.
+            #[examples("DEVSQ(A1:A6)", "DEVSQ(A1, A3, A5, B1:B6)")]
+            fn DEVSQ(span: Span, numbers: (Iter<f64>)) {
+                let numbers = numbers.collect::<CodeResult<Vec<f64>>>()?;
+                sum_of_squared_deviations(span, &numbers)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the sample excess kurtosis of all values, which
+            /// describes how heavy the tails of their distribution are compared
+            /// to a normal distribution.
+            ///
+            /// Requires at least four values.
+            #[examples("KURT(A1:A6)", "KURT(A1, A3, A5, B1:B6)")]
+            fn KURT(span: Span, numbers: (Iter<f64>)) {
+                let numbers = numbers.collect::<CodeResult<Vec<f64>>>()?;
+                let n = numbers.len() as f64;
+                if numbers.len() < 4 {
+                    return Err(RunErrorMsg::DivideByZero.with_span(span));
+                }
+                let sum = standardized_moment_sum(span, &numbers, 4)?;
+                n * (n + 1.0) / ((n - 1.0) * (n - 2.0) * (n - 3.0)) * sum
+                    - 3.0 * (n - 1.0).powi(2) / ((n - 2.0) * (n - 3.0))
+            }
+        ),
+        formula_fn!(
+            /// Returns the sample skewness of all values, which describes how
+            /// asymmetric their distribution is.
+            ///
+            /// Requires at least three values.
+            #[examples("SKEW(A1:A6)", "SKEW(A1, A3, A5, B1:B6)")]
+            fn SKEW(span: Span, numbers: (Iter<f64>)) {
+                let numbers = numbers.collect::<CodeResult<Vec<f64>>>()?;
+                let n = numbers.len() as f64;
+                if numbers.len() < 3 {
+                    return Err(RunErrorMsg::DivideByZero.with_span(span));
+                }
+                let sum = standardized_moment_sum(span, &numbers, 3)?;
+                n / ((n - 1.0) * (n - 2.0)) * sum
+            }
+        ),
+        // Ranking
+        formula_fn!(
+            /// Returns the `k`th largest value in `array`, where `k` starts at
+            /// 1.
+            #[examples("LARGE(A1:A10, 1)", "LARGE(A1:A10, {1, 2, 3})")]
+            #[zip_map]
+            fn LARGE(span: Span, array: (Spanned<Value>), [k]: i64) {
+                let mut numbers = sorted(numbers_in(array)?);
+                numbers.reverse();
+                nth(*span, &numbers, k)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the `k`th smallest value in `array`, where `k` starts at
+            /// 1.
+            #[examples("SMALL(A1:A10, 1)", "SMALL(A1:A10, {1, 2, 3})")]
+            #[zip_map]
+            fn SMALL(span: Span, array: (Spanned<Value>), [k]: i64) {
+                let numbers = sorted(numbers_in(array)?);
+                nth(*span, &numbers, k)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the rank of `number` among the values in `array`. If
+            /// multiple values are equal, they are all given the highest rank.
+            ///
+            /// If `ascending` is `FALSE` (the default), then the largest value
+            /// has rank 1. If `ascending` is `TRUE`, then the smallest value has
+            /// rank 1.
+            ///
+            /// Returns an error if `number` does not appear in `array`.
+            #[examples("RANK.EQ(A1, A1:A10)", "RANK.EQ(A1, A1:A10, TRUE)")]
+            #[zip_map]
+            fn "RANK.EQ"(
+                span: Span,
+                [number]: f64,
+                array: (Spanned<Value>),
+                [ascending]: (Option<bool>),
+            ) {
+                let numbers = numbers_in(array)?;
+                let (better, equal) = rank(&numbers, number, ascending.unwrap_or(false));
+                if equal == 0 {
+                    return Err(RunErrorMsg::NoMatch.with_span(span));
+                }
+                (better + 1) as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the rank of `number` among the values in `array`. If
+            /// multiple values are equal, they are all given the average of
+            /// their ranks.
+            ///
+            /// If `ascending` is `FALSE` (the default), then the largest value
+            /// has rank 1. If `ascending` is `TRUE`, then the smallest value has
+            /// rank 1.
+            ///
+            /// Returns an error if `number` does not appear in `array`.
+            #[examples("RANK.AVG(A1, A1:A10)", "RANK.AVG(A1, A1:A10, TRUE)")]
+            #[zip_map]
+            fn "RANK.AVG"(
+                span: Span,
+                [number]: f64,
+                array: (Spanned<Value>),
+                [ascending]: (Option<bool>),
+            ) {
+                let numbers = numbers_in(array)?;
+                let (better, equal) = rank(&numbers, number, ascending.unwrap_or(false));
+                if equal == 0 {
+                    return Err(RunErrorMsg::NoMatch.with_span(span));
+                }
+                better as f64 + (equal as f64 + 1.0) / 2.0
+            }
+        ),
+        formula_fn!(
+            /// Returns the `k`th percentile of the values in `array`, where `k`
+            /// is between 0 and 1 (inclusive). Interpolates between values if
+            /// necessary.
+            #[examples("PERCENTILE.INC(A1:A10, 0.9)", "PERCENTILE.INC(A1:A10, {0.25, 0.75})")]
+            #[zip_map]
+            fn "PERCENTILE.INC"(span: Span, array: (Spanned<Value>), [k]: f64) {
+                let sorted = sorted(numbers_in(array)?);
+                if sorted.is_empty() || !(0.0..=1.0).contains(&k) {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                percentile_inclusive(&sorted, k)
+            }
+        ),
+        formula_fn!(
+            /// Returns the `k`th percentile of the values in `array`, where `k`
+            /// is between 0 and 1 (exclusive). Interpolates between values if
+            /// necessary.
+            ///
+            /// Returns an error if `k` is too close to 0 or 1 for the number of
+            /// values.
+            #[examples("PERCENTILE.EXC(A1:A10, 0.9)", "PERCENTILE.EXC(A1:A10, {0.25, 0.75})")]
+            #[zip_map]
+            fn "PERCENTILE.EXC"(span: Span, array: (Spanned<Value>), [k]: f64) {
+                let sorted = sorted(numbers_in(array)?);
+                percentile_exclusive(*span, &sorted, k)?
+            }
+        ),
+        formula_fn!(
+            /// Returns a quartile of the values in `array`. `quart` is an
+            /// integer from 0 (minimum value) to 4 (maximum value), with 2
+            /// being the median. Interpolates between values if necessary.
+            #[examples("QUARTILE.INC(A1:A10, 1)", "QUARTILE.INC(A1:A10, {1, 3})")]
+            #[zip_map]
+            fn "QUARTILE.INC"(span: Span, array: (Spanned<Value>), [quart]: i64) {
+                let sorted = sorted(numbers_in(array)?);
+                if sorted.is_empty() || !(0..=4).contains(&quart) {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                percentile_inclusive(&sorted, quart as f64 / 4.0)
+            }
+        ),
+        formula_fn!(
+            /// Returns a quartile of the values in `array`. `quart` is an
+            /// integer from 1 to 3, with 2 being the median. Interpolates
+            /// between values if necessary.
+            #[examples("QUARTILE.EXC(A1:A10, 1)", "QUARTILE.EXC(A1:A10, {1, 3})")]
+            #[zip_map]
+            fn "QUARTILE.EXC"(span: Span, array: (Spanned<Value>), [quart]: i64) {
+                let sorted = sorted(numbers_in(array)?);
+                if !(1..=3).contains(&quart) {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                percentile_exclusive(*span, &sorted, quart as f64 / 4.0)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the rank of `x` among the values in `array` as a
+            /// fraction between 0 and 1 (inclusive). Interpolates between
+            /// values if necessary.
+            ///
+            /// The result is truncated to `significance` digits, which defaults
+            /// to 3.
+            ///
+            /// Returns an error if `x` is less than the smallest value or
+            /// greater than the largest value in `array`.
+            #[examples("PERCENTRANK(A1:A10, 5)", "PERCENTRANK(A1:A10, 5, 2)")]
+            #[zip_map]
+            fn PERCENTRANK(
+                span: Span,
+                array: (Spanned<Value>),
+                [x]: f64,
+                [significance]: (Option<i64>),
+            ) {
+                let significance = significance.unwrap_or(3);
+                if significance < 1 {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(span));
+                }
+                let sorted = sorted(numbers_in(array)?);
+                let rank = percent_rank(&sorted, x).ok_or(RunErrorMsg::NoMatch.with_span(span))?;
+                let scale = 10_f64.powi(significance as i32);
+                // Nudge the value up slightly so that floating-point error does
+                // not cause it to be truncated down.
+                (rank * scale + 1e-9).floor() / scale
+            }
+        ),
+        // Correlation and regression
+        formula_fn!(
+            /// Returns the Pearson correlation coefficient between two sets of
+            /// values, which must be the same size.
+            ///
+            /// Pairs where either value is blank or not a number are ignored.
+            #[examples("CORREL(A1:A10, B1:B10)")]
+            fn CORREL(array1: (Spanned<Array>), array2: (Spanned<Array>)) {
+                Pairs::new(&array1, &array2)?.correlation()?
+            }
+        ),
+        formula_fn!(
+            /// Returns the Pearson correlation coefficient between two sets of
+            /// values, which must be the same size. This is the same as
+            /// `CORREL`.
+            ///
+            /// Pairs where either value is blank or not a number are ignored.
+            #[examples("PEARSON(A1:A10, B1:B10)")]
+            fn PEARSON(array1: (Spanned<Array>), array2: (Spanned<Array>)) {
+                Pairs::new(&array1, &array2)?.correlation()?
+            }
+        ),
+        formula_fn!(
+            /// Returns the square of the Pearson correlation coefficient
+            /// between two sets of values, which must be the same size.
+            ///
+            /// Pairs where either value is blank or not a number are ignored.
+            #[examples("RSQ(B1:B10, A1:A10)")]
+            fn RSQ(known_ys: (Spanned<Array>), known_xs: (Spanned<Array>)) {
+                Pairs::new(&known_xs, &known_ys)?.correlation()?.powi(2)
+            }
+        ),
+        formula_fn!(
+            /// Returns the slope of the linear regression line through a set of
+            /// points, which must be the same size.
+            ///
+            /// Pairs where either value is blank or not a number are ignored.
+            #[examples("SLOPE(B1:B10, A1:A10)")]
+            fn SLOPE(known_ys: (Spanned<Array>), known_xs: (Spanned<Array>)) {
+                Pairs::new(&known_xs, &known_ys)?.slope()?
+            }
+        ),
+        formula_fn!(
+            /// Returns the y-intercept of the linear regression line through a
+            /// set of points, which must be the same size.
+            ///
+            /// Pairs where either value is blank or not a number are ignored.
+            #[examples("INTERCEPT(B1:B10, A1:A10)")]
+            fn INTERCEPT(known_ys: (Spanned<Array>), known_xs: (Spanned<Array>)) {
+                Pairs::new(&known_xs, &known_ys)?.intercept()?
+            }
+        ),
+        formula_fn!(
+            /// Returns the standard error of the y-values predicted by the
+            /// linear regression line through a set of points, which must be
+            /// the same size.
+            ///
+            /// Pairs where either value is blank or not a number are ignored.
+            #[examples("STEYX(B1:B10, A1:A10)")]
+            fn STEYX(known_ys: (Spanned<Array>), known_xs: (Spanned<Array>)) {
+                Pairs::new(&known_xs, &known_ys)?.standard_error()?
+            }
+        ),
+        formula_fn!(
+            /// Returns the sample covariance of two sets of values, which must
+            /// be the same size, treating them as a sample of a larger
+            /// population.
+            ///
+            /// Pairs where either value is blank or not a number are ignored.
+            #[examples("COVARIANCE.S(A1:A10, B1:B10)")]
+            fn "COVARIANCE.S"(array1: (Spanned<Array>), array2: (Spanned<Array>)) {
+                Pairs::new(&array1, &array2)?.covariance(1)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the covariance of two sets of values, which must be the
+            /// same size, treating them as the entire population.
+            ///
+            /// Pairs where either value is blank or not a number are ignored.
+            #[examples("COVARIANCE.P(A1:A10, B1:B10)")]
+            fn "COVARIANCE.P"(array1: (Spanned<Array>), array2: (Spanned<Array>)) {
+                Pairs::new(&array1, &array2)?.covariance(0)?
+            }
+        ),
+        formula_fn!(
+            /// Predicts the y-value for `x` using the linear regression line
+            /// through a set of points, which must be the same size.
+            ///
+            /// Pairs where either value is blank or not a number are ignored.
+            #[examples("FORECAST.LINEAR(10, B1:B10, A1:A10)")]
+            #[zip_map]
+            fn "FORECAST.LINEAR"(
+                [x]: f64,
+                known_ys: (Spanned<Array>),
+                known_xs: (Spanned<Array>),
+            ) {
+                let pairs = Pairs::new(known_xs, known_ys)?;
+                pairs.intercept()? + pairs.slope()? * x
+            }
+        ),
+        formula_fn!(
+            /// Predicts the y-value for `x` using the linear regression line
+            /// through a set of points, which must be the same size. This is
+            /// the same as `FORECAST.LINEAR`.
+            ///
+            /// Pairs where either value is blank or not a number are ignored.
+            #[examples("FORECAST(10, B1:B10, A1:A10)")]
+            #[zip_map]
+            fn FORECAST([x]: f64, known_ys: (Spanned<Array>), known_xs: (Spanned<Array>)) {
+                let pairs = Pairs::new(known_xs, known_ys)?;
+                pairs.intercept()? + pairs.slope()? * x
+            }
+        ),
+        formula_fn!(
+            /// Fits a line `y = m1*x1 + m2*x2 + ... + b` to a set of points
+            /// using the least squares method, and returns the slopes and
+            /// y-intercept of the line.
+            ///
+            /// `known_ys` must be a single row or column. If it is a column,
+            /// then each column of `known_xs` is a separate variable; if it is
+            /// a row, then each row of `known_xs` is a separate variable.
+            /// `known_xs` defaults to `{1, 2, 3, ...}`.
+            ///
+            /// The result is a row containing the slope for each variable in
+            /// reverse order, followed by the y-intercept. If `constant` is
+            /// false, then the y-intercept is forced to be zero.
+            ///
+            /// If `stats` is true, then four more rows are returned:
+            ///
+            /// 1. The standard error of each value in the first row
+            /// 2. The coefficient of determination and the standard error of
+            ///    the y-estimate
+            /// 3. The F statistic and the degrees of freedom
+            /// 4. The regression sum of squares and the residual sum of
+            ///    squares
+            ///
+            /// Returns an error if the variables are linearly dependent.
+            #[examples("LINEST(B1:B10, A1:A10)", "LINEST(C1:C10, A1:B10, TRUE, TRUE)")]
+            fn LINEST(
+                span: Span,
+                known_ys: (Spanned<Array>),
+                known_xs: (Option<Spanned<Array>>),
+                constant: (Option<bool>),
+                stats: (Option<bool>),
+            ) {
+                let data = RegressionData::new(&known_ys, known_xs.as_ref())?;
+                let fit = data.fit(span, constant.unwrap_or(true))?;
+                fit.to_array(span, stats.unwrap_or(false), |coefficient| coefficient)?
+            }
+        ),
+        formula_fn!(
+            /// Fits an exponential curve `y = b * m1^x1 * m2^x2 * ...` to a set
+            /// of points, and returns the bases and the constant `b`.
+            ///
+            /// The arguments and result have the same layout as `LINEST`, and
+            /// the statistics describe the linear fit of `LN(y)`. If
+            /// `constant` is false, then `b` is forced to be 1. Every value in
+            /// `known_ys` must be positive.
+            #[examples("LOGEST(B1:B10, A1:A10)", "LOGEST(C1:C10, A1:B10, TRUE, TRUE)")]
+            fn LOGEST(
+                span: Span,
+                known_ys: (Spanned<Array>),
+                known_xs: (Option<Spanned<Array>>),
+                constant: (Option<bool>),
+                stats: (Option<bool>),
+            ) {
+                let data = RegressionData::new(&known_ys, known_xs.as_ref())?.ln(known_ys.span)?;
+                let fit = data.fit(span, constant.unwrap_or(true))?;
+                fit.to_array(span, stats.unwrap_or(false), f64::exp)?
+            }
+        ),
+        formula_fn!(
+            /// Fits a line to a set of points using the least squares method,
+            /// and returns the y-values along that line for `new_xs`.
+            ///
+            /// `known_ys` and `known_xs` have the same layout as in `LINEST`.
+            /// `new_xs` defaults to `known_xs` and must have the same number of
+            /// variables. If there is only one variable, then the result has
+            /// the same size as `new_xs`. If `constant` is false, then the
+            /// y-intercept is forced to be zero.
+            #[examples("TREND(B1:B10, A1:A10, A11:A15)", "TREND(C1:C10, A1:B10, A11:B11)")]
+            fn TREND(
+                span: Span,
+                known_ys: (Spanned<Array>),
+                known_xs: (Option<Spanned<Array>>),
+                new_xs: (Option<Spanned<Array>>),
+                constant: (Option<bool>),
+            ) {
+                let data = RegressionData::new(&known_ys, known_xs.as_ref())?;
+                let fit = data.fit(span, constant.unwrap_or(true))?;
+                let new_xs = new_xs.or(known_xs);
+                data.predict(span, &fit, &known_ys, new_xs, |y| y)?
+            }
+        ),
+        formula_fn!(
+            /// Fits an exponential curve to a set of points, and returns the
+            /// y-values along that curve for `new_xs`.
+            ///
+            /// The arguments have the same layout as `TREND`, and the curve has
+            /// the same form as in `LOGEST`. If `constant` is false, then the
+            /// curve is forced to pass through `y = 1` where every x-value is
+            /// zero. Every value in `known_ys` must be positive.
+            #[examples("GROWTH(B1:B10, A1:A10, A11:A15)", "GROWTH(C1:C10, A1:B10, A11:B11)")]
+            fn GROWTH(
+                span: Span,
+                known_ys: (Spanned<Array>),
+                known_xs: (Option<Spanned<Array>>),
+                new_xs: (Option<Spanned<Array>>),
+                constant: (Option<bool>),
+            ) {
+                let data = RegressionData::new(&known_ys, known_xs.as_ref())?.ln(known_ys.span)?;
+                let fit = data.fit(span, constant.unwrap_or(true))?;
+                let new_xs = new_xs.or(known_xs);
+                data.predict(span, &fit, &known_ys, new_xs, f64::exp)?
+            }
+        ),
+    ]
+}
+
+/// Returns the numbers in `values`, ignoring blank and non-numeric values in
+/// arrays the same way that `AVERAGE` does.
+pub(super) fn numbers_in(values: &Spanned<Value>) -> CodeResult<Vec<f64>> {
+    values.clone().into_iter::<f64>().without_spans().collect()
+}
+
+fn sorted(mut numbers: Vec<f64>) -> Vec<f64> {
+    numbers.sort_by(f64::total_cmp);
+    numbers
+}
+
+pub(super) fn mean(span: Span, numbers: &[f64]) -> CodeResult<f64> {
+    util::average(span, numbers.iter().copied().map(Ok))
+}
+
+fn sum_of_squared_deviations(span: Span, numbers: &[f64]) -> CodeResult<f64> {
+    let mean = mean(span, numbers)?;
+    Ok(numbers.iter().map(|x| (x - mean).powi(2)).sum())
+}
+
+/// Returns the variance of `numbers`, dividing by the number of values minus
+/// `ddof` (1 for a sample, 0 for a population).
+pub(super) fn variance(span: Span, numbers: &[f64], ddof: usize) -> CodeResult<f64> {
+    let sum = sum_of_squared_deviations(span, numbers)?;
+    util::checked_div(span, sum, numbers.len() as f64 - ddof as f64)
+}
+
+/// Returns the sum of the `k`th powers of the values in `numbers` standardized
+/// using the sample standard deviation.
+fn standardized_moment_sum(span: Span, numbers: &[f64], k: i32) -> CodeResult<f64> {
+    let mean = mean(span, numbers)?;
+    let std_dev = variance(span, numbers, 1)?.sqrt();
+    numbers
+        .iter()
+        .map(|x| util::checked_div(span, x - mean, std_dev).map(|z| z.powi(k)))
+        .sum()
+}
+
+/// Returns the values that appear most often, in the order that they first
+/// appear, or an empty list if no value appears more than once.
+fn modes(numbers: &[f64]) -> Vec<f64> {
+    let counts = numbers.iter().map(|x| x.to_bits()).counts();
+    let max_count = counts.values().copied().max().unwrap_or(0);
+    if max_count < 2 {
+        return vec![];
+    }
+    numbers
+        .iter()
+        .filter(|x| counts[&x.to_bits()] == max_count)
+        .copied()
+        .unique_by(|x| x.to_bits())
+        .collect()
+}
+
+/// Returns the `k`th value in `numbers`, where `k` starts at 1.
+fn nth(span: Span, numbers: &[f64], k: i64) -> CodeResult<f64> {
+    usize::try_from(k - 1)
+        .ok()
+        .and_then(|i| numbers.get(i))
+        .copied()
+        .ok_or(RunErrorMsg::InvalidArgument.with_span(span))
+}
+
+/// Returns the number of values that rank ahead of `number` and the number of
+/// values equal to `number`.
+fn rank(numbers: &[f64], number: f64, ascending: bool) -> (usize, usize) {
+    let better = numbers
+        .iter()
+        .filter(|&&x| if ascending { x < number } else { x > number })
+        .count();
+    let equal = numbers.iter().filter(|&&x| x == number).count();
+    (better, equal)
+}
+
+/// Returns the `k`th percentile of the non-empty list `sorted`, where `k` is
+/// between 0 and 1 (inclusive).
+fn percentile_inclusive(sorted: &[f64], k: f64) -> f64 {
+    interpolate(sorted, k * (sorted.len() - 1) as f64)
+}
+
+/// Returns the `k`th percentile of `sorted`, where `k` is between 0 and 1
+/// (exclusive).
+fn percentile_exclusive(span: Span, sorted: &[f64], k: f64) -> CodeResult<f64> {
+    let index = k * (sorted.len() + 1) as f64 - 1.0;
+    if !(0.0..=(sorted.len() as f64 - 1.0)).contains(&index) {
+        return Err(RunErrorMsg::InvalidArgument.with_span(span));
+    }
+    Ok(interpolate(sorted, index))
+}
+
+/// Returns the value at a fractional index into `sorted`, interpolating
+/// linearly between neighboring values.
+fn interpolate(sorted: &[f64], index: f64) -> f64 {
+    let i = index.floor() as usize;
+    let lo = sorted[i];
+    match sorted.get(i + 1) {
+        Some(hi) => lo + (index - i as f64) * (hi - lo),
+        None => lo,
+    }
+}
+
+/// Returns the rank of `x` in `sorted` as a fraction between 0 and 1, or
+/// `None` if `x` is outside the range of values.
+fn percent_rank(sorted: &[f64], x: f64) -> Option<f64> {
+    let (&first, &last) = (sorted.first()?, sorted.last()?);
+    if !(first..=last).contains(&x) {
+        return None;
+    }
+    if sorted.len() == 1 {
+        return Some(1.0);
+    }
+    let denominator = (sorted.len() - 1) as f64;
+    let below = sorted.partition_point(|&v| v < x);
+    if sorted[below] == x {
+        return Some(below as f64 / denominator);
+    }
+    // Interpolate between the ranks of the neighboring values.
+    let (lo, hi) = (sorted[below - 1], sorted[below]);
+    let lo_rank = sorted.partition_point(|&v| v < lo) as f64 / denominator;
+    let hi_rank = below as f64 / denominator;
+    Some(lo_rank + (x - lo) / (hi - lo) * (hi_rank - lo_rank))
+}
+
+/// Paired values from two arrays of the same size, excluding pairs where
+/// either value is blank or not a number.
+pub(super) struct Pairs {
+    pub(super) span: Span,
+    pub(super) xs: Vec<f64>,
+    pub(super) ys: Vec<f64>,
+}
+impl Pairs {
+    pub(super) fn new(xs: &Spanned<Array>, ys: &Spanned<Array>) -> CodeResult<Self> {
+        let span = Span::merge(xs.span, ys.span);
+        if xs.inner.size() != ys.inner.size() {
+            return Err(RunErrorMsg::ExactArraySizeMismatch {
+                expected: xs.inner.size(),
+                got: ys.inner.size(),
+            }
+            .with_span(ys.span));
+        }
+        let mut ret = Pairs {
+            span,
+            xs: vec![],
+            ys: vec![],
+        };
+        for (x, y) in std::iter::zip(xs.inner.cell_values_slice(), ys.inner.cell_values_slice()) {
+            let x = Spanned {
+                span: xs.span,
+                inner: x,
+            };
+            let y = Spanned {
+                span: ys.span,
+                inner: y,
+            };
+            // Propagate errors even if the other value in the pair is ignored.
+            if let (Some(x), Some(y)) = (x.coerce_or_none::<f64>(), y.coerce_or_none::<f64>()) {
+                ret.xs.push(x?.inner);
+                ret.ys.push(y?.inner);
+            } else {
+                x.into_non_error_value()?;
+                y.into_non_error_value()?;
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Returns the sum of products of deviations from the means of `a` and
+    /// `b`.
+    fn sum_of_products(&self, a: &[f64], b: &[f64]) -> CodeResult<f64> {
+        let mean_a = mean(self.span, a)?;
+        let mean_b = mean(self.span, b)?;
+        Ok(std::iter::zip(a, b)
+            .map(|(a, b)| (a - mean_a) * (b - mean_b))
+            .sum())
+    }
+
+    fn covariance(&self, ddof: usize) -> CodeResult<f64> {
+        let sum = self.sum_of_products(&self.xs, &self.ys)?;
+        util::checked_div(self.span, sum, self.xs.len() as f64 - ddof as f64)
+    }
+
+    fn correlation(&self) -> CodeResult<f64> {
+        let sxy = self.sum_of_products(&self.xs, &self.ys)?;
+        let sxx = self.sum_of_products(&self.xs, &self.xs)?;
+        let syy = self.sum_of_products(&self.ys, &self.ys)?;
+        util::checked_div(self.span, sxy, (sxx * syy).sqrt())
+    }
+
+    fn slope(&self) -> CodeResult<f64> {
+        let sxy = self.sum_of_products(&self.xs, &self.ys)?;
+        let sxx = self.sum_of_products(&self.xs, &self.xs)?;
+        util::checked_div(self.span, sxy, sxx)
+    }
+
+    fn intercept(&self) -> CodeResult<f64> {
+        Ok(mean(self.span, &self.ys)? - self.slope()? * mean(self.span, &self.xs)?)
+    }
+
+    fn standard_error(&self) -> CodeResult<f64> {
+        let sxy = self.sum_of_products(&self.xs, &self.ys)?;
+        let sxx = self.sum_of_products(&self.xs, &self.xs)?;
+        let syy = self.sum_of_products(&self.ys, &self.ys)?;
+        let residual = syy - util::checked_div(self.span, sxy * sxy, sxx)?;
+        Ok(util::checked_div(self.span, residual, self.xs.len() as f64 - 2.0)?.sqrt())
+    }
+}
+
+/// Known values for a least squares regression with any number of variables.
+struct RegressionData {
+    /// Axis along which the observations are listed.
+    axis: Axis,
+    /// Y-value of each observation.
+    ys: Vec<f64>,
+    /// X-values of each observation, with one value per variable.
+    xs: Vec<Vec<f64>>,
+}
+impl RegressionData {
+    fn new(known_ys: &Spanned<Array>, known_xs: Option<&Spanned<Array>>) -> CodeResult<Self> {
+        let axis = known_ys.array_linear_axis()?.unwrap_or(Axis::Y);
+        let ys = matrix_from_array(known_ys)?.concat();
+        let xs = match known_xs {
+            Some(known_xs) => {
+                known_xs.check_array_size_on(axis, ys.len() as u32)?;
+                observations(axis, matrix_from_array(known_xs)?)
+            }
+            None => (1..=ys.len()).map(|x| vec![x as f64]).collect(),
+        };
+        Ok(Self { axis, ys, xs })
+    }
+
+    /// Replaces each y-value with its natural logarithm, for fitting an
+    /// exponential curve. Returns an error if any y-value is not positive.
+    fn ln(mut self, span: Span) -> CodeResult<Self> {
+        for y in &mut self.ys {
+            util::ensure(span, *y > 0.0)?;
+            *y = y.ln();
+        }
+        Ok(self)
+    }
+
+    /// Returns the number of variables.
+    fn variable_count(&self) -> usize {
+        self.xs.first().map_or(0, |x| x.len())
+    }
+
+    /// Fits a linear model to the data using the least squares method. If
+    /// `constant` is false, then the y-intercept is zero.
+    fn fit(&self, span: Span, constant: bool) -> CodeResult<LinearFit> {
+        // Solve the normal equations `XᵀX b = Xᵀy`, where `X` has a row for
+        // each observation and a column for each coefficient.
+        let x = self
+            .xs
+            .iter()
+            .map(|row| {
+                let mut row = row.clone();
+                if constant {
+                    row.push(1.0);
+                }
+                row
+            })
+            .collect_vec();
+        let x_transpose = matrix_transpose(&x);
+        let inverse = matrix_inverse(&matrix_product(&x_transpose, &x))
+            .ok_or_else(|| RunErrorMsg::SingularMatrix.with_span(span))?;
+        let y = self.ys.iter().map(|&y| vec![y]).collect_vec();
+        let mut coefficients = matrix_product(&inverse, &matrix_product(&x_transpose, &y)).concat();
+        let intercept = match constant {
+            true => coefficients.pop().unwrap_or(0.0),
+            false => 0.0,
+        };
+
+        let mut fit = LinearFit {
+            slopes: coefficients,
+            intercept,
+            constant,
+            inverse,
+            ss_reg: 0.0,
+            ss_resid: 0.0,
+            degrees_of_freedom: self.ys.len() as f64 - x_transpose.len() as f64,
+        };
+        fit.ss_resid = std::iter::zip(&self.ys, &self.xs)
+            .map(|(y, x)| (y - fit.predict(x)).powi(2))
+            .sum();
+        let ss_total = match constant {
+            true => sum_of_squared_deviations(span, &self.ys)?,
+            false => self.ys.iter().map(|y| y * y).sum(),
+        };
+        fit.ss_reg = ss_total - fit.ss_resid;
+        Ok(fit)
+    }
+
+    /// Returns the y-values predicted by `fit` for `new_xs`, which defaults to
+    /// `{1, 2, 3, ...}` in the same shape as `known_ys`. Each y-value is
+    /// passed through `f`.
+    fn predict(
+        &self,
+        span: Span,
+        fit: &LinearFit,
+        known_ys: &Spanned<Array>,
+        new_xs: Option<Spanned<Array>>,
+        f: fn(f64) -> f64,
+    ) -> CodeResult<Array> {
+        let new_xs = match new_xs {
+            Some(new_xs) => new_xs,
+            None => Spanned {
+                span: known_ys.span,
+                inner: Array::new_row_major(
+                    known_ys.inner.size(),
+                    (1..=self.ys.len() as i64).map(CellValue::from).collect(),
+                )?,
+            },
+        };
+
+        let matrix = matrix_from_array(&new_xs)?;
+        let predictions = if self.variable_count() == 1 {
+            // Predict a value for each x-value, keeping the same shape.
+            matrix
+                .iter()
+                .map(|row| row.iter().map(|&x| f(fit.predict(&[x]))).collect())
+                .collect_vec()
+        } else {
+            new_xs.check_array_size_on(self.axis.other_axis(), self.variable_count() as u32)?;
+            let ys = observations(self.axis, matrix)
+                .iter()
+                .map(|x| f(fit.predict(x)))
+                .collect_vec();
+            match self.axis {
+                Axis::X => vec![ys],
+                Axis::Y => ys.into_iter().map(|y| vec![y]).collect(),
+            }
+        };
+        array_from_matrix(span, &predictions)
+    }
+}
+
+/// Least squares fit of a linear model `y = m1*x1 + m2*x2 + ... + b`.
+struct LinearFit {
+    /// Slope for each variable.
+    slopes: Vec<f64>,
+    /// Y-intercept, which is zero if `constant` is false.
+    intercept: f64,
+    /// Whether the y-intercept was fitted.
+    constant: bool,
+    /// Inverse of `XᵀX`, which is used to compute the standard errors of the
+    /// coefficients.
+    inverse: Vec<Vec<f64>>,
+    /// Regression sum of squares.
+    ss_reg: f64,
+    /// Residual sum of squares.
+    ss_resid: f64,
+    degrees_of_freedom: f64,
+}
+impl LinearFit {
+    /// Returns the y-value predicted for a set of x-values.
+    fn predict(&self, xs: &[f64]) -> f64 {
+        let sum = std::iter::zip(&self.slopes, xs)
+            .map(|(m, x)| m * x)
+            .sum::<f64>();
+        sum + self.intercept
+    }
+
+    /// Returns the result of `LINEST` or `LOGEST`. Each coefficient in the
+    /// first row is passed through `f`.
+    fn to_array(&self, span: Span, stats: bool, f: fn(f64) -> f64) -> CodeResult<Array> {
+        let k = self.slopes.len();
+        let finite = |x: CodeResult<f64>| CellValue::from(x.and_then(|x| util::finite(span, x)));
+
+        let mut rows = vec![self
+            .slopes
+            .iter()
+            .rev()
+            .chain([&self.intercept])
+            .map(|&c| finite(Ok(f(c))))
+            .collect_vec()];
+
+        if stats {
+            let variance = util::checked_div(span, self.ss_resid, self.degrees_of_freedom);
+            let standard_error = |i: usize| -> CodeResult<f64> {
+                Ok((variance.clone()? * self.inverse[i][i]).sqrt())
+            };
+            let mut standard_errors = (0..k)
+                .rev()
+                .map(|i| finite(standard_error(i)))
+                .collect_vec();
+            standard_errors.push(match self.constant {
+                true => finite(standard_error(k)),
+                false => super::array::not_available(span),
+            });
+            rows.push(standard_errors);
+
+            let r_squared = util::checked_div(span, self.ss_reg, self.ss_reg + self.ss_resid);
+            let f_statistic = variance
+                .clone()
+                .and_then(|v| util::checked_div(span, self.ss_reg / k as f64, v));
+            let stats_rows = [
+                [r_squared, variance.map(f64::sqrt)],
+                [f_statistic, Ok(self.degrees_of_freedom)],
+                [Ok(self.ss_reg), Ok(self.ss_resid)],
+            ];
+            for stats_row in stats_rows {
+                let mut row = stats_row.into_iter().map(finite).collect_vec();
+                row.resize_with(k + 1, || super::array::not_available(span));
+                rows.push(row);
             }
-        ),
-    ]
+        }
+
+        let size = ArraySize::new_or_err(k as u32 + 1, rows.len() as u32)?;
+        Array::new_row_major(size, rows.into_iter().flatten().collect())
+    }
+}
+
+/// Returns a matrix with one row per observation, given a matrix where
+/// observations are listed along `axis`.
+fn observations(axis: Axis, matrix: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+    match axis {
+        Axis::X => matrix_transpose(&matrix),
+        Axis::Y => matrix,
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::util::assert_f64_approx_eq;
     use crate::{formulas::tests::*, Pos};
 
     #[test]
@@ -221,6 +1311,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_averageifs() {
+        let g = Grid::new();
+
+        assert_eq!(
+            "3.5",
+            eval_to_string(&g, "AVERAGEIFS(0..10, 0..10, \">=2\", 0..10, \"<=5\")"),
+        );
+        assert_eq!(
+            RunErrorMsg::DivideByZero,
+            eval_to_err(&g, "AVERAGEIFS(0..10, 0..10, \">20\")").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(1, 11).unwrap(),
+                got: ArraySize::new(2, 1).unwrap(),
+            },
+            eval_to_err(&g, "AVERAGEIFS(0..10, 0..10, \">2\", {1, 2}, 1)").msg,
+        );
+    }
+
     #[test]
     fn test_count() {
         let g = Grid::new();
@@ -295,6 +1406,49 @@ mod tests {
         assert_eq!("6", eval_to_string(&g, "COUNTIF(Bn5:B10, \"<=5\")"));
     }
 
+    #[test]
+    fn test_countifs() {
+        let g = Grid::new();
+        assert_eq!("6", eval_to_string(&g, "COUNTIFS(0..10, \"<=5\")"));
+        assert_eq!(
+            "3",
+            eval_to_string(&g, "COUNTIFS(0..10, \"<=5\", 0..10, \">2\")"),
+        );
+        assert_eq!(
+            "2",
+            eval_to_string(
+                &g,
+                "COUNTIFS({\"a\", \"b\", \"a\", \"a\"}, \"a\", {1, 1, 0, 1}, \"<>0\")",
+            ),
+        );
+
+        // Test that blank cells are ignored
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        for y in 0..=10 {
+            let _ = sheet.set_cell_value(Pos { x: 1, y }, y);
+        }
+        assert_eq!(
+            "3",
+            eval_to_string(&g, "COUNTIFS(Bn5:B10, \"<=5\", Bn5:B10, \">2\")"),
+        );
+
+        assert_eq!(
+            RunErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(1, 11).unwrap(),
+                got: ArraySize::new(1, 6).unwrap(),
+            },
+            eval_to_err(&g, "COUNTIFS(0..10, \"<=5\", 0..5, \">2\")").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::MissingRequiredArgument {
+                func_name: "COUNTIFS".into(),
+                arg_name: "criteria".into(),
+            },
+            eval_to_err(&g, "COUNTIFS(0..10, \"<=5\", 0..10)").msg,
+        );
+    }
+
     #[test]
     fn test_countblank() {
         let g = Grid::new();
@@ -319,4 +1473,447 @@ mod tests {
         let g = Grid::new();
         assert_eq!("3", eval_to_string(&g, "MAX(1, 3, 2)"));
     }
+
+    #[test]
+    fn test_minifs_maxifs() {
+        let g = Grid::new();
+        let (values, ranges) = ("{5, 1, 8, 3}", "{\"a\", \"b\", \"a\", \"a\"}, \"a\"");
+        assert_eq!(
+            "3",
+            eval_to_string(&g, &format!("MINIFS({values}, {ranges})"))
+        );
+        assert_eq!(
+            "8",
+            eval_to_string(&g, &format!("MAXIFS({values}, {ranges})"))
+        );
+        assert_eq!(
+            "5",
+            eval_to_string(&g, &format!("MAXIFS({values}, {ranges}, {values}, \"<8\")")),
+        );
+
+        // No matches
+        assert_eq!(
+            "0",
+            eval_to_string(&g, &format!("MINIFS({values}, {ranges}, {values}, 0)"))
+        );
+        assert_eq!(
+            "0",
+            eval_to_string(&g, &format!("MAXIFS({values}, {ranges}, {values}, 0)"))
+        );
+
+        assert_eq!(
+            RunErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(4, 1).unwrap(),
+                got: ArraySize::new(1, 4).unwrap(),
+            },
+            eval_to_err(&g, &format!("MAXIFS({values}, {{1; 2; 3; 4}}, \">0\")")).msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_central_tendency() {
+        let g = Grid::new();
+
+        assert_eq!("3.5", eval_to_string(&g, "MEDIAN(1, 2, 3, 4, 5, 6)"));
+        assert_eq!(
+            "3",
+            eval_to_string(&g, "MEDIAN({5, \"a\", 1; 3, \"\", 2}, 4)")
+        );
+        expect_err(&RunErrorMsg::EmptyArray, &g, "MEDIAN({\"a\"})");
+
+        assert_eq!("4", eval_to_string(&g, "MODE.SNGL({5.6, 4, 4, 3, 2, 4})"));
+        assert_eq!("3", eval_to_string(&g, "MODE.SNGL(3, 1, 1, 3)"));
+        expect_err(&RunErrorMsg::NoMatch, &g, "MODE.SNGL(1, 2, 3)");
+        assert_eq!(
+            "{1; 2; 3}",
+            eval_to_string(&g, "MODE.MULT({1, 2, 3, 4, 3, 2, 1, 2, 3, 5, 6, 1})"),
+        );
+
+        let data = "{4, 5, 8, 7, 11, 4, 3}";
+        assert_f64_approx_eq(
+            5.476986969656962,
+            &eval_to_string(&g, &format!("GEOMEAN({data})")),
+        );
+        assert_f64_approx_eq(
+            5.028375962061728,
+            &eval_to_string(&g, &format!("HARMEAN({data})")),
+        );
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "GEOMEAN(1, 0)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "HARMEAN(1, -1)");
+
+        assert_f64_approx_eq(
+            3.7777777777777777,
+            &eval_to_string(&g, "TRIMMEAN({4, 5, 6, 7, 2, 3, 4, 5, 1, 2, 3}, 0.2)"),
+        );
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "TRIMMEAN({1, 2}, 1)");
+    }
+
+    #[test]
+    fn test_formula_dispersion() {
+        let g = Grid::new();
+
+        let data = "{2, 4, 4, 4, \"\", 5, 5, 7, 9, \"text\"}";
+        assert_f64_approx_eq(
+            4.571428571428571,
+            &eval_to_string(&g, &format!("VAR.S({data})")),
+        );
+        assert_eq!("4", eval_to_string(&g, &format!("VAR.P({data})")));
+        assert_f64_approx_eq(
+            2.138089935299395,
+            &eval_to_string(&g, &format!("STDEV.S({data})")),
+        );
+        assert_eq!("2", eval_to_string(&g, &format!("STDEV.P({data})")));
+        assert_eq!("1.5", eval_to_string(&g, &format!("AVEDEV({data})")));
+        assert_eq!("32", eval_to_string(&g, &format!("DEVSQ({data})")));
+        expect_err(&RunErrorMsg::DivideByZero, &g, "VAR.S(1)");
+        expect_err(&RunErrorMsg::DivideByZero, &g, "STDEV.P({\"a\"})");
+
+        assert_f64_approx_eq(
+            1.0204081632653061,
+            &eval_to_string(&g, "AVEDEV(4, 5, 6, 7, 5, 4, 3)"),
+        );
+        assert_eq!("48", eval_to_string(&g, "DEVSQ(4, 5, 6, 7, 5, 4, 3)"));
+
+        let data = "{3, 4, 5, 2, 3, 4, 5, 6, 4, 7}";
+        assert_f64_approx_eq(
+            -0.15179963720841627,
+            &eval_to_string(&g, &format!("KURT({data})")),
+        );
+        assert_f64_approx_eq(
+            0.3595430714067974,
+            &eval_to_string(&g, &format!("SKEW({data})")),
+        );
+        expect_err(&RunErrorMsg::DivideByZero, &g, "KURT(1, 2, 3)");
+        expect_err(&RunErrorMsg::DivideByZero, &g, "SKEW(1, 1, 1)");
+    }
+
+    #[test]
+    fn test_formula_ranking() {
+        let g = Grid::new();
+
+        assert_eq!(
+            "5",
+            eval_to_string(&g, "LARGE({3, 5, 3, 5, 4; 4, 2, 4, 6, 7}, 3)"),
+        );
+        assert_eq!(
+            "4",
+            eval_to_string(&g, "SMALL({3, 4, 5, 2, 3, 4, 5, 6, 4, 7}, 4)"),
+        );
+        assert_eq!("{7, 6}", eval_to_string(&g, "LARGE(1..7, {1, 2})"));
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "SMALL({1, 2}, 3)");
+        expect_err(&RunErrorMsg::InvalidArgument, &g, "LARGE({1, 2}, 0)");
+
+        let data = "{7, 3.5, 3.5, 1, 2}";
+        assert_eq!(
+            "5",
+            eval_to_string(&g, &format!("RANK.EQ(7, {data}, TRUE)"))
+        );
+        assert_eq!("4", eval_to_string(&g, &format!("RANK.EQ(2, {data})")));
+        assert_eq!("2", eval_to_string(&g, &format!("RANK.EQ(3.5, {data})")));
+        assert_eq!("2.5", eval_to_string(&g, &format!("RANK.AVG(3.5, {data})")));
+        assert_eq!(
+            "4",
+            eval_to_string(&g, "RANK.AVG(94, {89, 88, 92, 101, 94, 97, 95})"),
+        );
+        expect_err(&RunErrorMsg::NoMatch, &g, &format!("RANK.EQ(5, {data})"));
+
+        assert_f64_approx_eq(
+            1.9,
+            &eval_to_string(&g, "PERCENTILE.INC({1, 3, 2, 4}, 0.3)"),
+        );
+        assert_eq!("4", eval_to_string(&g, "PERCENTILE.INC({1, 3, 2, 4}, 1)"));
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "PERCENTILE.INC({1, 2}, 1.5)",
+        );
+
+        let data = "{1, 2, 3, 6, 6, 6, 7, 8, 9}";
+        assert_eq!(
+            "2.5",
+            eval_to_string(&g, &format!("PERCENTILE.EXC({data}, 0.25)"))
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            &format!("PERCENTILE.EXC({data}, 0)"),
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            &format!("PERCENTILE.EXC({data}, 0.01)"),
+        );
+
+        assert_eq!(
+            "3.5",
+            eval_to_string(&g, "QUARTILE.INC({1, 2, 4, 7, 8, 9, 10, 12}, 1)"),
+        );
+        let data = "{6, 7, 15, 36, 39, 40, 41, 42, 43, 47, 49}";
+        assert_eq!(
+            "15",
+            eval_to_string(&g, &format!("QUARTILE.EXC({data}, 1)"))
+        );
+        assert_eq!(
+            "43",
+            eval_to_string(&g, &format!("QUARTILE.EXC({data}, 3)"))
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            &format!("QUARTILE.EXC({data}, 4)"),
+        );
+
+        let data = "{13, 12, 11, 8, 4, 3, 2, 1, 1, 1}";
+        assert_eq!(
+            "0.333",
+            eval_to_string(&g, &format!("PERCENTRANK({data}, 2)"))
+        );
+        assert_eq!(
+            "0.555",
+            eval_to_string(&g, &format!("PERCENTRANK({data}, 4)"))
+        );
+        assert_eq!(
+            "0.666",
+            eval_to_string(&g, &format!("PERCENTRANK({data}, 8)"))
+        );
+        assert_eq!(
+            "0.583",
+            eval_to_string(&g, &format!("PERCENTRANK({data}, 5)"))
+        );
+        assert_eq!(
+            "0.58",
+            eval_to_string(&g, &format!("PERCENTRANK({data}, 5, 2)"))
+        );
+        expect_err(
+            &RunErrorMsg::NoMatch,
+            &g,
+            &format!("PERCENTRANK({data}, 14)"),
+        );
+    }
+
+    #[test]
+    fn test_formula_correlation_regression() {
+        let g = Grid::new();
+
+        let (xs, ys) = ("{3, 2, 4, 5, 6}", "{9, 7, 12, 15, 17}");
+        assert_f64_approx_eq(
+            0.9970544855015815,
+            &eval_to_string(&g, &format!("CORREL({xs}, {ys})")),
+        );
+        assert_f64_approx_eq(
+            0.9970544855015815,
+            &eval_to_string(&g, &format!("PEARSON({xs}, {ys})")),
+        );
+        assert_eq!(
+            "5.2",
+            eval_to_string(&g, &format!("COVARIANCE.P({xs}, {ys})"))
+        );
+        assert_eq!(
+            "6.5",
+            eval_to_string(&g, &format!("COVARIANCE.S({xs}, {ys})"))
+        );
+        assert_f64_approx_eq(
+            9.666666666666668,
+            &eval_to_string(&g, "COVARIANCE.S({2, 4, 8}, {5, 11, 12})"),
+        );
+
+        let (ys, xs) = ("{2, 3, 9, 1, 8, 7, 5}", "{6, 5, 11, 7, 5, 4, 4}");
+        assert_f64_approx_eq(
+            0.05795019157088123,
+            &eval_to_string(&g, &format!("RSQ({ys}, {xs})")),
+        );
+        assert_f64_approx_eq(
+            0.3055555555555556,
+            &eval_to_string(&g, &format!("SLOPE({ys}, {xs})")),
+        );
+        assert_f64_approx_eq(
+            3.305718950210041,
+            &eval_to_string(&g, &format!("STEYX({ys}, {xs})")),
+        );
+        assert_f64_approx_eq(
+            0.04838709677419217,
+            &eval_to_string(&g, "INTERCEPT({2, 3, 9, 1, 8}, {6, 5, 11, 7, 5})"),
+        );
+        assert_f64_approx_eq(
+            10.607253086419755,
+            &eval_to_string(
+                &g,
+                "FORECAST.LINEAR(30, {6, 7, 9, 15, 21}, {20, 28, 31, 38, 40})",
+            ),
+        );
+        assert_f64_approx_eq(
+            10.607253086419755,
+            &eval_to_string(&g, "FORECAST(30, {6, 7, 9, 15, 21}, {20, 28, 31, 38, 40})"),
+        );
+
+        // Pairs with a blank or text value are ignored.
+        assert_f64_approx_eq(
+            0.3055555555555556,
+            &eval_to_string(
+                &g,
+                "SLOPE({2, 3, 9, 1, 8, 7, 5, \"\"}, {6, 5, 11, 7, 5, 4, 4, 10})",
+            ),
+        );
+        assert_f64_approx_eq(
+            0.3055555555555556,
+            &eval_to_string(
+                &g,
+                "SLOPE({2, 3, 9, 1, 8, 7, 5, 10}, {6, 5, 11, 7, 5, 4, 4, \"a\"})",
+            ),
+        );
+
+        assert_eq!(
+            RunErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(3, 1).unwrap(),
+                got: ArraySize::new(2, 1).unwrap(),
+            },
+            eval_to_err(&g, "CORREL({1, 2, 3}, {1, 2})").msg,
+        );
+        expect_err(
+            &RunErrorMsg::DivideByZero,
+            &g,
+            "SLOPE({1, 2, 3}, {1, 1, 1})",
+        );
+        expect_err(
+            &RunErrorMsg::DivideByZero,
+            &g,
+            "CORREL({1, 2, 3}, {1, 1, 1})",
+        );
+    }
+
+    #[test]
+    fn test_linest() {
+        let g = Grid::new();
+        assert_eq!(
+            "{0.9, 0.3}",
+            eval_to_string(&g, "ROUND(LINEST({1, 2, 4, 3, 5}, {1, 2, 3, 4, 5}), 9)"),
+        );
+        assert_eq!(
+            "{0.9, 0.3}",
+            eval_to_string(&g, "ROUND(LINEST({1; 2; 4; 3; 5}), 9)"),
+        );
+
+        let linest = "LINEST({1, 2, 4, 3, 5}, {1, 2, 3, 4, 5}, TRUE, TRUE)";
+        for (row, col, expected) in [
+            (2, 1, 0.2516611478423583),
+            (2, 2, 0.834665601703261),
+            (3, 1, 0.81),
+            (3, 2, 0.7958224257542215),
+            (4, 1, 12.789473684210526),
+            (4, 2, 3.0),
+            (5, 1, 8.1),
+            (5, 2, 1.9),
+        ] {
+            let formula = format!("INDEX({linest}, {row}, {col})");
+            assert_f64_approx_eq(expected, &eval_to_string(&g, &formula));
+        }
+
+        // y = 1 + 2*x1 + 3*x2
+        let ys = "{9; 8; 19; 18; 29}";
+        let xs = "{1, 2; 2, 1; 3, 4; 4, 3; 5, 6}";
+        assert_eq!(
+            "{3, 2, 1}",
+            eval_to_string(&g, &format!("ROUND(LINEST({ys}, {xs}), 9)")),
+        );
+        assert_eq!(
+            "{3, 2, 1}",
+            eval_to_string(
+                &g,
+                "ROUND(LINEST({9, 8, 19, 18, 29}, {1, 2, 3, 4, 5; 2, 1, 4, 3, 6}), 9)"
+            ),
+        );
+        assert_eq!(
+            "{2, 0}",
+            eval_to_string(&g, "ROUND(LINEST({2, 4, 6}, {1, 2, 3}, FALSE), 9)"),
+        );
+        assert_eq!(
+            "5",
+            eval_to_string(&g, "ROWS(LINEST({2, 4, 6}, {1, 2, 3}, FALSE, TRUE))"),
+        );
+
+        expect_err(
+            &RunErrorMsg::SingularMatrix,
+            &g,
+            "LINEST({1; 2; 3}, {1, 2; 2, 4; 3, 6})",
+        );
+        assert_eq!(
+            RunErrorMsg::ExactArrayAxisMismatch {
+                axis: Axis::X,
+                expected: 3,
+                got: 2,
+            },
+            eval_to_err(&g, "LINEST({1, 2, 3}, {1, 2})").msg,
+        );
+    }
+
+    #[test]
+    fn test_logest() {
+        let g = Grid::new();
+        assert_eq!(
+            "{2, 1}",
+            eval_to_string(&g, "ROUND(LOGEST({2, 4, 8, 16}, {1, 2, 3, 4}), 9)"),
+        );
+        assert_eq!(
+            "{2, 3}",
+            eval_to_string(&g, "ROUND(LOGEST({6; 12; 24; 48}), 9)"),
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "LOGEST({2, -4, 8}, {1, 2, 3})",
+        );
+    }
+
+    #[test]
+    fn test_trend_growth() {
+        let g = Grid::new();
+        let ys = "{1, 2, 4, 3, 5}";
+        let xs = "{1, 2, 3, 4, 5}";
+        assert_eq!(
+            "{5.7, 6.6}",
+            eval_to_string(&g, &format!("ROUND(TREND({ys}, {xs}, {{6, 7}}), 9)")),
+        );
+        assert_eq!(
+            "{5.7; 6.6}",
+            eval_to_string(&g, &format!("ROUND(TREND({ys}, {xs}, {{6; 7}}), 9)")),
+        );
+        assert_eq!(
+            "{1.2, 2.1, 3, 3.9, 4.8}",
+            eval_to_string(&g, &format!("ROUND(TREND({ys}), 9)")),
+        );
+        assert_eq!(
+            "{1.2, 2.1, 3, 3.9, 4.8}",
+            eval_to_string(&g, &format!("ROUND(TREND({ys}, {xs}), 9)")),
+        );
+
+        // y = 1 + 2*x1 + 3*x2
+        let ys = "{9; 8; 19; 18; 29}";
+        let xs = "{1, 2; 2, 1; 3, 4; 4, 3; 5, 6}";
+        assert_eq!(
+            "{34; 8}",
+            eval_to_string(&g, &format!("ROUND(TREND({ys}, {xs}, {{6, 7; 2, 1}}), 9)")),
+        );
+        assert_eq!(
+            RunErrorMsg::ExactArrayAxisMismatch {
+                axis: Axis::X,
+                expected: 2,
+                got: 3,
+            },
+            eval_to_err(&g, &format!("TREND({ys}, {xs}, {{1, 2, 3}})")).msg,
+        );
+
+        assert_eq!(
+            "32",
+            eval_to_string(&g, "ROUND(GROWTH({2, 4, 8, 16}, {1, 2, 3, 4}, 5), 9)"),
+        );
+        assert_eq!(
+            "{6, 12, 24, 48}",
+            eval_to_string(&g, "ROUND(GROWTH({6, 12, 24, 48}), 9)"),
+        );
+        expect_err(
+            &RunErrorMsg::InvalidArgument,
+            &g,
+            "GROWTH({2, 0, 8}, {1, 2, 3}, 4)",
+        );
+    }
 }
@@ -4,19 +4,654 @@ pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
     include_in_docs: true,
     include_in_completions: true,
     name: "String functions",
-    docs: "",
+    docs: "Positions and lengths in these functions are measured in Unicode \
+           characters, and positions start at 1.",
     get_functions,
 };
 
 fn get_functions() -> Vec<FormulaFunction> {
-    vec![formula_fn!(
-        /// [Concatenates](https://en.wikipedia.org/wiki/Concatenation) all
-        /// values as strings.
-        #[examples("CONCAT(\"Hello, \", C0, \"!\")")]
-        fn CONCAT(strings: (Iter<String>)) {
-            strings.try_fold(String::new(), |a, b| Ok(a + &b?))
+    vec![
+        formula_fn!(
+            /// [Concatenates](https://en.wikipedia.org/wiki/Concatenation) all
+            /// values as strings.
+            #[examples("CONCAT(\"Hello, \", C0, \"!\")")]
+            fn CONCAT(strings: (Iter<String>)) {
+                strings.try_fold(String::new(), |a, b| Ok(a + &b?))
+            }
+        ),
+        formula_fn!(
+            /// Concatenates all values as strings, with `delimiter` between
+            /// each one.
+            ///
+            /// If `ignore_empty` is `TRUE`, then blank cells and empty strings
+            /// are skipped.
+            #[examples(
+                "TEXTJOIN(\", \", TRUE, A1:A10)",
+                "TEXTJOIN(\" \", FALSE, \"Hello\", B2, \"!\")"
+            )]
+            fn TEXTJOIN(delimiter: String, ignore_empty: bool, strings: (Iter<CellValue>)) {
+                let mut strings_to_join = vec![];
+                for value in strings {
+                    let s = String::try_from(value?)?;
+                    if !(ignore_empty && s.is_empty()) {
+                        strings_to_join.push(s);
+                    }
+                }
+                strings_to_join.join(&delimiter)
+            }
+        ),
+        formula_fn!(
+            /// Returns the first `char_count` characters of a string.
+            ///
+            /// If `char_count` is omitted, it is assumed to be 1.
+            #[examples(
+                "LEFT(\"Hello, world!\") = \"H\"",
+                "LEFT(\"Hello, world!\", 5) = \"Hello\""
+            )]
+            #[zip_map]
+            fn LEFT([s]: String, [char_count]: (Option<Spanned<i64>>)) {
+                let char_count = nonnegative_count(char_count, 1)?;
+                s.chars().take(char_count).collect::<String>()
+            }
+        ),
+        formula_fn!(
+            /// Returns the last `char_count` characters of a string.
+            ///
+            /// If `char_count` is omitted, it is assumed to be 1.
+            #[examples(
+                "RIGHT(\"Hello, world!\") = \"!\"",
+                "RIGHT(\"Hello, world!\", 6) = \"world!\""
+            )]
+            #[zip_map]
+            fn RIGHT([s]: String, [char_count]: (Option<Spanned<i64>>)) {
+                let char_count = nonnegative_count(char_count, 1)?;
+                let len = s.chars().count();
+                s.chars()
+                    .skip(len.saturating_sub(char_count))
+                    .collect::<String>()
+            }
+        ),
+        formula_fn!(
+            /// Returns the substring of a string starting at `start_char` and
+            /// containing `char_count` characters.
+            #[examples("MID(\"Hello, world!\", 4, 6) = \"lo, wo\"")]
+            #[zip_map]
+            fn MID([s]: String, [start_char]: (Spanned<i64>), [char_count]: (Spanned<i64>)) {
+                let start_index = one_based_index(start_char)?;
+                let char_count = nonnegative_count(Some(char_count), 0)?;
+                s.chars()
+                    .skip(start_index)
+                    .take(char_count)
+                    .collect::<String>()
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of characters in a string.
+            #[examples("LEN(\"abc\") = 3", "LEN(\"\") = 0")]
+            #[zip_map]
+            fn LEN([s]: String) {
+                s.chars().count() as f64
+            }
+        ),
+        formula_fn!(
+            /// Converts a string to uppercase.
+            #[examples("UPPER(\"Hello, world!\") = \"HELLO, WORLD!\"")]
+            #[zip_map]
+            fn UPPER([s]: String) {
+                s.to_uppercase()
+            }
+        ),
+        formula_fn!(
+            /// Converts a string to lowercase.
+            #[examples("LOWER(\"Hello, world!\") = \"hello, world!\"")]
+            #[zip_map]
+            fn LOWER([s]: String) {
+                s.to_lowercase()
+            }
+        ),
+        formula_fn!(
+            /// Capitalizes the first letter of each word in a string and
+            /// converts all other letters to lowercase. A word is any sequence
+            /// of letters.
+            #[examples("PROPER(\"hELLO, wORLD!\") = \"Hello, World!\"")]
+            #[zip_map]
+            fn PROPER([s]: String) {
+                let mut ret = String::with_capacity(s.len());
+                let mut is_start_of_word = true;
+                for c in s.chars() {
+                    if is_start_of_word {
+                        ret.extend(c.to_uppercase());
+                    } else {
+                        ret.extend(c.to_lowercase());
+                    }
+                    is_start_of_word = !c.is_alphabetic();
+                }
+                ret
+            }
+        ),
+        formula_fn!(
+            /// Removes spaces from the beginning and end of a string, and
+            /// replaces each run of consecutive spaces within the string with a
+            /// single space.
+            ///
+            /// Other whitespace characters, such as tabs and newlines, are not
+            /// affected.
+            #[examples("TRIM(\"  a   b  \") = \"a b\"")]
+            #[zip_map]
+            fn TRIM([s]: String) {
+                s.split(' ').filter(|word| !word.is_empty()).join(" ")
+            }
+        ),
+        formula_fn!(
+            /// Removes all nonprintable ASCII characters (codes 0 through 31)
+            /// from a string.
+            #[examples("CLEAN(CHAR(9) & \"(only the parenthetical will survive)\" & CHAR(10))")]
+            #[zip_map]
+            fn CLEAN([s]: String) {
+                s.chars().filter(|&c| c as u32 >= 32).collect::<String>()
+            }
+        ),
+        formula_fn!(
+            /// Replaces `old_text` with `new_text` in a string.
+            ///
+            /// If `instance_num` is given, then only that occurrence of
+            /// `old_text` is replaced (starting from 1). Otherwise, every
+            /// occurrence is replaced.
+            ///
+            /// The search is case-sensitive.
+            #[examples(
+                "SUBSTITUTE(\"a-b-c\", \"-\", \"+\") = \"a+b+c\"",
+                "SUBSTITUTE(\"a-b-c\", \"-\", \"+\", 2) = \"a-b+c\""
+            )]
+            #[zip_map]
+            fn SUBSTITUTE(
+                [s]: String,
+                [old_text]: String,
+                [new_text]: String,
+                [instance_num]: (Option<Spanned<i64>>),
+            ) {
+                if old_text.is_empty() {
+                    return Ok(CellValue::from(s));
+                }
+                match instance_num {
+                    None => s.replace(&old_text, &new_text),
+                    Some(instance_num) => {
+                        let i = one_based_index(instance_num)?;
+                        let byte_index = s.match_indices(&old_text).nth(i).map(|(j, _)| j);
+                        let mut ret = s;
+                        if let Some(byte_index) = byte_index {
+                            ret.replace_range(byte_index..byte_index + old_text.len(), &new_text);
+                        }
+                        ret
+                    }
+                }
+            }
+        ),
+        formula_fn!(
+            /// Replaces `char_count` characters of a string starting at
+            /// `start_char` with `new_text`.
+            #[examples("REPLACE(\"Hello, world!\", 8, 5, \"Quadratic\") = \"Hello, Quadratic!\"")]
+            #[zip_map]
+            fn REPLACE(
+                [s]: String,
+                [start_char]: (Spanned<i64>),
+                [char_count]: (Spanned<i64>),
+                [new_text]: String,
+            ) {
+                let start_index = one_based_index(start_char)?;
+                let char_count = nonnegative_count(Some(char_count), 0)?;
+                let mut ret = s.chars().take(start_index).collect::<String>();
+                ret.push_str(&new_text);
+                ret.extend(s.chars().skip(start_index.saturating_add(char_count)));
+                ret
+            }
+        ),
+        formula_fn!(
+            /// Returns the position of the first occurrence of `search_for`
+            /// within `s`, or an error if there is no match.
+            ///
+            /// The search starts at `start_char`, or at the beginning of the
+            /// string if `start_char` is omitted.
+            ///
+            /// The search is case-sensitive and does not support wildcards. For
+            /// a case-insensitive search, use `SEARCH`.
+            #[examples(
+                "FIND(\"o\", \"Hello, world!\") = 5",
+                "FIND(\"o\", \"Hello, world!\", 6) = 9"
+            )]
+            #[zip_map]
+            fn FIND(
+                span: Span,
+                [search_for]: String,
+                [s]: String,
+                [start_char]: (Option<Spanned<i64>>),
+            ) {
+                let haystack = s.chars().collect_vec();
+                let needle = search_for.chars().collect_vec();
+                let start_index = start_char_index(start_char, haystack.len())?;
+                (start_index..=haystack.len().saturating_sub(needle.len()))
+                    .find(|&i| haystack[i..].starts_with(&needle))
+                    .map(|i| (i + 1) as f64)
+                    .ok_or_else(|| RunErrorMsg::NoMatch.with_span(span))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the position of the first occurrence of `search_for`
+            /// within `s`, or an error if there is no match.
+            ///
+            /// The search starts at `start_char`, or at the beginning of the
+            /// string if `start_char` is omitted.
+            ///
+            /// The search is case-insensitive and supports wildcards. For a
+            /// case-sensitive search, use `FIND`.
+            #[doc = see_docs_for_more_about_wildcards!()]
+            #[examples(
+                "SEARCH(\"O\", \"Hello, world!\") = 5",
+                "SEARCH(\"w?r\", \"Hello, world!\") = 8"
+            )]
+            #[zip_map]
+            fn SEARCH(
+                span: Span,
+                [search_for]: String,
+                [s]: String,
+                [start_char]: (Option<Spanned<i64>>),
+            ) {
+                let len = s.chars().count();
+                let start_index = start_char_index(start_char, len)?;
+                let start_byte = s
+                    .char_indices()
+                    .nth(start_index)
+                    .map_or(s.len(), |(i, _)| i);
+                let regex = crate::formulas::wildcard_pattern_to_unanchored_regex(&search_for)?;
+                let m = regex
+                    .find(&s[start_byte..])
+                    .ok_or_else(|| RunErrorMsg::NoMatch.with_span(span))?;
+                (start_index + s[start_byte..start_byte + m.start()].chars().count() + 1) as f64
+            }
+        ),
+        formula_fn!(
+            /// Repeats a string `count` times.
+            #[examples("REPT(\"ab\", 3) = \"ababab\"")]
+            #[zip_map]
+            fn REPT([s]: String, [count]: (Spanned<i64>)) {
+                let count_span = count.span;
+                let count = nonnegative_count(Some(count), 0)?;
+                if s.len().saturating_mul(count) > crate::limits::STRING_LENGTH_LIMIT {
+                    return Err(RunErrorMsg::Overflow.with_span(count_span));
+                }
+                s.repeat(count)
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if two strings are exactly the same, including
+            /// case, and `FALSE` otherwise.
+            #[examples("EXACT(\"abc\", \"abc\") = TRUE", "EXACT(\"abc\", \"ABC\") = FALSE")]
+            #[zip_map]
+            fn EXACT([s1]: String, [s2]: String) {
+                s1 == s2
+            }
+        ),
+        formula_fn!(
+            /// Returns the portion of a string before an occurrence of
+            /// `delimiter`.
+            ///
+            /// If `instance_num` is positive, then the search starts from the
+            /// beginning of the string and the `instance_num`th occurrence of
+            /// `delimiter` is used. If `instance_num` is negative, then the
+            /// search starts from the end. If `instance_num` is omitted, it is
+            /// assumed to be 1.
+            ///
+            /// If `match_mode` is `0` (default), then `delimiter` is matched
+            /// case-sensitively. If `match_mode` is `1`, it is matched
+            /// case-insensitively.
+            ///
+            /// If `match_end` is `TRUE`, then the end of the string (or the
+            /// beginning, when searching from the end) is also treated as a
+            /// delimiter.
+            ///
+            /// If no match is found, then `if_not_found` is returned instead.
+            /// If there is no match and `if_not_found` is omitted, then returns
+            /// an error.
+            #[examples(
+                "TEXTBEFORE(\"apple, banana, cherry\", \", \") = \"apple\"",
+                "TEXTBEFORE(\"apple, banana, cherry\", \", \", -1) = \"apple, banana\""
+            )]
+            #[zip_map]
+            fn TEXTBEFORE(
+                span: Span,
+                [s]: String,
+                [delimiter]: String,
+                [instance_num]: (Option<Spanned<i64>>),
+                [match_mode]: (Option<Spanned<i64>>),
+                [match_end]: (Option<bool>),
+                [if_not_found]: (Option<CellValue>),
+            ) {
+                let chars = s.chars().collect_vec();
+                match find_delimiter(&chars, &delimiter, instance_num, match_mode, match_end)? {
+                    Some((start, _end)) => {
+                        CellValue::from(chars[..start].iter().collect::<String>())
+                    }
+                    None => match if_not_found {
+                        Some(v) => v.clone(),
+                        None => return Err(RunErrorMsg::NoMatch.with_span(span)),
+                    },
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the portion of a string after an occurrence of
+            /// `delimiter`.
+            ///
+            /// If `instance_num` is positive, then the search starts from the
+            /// beginning of the string and the `instance_num`th occurrence of
+            /// `delimiter` is used. If `instance_num` is negative, then the
+            /// search starts from the end. If `instance_num` is omitted, it is
+            /// assumed to be 1.
+            ///
+            /// If `match_mode` is `0` (default), then `delimiter` is matched
+            /// case-sensitively. If `match_mode` is `1`, it is matched
+            /// case-insensitively.
+            ///
+            /// If `match_end` is `TRUE`, then the end of the string (or the
+            /// beginning, when searching from the end) is also treated as a
+            /// delimiter.
+            ///
+            /// If no match is found, then `if_not_found` is returned instead.
+            /// If there is no match and `if_not_found` is omitted, then returns
+            /// an error.
+            #[examples(
+                "TEXTAFTER(\"apple, banana, cherry\", \", \") = \"banana, cherry\"",
+                "TEXTAFTER(\"apple, banana, cherry\", \", \", -1) = \"cherry\""
+            )]
+            #[zip_map]
+            fn TEXTAFTER(
+                span: Span,
+                [s]: String,
+                [delimiter]: String,
+                [instance_num]: (Option<Spanned<i64>>),
+                [match_mode]: (Option<Spanned<i64>>),
+                [match_end]: (Option<bool>),
+                [if_not_found]: (Option<CellValue>),
+            ) {
+                let chars = s.chars().collect_vec();
+                match find_delimiter(&chars, &delimiter, instance_num, match_mode, match_end)? {
+                    Some((_start, end)) => CellValue::from(chars[end..].iter().collect::<String>()),
+                    None => match if_not_found {
+                        Some(v) => v.clone(),
+                        None => return Err(RunErrorMsg::NoMatch.with_span(span)),
+                    },
+                }
+            }
+        ),
+        formula_fn!(
+            /// Splits a string into an array, using `col_delimiter` to separate
+            /// columns and `row_delimiter` to separate rows.
+            ///
+            /// If `ignore_empty` is `TRUE`, then consecutive delimiters are
+            /// treated as one. Otherwise, they produce empty strings.
+            ///
+            /// If `match_mode` is `0` (default), then delimiters are matched
+            /// case-sensitively. If `match_mode` is `1`, they are matched
+            /// case-insensitively.
+            ///
+            /// If rows have different lengths, then shorter rows are padded
+            /// with `pad_with`, or with an error if `pad_with` is omitted.
+            #[examples("TEXTSPLIT(\"a,b,c\", \",\")", "TEXTSPLIT(\"a=1;b=2\", \"=\", \";\")")]
+            fn TEXTSPLIT(
+                span: Span,
+                s: String,
+                col_delimiter: String,
+                row_delimiter: (Option<String>),
+                ignore_empty: (Option<bool>),
+                match_mode: (Option<Spanned<i64>>),
+                pad_with: (Option<CellValue>),
+            ) {
+                let case_insensitive = is_case_insensitive(match_mode)?;
+                let ignore_empty = ignore_empty.unwrap_or(false);
+                let row_delimiter = row_delimiter.unwrap_or_default();
+                let pad_with = pad_with.unwrap_or_else(|| {
+                    CellValue::Error(Box::new(RunErrorMsg::NoMatch.with_span(span)))
+                });
+
+                let chars = s.chars().collect_vec();
+                let rows = split_chars(&chars, &row_delimiter, case_insensitive)
+                    .into_iter()
+                    .filter(|row| !(ignore_empty && row.is_empty()))
+                    .map(|row| {
+                        split_chars(row, &col_delimiter, case_insensitive)
+                            .into_iter()
+                            .filter(|cell| !(ignore_empty && cell.is_empty()))
+                            .map(|cell| CellValue::from(cell.iter().collect::<String>()))
+                            .collect_vec()
+                    })
+                    .filter(|row| !row.is_empty())
+                    .collect_vec();
+
+                let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+                let height = rows.len();
+                if width == 0 || height == 0 {
+                    return Err(RunErrorMsg::EmptyArray.with_span(span));
+                }
+                let size = ArraySize::new_or_err(width as u32, height as u32)?;
+                let values = rows
+                    .into_iter()
+                    .flat_map(|row| {
+                        let padding = width - row.len();
+                        row.into_iter()
+                            .chain(std::iter::repeat(pad_with.clone()).take(padding))
+                    })
+                    .collect();
+                Array::new_row_major(size, values)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the character with the given code, from 1 to 255.
+            ///
+            /// Codes are interpreted as
+            /// [Latin-1](https://en.wikipedia.org/wiki/ISO/IEC_8859-1), which
+            /// matches Unicode for the first 256 characters. Use `UNICHAR` for
+            /// other Unicode characters.
+            #[examples("CHAR(65) = \"A\"")]
+            #[zip_map]
+            fn CHAR([code]: (Spanned<i64>)) {
+                match u8::try_from(code.inner) {
+                    Ok(byte) if byte != 0 => char::from(byte).to_string(),
+                    _ => return Err(RunErrorMsg::InvalidArgument.with_span(code.span)),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the code of the first character in a string, from 1 to
+            /// 255.
+            ///
+            /// Codes are interpreted as
+            /// [Latin-1](https://en.wikipedia.org/wiki/ISO/IEC_8859-1), the
+            /// same as for `CHAR`. Use `UNICODE` for other Unicode characters.
+            ///
+            /// Returns an error if the string is empty or if its first
+            /// character is not in Latin-1.
+            #[examples("CODE(\"A\") = 65")]
+            #[zip_map]
+            fn CODE([s]: (Spanned<String>)) {
+                let code = first_char_code(&s)?;
+                if !(1..=255).contains(&code) {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(s.span));
+                }
+                code
+            }
+        ),
+        formula_fn!(
+            /// Returns the Unicode character with the given code point.
+            #[examples("UNICHAR(65) = \"A\"", "UNICHAR(960) = \"π\"")]
+            #[zip_map]
+            fn UNICHAR([code]: (Spanned<i64>)) {
+                u32::try_from(code.inner)
+                    .ok()
+                    .filter(|&c| c != 0)
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| RunErrorMsg::InvalidArgument.with_span(code.span))?
+                    .to_string()
+            }
+        ),
+        formula_fn!(
+            /// Returns the Unicode code point of the first character in a
+            /// string.
+            ///
+            /// Returns an error if the string is empty.
+            #[examples("UNICODE(\"A\") = 65", "UNICODE(\"π\") = 960")]
+            #[zip_map]
+            fn UNICODE([s]: (Spanned<String>)) {
+                first_char_code(&s)?
+            }
+        ),
+    ]
+}
+
+/// Converts an optional count of characters to a `usize`, returning an error
+/// if it is negative.
+fn nonnegative_count(count: Option<Spanned<i64>>, default: usize) -> CodeResult<usize> {
+    match count {
+        None => Ok(default),
+        Some(count) => {
+            usize::try_from(count.inner).map_err(|_| RunErrorMsg::InvalidArgument.with_span(count))
+        }
+    }
+}
+
+/// Converts a 1-based index into a string to a 0-based index, returning an
+/// error if it is less than 1.
+fn one_based_index(index: Spanned<i64>) -> CodeResult<usize> {
+    match usize::try_from(index.inner) {
+        Ok(i) if i >= 1 => Ok(i - 1),
+        _ => Err(RunErrorMsg::InvalidArgument.with_span(index)),
+    }
+}
+
+/// Converts an optional 1-based starting character for `FIND` and `SEARCH` to
+/// a 0-based index, returning an error if it is out of range.
+fn start_char_index(start_char: Option<Spanned<i64>>, len: usize) -> CodeResult<usize> {
+    match start_char {
+        None => Ok(0),
+        Some(start_char) => {
+            let i = one_based_index(start_char)?;
+            if i > len {
+                return Err(RunErrorMsg::InvalidArgument.with_span(start_char));
+            }
+            Ok(i)
         }
-    )]
+    }
+}
+
+/// Returns the Unicode code point of the first character in a string.
+fn first_char_code(s: &Spanned<String>) -> CodeResult<u32> {
+    s.inner
+        .chars()
+        .next()
+        .map(|c| c as u32)
+        .ok_or_else(|| RunErrorMsg::InvalidArgument.with_span(s))
+}
+
+/// Returns whether a `match_mode` argument requests case-insensitive matching.
+fn is_case_insensitive(match_mode: Option<Spanned<i64>>) -> CodeResult<bool> {
+    match match_mode {
+        None => Ok(false),
+        Some(Spanned { inner: 0, .. }) => Ok(false),
+        Some(Spanned { inner: 1, .. }) => Ok(true),
+        Some(Spanned { span, .. }) => Err(RunErrorMsg::InvalidArgument.with_span(span)),
+    }
+}
+
+fn chars_eq(a: char, b: char, case_insensitive: bool) -> bool {
+    a == b || (case_insensitive && a.to_lowercase().eq(b.to_lowercase()))
+}
+
+/// Returns the index of every non-overlapping occurrence of `needle` in
+/// `haystack`, scanning from left to right. `needle` must not be empty.
+fn find_all(haystack: &[char], needle: &[char], case_insensitive: bool) -> Vec<usize> {
+    let mut ret = vec![];
+    let mut i = 0;
+    while i + needle.len() <= haystack.len() {
+        let is_match =
+            std::iter::zip(&haystack[i..], needle).all(|(&a, &b)| chars_eq(a, b, case_insensitive));
+        if is_match {
+            ret.push(i);
+            i += needle.len();
+        } else {
+            i += 1;
+        }
+    }
+    ret
+}
+
+/// Splits `haystack` at each occurrence of `delimiter`. If `delimiter` is
+/// empty, returns `haystack` unchanged.
+fn split_chars<'a>(
+    haystack: &'a [char],
+    delimiter: &str,
+    case_insensitive: bool,
+) -> Vec<&'a [char]> {
+    let delimiter = delimiter.chars().collect_vec();
+    if delimiter.is_empty() {
+        return vec![haystack];
+    }
+    let mut ret = vec![];
+    let mut start = 0;
+    for i in find_all(haystack, &delimiter, case_insensitive) {
+        ret.push(&haystack[start..i]);
+        start = i + delimiter.len();
+    }
+    ret.push(&haystack[start..]);
+    ret
+}
+
+/// Finds the occurrence of `delimiter` requested by the arguments to
+/// `TEXTBEFORE` or `TEXTAFTER`, and returns the range of characters it
+/// occupies, or `None` if there is no such occurrence.
+fn find_delimiter(
+    chars: &[char],
+    delimiter: &str,
+    instance_num: Option<Spanned<i64>>,
+    match_mode: Option<Spanned<i64>>,
+    match_end: Option<bool>,
+) -> CodeResult<Option<(usize, usize)>> {
+    let case_insensitive = is_case_insensitive(match_mode)?;
+    let match_end = match_end.unwrap_or(false);
+    let n = match instance_num {
+        None => 1,
+        Some(Spanned { span, inner: n }) => {
+            if n == 0 || n.unsigned_abs() as usize > chars.len().max(1) {
+                return Err(RunErrorMsg::InvalidArgument.with_span(span));
+            }
+            n
+        }
+    };
+
+    let delimiter = delimiter.chars().collect_vec();
+    if delimiter.is_empty() {
+        // An empty delimiter matches immediately at the start (or end).
+        let i = if n > 0 { 0 } else { chars.len() };
+        return Ok(Some((i, i)));
+    }
+
+    let mut matches = find_all(chars, &delimiter, case_insensitive)
+        .into_iter()
+        .map(|i| (i, i + delimiter.len()))
+        .collect_vec();
+    if n > 0 {
+        if match_end {
+            matches.push((chars.len(), chars.len()));
+        }
+        Ok(matches.get(n as usize - 1).copied())
+    } else {
+        if match_end {
+            matches.insert(0, (0, 0));
+        }
+        Ok(matches
+            .iter()
+            .rev()
+            .nth(n.unsigned_abs() as usize - 1)
+            .copied())
+    }
 }
 
 #[cfg(test)]
@@ -30,5 +665,280 @@ mod tests {
             "Hello, 14000605 worlds!".to_string(),
             eval_to_string(&g, "'Hello, ' & 14000605 & ' worlds!'"),
         );
+        assert_eq!(
+            "Hello, 14000605 worlds!".to_string(),
+            eval_to_string(&g, "CONCAT('Hello, ', 14000605, ' worlds!')"),
+        );
+    }
+
+    #[test]
+    fn test_formula_textjoin() {
+        let g = Grid::new();
+        assert_eq!(
+            "a, b, c",
+            eval_to_string(&g, "TEXTJOIN(', ', TRUE, 'a', 'b', 'c')"),
+        );
+        assert_eq!(
+            "a-c",
+            eval_to_string(&g, "TEXTJOIN('-', TRUE, {'a', '', 'c'})")
+        );
+        assert_eq!(
+            "a--c",
+            eval_to_string(&g, "TEXTJOIN('-', FALSE, {'a', '', 'c'})"),
+        );
+        assert_eq!("1+2+3", eval_to_string(&g, "TEXTJOIN('+', TRUE, 1..3)"));
+    }
+
+    #[test]
+    fn test_formula_left_right_mid() {
+        let g = Grid::new();
+        assert_eq!("H", eval_to_string(&g, "LEFT('Hello')"));
+        assert_eq!("Hel", eval_to_string(&g, "LEFT('Hello', 3)"));
+        assert_eq!("Hello", eval_to_string(&g, "LEFT('Hello', 99)"));
+        assert_eq!("", eval_to_string(&g, "LEFT('Hello', 0)"));
+        assert_eq!("o", eval_to_string(&g, "RIGHT('Hello')"));
+        assert_eq!("llo", eval_to_string(&g, "RIGHT('Hello', 3)"));
+        assert_eq!("Hello", eval_to_string(&g, "RIGHT('Hello', 99)"));
+        assert_eq!("ell", eval_to_string(&g, "MID('Hello', 2, 3)"));
+        assert_eq!("", eval_to_string(&g, "MID('Hello', 10, 3)"));
+
+        // Positions are measured in characters, not bytes.
+        assert_eq!("πρ", eval_to_string(&g, "LEFT('πρόβλημα', 2)"));
+        assert_eq!("μα", eval_to_string(&g, "RIGHT('πρόβλημα', 2)"));
+        assert_eq!("όβ", eval_to_string(&g, "MID('πρόβλημα', 3, 2)"));
+
+        // Arrays are zip-mapped.
+        assert_eq!(
+            "{a, ab, abc}",
+            eval_to_string(&g, "LEFT('abcdef', {1, 2, 3})"),
+        );
+
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "LEFT('Hello', -1)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "MID('Hello', 0, 1)").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_len() {
+        let g = Grid::new();
+        assert_eq!("0", eval_to_string(&g, "LEN('')"));
+        assert_eq!("5", eval_to_string(&g, "LEN('Hello')"));
+        assert_eq!("8", eval_to_string(&g, "LEN('πρόβλημα')"));
+        assert_eq!("{1, 2, 3}", eval_to_string(&g, "LEN({'a', 'bb', 'ccc'})"));
+        assert_eq!("3", eval_to_string(&g, "LEN(123)"));
+    }
+
+    #[test]
+    fn test_formula_case() {
+        let g = Grid::new();
+        assert_eq!(
+            "HELLO, ΚΌΣΜΕ!",
+            eval_to_string(&g, "UPPER('Hello, κόσμε!')"),
+        );
+        assert_eq!(
+            "hello, κόσμε!",
+            eval_to_string(&g, "LOWER('Hello, ΚΌΣΜΕ!')"),
+        );
+        assert_eq!(
+            "Hello, World! 2nd Try-Again",
+            eval_to_string(&g, "PROPER('hELLO, wORLD! 2ND try-again')"),
+        );
+        assert_eq!("TRUE", eval_to_string(&g, "EXACT('abc', 'abc')"));
+        assert_eq!("FALSE", eval_to_string(&g, "EXACT('abc', 'ABC')"));
+    }
+
+    #[test]
+    fn test_formula_trim_clean() {
+        let g = Grid::new();
+        assert_eq!("a b c", eval_to_string(&g, "TRIM('   a  b c   ')"));
+        assert_eq!("", eval_to_string(&g, "TRIM('    ')"));
+        assert_eq!(
+            "abc",
+            eval_to_string(&g, "CLEAN(CHAR(9) & 'a' & CHAR(10) & 'bc')"),
+        );
+    }
+
+    #[test]
+    fn test_formula_substitute_replace() {
+        let g = Grid::new();
+        assert_eq!("a+b+c", eval_to_string(&g, "SUBSTITUTE('a-b-c', '-', '+')"));
+        assert_eq!(
+            "a-b+c",
+            eval_to_string(&g, "SUBSTITUTE('a-b-c', '-', '+', 2)"),
+        );
+        assert_eq!(
+            "a-b-c",
+            eval_to_string(&g, "SUBSTITUTE('a-b-c', '-', '+', 3)"),
+        );
+        assert_eq!("a-b-c", eval_to_string(&g, "SUBSTITUTE('a-b-c', '', '+')"));
+        assert_eq!("a-b-c", eval_to_string(&g, "SUBSTITUTE('a-b-c', 'B', '+')"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "SUBSTITUTE('a-b-c', '-', '+', 0)").msg,
+        );
+
+        assert_eq!(
+            "Hello, Quadratic!",
+            eval_to_string(&g, "REPLACE('Hello, world!', 8, 5, 'Quadratic')"),
+        );
+        assert_eq!("XHello", eval_to_string(&g, "REPLACE('Hello', 1, 0, 'X')"));
+        assert_eq!("HelloX", eval_to_string(&g, "REPLACE('Hello', 99, 1, 'X')"));
+        assert_eq!(
+            "πXβλημα",
+            eval_to_string(&g, "REPLACE('πρόβλημα', 2, 2, 'X')"),
+        );
+    }
+
+    #[test]
+    fn test_formula_find_search() {
+        let g = Grid::new();
+        assert_eq!("5", eval_to_string(&g, "FIND('o', 'Hello, world!')"));
+        assert_eq!("9", eval_to_string(&g, "FIND('o', 'Hello, world!', 6)"));
+        assert_eq!("1", eval_to_string(&g, "FIND('', 'Hello')"));
+        assert_eq!("3", eval_to_string(&g, "FIND('', 'Hello', 3)"));
+        assert_eq!("4", eval_to_string(&g, "FIND('β', 'πρόβλημα')"));
+        assert_eq!(
+            RunErrorMsg::NoMatch,
+            eval_to_err(&g, "FIND('O', 'Hello')").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "FIND('o', 'Hello', 0)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "FIND('o', 'Hello', 7)").msg,
+        );
+
+        assert_eq!("5", eval_to_string(&g, "SEARCH('O', 'Hello, world!')"));
+        assert_eq!("9", eval_to_string(&g, "SEARCH('O', 'Hello, world!', 6)"));
+        assert_eq!("8", eval_to_string(&g, "SEARCH('w?r', 'Hello, world!')"));
+        assert_eq!("3", eval_to_string(&g, "SEARCH('l*o', 'Hello, world!')"));
+        assert_eq!("6", eval_to_string(&g, "SEARCH('~?', 'Hello?')"));
+        assert_eq!("4", eval_to_string(&g, "SEARCH('Β', 'πρόβλημα')"));
+        assert_eq!(
+            RunErrorMsg::NoMatch,
+            eval_to_err(&g, "SEARCH('z', 'Hello')").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_rept() {
+        let g = Grid::new();
+        assert_eq!("ababab", eval_to_string(&g, "REPT('ab', 3)"));
+        assert_eq!("", eval_to_string(&g, "REPT('ab', 0)"));
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_to_err(&g, "REPT('ab', -1)").msg,
+        );
+        assert_eq!(
+            RunErrorMsg::Overflow,
+            eval_to_err(&g, "REPT('ab', 999999999)").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_textbefore_textafter() {
+        let g = Grid::new();
+        let s = "'apple, Banana, cherry'";
+        let eval_with = |formula: &str| eval_to_string(&g, &formula.replace("$S", s));
+        let eval_err_with = |formula: &str| eval_to_err(&g, &formula.replace("$S", s)).msg;
+
+        assert_eq!("apple", eval_with("TEXTBEFORE($S, ', ')"));
+        assert_eq!("apple, Banana", eval_with("TEXTBEFORE($S, ', ', 2)"));
+        assert_eq!("apple, Banana", eval_with("TEXTBEFORE($S, ', ', -1)"));
+        assert_eq!("apple", eval_with("TEXTBEFORE($S, ', ', -2)"));
+        assert_eq!("Banana, cherry", eval_with("TEXTAFTER($S, ', ')"));
+        assert_eq!("cherry", eval_with("TEXTAFTER($S, ', ', 2)"));
+        assert_eq!("cherry", eval_with("TEXTAFTER($S, ', ', -1)"));
+
+        // Case sensitivity
+        assert_eq!(RunErrorMsg::NoMatch, eval_err_with("TEXTBEFORE($S, 'b')"));
+        assert_eq!("apple, ", eval_with("TEXTBEFORE($S, 'b',, 1)"));
+        assert_eq!("anana, cherry", eval_with("TEXTAFTER($S, 'b',, 1)"));
+
+        // Match end
+        assert_eq!(
+            RunErrorMsg::NoMatch,
+            eval_err_with("TEXTBEFORE($S, ', ', 3)")
+        );
+        assert_eq!(
+            "apple, Banana, cherry",
+            eval_with("TEXTBEFORE($S, ', ', 3,, TRUE)"),
+        );
+        assert_eq!("", eval_with("TEXTAFTER($S, ', ', 3,, TRUE)"));
+        assert_eq!(
+            "apple, Banana, cherry",
+            eval_with("TEXTAFTER($S, ', ', -3,, TRUE)")
+        );
+
+        // Fallback
+        assert_eq!("nope", eval_with("TEXTAFTER($S, 'zzz',,,, 'nope')"));
+
+        // Empty delimiter
+        assert_eq!("", eval_to_string(&g, "TEXTBEFORE('abc', '')"));
+        assert_eq!("abc", eval_to_string(&g, "TEXTAFTER('abc', '')"));
+
+        // Bad instance number
+        assert_eq!(
+            RunErrorMsg::InvalidArgument,
+            eval_err_with("TEXTAFTER($S, ', ', 0)"),
+        );
+    }
+
+    #[test]
+    fn test_formula_textsplit() {
+        let g = Grid::new();
+        assert_eq!("{a, b, c}", eval_to_string(&g, "TEXTSPLIT('a,b,c', ',')"));
+        assert_eq!("{a, , c}", eval_to_string(&g, "TEXTSPLIT('a,,c', ',')"));
+        assert_eq!(
+            "{a, c}",
+            eval_to_string(&g, "TEXTSPLIT('a,,c', ',',, TRUE)")
+        );
+        assert_eq!(
+            "{a, 1; b, 2}",
+            eval_to_string(&g, "TEXTSPLIT('a=1;b=2', '=', ';')"),
+        );
+        assert_eq!(
+            "{a, 1; b, -}",
+            eval_to_string(&g, "TEXTSPLIT('a=1;b', '=', ';',,, '-')"),
+        );
+        assert_eq!(
+            RunErrorMsg::NoMatch,
+            eval(&g, "TEXTSPLIT('a=1;b', '=', ';')").cell_values_slice()[3]
+                .error()
+                .unwrap()
+                .msg,
+        );
+        assert_eq!("{a, b}", eval_to_string(&g, "TEXTSPLIT('aXb', 'x',,, 1)"));
+        assert_eq!("aXb", eval_to_string(&g, "TEXTSPLIT('aXb', 'x')"));
+    }
+
+    #[test]
+    fn test_formula_char_code() {
+        let g = Grid::new();
+        assert_eq!("A", eval_to_string(&g, "CHAR(65)"));
+        assert_eq!("é", eval_to_string(&g, "CHAR(233)"));
+        assert_eq!("65", eval_to_string(&g, "CODE('ABC')"));
+        assert_eq!("233", eval_to_string(&g, "CODE('é')"));
+        assert_eq!("é", eval_to_string(&g, "CHAR(CODE('é'))"));
+        assert_eq!("π", eval_to_string(&g, "UNICHAR(960)"));
+        assert_eq!("960", eval_to_string(&g, "UNICODE('π')"));
+        assert_eq!("{A, B}", eval_to_string(&g, "CHAR({65, 66})"));
+        for formula in [
+            "CHAR(0)",
+            "CHAR(256)",
+            "UNICHAR(0)",
+            "UNICHAR(55296)",
+            "CODE('')",
+            "CODE('π')",
+        ] {
+            assert_eq!(RunErrorMsg::InvalidArgument, eval_to_err(&g, formula).msg);
+        }
     }
 }
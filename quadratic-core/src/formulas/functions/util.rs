@@ -1,3 +1,5 @@
+use bigdecimal::{BigDecimal, RoundingMode, Zero};
+
 use super::*;
 
 /// Divides one number by another, handling the error case of division by zero.
@@ -9,6 +11,74 @@ pub fn checked_div(span: impl Into<Span>, dividend: f64, divisor: f64) -> CodeRe
     }
 }
 
+/// Number of significant digits kept in the result of a decimal operation
+/// that could otherwise produce an infinite or very long expansion, such as
+/// division or exponentiation.
+pub const DECIMAL_PRECISION: u64 = 30;
+
+/// Number of significant digits kept in intermediate results of a chain of
+/// decimal operations, such as a product of many numbers, before the final
+/// result is rounded to [`DECIMAL_PRECISION`].
+pub const INTERMEDIATE_PRECISION: u64 = DECIMAL_PRECISION + 10;
+
+/// Divides one decimal number by another, handling the error case of division
+/// by zero. The result is rounded to [`DECIMAL_PRECISION`] significant digits.
+pub fn checked_decimal_div(
+    span: impl Into<Span>,
+    dividend: &BigDecimal,
+    divisor: &BigDecimal,
+) -> CodeResult<BigDecimal> {
+    if divisor.is_zero() {
+        return Err(RunErrorMsg::DivideByZero.with_span(span));
+    }
+    Ok(round_to_precision(dividend / divisor, DECIMAL_PRECISION))
+}
+
+/// Rounds a number to at most `precision` significant digits, rounding
+/// halfway cases to even, and removes trailing zeros.
+pub fn round_to_precision(number: BigDecimal, precision: u64) -> BigDecimal {
+    let (digits, scale) = number.as_bigint_and_exponent();
+    let digit_count = digits.magnitude().to_string().len() as i64;
+    let excess_digits = digit_count - precision as i64;
+    if excess_digits > 0 {
+        trim_zeros(number.with_scale_round(scale - excess_digits, RoundingMode::HalfEven))
+    } else {
+        trim_zeros(number)
+    }
+}
+
+/// Removes trailing zeros after the decimal point, so that the result of
+/// rounding 2.50 is displayed as 2.5.
+pub fn trim_zeros(number: BigDecimal) -> BigDecimal {
+    let number = number.normalized();
+    let (_, scale) = number.as_bigint_and_exponent();
+    if scale < 0 {
+        number.with_scale(0)
+    } else {
+        number
+    }
+}
+
+/// Returns an error if `value` is NaN or infinite.
+pub fn finite(span: impl Into<Span>, value: f64) -> CodeResult<f64> {
+    if value.is_nan() {
+        Err(RunErrorMsg::NotANumber.with_span(span))
+    } else if value.is_infinite() {
+        Err(RunErrorMsg::Infinity.with_span(span))
+    } else {
+        Ok(value)
+    }
+}
+
+/// Returns an error if `condition` is false, indicating that an argument is
+/// outside the domain of the function.
+pub fn ensure(span: Span, condition: bool) -> CodeResult<()> {
+    match condition {
+        true => Ok(()),
+        false => Err(RunErrorMsg::InvalidArgument.with_span(span)),
+    }
+}
+
 pub fn average(
     span: impl Into<Span>,
     numbers: impl IntoIterator<Item = CodeResult<f64>>,
@@ -21,3 +91,30 @@ pub fn average(
     }
     util::checked_div(span, sum, count as f64)
 }
+
+/// Parses the `(eval_range, criteria)` pairs given to a function such as
+/// `SUMIFS`, where the first pair is required and any number of additional
+/// pairs may follow.
+pub fn criteria_pairs(
+    func_name: &'static str,
+    eval_range1: Spanned<Array>,
+    criteria1: Spanned<CellValue>,
+    mut more_eval_ranges_and_criteria: impl Iterator<Item = CodeResult<Spanned<Value>>>,
+) -> CodeResult<Vec<(Spanned<Array>, Criterion)>> {
+    let mut ret = vec![(eval_range1, Criterion::try_from(criteria1.as_ref())?)];
+    while let Some(eval_range) = more_eval_ranges_and_criteria.next() {
+        let eval_range = eval_range?.map(Array::from);
+        let criteria = more_eval_ranges_and_criteria
+            .next()
+            .ok_or_else(|| {
+                RunErrorMsg::MissingRequiredArgument {
+                    func_name: func_name.into(),
+                    arg_name: "criteria".into(),
+                }
+                .with_span(eval_range.span)
+            })??
+            .into_cell_value()?;
+        ret.push((eval_range, Criterion::try_from(criteria.as_ref())?));
+    }
+    Ok(ret)
+}
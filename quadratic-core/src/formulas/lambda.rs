@@ -0,0 +1,428 @@
+//! Local names (`LET()`), lambdas (`LAMBDA()`), and functions that take
+//! lambdas as arguments (such as `MAP()`).
+//!
+//! These functions need to inspect their arguments before evaluating them, so
+//! they are evaluated as special forms by [`AstNode::eval()`] rather than as
+//! ordinary formula functions. Their documentation is in
+//! `functions/lambda.rs`.
+
+use std::rc::Rc;
+
+use smallvec::SmallVec;
+
+use super::ast::AstNodeContents;
+use super::functions::normalize_function_name;
+use super::*;
+use crate::{
+    Array, ArraySize, CellValue, CodeResult, CoerceInto, RunError, RunErrorMsg, Span, Spanned,
+    Value,
+};
+
+/// Names of functions that are evaluated as special forms.
+const SPECIAL_FORMS: &[&str] = &[
+    "LET",
+    "LAMBDA",
+    "ISOMITTED",
+    "MAP",
+    "REDUCE",
+    "SCAN",
+    "BYROW",
+    "BYCOL",
+    "MAKEARRAY",
+];
+
+/// Value bound to a name.
+#[derive(Debug, Clone)]
+pub enum Binding {
+    /// Ordinary value.
+    Value(Rc<Value>),
+    /// Lambda, which can be called like a function.
+    Lambda(Rc<Lambda>),
+    /// Lambda parameter for which no argument was given.
+    Omitted,
+}
+impl From<Value> for Binding {
+    fn from(value: Value) -> Self {
+        Binding::Value(Rc::new(value))
+    }
+}
+
+/// Lambda defined using `LAMBDA()`.
+#[derive(Debug, Clone)]
+pub struct Lambda {
+    /// Names of the parameters.
+    params: Vec<String>,
+    /// Expression to evaluate when the lambda is called.
+    body: AstNode,
+    /// Bindings that were in scope when the lambda was defined.
+    captured: Vec<(String, Binding)>,
+}
+
+/// Returns whether a call to a function named `func_name` must be evaluated
+/// using [`eval_special_form()`], either because the name is bound to a lambda
+/// or because the function takes unevaluated arguments.
+pub(super) fn is_special_form(ctx: &Ctx<'_>, func_name: &str) -> bool {
+    ctx.lookup(func_name).is_some()
+        || SPECIAL_FORMS.contains(&normalize_function_name(func_name).as_str())
+}
+
+/// Evaluates a call to a lambda bound to a name, or to a special form.
+pub(super) fn eval_special_form(
+    ctx: &mut Ctx<'_>,
+    only_parse: bool,
+    span: Span,
+    func: &Spanned<String>,
+    args: &[AstNode],
+) -> CodeResult<Value> {
+    if let Some(binding) = ctx.lookup(&func.inner) {
+        let Binding::Lambda(lambda) = binding.clone() else {
+            return Err(RunErrorMsg::Expected {
+                expected: "lambda".into(),
+                got: Some("value".into()),
+            }
+            .with_span(func.span));
+        };
+        let args = args
+            .iter()
+            .map(|arg| match arg.inner {
+                AstNodeContents::Empty => Ok(Binding::Omitted),
+                _ => eval_binding(ctx, only_parse, arg),
+            })
+            .collect::<CodeResult<Vec<_>>>()?;
+        return call(ctx, only_parse, span, &lambda, args);
+    }
+
+    match normalize_function_name(&func.inner).as_str() {
+        "LET" => {
+            let Some((calculation, names_and_values)) = args.split_last() else {
+                return Err(missing_arg("LET", "name1", span));
+            };
+            if names_and_values.len() % 2 != 0 || names_and_values.is_empty() {
+                let arg_name = if args.len() == 1 {
+                    "value1"
+                } else {
+                    "calculation"
+                };
+                return Err(missing_arg("LET", arg_name, span));
+            }
+            with_scope(ctx, |ctx| {
+                for pair in names_and_values.chunks(2) {
+                    let name = name_of(&pair[0])?;
+                    let binding = eval_binding(ctx, only_parse, &pair[1])?;
+                    ctx.bindings.push((name, binding));
+                }
+                Ok(calculation.eval(ctx, only_parse)?.inner)
+            })
+        }
+
+        // A lambda that is not called cannot be used as a value.
+        "LAMBDA" => Err(RunErrorMsg::Expected {
+            expected: "value".into(),
+            got: Some("lambda".into()),
+        }
+        .with_span(span)),
+
+        "ISOMITTED" => {
+            let [argument] = exact_args("ISOMITTED", span, args, ["argument"])?;
+            let name = name_of(argument)?;
+            match ctx.lookup(&name) {
+                Some(binding) => Ok(Value::from(matches!(binding, Binding::Omitted))),
+                None => Err(RunErrorMsg::UnknownName(name.into()).with_span(argument.span)),
+            }
+        }
+
+        "MAP" => {
+            let (function, arrays) = match args.split_last() {
+                Some((function, arrays)) if !arrays.is_empty() => (function, arrays),
+                _ => {
+                    let arg_name = if args.is_empty() {
+                        "array1"
+                    } else {
+                        "function"
+                    };
+                    return Err(missing_arg("MAP", arg_name, span));
+                }
+            };
+            let arrays = arrays
+                .iter()
+                .map(|array| array.eval(ctx, only_parse))
+                .collect::<CodeResult<Vec<_>>>()?;
+            let function = eval_lambda(ctx, function)?;
+            ctx.zip_map(&arrays, |ctx, values| {
+                let args = values
+                    .iter()
+                    .map(|value| Value::from(value.inner.clone()).into())
+                    .collect();
+                call_for_cell_value(ctx, only_parse, span, &function, args)
+            })
+        }
+
+        "REDUCE" => {
+            let [initial_value, array, function] =
+                exact_args("REDUCE", span, args, ["initial_value", "array", "function"])?;
+            let mut accumulator = initial_value.eval(ctx, only_parse)?.inner;
+            let array = Array::from(array.eval(ctx, only_parse)?.inner);
+            let function = eval_lambda(ctx, function)?;
+            for value in array.cell_values_slice() {
+                let args = vec![accumulator.into(), Value::from(value.clone()).into()];
+                accumulator = call(ctx, only_parse, span, &function, args)?;
+            }
+            Ok(accumulator)
+        }
+
+        "SCAN" => {
+            let [initial_value, array, function] =
+                exact_args("SCAN", span, args, ["initial_value", "array", "function"])?;
+            let mut accumulator = initial_value.eval(ctx, only_parse)?.inner;
+            let array = Array::from(array.eval(ctx, only_parse)?.inner);
+            let function = eval_lambda(ctx, function)?;
+            let mut results = SmallVec::with_capacity(array.size().len());
+            for value in array.cell_values_slice() {
+                let args = vec![accumulator.into(), Value::from(value.clone()).into()];
+                let result = call_for_cell_value(ctx, only_parse, span, &function, args)?;
+                results.push(result.clone());
+                accumulator = Value::from(result);
+            }
+            Ok(Array::new_row_major(array.size(), results)?.into())
+        }
+
+        "BYROW" => {
+            let [array, function] = exact_args("BYROW", span, args, ["array", "function"])?;
+            let array = Array::from(array.eval(ctx, only_parse)?.inner);
+            let function = eval_lambda(ctx, function)?;
+            Ok(map_rows(ctx, only_parse, span, array, &function)?.into())
+        }
+
+        "BYCOL" => {
+            let [array, function] = exact_args("BYCOL", span, args, ["array", "function"])?;
+            let array = Array::from(array.eval(ctx, only_parse)?.inner);
+            let function = eval_lambda(ctx, function)?;
+            // Each column is passed to `function` as a column, so transpose it
+            // back after splitting the transposed array into rows.
+            let columns = array.transpose();
+            let mut results = SmallVec::with_capacity(columns.height() as usize);
+            for column in columns.rows() {
+                let size = ArraySize::new_or_err(1, column.len() as u32)?;
+                let column = Array::new_row_major(size, column.iter().cloned().collect())?;
+                let args = vec![Value::from(column).into()];
+                results.push(call_for_cell_value(ctx, only_parse, span, &function, args)?);
+            }
+            let size = ArraySize::new_or_err(array.width(), 1)?;
+            Ok(Array::new_row_major(size, results)?.into())
+        }
+
+        "MAKEARRAY" => {
+            let [rows, columns, function] =
+                exact_args("MAKEARRAY", span, args, ["rows", "columns", "function"])?;
+            let rows = rows.eval(ctx, only_parse)?.try_coerce::<i64>()?;
+            let columns = columns.eval(ctx, only_parse)?.try_coerce::<i64>()?;
+            let function = eval_lambda(ctx, function)?;
+            for n in [&rows, &columns] {
+                if n.inner < 1 {
+                    return Err(RunErrorMsg::InvalidArgument.with_span(n.span));
+                }
+            }
+            if rows.inner.saturating_mul(columns.inner) > crate::limits::CELL_RANGE_LIMIT as i64 {
+                return Err(RunErrorMsg::ArrayTooBig.with_span(span));
+            }
+            let size = ArraySize::new_or_err(columns.inner as u32, rows.inner as u32)?;
+            let values = size
+                .iter()
+                .map(|(x, y)| {
+                    let args = vec![Value::from(y + 1).into(), Value::from(x + 1).into()];
+                    call_for_cell_value(ctx, only_parse, span, &function, args)
+                })
+                .collect::<CodeResult<_>>()?;
+            Ok(Array::new_row_major(size, values)?.into())
+        }
+
+        _ => internal_error!("unknown special form {:?}", func.inner),
+    }
+}
+
+/// Evaluates a name defined using `LET()`, bound to a lambda parameter, or
+/// defined on the grid.
+///
+/// When only parsing, names that are not bound evaluate to blank, since names
+/// defined on the grid may not be available.
+pub(super) fn eval_name(
+    ctx: &mut Ctx<'_>,
+    only_parse: bool,
+    name: &str,
+    span: Span,
+) -> CodeResult<Value> {
+    match ctx.lookup(name) {
+        Some(Binding::Value(value)) => Ok((**value).clone()),
+        Some(Binding::Omitted) => Ok(CellValue::Blank.into()),
+        Some(Binding::Lambda(_)) => Err(RunErrorMsg::Expected {
+            expected: "value".into(),
+            got: Some("lambda".into()),
+        }
+        .with_span(span)),
+        None if only_parse => Ok(CellValue::Blank.into()),
+        None => ctx
+            .with_defined_name(name, span, |ctx, formula| formula.eval(ctx, only_parse))
+            .unwrap_or_else(|| {
+                Err(RunErrorMsg::UnknownName(name.to_string().into()).with_span(span))
+            }),
+    }
+}
+
+/// Returns an error for a missing argument.
+fn missing_arg(func_name: &'static str, arg_name: &'static str, span: Span) -> RunError {
+    RunErrorMsg::MissingRequiredArgument {
+        func_name: func_name.into(),
+        arg_name: arg_name.into(),
+    }
+    .with_span(span)
+}
+
+/// Returns exactly `N` arguments, or an error if there are too many or too
+/// few.
+fn exact_args<'a, const N: usize>(
+    func_name: &'static str,
+    span: Span,
+    args: &'a [AstNode],
+    arg_names: [&'static str; N],
+) -> CodeResult<&'a [AstNode; N]> {
+    if let Some(extra_arg) = args.get(N) {
+        return Err(RunErrorMsg::TooManyArguments {
+            func_name: func_name.into(),
+            max_arg_count: N,
+        }
+        .with_span(extra_arg.span));
+    }
+    match args.try_into() {
+        Ok(args) => Ok(args),
+        Err(_) => Err(missing_arg(func_name, arg_names[args.len()], span)),
+    }
+}
+
+/// Returns the name in an expression that must be a name.
+fn name_of(node: &AstNode) -> CodeResult<String> {
+    match &node.inner {
+        AstNodeContents::Name(name) => Ok(name.clone()),
+        other => Err(RunErrorMsg::Expected {
+            expected: "name".into(),
+            got: Some(other.type_string().into()),
+        }
+        .with_span(node.span)),
+    }
+}
+
+/// Returns the lambda that an expression evaluates to, or `None` if the
+/// expression is not a lambda.
+fn as_lambda(ctx: &Ctx<'_>, node: &AstNode) -> Option<CodeResult<Rc<Lambda>>> {
+    match &node.inner {
+        AstNodeContents::Paren(inner) => as_lambda(ctx, inner),
+        AstNodeContents::Name(name) => match ctx.lookup(name)? {
+            Binding::Lambda(lambda) => Some(Ok(Rc::clone(lambda))),
+            _ => None,
+        },
+        AstNodeContents::FunctionCall { func, args }
+            if ctx.lookup(&func.inner).is_none()
+                && normalize_function_name(&func.inner) == "LAMBDA" =>
+        {
+            let Some((body, params)) = args.split_last() else {
+                return Some(Err(missing_arg("LAMBDA", "calculation", node.span)));
+            };
+            let params = match params.iter().map(name_of).collect::<CodeResult<Vec<_>>>() {
+                Ok(params) => params,
+                Err(e) => return Some(Err(e)),
+            };
+            Some(Ok(Rc::new(Lambda {
+                params,
+                body: body.clone(),
+                captured: ctx.bindings.clone(),
+            })))
+        }
+        _ => None,
+    }
+}
+
+/// Evaluates an expression that must be a lambda.
+fn eval_lambda(ctx: &Ctx<'_>, node: &AstNode) -> CodeResult<Rc<Lambda>> {
+    as_lambda(ctx, node).unwrap_or_else(|| {
+        Err(RunErrorMsg::Expected {
+            expected: "lambda".into(),
+            got: Some(node.inner.type_string().into()),
+        }
+        .with_span(node.span))
+    })
+}
+
+/// Evaluates an expression that may be a lambda.
+fn eval_binding(ctx: &mut Ctx<'_>, only_parse: bool, node: &AstNode) -> CodeResult<Binding> {
+    match as_lambda(ctx, node) {
+        Some(lambda) => Ok(Binding::Lambda(lambda?)),
+        None => Ok(node.eval(ctx, only_parse)?.inner.into()),
+    }
+}
+
+/// Runs `f`, then removes any bindings that it added.
+fn with_scope<T>(
+    ctx: &mut Ctx<'_>,
+    f: impl FnOnce(&mut Ctx<'_>) -> CodeResult<T>,
+) -> CodeResult<T> {
+    let len = ctx.bindings.len();
+    let result = f(ctx);
+    ctx.bindings.truncate(len);
+    result
+}
+
+/// Calls a lambda. Parameters without a corresponding argument are omitted.
+fn call(
+    ctx: &mut Ctx<'_>,
+    only_parse: bool,
+    span: Span,
+    lambda: &Lambda,
+    args: Vec<Binding>,
+) -> CodeResult<Value> {
+    if args.len() > lambda.params.len() {
+        return Err(RunErrorMsg::TooManyArguments {
+            func_name: "LAMBDA".into(),
+            max_arg_count: lambda.params.len(),
+        }
+        .with_span(span));
+    }
+
+    let mut bindings = lambda.captured.clone();
+    let args = args.into_iter().chain(std::iter::repeat(Binding::Omitted));
+    bindings.extend(lambda.params.iter().cloned().zip(args));
+
+    let outer_bindings = std::mem::replace(&mut ctx.bindings, bindings);
+    let result = lambda.body.eval(ctx, only_parse);
+    ctx.bindings = outer_bindings;
+    Ok(result?.inner)
+}
+
+/// Calls a lambda that must return a single value.
+fn call_for_cell_value(
+    ctx: &mut Ctx<'_>,
+    only_parse: bool,
+    span: Span,
+    lambda: &Lambda,
+    args: Vec<Binding>,
+) -> CodeResult<CellValue> {
+    let inner = call(ctx, only_parse, span, lambda, args)?;
+    Ok(Spanned { span, inner }.into_cell_value()?.inner)
+}
+
+/// Calls a lambda on each row of an array, returning a column of the results.
+fn map_rows(
+    ctx: &mut Ctx<'_>,
+    only_parse: bool,
+    span: Span,
+    array: Array,
+    lambda: &Lambda,
+) -> CodeResult<Array> {
+    let mut results = SmallVec::with_capacity(array.height() as usize);
+    for row in array.rows() {
+        let size = ArraySize::new_or_err(row.len() as u32, 1)?;
+        let row = Array::new_row_major(size, row.iter().cloned().collect())?;
+        let args = vec![Value::from(row).into()];
+        results.push(call_for_cell_value(ctx, only_parse, span, lambda, args)?);
+    }
+    let size = ArraySize::new_or_err(1, array.height())?;
+    Array::new_row_major(size, results)
+}
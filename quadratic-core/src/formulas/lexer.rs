@@ -20,6 +20,13 @@ pub fn tokenize(input_str: &str) -> impl '_ + Iterator<Item = Spanned<Token>> {
     })
 }
 
+/// Returns whether `s` starts with an unquoted three-dimensional sheet
+/// reference, such as `Jan:Dec!`, rather than a cell range whose end is on
+/// another sheet, such as `A1:Data!`.
+pub fn is_unquoted_sheet_range_reference(s: &str) -> bool {
+    UNQUOTED_SHEET_RANGE_REFERENCE.is_match(s) && !CELL_RANGE_TO_SHEET_REGEX.is_match(s)
+}
+
 fn new_fullmatch_regex(s: &str) -> Regex {
     Regex::new(&("^(".to_owned() + s + ")")).unwrap()
 }
@@ -39,6 +46,34 @@ const FUNCTION_CALL_PATTERN: &str = r"[A-Za-z_](\.?[A-Za-z_\d])*\(";
 const A1_CELL_REFERENCE_PATTERN: &str = r"\$?n?[A-Z]+\$?n?\d+";
 const INTERNAL_CELL_REFERENCE_PATTERN: &str = r"R([\[|\{]-?\d+[\]|\}])C([\[|\{]-?\d+[\]|\}])";
 
+/// A1-style whole-column range reference, such as `A:C`. This is
+/// case-sensitive so that names separated by a colon are not mistaken for
+/// columns.
+///
+/// (?-i:\$?n?[A-Z]+:\$?n?[A-Z]+)\b
+/// (?-i:                        )      case-sensitive
+///      \$?n?[A-Z]+                    first column
+///                 :                   colon
+///                  \$?n?[A-Z]+        last column
+///                               \b    not followed by a row number
+const A1_COL_RANGE_REFERENCE_PATTERN: &str = r"(?-i:\$?n?[A-Z]+:\$?n?[A-Z]+)\b";
+/// A1-style whole-row range reference, such as `3:5`. This is case-sensitive
+/// so that a cell range such as `N3:N5` is not mistaken for negative rows.
+///
+/// (?-i:\$?n?\d+:\$?n?\d+)\b
+/// (?-i:                  )      case-sensitive
+///      \$?n?\d+                first row
+///              :                colon
+///               \$?n?\d+       last row
+///                         \b    not followed by a column name
+const A1_ROW_RANGE_REFERENCE_PATTERN: &str = r"(?-i:\$?n?\d+:\$?n?\d+)\b";
+const INTERNAL_COL_RANGE_REFERENCE_PATTERN: &str = r"C([\[|\{]-?\d+[\]|\}]):C([\[|\{]-?\d+[\]|\}])";
+const INTERNAL_ROW_RANGE_REFERENCE_PATTERN: &str = r"R([\[|\{]-?\d+[\]|\}]):R([\[|\{]-?\d+[\]|\}])";
+
+/// Name, such as a variable defined using `LET()`, consisting of a letter or
+/// underscore followed by any letters, digits, and/or underscores.
+const NAME_PATTERN: &str = r"[A-Za-z_][A-Za-z_\d]*";
+
 /// Floating-point or integer number, without leading sign.
 ///
 /// (\d+(\.\d*)?|\.\d+)([eE][+-]?\d+)?
@@ -62,6 +97,19 @@ const DOUBLE_QUOTE_STRING_LITERAL_PATTERN: &str = r#""([^"\\]|\\[\s\S])*""#;
 /// as `'Sheet1'!` is parsed as a string followed by a sheet reference operator
 /// `!`.
 const UNQUOTED_SHEET_REFERENCE_PATTERN: &str = r"[A-Za-z_][A-Za-z0-9_\.]*\s*!";
+/// Unquoted three-dimensional sheet reference, such as `Jan:Dec!`, which
+/// refers to every sheet from `Jan` to `Dec`. A quoted three-dimensional sheet
+/// reference such as `'Jan':'Dec'!` is parsed as two strings separated by a
+/// cell range operator `:` followed by a sheet reference operator `!`.
+const UNQUOTED_SHEET_RANGE_REFERENCE_PATTERN: &str =
+    r"[A-Za-z_][A-Za-z0-9_\.]*:[A-Za-z_][A-Za-z0-9_\.]*\s*!";
+/// Start of a cell range whose end is on another sheet, such as `A1:` in
+/// `A1:Data!B2`, which is not a three-dimensional sheet reference. The first
+/// cell is in A1 or R1C1 notation.
+const CELL_RANGE_TO_SHEET_PATTERN: &str = r"(?i:n?[A-Z]+n?\d+|R\d*C\d*):";
+/// Excel error code, such as `#DIV/0!` or `#N/A` (case-insensitive).
+const ERROR_LITERAL_PATTERN: &str =
+    r"(?i:#(DIV/0!|VALUE!|REF!|NAME\?|NUM!|N/A|SPILL!|CALC!|NULL!))";
 /// Unterminated string literal.
 const UNTERMINATED_STRING_LITERAL_PATTERN: &str = r#"["']"#;
 
@@ -75,22 +123,35 @@ const TOKEN_PATTERNS: &[&str] = &[
     r"//[^\n]*",
     // Start of a block comment (block comment has special handling).
     r"/\*",
+    // Error literal.
+    ERROR_LITERAL_PATTERN,
+    // Three-dimensional sheet reference, which must come before sheet
+    // references and whole-column ranges.
+    UNQUOTED_SHEET_RANGE_REFERENCE_PATTERN,
     // Sheet reference.
     UNQUOTED_SHEET_REFERENCE_PATTERN,
     // String literal.
     SINGLE_QUOTE_STRING_LITERAL_PATTERN,
     DOUBLE_QUOTE_STRING_LITERAL_PATTERN,
     UNTERMINATED_STRING_LITERAL_PATTERN,
+    // Reference to a whole column or row range, which must come before
+    // numeric literals and names.
+    A1_COL_RANGE_REFERENCE_PATTERN,
+    A1_ROW_RANGE_REFERENCE_PATTERN,
+    INTERNAL_COL_RANGE_REFERENCE_PATTERN,
+    INTERNAL_ROW_RANGE_REFERENCE_PATTERN,
     // Numeric literal.
     NUMERIC_LITERAL_PATTERN,
     // Function call.
     FUNCTION_CALL_PATTERN,
-    // Boolean literal (case-insensitive).
-    r#"false|true"#,
+    // Boolean literal (case-insensitive), but not the start of a longer name.
+    r#"(false|true)\b"#,
     // Reference to a cell.
     A1_CELL_REFERENCE_PATTERN,
     // Internal cell reference.
     INTERNAL_CELL_REFERENCE_PATTERN,
+    // Name.
+    NAME_PATTERN,
     // Whitespace.
     r"\s+",
     // Any other single Unicode character.
@@ -103,10 +164,38 @@ lazy_static! {
     pub static ref TOKEN_REGEX: Regex =
         RegexBuilder::new(&TOKEN_PATTERNS.join("|")).case_insensitive(true).build().unwrap();
 
+    /// Regex that matches the same tokens as `TOKEN_REGEX`, except for
+    /// three-dimensional sheet references.
+    static ref TOKEN_REGEX_WITHOUT_SHEET_RANGE: Regex = RegexBuilder::new(
+        &TOKEN_PATTERNS
+            .iter()
+            .filter(|&&pattern| pattern != UNQUOTED_SHEET_RANGE_REFERENCE_PATTERN)
+            .copied()
+            .collect::<Vec<_>>()
+            .join("|"),
+    )
+    .case_insensitive(true)
+    .build()
+    .unwrap();
+
+    /// Regex that matches the start of a cell range whose end is on another
+    /// sheet, such as `A1:Data!`.
+    static ref CELL_RANGE_TO_SHEET_REGEX: Regex =
+        new_fullmatch_regex(CELL_RANGE_TO_SHEET_PATTERN);
+
+    /// Regex that matches an Excel error code.
+    pub static ref ERROR_LITERAL_REGEX: Regex =
+        new_fullmatch_regex(ERROR_LITERAL_PATTERN);
+
     /// Regex that matches a valid function call.
     pub static ref FUNCTION_CALL_REGEX: Regex =
         new_fullmatch_regex(FUNCTION_CALL_PATTERN);
 
+    /// Regex that matches an unquoted three-dimensional sheet reference, such
+    /// as `Jan:Dec!`.
+    pub static ref UNQUOTED_SHEET_RANGE_REFERENCE: Regex =
+        new_fullmatch_regex(UNQUOTED_SHEET_RANGE_REFERENCE_PATTERN);
+
     /// Regex that matches an unquoted sheet reference, such as `Sheet1!`.
     pub static ref UNQUOTED_SHEET_REFERENCE: Regex =
         new_fullmatch_regex(UNQUOTED_SHEET_REFERENCE_PATTERN);
@@ -119,6 +208,24 @@ lazy_static! {
     pub static ref INTERNAL_CELL_REFERENCE_REGEX: Regex =
         new_fullmatch_regex(INTERNAL_CELL_REFERENCE_PATTERN);
 
+    /// Regex that matches a valid whole-column range reference.
+    pub static ref COL_RANGE_REFERENCE_REGEX: Regex =
+        new_fullmatch_regex(&[
+            A1_COL_RANGE_REFERENCE_PATTERN,
+            INTERNAL_COL_RANGE_REFERENCE_PATTERN,
+        ].join("|"));
+
+    /// Regex that matches a valid whole-row range reference.
+    pub static ref ROW_RANGE_REFERENCE_REGEX: Regex =
+        new_fullmatch_regex(&[
+            A1_ROW_RANGE_REFERENCE_PATTERN,
+            INTERNAL_ROW_RANGE_REFERENCE_PATTERN,
+        ].join("|"));
+
+    /// Regex that matches a valid name.
+    pub static ref NAME_REGEX: Regex =
+        new_fullmatch_regex(NAME_PATTERN);
+
     /// Regex that matches all valid numeric literals and some invalid ones.
     pub static ref NUMERIC_LITERAL_REGEX: Regex =
         new_fullmatch_regex(NUMERIC_LITERAL_PATTERN);
@@ -194,6 +301,10 @@ pub enum Token {
     CellRangeOp, // :
     #[strum(to_string = "sheet reference operator")]
     SheetRefOp, // !
+    #[strum(to_string = "spill range operator")]
+    SpillOp, // #
+    #[strum(to_string = "implicit intersection operator")]
+    ImplicitIntersectionOp, // @
     #[strum(to_string = "ellipsis")]
     Ellipsis, // ...
 
@@ -203,6 +314,10 @@ pub enum Token {
     #[strum(to_string = "TRUE")]
     True,
 
+    // Errors
+    #[strum(to_string = "error literal")]
+    ErrorLiteral,
+
     // Comments
     #[strum(to_string = "comment")]
     Comment,
@@ -214,6 +329,8 @@ pub enum Token {
     FunctionCall,
     #[strum(to_string = "unquoted sheet reference")]
     UnquotedSheetReference,
+    #[strum(to_string = "unquoted sheet range reference")]
+    UnquotedSheetRangeReference,
     #[strum(to_string = "string literal")]
     StringLiteral,
     #[strum(to_string = "unterminated string literal")]
@@ -224,6 +341,12 @@ pub enum Token {
     CellRef,
     #[strum(to_string = "internal cell reference")]
     InternalCellRef,
+    #[strum(to_string = "column range reference")]
+    ColRangeRef,
+    #[strum(to_string = "row range reference")]
+    RowRangeRef,
+    #[strum(to_string = "name")]
+    Name,
     #[strum(to_string = "whitespace")]
     Whitespace,
     #[strum(to_string = "unknown symbol")]
@@ -234,7 +357,14 @@ impl Token {
     /// the next character after the token.
     fn consume_from_input(input_str: &str, start: usize) -> Option<(Self, usize)> {
         // Find next token.
-        let m = TOKEN_REGEX.find_at(input_str, start)?;
+        let mut m = TOKEN_REGEX.find_at(input_str, start)?;
+        if UNQUOTED_SHEET_RANGE_REFERENCE.is_match(m.as_str())
+            && !is_unquoted_sheet_range_reference(m.as_str())
+        {
+            // `A1:Data!B2` is a cell range, not a reference to the sheets
+            // from `A1` to `Data`.
+            m = TOKEN_REGEX_WITHOUT_SHEET_RANGE.find_at(input_str, start)?;
+        }
 
         let mut end = m.end();
 
@@ -263,6 +393,8 @@ impl Token {
             "%" => Self::Percent,
             ":" => Self::CellRangeOp,
             "!" => Self::SheetRefOp,
+            "#" => Self::SpillOp,
+            "@" => Self::ImplicitIntersectionOp,
             "..." => Self::Ellipsis,
             s if s.eq_ignore_ascii_case("false") => Self::False,
             s if s.eq_ignore_ascii_case("true") => Self::True,
@@ -297,15 +429,20 @@ impl Token {
             }
 
             // Match anything else.
+            s if ERROR_LITERAL_REGEX.is_match(s) => Self::ErrorLiteral,
             s if FUNCTION_CALL_REGEX.is_match(s) => Self::FunctionCall,
+            s if is_unquoted_sheet_range_reference(s) => Self::UnquotedSheetRangeReference,
             s if UNQUOTED_SHEET_REFERENCE.is_match(s) => Self::UnquotedSheetReference,
             s if STRING_LITERAL_REGEX.is_match(s) => Self::StringLiteral,
             s if UNTERMINATED_STRING_LITERAL_REGEX.is_match(s) => Self::UnterminatedStringLiteral,
             s if s.eq_ignore_ascii_case("false") => Self::False,
             s if s.eq_ignore_ascii_case("true") => Self::True,
+            s if COL_RANGE_REFERENCE_REGEX.is_match(s) => Self::ColRangeRef,
+            s if ROW_RANGE_REFERENCE_REGEX.is_match(s) => Self::RowRangeRef,
             s if NUMERIC_LITERAL_REGEX.is_match(s) => Self::NumericLiteral,
             s if A1_CELL_REFERENCE_REGEX.is_match(s) => Self::CellRef,
             s if INTERNAL_CELL_REFERENCE_REGEX.is_match(s) => Self::InternalCellRef,
+            s if NAME_REGEX.is_match(s) => Self::Name,
             s if s.trim().is_empty() => Self::Whitespace,
 
             // Give up.
@@ -353,6 +490,27 @@ mod tests {
         test_block_comment(false, "/* /*");
         test_block_comment(false, "/*/");
     }
+    #[test]
+    fn test_lex_sheet_range_reference() {
+        let lex = |s| tokenize(s).map(|token| token.inner).collect_vec();
+        assert_eq!(
+            lex("Jan:Dec!C1"),
+            [Token::UnquotedSheetRangeReference, Token::CellRef],
+        );
+        for s in ["A1:Data!B2", "a1:Data!B2", "nA0:Data!B2"] {
+            assert_eq!(
+                lex(s),
+                [
+                    Token::CellRef,
+                    Token::CellRangeOp,
+                    Token::UnquotedSheetReference,
+                    Token::CellRef,
+                ],
+                "{s}",
+            );
+        }
+    }
+
     fn test_block_comment(expected_to_end: bool, s: &str) {
         let tokens = tokenize(s).collect_vec();
         if expected_to_end {
@@ -8,24 +8,30 @@ mod criteria;
 mod ctx;
 #[allow(clippy::vec_init_then_push)]
 pub mod functions;
+mod lambda;
 mod lexer;
 pub mod lsp;
 mod params;
 mod parser;
+mod references;
 mod wildcards;
 
 use ast::AstNode;
 pub use ast::Formula;
 pub use cell_ref::*;
-pub use criteria::Criterion;
+pub use criteria::{Criterion, DatabaseCriteria};
 pub use ctx::Ctx;
 use functions::FormulaFnArgs;
+pub use lambda::{Binding, Lambda};
 use params::{Param, ParamKind};
 pub use parser::{
-    find_cell_references, parse_and_check_formula, parse_formula, replace_a1_notation,
-    replace_internal_cell_references,
+    add_implicit_intersection_operators, find_cell_references, find_sheet_range_references,
+    formula_uses_name, is_valid_defined_name, parse_and_check_formula, parse_formula,
+    replace_a1_notation, replace_internal_cell_references, replace_moved_cell_references,
+    replace_moved_formula_cell_references, replace_sheet_name,
+    replace_sheet_references_with_ref_error,
 };
-use wildcards::wildcard_pattern_to_regex;
+use wildcards::{wildcard_pattern_to_regex, wildcard_pattern_to_unanchored_regex};
 
 /// Escapes a formula string.
 pub fn escape_string(s: &str) -> String {
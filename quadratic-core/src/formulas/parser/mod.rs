@@ -12,7 +12,7 @@ use lexer::Token;
 use rules::SyntaxRule;
 
 use super::*;
-use crate::{grid::Grid, CodeResult, Pos, RunError, RunErrorMsg, Span, Spanned};
+use crate::{grid::Grid, CodeResult, Pos, Rect, RunError, RunErrorMsg, Span, Spanned};
 
 pub fn parse_formula(source: &str, pos: Pos) -> CodeResult<ast::Formula> {
     Ok(Formula {
@@ -63,6 +63,22 @@ pub fn parse_and_check_formula(formula_string: &str, x: i64, y: i64) -> bool {
     }
 }
 
+/// Returns whether `name` can be used as a defined name. It must be a single
+/// name token, so that it cannot be mistaken for a cell reference, boolean, or
+/// function call.
+pub fn is_valid_defined_name(name: &str) -> bool {
+    let tokens = lexer::tokenize(name).collect_vec();
+    matches!(tokens.as_slice(), [token] if token.inner == Token::Name)
+}
+
+/// Returns whether a formula uses the name `name`, ignoring case.
+pub fn formula_uses_name(source: &str, name: &str) -> bool {
+    lexer::tokenize(source).any(|token| {
+        token.inner == Token::Name
+            && source[Range::<usize>::from(token.span)].eq_ignore_ascii_case(name)
+    })
+}
+
 /// Replace internal cell references in a formula with A1 notation.
 ///
 /// # Example
@@ -74,7 +90,7 @@ pub fn parse_and_check_formula(formula_string: &str, x: i64, y: i64) -> bool {
 /// assert_eq!(replaced, "SUM(nA0)");
 /// ```
 pub fn replace_internal_cell_references(source: &str, pos: Pos) -> String {
-    let replace_fn = |cell_ref: RangeRef| cell_ref.a1_string(pos);
+    let replace_fn = |cell_ref: RangeRef, _: &str| Some(cell_ref.a1_string(pos));
     replace_cell_references(source, pos, &replace_fn)
 }
 
@@ -89,14 +105,414 @@ pub fn replace_internal_cell_references(source: &str, pos: Pos) -> String {
 /// assert_eq!(replaced, "SUM(R[0]C[-1])");
 /// ```
 pub fn replace_a1_notation(source: &str, pos: Pos) -> String {
-    let replace_fn = |cell_ref: RangeRef| cell_ref.to_string();
+    let replace_fn = |cell_ref: RangeRef, _: &str| Some(cell_ref.to_string());
     replace_cell_references(source, pos, &replace_fn)
 }
 
+/// Replaces the sheet name `old_name` with `new_name` in every cell reference
+/// in a formula, such as when a sheet is renamed.
+///
+/// # Example
+/// ```rust
+/// use quadratic_core::formulas::replace_sheet_name;
+///
+/// let replaced = replace_sheet_name("SUM(Data!A1:A5) + 'Data'!B1", "Data", "Sales 2024");
+/// assert_eq!(replaced, r#"SUM("Sales 2024"!A1:A5) + "Sales 2024"!B1"#);
+/// ```
+pub fn replace_sheet_name(source: &str, old_name: &str, new_name: &str) -> String {
+    let tokens = lexer::tokenize(source)
+        .filter(|t| !t.inner.is_skip())
+        .collect_vec();
+    let mut replaced = source.to_string();
+
+    // replace in reverse order to preserve previous span references
+    for (i, token) in tokens.iter().enumerate().rev() {
+        let span: Range<usize> = token.span.into();
+        let token_str = &source[span.clone()];
+        let new_str = match token.inner {
+            Token::UnquotedSheetReference => {
+                let name = token_str.strip_suffix('!').unwrap_or(token_str).trim();
+                let unquoted = format!("{new_name}!");
+                (name == old_name).then(|| {
+                    if lexer::UNQUOTED_SHEET_REFERENCE.is_match(&unquoted) {
+                        unquoted
+                    } else {
+                        format!("{}!", escape_string(new_name))
+                    }
+                })
+            }
+            Token::UnquotedSheetRangeReference => {
+                let names = token_str.strip_suffix('!').unwrap_or(token_str);
+                let (first, last) = names.split_once(':').unwrap_or((names, names));
+                let (first, last) = (first.trim(), last.trim());
+                (first == old_name || last == old_name).then(|| {
+                    let rename = |name| if name == old_name { new_name } else { name };
+                    let (first, last) = (rename(first), rename(last));
+                    let unquoted = format!("{first}:{last}!");
+                    if lexer::is_unquoted_sheet_range_reference(&unquoted) {
+                        unquoted
+                    } else {
+                        format!("{}:{}!", escape_string(first), escape_string(last))
+                    }
+                })
+            }
+            Token::StringLiteral if is_sheet_name_token(&tokens[i + 1..]) => {
+                let name = parse_string_literal(token_str);
+                (name.as_deref() == Some(old_name)).then(|| escape_string(new_name))
+            }
+            _ => None,
+        };
+        if let Some(new_str) = new_str {
+            replaced.replace_range(span, &new_str);
+        }
+    }
+
+    replaced
+}
+
+/// Returns whether a string literal followed by `next_tokens` is a sheet name,
+/// either before `!` or as the first sheet of a sheet range such as
+/// `"Jan":"Dec"!`.
+fn is_sheet_name_token(next_tokens: &[Spanned<Token>]) -> bool {
+    match next_tokens {
+        [next, ..] if next.inner == Token::SheetRefOp => true,
+        [op, last, bang, ..] => {
+            op.inner == Token::CellRangeOp
+                && last.inner == Token::StringLiteral
+                && bang.inner == Token::SheetRefOp
+        }
+        _ => false,
+    }
+}
+
+/// Replaces every cell reference to the sheet `sheet_name` in a formula with
+/// `#REF!`, such as when the sheet is deleted.
+///
+/// `sheet_names` are the names of all sheets in sheet order, including the
+/// deleted sheet. If the deleted sheet is the first or last sheet of a
+/// three-dimensional reference, that end of the reference is moved to the
+/// neighboring sheet in the span instead.
+///
+/// # Example
+/// ```rust
+/// use quadratic_core::{formulas::replace_sheet_references_with_ref_error, Pos};
+///
+/// let pos = Pos { x: 0, y: 0 };
+/// let sheet_names = ["Jan", "Data", "Feb"];
+/// let src = "SUM(Data!A1:A5, B1) + SUM(Jan:Data!C1)";
+/// let replaced = replace_sheet_references_with_ref_error(src, pos, "Data", &sheet_names);
+/// assert_eq!(replaced, "SUM(#REF!, B1) + SUM(\"Jan\"!C1)");
+/// ```
+pub fn replace_sheet_references_with_ref_error(
+    source: &str,
+    pos: Pos,
+    sheet_name: &str,
+    sheet_names: &[&str],
+) -> String {
+    let is_sheet = |sheet: &Option<String>| sheet.as_deref() == Some(sheet_name);
+    let ref_error = || RunErrorMsg::BadCellReference.excel_code().to_string();
+    let replace_fn = |cell_ref: RangeRef, original: &str| {
+        let refers_to_sheet = match cell_ref {
+            RangeRef::RowRange { ref sheet, .. } | RangeRef::ColRange { ref sheet, .. } => {
+                is_sheet(sheet)
+            }
+            RangeRef::CellRange { ref start, ref end } => {
+                is_sheet(&start.sheet) || is_sheet(&end.sheet)
+            }
+            RangeRef::Cell { ref pos } | RangeRef::Spill { ref pos } => is_sheet(&pos.sheet),
+            RangeRef::SheetRange {
+                first_sheet,
+                last_sheet,
+                range,
+            } => {
+                if first_sheet != sheet_name && last_sheet != sheet_name {
+                    return None;
+                }
+                let index = |name: &str| sheet_names.iter().position(|s| *s == name);
+                let (Some(first), Some(last)) = (index(&first_sheet), index(&last_sheet)) else {
+                    return Some(ref_error());
+                };
+                if first == last {
+                    return Some(ref_error());
+                }
+                // move the deleted end one sheet toward the other end
+                let step_toward = |from: usize, to: usize| match from < to {
+                    true => from + 1,
+                    false => from - 1,
+                };
+                let (first, last) = match first_sheet == sheet_name {
+                    true => (step_toward(first, last), last),
+                    false => (first, step_toward(last, first)),
+                };
+                let moved = match first == last {
+                    true => (*range).with_sheet(Some(sheet_names[first].to_string())),
+                    false => RangeRef::SheetRange {
+                        first_sheet: sheet_names[first].to_string(),
+                        last_sheet: sheet_names[last].to_string(),
+                        range,
+                    },
+                };
+                return match is_internal_notation(original) {
+                    true => Some(moved.to_string()),
+                    false => Some(moved.a1_string(pos)),
+                };
+            }
+        };
+        refers_to_sheet.then(ref_error)
+    };
+    replace_cell_references(source, pos, &replace_fn)
+}
+
+/// Returns the first and last sheet of every three-dimensional reference in a
+/// formula, such as `("Jan", "Dec")` for `SUM(Jan:Dec!C10)`.
+pub fn find_sheet_range_references(source: &str, pos: Pos) -> Vec<(String, String)> {
+    find_cell_references(source, pos)
+        .into_iter()
+        .filter_map(|cell_ref| match cell_ref.inner {
+            RangeRef::SheetRange {
+                first_sheet,
+                last_sheet,
+                ..
+            } => Some((first_sheet, last_sheet)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Updates references in a formula to cells that were moved from `from` to
+/// the region with its top left corner at `to`, so that they follow the moved
+/// cells. Both absolute and relative references are updated, and a cell range
+/// is only updated if all of its cells were moved.
+///
+/// `is_from_sheet` returns whether a sheet name (`None` for the sheet
+/// containing the formula) refers to the sheet that the cells were moved
+/// from. `to_sheet` is the name of the sheet that the cells were moved to, if
+/// it is a different sheet.
+///
+/// # Example
+/// ```rust
+/// use quadratic_core::{formulas::replace_moved_cell_references, Pos, Rect};
+///
+/// let pos = Pos { x: 0, y: 0 };
+/// let from = Rect::new(1, 1, 2, 2);
+/// let to = Pos { x: 5, y: 1 };
+/// let replaced = replace_moved_cell_references("B1 + $C$2", pos, &|_| true, from, to, None);
+/// assert_eq!(replaced, "F1 + $G$2");
+/// ```
+pub fn replace_moved_cell_references(
+    source: &str,
+    pos: Pos,
+    is_from_sheet: &dyn Fn(&Option<String>) -> bool,
+    from: Rect,
+    to: Pos,
+    to_sheet: Option<&str>,
+) -> String {
+    replace_references_to_moved_cells(source, pos, is_from_sheet, from, to, to_sheet, false)
+}
+
+/// Updates references in a formula at `pos` that was itself moved along with
+/// the cells from `from` to the region with its top left corner at `to`, so
+/// that they follow the moved cells. `pos` is the position of the formula
+/// before it was moved.
+///
+/// Relative references keep pointing at the moved cells since they move
+/// together with the formula, so only absolute references and sheet names are
+/// updated. The arguments are the same as for
+/// [`replace_moved_cell_references()`].
+///
+/// # Example
+/// ```rust
+/// use quadratic_core::{formulas::replace_moved_formula_cell_references, Pos, Rect};
+///
+/// let pos = Pos { x: 1, y: 2 };
+/// let from = Rect::new(1, 1, 2, 2);
+/// let to = Pos { x: 5, y: 1 };
+/// let replaced =
+///     replace_moved_formula_cell_references("B1 + $C$1", pos, &|_| true, from, to, None);
+/// assert_eq!(replaced, "F1 + $G$1");
+/// ```
+pub fn replace_moved_formula_cell_references(
+    source: &str,
+    pos: Pos,
+    is_from_sheet: &dyn Fn(&Option<String>) -> bool,
+    from: Rect,
+    to: Pos,
+    to_sheet: Option<&str>,
+) -> String {
+    replace_references_to_moved_cells(source, pos, is_from_sheet, from, to, to_sheet, true)
+}
+
+fn replace_references_to_moved_cells(
+    source: &str,
+    pos: Pos,
+    is_from_sheet: &dyn Fn(&Option<String>) -> bool,
+    from: Rect,
+    to: Pos,
+    to_sheet: Option<&str>,
+    formula_moved: bool,
+) -> String {
+    let (dx, dy) = (to.x - from.min.x, to.y - from.min.y);
+    let translate = |coord: CellRefCoord, delta: i64| match coord {
+        CellRefCoord::Relative(offset) if formula_moved => CellRefCoord::Relative(offset),
+        CellRefCoord::Relative(offset) => CellRefCoord::Relative(offset + delta),
+        CellRefCoord::Absolute(coord) => CellRefCoord::Absolute(coord + delta),
+    };
+    let move_cell_ref = |cell_ref: CellRef| CellRef {
+        // a moved formula is on the same sheet as the moved cells
+        sheet: match cell_ref.sheet {
+            None if formula_moved => None,
+            sheet => to_sheet.map(String::from).or(sheet),
+        },
+        x: translate(cell_ref.x, dx),
+        y: translate(cell_ref.y, dy),
+    };
+    let new_pos = if formula_moved {
+        Pos {
+            x: pos.x + dx,
+            y: pos.y + dy,
+        }
+    } else {
+        pos
+    };
+    let is_moved = |cell_ref: &CellRef| from.contains(cell_ref.resolve_from(pos));
+
+    let replace_fn = |range_ref: RangeRef, original: &str| {
+        let moved = match range_ref {
+            RangeRef::Cell { pos: cell_ref }
+                if is_from_sheet(&cell_ref.sheet) && is_moved(&cell_ref) =>
+            {
+                RangeRef::Cell {
+                    pos: move_cell_ref(cell_ref),
+                }
+            }
+            RangeRef::Spill { pos: cell_ref }
+                if is_from_sheet(&cell_ref.sheet) && is_moved(&cell_ref) =>
+            {
+                RangeRef::Spill {
+                    pos: move_cell_ref(cell_ref),
+                }
+            }
+            RangeRef::CellRange { start, end }
+                if is_from_sheet(&start.sheet) && is_moved(&start) && is_moved(&end) =>
+            {
+                // the end of a range without a sheet is on the sheet of the start
+                let end_has_sheet = end.sheet.is_some();
+                let mut end = move_cell_ref(end);
+                if !end_has_sheet {
+                    end.sheet = None;
+                }
+                RangeRef::CellRange {
+                    start: move_cell_ref(start),
+                    end,
+                }
+            }
+            _ => return None,
+        };
+        if is_internal_notation(original) {
+            Some(moved.to_string())
+        } else {
+            Some(moved.a1_string(new_pos))
+        }
+    };
+    replace_cell_references(source, pos, &replace_fn)
+}
+
+/// Adds the implicit intersection operator `@` to a formula written for a
+/// version of Excel without dynamic arrays, so that it has the same result.
+///
+/// Those versions of Excel implicitly intersect a range with the row or column
+/// of the formula wherever a single value is expected, such as in the result
+/// of the formula or in an argument that a function or operator is mapped
+/// over, such as the argument to `ABS()`. Other arguments are not changed,
+/// since functions such as `SUM()` take whole ranges. Formulas that cannot be
+/// parsed are returned unchanged.
+///
+/// # Example
+/// ```rust
+/// use quadratic_core::{formulas::add_implicit_intersection_operators, Pos};
+///
+/// let pos = Pos { x: 1, y: 5 };
+/// let replaced = add_implicit_intersection_operators("A:A * 2 + SUM(C1:C10)", pos);
+/// assert_eq!(replaced, "@A:A * 2 + SUM(C1:C10)");
+/// ```
+pub fn add_implicit_intersection_operators(source: &str, pos: Pos) -> String {
+    let Ok(formula) = parse_formula(source, pos) else {
+        return source.to_string();
+    };
+    let mut spans = vec![];
+    find_implicit_intersections(&formula.ast, true, &mut spans);
+
+    // insert in reverse order to preserve previous span references
+    let mut replaced = source.to_string();
+    spans.sort_by_key(|(span, _)| span.start);
+    for (span, needs_parens) in spans.into_iter().rev() {
+        if needs_parens {
+            replaced.insert(span.end as usize, ')');
+            replaced.insert_str(span.start as usize, "@(");
+        } else {
+            replaced.insert(span.start as usize, '@');
+        }
+    }
+    replaced
+}
+
+/// Collects the spans of ranges in `node` that are implicitly intersected,
+/// along with whether each one needs parentheses after adding `@`. `scalar` is
+/// whether a single value is expected for `node`.
+fn find_implicit_intersections(node: &ast::AstNode, scalar: bool, spans: &mut Vec<(Span, bool)>) {
+    if scalar && is_range_syntax(node) {
+        let needs_parens = matches!(
+            &node.inner,
+            ast::AstNodeContents::FunctionCall { func, .. } if ast::is_area_op(&func.inner),
+        );
+        spans.push((node.span, needs_parens));
+        return;
+    }
+    match &node.inner {
+        ast::AstNodeContents::Paren(contents) => {
+            find_implicit_intersections(contents, scalar, spans);
+        }
+        ast::AstNodeContents::FunctionCall { func, args }
+            if func.inner != ast::IMPLICIT_INTERSECTION_OP =>
+        {
+            // Arguments that the function is mapped over expect single values
+            // if its result is a single value, but other arguments may take
+            // ranges.
+            let function = functions::lookup_function(&func.inner);
+            for (i, arg) in args.iter().enumerate() {
+                let is_scalar_arg = function.is_some_and(|f| f.is_scalar_arg(i));
+                find_implicit_intersections(arg, scalar && is_scalar_arg, spans);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Returns whether an expression is syntactically a reference to a range that
+/// may contain more than one cell.
+fn is_range_syntax(node: &ast::AstNode) -> bool {
+    match &node.inner {
+        ast::AstNodeContents::RangeRef(RangeRef::RowRange { .. } | RangeRef::ColRange { .. }) => {
+            true
+        }
+        ast::AstNodeContents::FunctionCall { func, .. } => {
+            func.inner == ":" || ast::is_area_op(&func.inner)
+        }
+        ast::AstNodeContents::Paren(contents) => is_range_syntax(contents),
+        _ => false,
+    }
+}
+
+/// Returns whether a cell reference is written in internal notation, such as
+/// `R[0]C[-1]`, rather than A1 notation.
+fn is_internal_notation(reference: &str) -> bool {
+    lexer::tokenize(reference).any(|token| token.inner == Token::InternalCellRef)
+}
+
 fn replace_cell_references(
     source: &str,
     pos: Pos,
-    replace_fn: &dyn Fn(RangeRef) -> String,
+    replace_fn: &dyn Fn(RangeRef, &str) -> Option<String>,
 ) -> String {
     let spans = find_cell_references(source, pos);
     let mut replaced = source.to_string();
@@ -107,8 +523,9 @@ fn replace_cell_references(
         .rev()
         .for_each(|spanned: Spanned<RangeRef>| {
             let Spanned { span, inner } = spanned;
-            let cell = replace_fn(inner);
-            replaced.replace_range::<Range<usize>>(span.into(), &cell);
+            if let Some(cell) = replace_fn(inner, &source[Range::<usize>::from(span)]) {
+                replaced.replace_range::<Range<usize>>(span.into(), &cell);
+            }
         });
 
     replaced
@@ -310,6 +727,170 @@ mod tests {
         assert_eq!(replaced, expected);
     }
 
+    #[test]
+    fn test_replace_sheet_name() {
+        let src = r#"Data!A1 + 'Data'!B2 + "Data" ! C3 + "Data" + Other!D4 + data!E5"#;
+        let expected = r#"Sales!A1 + "Sales"!B2 + "Sales" ! C3 + "Data" + Other!D4 + data!E5"#;
+        assert_eq!(replace_sheet_name(src, "Data", "Sales"), expected);
+
+        let src = r#"SUM("Data"!R[0]C[1]:R[2]C[1])"#;
+        let expected = r#"SUM("My \"Data\""!R[0]C[1]:R[2]C[1])"#;
+        assert_eq!(replace_sheet_name(src, "Data", "My \"Data\""), expected);
+
+        let src = r#"SUM(Data:Dec!C1) + SUM(Jan:Data!C1) + SUM("Data":"Dec"!C1)"#;
+        let expected = r#"SUM(Sales:Dec!C1) + SUM(Jan:Sales!C1) + SUM("Sales":"Dec"!C1)"#;
+        assert_eq!(replace_sheet_name(src, "Data", "Sales"), expected);
+
+        let src = "SUM(Data:Dec!R[0]C[1])";
+        let expected = r#"SUM("Sales 2024":"Dec"!R[0]C[1])"#;
+        assert_eq!(replace_sheet_name(src, "Data", "Sales 2024"), expected);
+
+        // a sheet named like a cell must be quoted
+        let src = "SUM(Data:Dec!C1)";
+        let expected = r#"SUM("A1":"Dec"!C1)"#;
+        assert_eq!(replace_sheet_name(src, "Data", "A1"), expected);
+    }
+
+    #[test]
+    fn test_sheet_range_notation() {
+        let pos = Pos { x: 0, y: 0 };
+        let internal = replace_a1_notation("SUM(Jan:Dec!C10) + SUM('Jan':'Dec'!A:B)", pos);
+        assert_eq!(
+            internal,
+            r#"SUM("Jan":"Dec"!R[10]C[2]) + SUM("Jan":"Dec"!C[0]:C[1])"#
+        );
+        assert_eq!(
+            replace_internal_cell_references(&internal, pos),
+            r#"SUM("Jan":"Dec"!C10) + SUM("Jan":"Dec"!A:B)"#
+        );
+    }
+
+    #[test]
+    fn test_add_implicit_intersection_operators() {
+        let pos = Pos { x: 2, y: 5 };
+        for (src, expected) in [
+            ("A:A*2", "@A:A*2"),
+            ("A1:A10", "@A1:A10"),
+            ("SUM(A1:A10) + B1:B10", "SUM(A1:A10) + @B1:B10"),
+            ("(A1:A10)*2", "@(A1:A10)*2"),
+            ("A1:C3 B2:D4", "@(A1:C3 B2:D4)"),
+            ("(A1:A3, C1:C3)", "@(A1:A3, C1:C3)"),
+            ("3:3 = 1", "@3:3 = 1"),
+            ("@A:A", "@A:A"),
+            ("A1 + 1", "A1 + 1"),
+            (
+                "SUMPRODUCT((A1:A5 > 0) * B1:B5)",
+                "SUMPRODUCT((A1:A5 > 0) * B1:B5)",
+            ),
+            ("SUM(A1:A5", "SUM(A1:A5"),
+            ("ABS(A:A)", "ABS(@A:A)"),
+            ("ROUND(A1:A10,0)", "ROUND(@A1:A10,0)"),
+            ("IF(A:A>0,1,0)", "IF(@A:A>0,1,0)"),
+            ("IF(A1>0,SUM(B:B),0)", "IF(A1>0,SUM(B:B),0)"),
+            ("CONCAT(A1:A3)", "CONCAT(A1:A3)"),
+        ] {
+            assert_eq!(expected, add_implicit_intersection_operators(src, pos));
+        }
+    }
+
+    #[test]
+    fn test_spill_range_notation() {
+        let pos = Pos { x: 0, y: 0 };
+        let internal = replace_a1_notation("SUM(B2#) + COUNT('Data'!$C$3#)", pos);
+        assert_eq!(internal, r#"SUM(R[2]C[1]#) + COUNT("Data"!R{3}C{2}#)"#);
+        assert_eq!(
+            replace_internal_cell_references(&internal, pos),
+            r#"SUM(B2#) + COUNT("Data"!$C$3#)"#
+        );
+        assert_eq!(
+            replace_sheet_references_with_ref_error("SUM(Data!B2#)", pos, "Data", &["Data"]),
+            "SUM(#REF!)"
+        );
+    }
+
+    #[test]
+    fn test_replace_sheet_references_with_ref_error() {
+        let pos = Pos { x: 0, y: 0 };
+        let sheet_names = ["Jan", "Feb", "Data", "Nov", "Dec"];
+        let replace =
+            |src: &str| replace_sheet_references_with_ref_error(src, pos, "Data", &sheet_names);
+
+        let src = r#"SUM(Data!A1:B5) + 'Data'!C:C + A1:Data!B2 + Other!A1 + "Data""#;
+        let expected = r#"SUM(#REF!) + #REF! + #REF! + Other!A1 + "Data""#;
+        assert_eq!(replace(src), expected);
+
+        // deleting the first or last sheet of a three-dimensional reference
+        // moves that end to the neighboring sheet in the span
+        let src = r#"SUM(Jan:Data!C1) + SUM("Data":"Dec"!C:C) + SUM(Jan:Dec!C1)"#;
+        let expected = r#"SUM("Jan":"Feb"!C1) + SUM("Nov":"Dec"!C:C) + SUM(Jan:Dec!C1)"#;
+        assert_eq!(replace(src), expected);
+        assert_eq!(replace("SUM(Dec:Data!C1)"), r#"SUM("Dec":"Nov"!C1)"#);
+        assert_eq!(replace("SUM(Data:Nov!$C$1)"), r#"SUM("Nov"!$C$1)"#);
+        assert_eq!(replace("SUM(Data:Data!C1)"), "SUM(#REF!)");
+        assert_eq!(replace("SUM(Data:Missing!C1)"), "SUM(#REF!)");
+    }
+
+    #[test]
+    fn test_replace_moved_cell_references() {
+        let pos = Pos { x: 0, y: 0 };
+        // B1:C2 is moved down by four rows to B5:C6
+        let from = Rect::new(1, 1, 2, 2);
+        let to = Pos { x: 1, y: 5 };
+        let is_from_sheet =
+            |sheet: &Option<String>| sheet.is_none() || *sheet == Some("Data".into());
+        let replace = |src: &str, to_sheet: Option<&str>| {
+            replace_moved_cell_references(src, pos, &is_from_sheet, from, to, to_sheet)
+        };
+
+        assert_eq!(
+            replace("B1 + $C$2 + SUM(B1:C2) + Data!B2", None),
+            r#"B5 + $C$6 + SUM(B5:C6) + "Data"!B6"#
+        );
+        assert_eq!(replace("R[1]C[1] + R{2}C{2}", None), "R[5]C[1] + R{6}C{2}");
+
+        // cells that were not moved and partially moved ranges are unchanged
+        let src = "SUM(A1:C2) + D1 + Other!B1";
+        assert_eq!(replace(src, None), src);
+
+        // cells moved to another sheet
+        assert_eq!(
+            replace("B1 + SUM(B1:C2)", Some("Other")),
+            r#""Other"!B5 + SUM("Other"!B5:C6)"#
+        );
+    }
+
+    #[test]
+    fn test_replace_moved_formula_cell_references() {
+        // C1 is moved down by four rows to C5, together with B1:C2
+        let pos = Pos { x: 2, y: 1 };
+        let from = Rect::new(1, 1, 2, 2);
+        let to = Pos { x: 1, y: 5 };
+        let is_from_sheet =
+            |sheet: &Option<String>| sheet.is_none() || *sheet == Some("Data".into());
+        let replace = |src: &str, to_sheet: Option<&str>| {
+            replace_moved_formula_cell_references(src, pos, &is_from_sheet, from, to, to_sheet)
+        };
+
+        assert_eq!(
+            replace("$B$1 + B2 + SUM($B$1:$C$2) + Data!$B$2", None),
+            r#"$B$5 + B6 + SUM($B$5:$C$6) + "Data"!$B$6"#
+        );
+        assert_eq!(
+            replace("R{1}C{1} + R[1]C[-1]", None),
+            "R{5}C{1} + R[1]C[-1]"
+        );
+
+        // cells that were not moved are unchanged
+        let src = "$A$1 + $D$1 + Other!$B$1";
+        assert_eq!(replace(src, None), src);
+
+        // cells moved to another sheet
+        assert_eq!(
+            replace("$B$1 + Data!B2", Some("Other")),
+            r#"$B$5 + "Other"!B6"#
+        );
+    }
+
     #[test]
     fn check_formula() {
         assert!(parse_and_check_formula("SUM(10)", 0, 0));
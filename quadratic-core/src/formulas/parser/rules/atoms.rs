@@ -1,3 +1,5 @@
+use bigdecimal::BigDecimal;
+
 use super::*;
 use std::str::FromStr;
 
@@ -33,7 +35,8 @@ impl SyntaxRule for NumericLiteral {
     fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
         match p.next() {
             Some(Token::NumericLiteral) => {
-                let Ok(n) = p.token_str().parse::<f64>() else {
+                // Parse as a decimal so that literals such as `0.1` are exact.
+                let Ok(n) = p.token_str().parse::<BigDecimal>() else {
                     return Err(RunErrorMsg::BadNumber.with_span(p.span()));
                 };
                 Ok(AstNode {
@@ -81,6 +84,87 @@ impl SyntaxRule for SheetRefPrefix {
     }
 }
 
+/// Matches a three-dimensional sheet reference prefix, which refers to every
+/// sheet from the first sheet to the last sheet, and returns the names of the
+/// first and last sheets.
+#[derive(Debug, Copy, Clone)]
+pub struct SheetRangeRefPrefix;
+impl_display!(for SheetRangeRefPrefix, "sheet range reference, such as 'Jan:Dec!'");
+impl SyntaxRule for SheetRangeRefPrefix {
+    type Output = (String, String);
+
+    fn prefix_matches(&self, mut p: Parser<'_>) -> bool {
+        match p.next() {
+            Some(Token::UnquotedSheetRangeReference) => true,
+            Some(Token::StringLiteral) => {
+                p.next() == Some(Token::CellRangeOp)
+                    && p.next() == Some(Token::StringLiteral)
+                    && p.next() == Some(Token::SheetRefOp)
+            }
+            _ => false,
+        }
+    }
+
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        match p.peek_next() {
+            Some(Token::StringLiteral) => {
+                let first = p.parse(StringLiteral)?;
+                p.parse(Token::CellRangeOp)?;
+                let last = p.parse(StringLiteral)?;
+                p.parse(Token::SheetRefOp)?;
+                Ok((first, last))
+            }
+            Some(Token::UnquotedSheetRangeReference) => {
+                p.next();
+                let names = p
+                    .token_str()
+                    .strip_suffix('!')
+                    .and_then(|names| names.split_once(':'))
+                    .ok_or_else(|| {
+                        RunErrorMsg::InternalError(
+                            "expected ':' and '!' in unquoted sheet range reference".into(),
+                        )
+                    })?;
+                Ok((names.0.trim().to_string(), names.1.trim().to_string()))
+            }
+            _ => p.expected(self),
+        }
+    }
+}
+
+/// Matches a three-dimensional reference to the same cell, cell range, or
+/// whole-column or whole-row range on several sheets, such as `Jan:Dec!C10`.
+#[derive(Debug, Copy, Clone)]
+pub struct SheetRangeReference;
+impl_display!(for SheetRangeReference, "sheet range reference, such as 'Jan:Dec!C10'");
+impl SyntaxRule for SheetRangeReference {
+    type Output = Spanned<RangeRef>;
+
+    fn prefix_matches(&self, p: Parser<'_>) -> bool {
+        SheetRangeRefPrefix.prefix_matches(p)
+    }
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        let start_span = p.peek_next_span();
+
+        let (first_sheet, last_sheet) = p.parse(SheetRangeRefPrefix)?;
+        let range = p.parse(CellRangeReference)?;
+
+        // The range must not have its own sheet.
+        if range.inner.clone().with_sheet(None) != range.inner {
+            return Err(RunErrorMsg::BadCellReference.with_span(range.span));
+        }
+
+        Ok(Spanned {
+            span: Span::merge(start_span, range.span),
+            inner: RangeRef::SheetRange {
+                first_sheet,
+                last_sheet,
+                range: Box::new(range.inner),
+            },
+        })
+    }
+}
+
 /// Matches a single cell reference.
 #[derive(Debug, Copy, Clone)]
 pub struct CellReference;
@@ -118,6 +202,44 @@ impl SyntaxRule for CellReference {
     }
 }
 
+/// Matches a whole-column or whole-row range reference.
+#[derive(Debug, Copy, Clone)]
+pub struct RowOrColRangeReference;
+impl_display!(for RowOrColRangeReference, "column or row range reference, such as 'A:C' or '3:5'");
+impl SyntaxRule for RowOrColRangeReference {
+    type Output = Spanned<RangeRef>;
+
+    fn prefix_matches(&self, mut p: Parser<'_>) -> bool {
+        // Skip the sheet reference, if there is one.
+        if let Some(Err(_)) = p.try_parse(SheetRefPrefix) {
+            return false;
+        }
+        matches!(p.next(), Some(Token::ColRangeRef | Token::RowRangeRef))
+    }
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        let start_span = p.peek_next_span();
+
+        let sheet_name = p.try_parse(SheetRefPrefix).transpose()?;
+
+        p.next();
+
+        let range_ref = RangeRef::parse_a1_row_or_col_range(p.token_str(), p.pos)
+            .or_else(|| RangeRef::parse_internal_row_or_col_range(p.token_str()));
+
+        let Some(mut range_ref) = range_ref else {
+            return Err(RunErrorMsg::BadCellReference.with_span(p.span()));
+        };
+        if let RangeRef::RowRange { sheet, .. } | RangeRef::ColRange { sheet, .. } = &mut range_ref
+        {
+            *sheet = sheet_name;
+        }
+        Ok(Spanned {
+            span: Span::merge(start_span, p.span()),
+            inner: range_ref,
+        })
+    }
+}
+
 /// Matches a single cell reference or a cell range reference on its own, not as
 /// part of an expression.
 #[derive(Debug, Copy, Clone)]
@@ -127,11 +249,29 @@ impl SyntaxRule for CellRangeReference {
     type Output = Spanned<RangeRef>;
 
     fn prefix_matches(&self, p: Parser<'_>) -> bool {
-        CellReference.prefix_matches(p)
+        SheetRangeReference.prefix_matches(p)
+            || RowOrColRangeReference.prefix_matches(p)
+            || CellReference.prefix_matches(p)
     }
     fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        if let Some(range_ref) = p.try_parse(SheetRangeReference) {
+            return range_ref;
+        }
+        if let Some(range_ref) = p.try_parse(RowOrColRangeReference) {
+            return range_ref;
+        }
+
         let pos1 = p.parse(CellReference)?;
 
+        // Check for a spill range reference.
+        if p.try_parse(Token::SpillOp).is_some() {
+            let span = Span::merge(pos1.span, p.span());
+            return Ok(Spanned {
+                span,
+                inner: RangeRef::Spill { pos: pos1.inner },
+            });
+        }
+
         // Check for a range reference.
         if p.try_parse(Token::CellRangeOp).is_some() {
             let pos2 = p.parse(CellReference)?;
@@ -166,3 +306,25 @@ impl SyntaxRule for BoolExpression {
         })
     }
 }
+
+/// Matches an error literal, such as `#N/A` or `#DIV/0!`.
+#[derive(Debug, Copy, Clone)]
+pub struct ErrorLiteral;
+impl_display!(for ErrorLiteral, "error literal, such as '#N/A' or '#DIV/0!'");
+impl SyntaxRule for ErrorLiteral {
+    type Output = AstNode;
+
+    fn prefix_matches(&self, mut p: Parser<'_>) -> bool {
+        p.next() == Some(Token::ErrorLiteral)
+    }
+
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        p.parse(Token::ErrorLiteral)?;
+        let msg = RunErrorMsg::from_excel_code(p.token_str())
+            .ok_or_else(|| internal_error_value!("error in error literal parsing"))?;
+        Ok(AstNode {
+            span: p.span(),
+            inner: ast::AstNodeContents::Error(msg),
+        })
+    }
+}
@@ -9,6 +9,8 @@ pub enum OpPrecedence {
     MulDiv,
     Pow,
     Range,
+    Intersection,
+    ImplicitIntersection,
     CellRange,
     Prefix,
     Suffix,
@@ -33,7 +35,9 @@ impl OpPrecedence {
             Self::AddSub => Self::MulDiv,
             Self::MulDiv => Self::Pow,
             Self::Pow => Self::Range,
-            Self::Range => Self::CellRange,
+            Self::Range => Self::Intersection,
+            Self::Intersection => Self::ImplicitIntersection,
+            Self::ImplicitIntersection => Self::CellRange,
             Self::CellRange => Self::Prefix,
             Self::Prefix => Self::Suffix,
             Self::Suffix => Self::Atom,
@@ -51,6 +55,8 @@ impl OpPrecedence {
             Self::MulDiv => &[Mult, Div],
             Self::Pow => &[Power],
             Self::Range => &[RangeOp],
+            Self::Intersection => &[], // whitespace; see `parse_intersection_ops()`
+            Self::ImplicitIntersection => &[],
             Self::CellRange => &[CellRangeOp],
             Self::Prefix => &[],
             Self::Suffix => &[],
@@ -62,6 +68,9 @@ impl OpPrecedence {
     pub fn prefix_ops(self) -> &'static [Token] {
         use Token::*;
         match self {
+            // `@` has lower precedence than `:` so that `@A1:A10` applies to
+            // the whole range.
+            Self::ImplicitIntersection => &[ImplicitIntersectionOp],
             Self::Prefix => &[Plus, Minus],
             _ => &[],
         }
@@ -124,7 +133,7 @@ impl SyntaxRule for ExpressionWithPrecedence {
 
                 Token::Eql | Token::Neq | Token::Lt | Token::Gt | Token::Lte | Token::Gte => false,
 
-                Token::Plus | Token::Minus => true,
+                Token::Plus | Token::Minus | Token::ImplicitIntersectionOp => true,
 
                 Token::Mult
                 | Token::Div
@@ -134,19 +143,26 @@ impl SyntaxRule for ExpressionWithPrecedence {
                 | Token::Percent
                 | Token::CellRangeOp
                 | Token::SheetRefOp
+                | Token::SpillOp
                 | Token::Ellipsis => false,
 
                 Token::False | Token::True => true,
 
+                Token::ErrorLiteral => true,
+
                 Token::Comment | Token::UnterminatedBlockComment => false,
 
                 Token::FunctionCall
                 | Token::UnquotedSheetReference
+                | Token::UnquotedSheetRangeReference
                 | Token::StringLiteral
                 | Token::UnterminatedStringLiteral
                 | Token::NumericLiteral
                 | Token::CellRef
-                | Token::InternalCellRef => true,
+                | Token::InternalCellRef
+                | Token::ColRangeRef
+                | Token::RowRangeRef
+                | Token::Name => true,
 
                 Token::Whitespace => false,
                 Token::Unknown => false,
@@ -163,11 +179,15 @@ impl SyntaxRule for ExpressionWithPrecedence {
                 p,
                 [
                     FunctionCall.map(Some),
+                    SheetRangeExpression.map(Some),
+                    RowOrColRangeExpression.map(Some),
                     CellReferenceExpression.map(Some),
+                    NameExpression.map(Some),
                     StringLiteralExpression.map(Some),
                     NumericLiteral.map(Some),
                     ArrayLiteral.map(Some),
                     BoolExpression.map(Some),
+                    ErrorLiteral.map(Some),
                     ParenExpression.map(Some),
                     EmptyExpression.map(Some),
                 ],
@@ -175,6 +195,7 @@ impl SyntaxRule for ExpressionWithPrecedence {
             .transpose()
             .unwrap_or_else(|| p.expected(self)),
 
+            OpPrecedence::Intersection => parse_intersection_ops(p),
             prec if !prec.binary_ops().is_empty() => parse_binary_ops_expr(p, prec),
             prec if !prec.prefix_ops().is_empty() => parse_prefix_ops(p, prec),
             prec if !prec.suffix_ops().is_empty() => parse_suffix_ops(p, prec),
@@ -244,6 +265,47 @@ fn parse_binary_ops_expr(p: &mut Parser<'_>, precedence: OpPrecedence) -> CodeRe
     Ok(ret)
 }
 
+/// Parses an expression with any number of intersection operators, which are
+/// written as whitespace between two references, such as `A1:C3 B2:D4`.
+fn parse_intersection_ops(p: &mut Parser<'_>) -> CodeResult<ast::AstNode> {
+    let recursive_expression = ExpressionWithPrecedence(OpPrecedence::Intersection.next());
+
+    let mut ret = p.parse(recursive_expression)?;
+    while is_intersection_op(*p) {
+        p.next_noskip();
+        let op = Spanned {
+            span: p.span(),
+            inner: ast::INTERSECTION_OP.to_string(),
+        };
+        let rhs = p.parse(recursive_expression)?;
+        ret = AstNode {
+            span: Span::merge(ret.span, rhs.span),
+            inner: ast::AstNodeContents::FunctionCall {
+                func: op,
+                args: vec![ret, rhs],
+            },
+        };
+    }
+    Ok(ret)
+}
+
+/// Returns whether the token after the cursor is whitespace that is an
+/// intersection operator, which is only the case if it is followed by
+/// something that may be a reference.
+fn is_intersection_op(mut p: Parser<'_>) -> bool {
+    p.next_noskip() == Some(Token::Whitespace)
+        && (CellRangeReference.prefix_matches(p)
+            || matches!(
+                p.peek_next(),
+                Some(
+                    Token::LParen
+                        | Token::FunctionCall
+                        | Token::Name
+                        | Token::ImplicitIntersectionOp
+                ),
+            ))
+}
+
 /// Parses an expression with any number of prefix operators.
 fn parse_prefix_ops(p: &mut Parser<'_>, precedence: OpPrecedence) -> CodeResult<ast::AstNode> {
     let allowed_ops = precedence.prefix_ops();
@@ -251,6 +313,7 @@ fn parse_prefix_ops(p: &mut Parser<'_>, precedence: OpPrecedence) -> CodeResult<
     // Build a list of operators in the order that they appear in the source
     // code.
     let mut ops: Vec<Spanned<String>> = vec![];
+    let mut operand_precedence = precedence.next();
     while let Some(tok) = p.peek_next() {
         if allowed_ops.contains(&tok) {
             p.next();
@@ -259,11 +322,16 @@ fn parse_prefix_ops(p: &mut Parser<'_>, precedence: OpPrecedence) -> CodeResult<
                 inner: p.token_str().to_string(),
             });
             continue;
+        } else if precedence == OpPrecedence::Prefix && tok == Token::ImplicitIntersectionOp {
+            // `@` may follow another prefix operator, such as in `-@A1:A10`,
+            // and still applies to the whole range.
+            operand_precedence = OpPrecedence::ImplicitIntersection;
+            break;
         } else {
             break;
         }
     }
-    let mut ret = p.parse(ExpressionWithPrecedence(precedence.next()))?;
+    let mut ret = p.parse(ExpressionWithPrecedence(operand_precedence))?;
     // Now pop the operators off the list from right to left.
     for op in ops {
         ret = AstNode {
@@ -344,7 +412,7 @@ impl SyntaxRule for FunctionCall {
     }
 }
 
-/// Matches a single cell reference.
+/// Matches a single cell reference or a spill range reference.
 #[derive(Debug, Copy, Clone)]
 pub struct CellReferenceExpression;
 impl_display!(for CellReferenceExpression, "cell reference, such as 'A6' or '$ZB$3'");
@@ -355,11 +423,74 @@ impl SyntaxRule for CellReferenceExpression {
         CellReference.prefix_matches(p)
     }
     fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
-        Ok(p.parse(CellReference)?.map(ast::AstNodeContents::CellRef))
+        let cell_ref = p.parse(CellReference)?;
+        if p.try_parse(Token::SpillOp).is_some() {
+            let span = Span::merge(cell_ref.span, p.span());
+            let range_ref = RangeRef::Spill {
+                pos: cell_ref.inner,
+            };
+            return Ok(AstNode {
+                span,
+                inner: ast::AstNodeContents::RangeRef(range_ref),
+            });
+        }
+        Ok(cell_ref.map(ast::AstNodeContents::CellRef))
+    }
+}
+
+/// Matches a whole-column or whole-row range reference.
+#[derive(Debug, Copy, Clone)]
+pub struct RowOrColRangeExpression;
+impl_display!(for RowOrColRangeExpression, "column or row range reference, such as 'A:C' or '3:5'");
+impl SyntaxRule for RowOrColRangeExpression {
+    type Output = AstNode;
+
+    fn prefix_matches(&self, p: Parser<'_>) -> bool {
+        RowOrColRangeReference.prefix_matches(p)
+    }
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        Ok(p.parse(RowOrColRangeReference)?
+            .map(ast::AstNodeContents::RangeRef))
+    }
+}
+
+/// Matches a three-dimensional reference, such as `Jan:Dec!C10`.
+#[derive(Debug, Copy, Clone)]
+pub struct SheetRangeExpression;
+impl_display!(for SheetRangeExpression, "sheet range reference, such as 'Jan:Dec!C10'");
+impl SyntaxRule for SheetRangeExpression {
+    type Output = AstNode;
+
+    fn prefix_matches(&self, p: Parser<'_>) -> bool {
+        SheetRangeReference.prefix_matches(p)
+    }
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        Ok(p.parse(SheetRangeReference)?
+            .map(ast::AstNodeContents::RangeRef))
+    }
+}
+
+/// Matches a name, such as a variable defined using `LET()`.
+#[derive(Debug, Copy, Clone)]
+pub struct NameExpression;
+impl_display!(for NameExpression, "name");
+impl SyntaxRule for NameExpression {
+    type Output = AstNode;
+
+    fn prefix_matches(&self, mut p: Parser<'_>) -> bool {
+        p.next() == Some(Token::Name)
+    }
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        p.parse(Token::Name)?;
+        Ok(AstNode {
+            span: p.span(),
+            inner: ast::AstNodeContents::Name(p.token_str().to_string()),
+        })
     }
 }
 
-/// Matches a pair of parentheses containing an expression.
+/// Matches a pair of parentheses containing an expression, or a union of
+/// references separated by commas, such as `(A1:A3, C1:C3)`.
 #[derive(Debug, Copy, Clone)]
 pub struct ParenExpression;
 impl_display!(for ParenExpression, "{}", Surround::paren(Expression));
@@ -370,9 +501,34 @@ impl SyntaxRule for ParenExpression {
         Surround::paren(Expression).prefix_matches(p)
     }
     fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
-        p.parse(Surround::paren(
-            Expression.map(|expr| ast::AstNodeContents::Paren(Box::new(expr))),
-        ))
+        let start_span = p.peek_next_span();
+
+        p.parse(Token::LParen)?;
+        let mut exprs = vec![p.parse(Expression)?];
+        // A comma-separated list of references in parentheses is a union.
+        while p.try_parse(Token::ArgSep).is_some() {
+            exprs.push(p.parse(Expression)?);
+        }
+        p.parse(Token::RParen)?;
+
+        let span = Span::merge(start_span, p.span());
+        let contents = match exprs.len() {
+            1 => exprs.remove(0),
+            _ => AstNode {
+                span: Span::merge(exprs[0].span, exprs[exprs.len() - 1].span),
+                inner: ast::AstNodeContents::FunctionCall {
+                    func: Spanned {
+                        span,
+                        inner: ast::UNION_OP.to_string(),
+                    },
+                    args: exprs,
+                },
+            },
+        };
+        Ok(AstNode {
+            span,
+            inner: ast::AstNodeContents::Paren(Box::new(contents)),
+        })
     }
 }
 
@@ -0,0 +1,348 @@
+//! Functions that take cell references, rather than the values in those cells,
+//! as arguments (such as `ROW()` and `OFFSET()`).
+//!
+//! Like the functions in [`super::lambda`], these are evaluated as special
+//! forms by [`AstNode::eval()`]. Their documentation is in
+//! `functions/lookup.rs` and `functions/information.rs`.
+
+use super::ast::AstNodeContents;
+use super::functions::normalize_function_name;
+use super::*;
+use crate::{
+    Array, ArraySize, CellValue, CodeResult, CoerceInto, Pos, Rect, RunErrorMsg, Span, Spanned,
+    Value,
+};
+
+/// Names of functions that take cell references as arguments.
+const REFERENCE_FUNCTIONS: &[&str] = &[
+    "ROW",
+    "COLUMN",
+    "AREAS",
+    "OFFSET",
+    "INDEX",
+    "ISFORMULA",
+    "ISREF",
+];
+
+/// Returns whether a call to a function named `func_name` must be evaluated
+/// using [`eval_reference_function()`].
+pub(super) fn is_reference_function(func_name: &str) -> bool {
+    REFERENCE_FUNCTIONS.contains(&normalize_function_name(func_name).as_str())
+}
+
+/// Evaluates a call to a function that takes cell references as arguments.
+pub(super) fn eval_reference_function(
+    ctx: &mut Ctx<'_>,
+    only_parse: bool,
+    span: Span,
+    func: &Spanned<String>,
+    args: &[AstNode],
+) -> CodeResult<Value> {
+    match normalize_function_name(&func.inner).as_str() {
+        "ROW" => {
+            let ([], [reference]) = split_args("ROW", span, args, [], ["reference"])?;
+            let (y, height) = match reference {
+                Some(reference) => {
+                    let (_, rect) = reference.to_range(ctx)?;
+                    (rect.min.y, rect.height())
+                }
+                None => (ctx.sheet_pos.y, 1),
+            };
+            sequence(span, 1, height, y)
+        }
+
+        "COLUMN" => {
+            let ([], [reference]) = split_args("COLUMN", span, args, [], ["reference"])?;
+            // Column A has an X coordinate of 0.
+            let (x, width) = match reference {
+                Some(reference) => {
+                    let (_, rect) = reference.to_range(ctx)?;
+                    (rect.min.x, rect.width())
+                }
+                None => (ctx.sheet_pos.x, 1),
+            };
+            sequence(span, width, 1, x + 1)
+        }
+
+        "AREAS" => {
+            let ([reference], []) = split_args("AREAS", span, args, ["reference"], [])?;
+            let areas = reference.to_ranges(ctx)?;
+            Ok(Value::from(areas.len() as i64))
+        }
+
+        "OFFSET" => {
+            let ([reference, rows, columns], [height, width]) = split_args(
+                "OFFSET",
+                span,
+                args,
+                ["reference", "rows", "columns"],
+                ["height", "width"],
+            )?;
+            let (sheet_name, rect) = reference.to_range(ctx)?;
+            let rows = eval_i64(ctx, only_parse, rows)?.inner;
+            let columns = eval_i64(ctx, only_parse, columns)?.inner;
+            let height = eval_len(ctx, only_parse, height, rect.height())?;
+            let width = eval_len(ctx, only_parse, width, rect.width())?;
+
+            let x = rect.min.x.saturating_add(columns);
+            let y = rect.min.y.saturating_add(rows);
+            let rect = Rect::from_numbers(x, y, width, height);
+            Ok(ctx.get_cell_array(sheet_name, rect, span)?.inner.into())
+        }
+
+        "INDEX" => {
+            let ([array], [row, column, area_num]) = split_args(
+                "INDEX",
+                span,
+                args,
+                ["array"],
+                ["row", "column", "area_num"],
+            )?;
+            let row = row.map(|row| eval_i64(ctx, only_parse, row)).transpose()?;
+            let column = column
+                .map(|column| eval_i64(ctx, only_parse, column))
+                .transpose()?;
+            let area_num = area_num
+                .map(|area_num| eval_i64(ctx, only_parse, area_num))
+                .transpose()?;
+
+            if is_reference(ctx, array) {
+                // Return a reference, and only access the cells in it.
+                let mut areas = array.to_ranges(ctx)?;
+                let (sheet_name, rect) = areas.swap_remove(area_index(area_num, areas.len())?);
+                let region = index_region(rect.size(), row, column)?;
+                let rect = Rect::new(
+                    rect.min.x + region.min.x,
+                    rect.min.y + region.min.y,
+                    rect.min.x + region.max.x,
+                    rect.min.y + region.max.y,
+                );
+                Ok(ctx.get_cell_array(sheet_name, rect, span)?.inner.into())
+            } else {
+                area_index(area_num, 1)?;
+                let array = Array::from(array.eval(ctx, only_parse)?.inner);
+                let region = index_region(array.size(), row, column)?;
+                let size = region.size();
+                let values = region
+                    .y_range()
+                    .flat_map(|y| region.x_range().map(move |x| (x as u32, y as u32)))
+                    .map(|(x, y)| array.get(x, y).cloned())
+                    .collect::<Result<_, _>>()?;
+                let result = Array::new_row_major(size, values)?;
+                Ok(match result.into_cell_value() {
+                    Ok(value) => Value::Single(value),
+                    Err(result) => Value::Array(result),
+                })
+            }
+        }
+
+        "ISFORMULA" => {
+            let ([reference], []) = split_args("ISFORMULA", span, args, ["reference"], [])?;
+            // Only the top left cell of a range is checked.
+            let (sheet_name, rect) = reference.to_range(ctx)?;
+            let cell_ref = CellRef::absolute(sheet_name, rect.min);
+            Ok(Value::from(ctx.is_formula_cell(&cell_ref, reference.span)?))
+        }
+
+        "ISREF" => {
+            let ([value], []) = split_args("ISREF", span, args, ["value"], [])?;
+            Ok(Value::from(is_reference(ctx, value)))
+        }
+
+        _ => internal_error!("unknown reference function {:?}", func.inner),
+    }
+}
+
+/// Returns the cell in `reference` that is in the same row or column as the
+/// formula, which is the result of the implicit intersection operator `@`.
+///
+/// Along each axis, a range that is a single cell wide is used as-is, and a
+/// wider range is intersected with the row or column of the formula. If that
+/// intersection is empty, then an error is returned.
+pub(super) fn implicit_intersection(
+    ctx: &mut Ctx<'_>,
+    reference: &AstNode,
+    span: Span,
+) -> CodeResult<(Option<String>, Rect)> {
+    let (sheet_name, rect) = reference.to_range(ctx)?;
+    let intersect = |min: i64, max: i64, formula_coord: i64| match min == max {
+        true => Some(min),
+        false => (min..=max)
+            .contains(&formula_coord)
+            .then_some(formula_coord),
+    };
+    let x = intersect(rect.min.x, rect.max.x, ctx.sheet_pos.x);
+    let y = intersect(rect.min.y, rect.max.y, ctx.sheet_pos.y);
+    match x.zip(y) {
+        Some((x, y)) => Ok((sheet_name, Rect::single_pos(Pos { x, y }))),
+        None => Err(RunErrorMsg::InvalidArgument.with_span(span)),
+    }
+}
+
+/// Evaluates the implicit intersection operator `@`, which returns a single
+/// value. A reference is intersected using [`implicit_intersection()`], and
+/// the top left value of an array is used.
+pub(super) fn eval_implicit_intersection(
+    ctx: &mut Ctx<'_>,
+    only_parse: bool,
+    span: Span,
+    arg: &AstNode,
+) -> CodeResult<Value> {
+    if is_reference(ctx, arg) {
+        let (sheet_name, rect) = implicit_intersection(ctx, arg, span)?;
+        let cell_ref = CellRef::absolute(sheet_name, rect.min);
+        Ok(ctx.get_cell(&cell_ref, span)?.inner.into())
+    } else {
+        match arg.eval(ctx, only_parse)?.inner {
+            Value::Array(array) => Ok(array.get(0, 0)?.clone().into()),
+            value => Ok(value),
+        }
+    }
+}
+
+/// Returns whether an expression is a cell reference or cell range, or a
+/// defined name that refers to one.
+fn is_reference(ctx: &Ctx<'_>, node: &AstNode) -> bool {
+    match &node.inner {
+        AstNodeContents::Name(name) if ctx.lookup(name).is_none() => matches!(
+            ctx.defined_name_formula(name),
+            Some(Ok(formula)) if is_reference_syntax(&formula.ast),
+        ),
+        _ => is_reference_syntax(node),
+    }
+}
+
+/// Returns whether an expression is syntactically a cell reference, cell
+/// range, or intersection or union of references.
+fn is_reference_syntax(node: &AstNode) -> bool {
+    match &node.inner {
+        AstNodeContents::CellRef(_) | AstNodeContents::RangeRef(_) => true,
+        AstNodeContents::FunctionCall { func, args }
+            if func.inner == ast::IMPLICIT_INTERSECTION_OP =>
+        {
+            args.iter().all(is_reference_syntax)
+        }
+        AstNodeContents::FunctionCall { func, .. } => {
+            func.inner == ":" || ast::is_area_op(&func.inner)
+        }
+        AstNodeContents::Paren(contents) => is_reference_syntax(contents),
+        _ => false,
+    }
+}
+
+/// Returns the `R` required arguments followed by the `O` optional arguments,
+/// or an error if there are too many or too few. Optional arguments that are
+/// omitted or blank are `None`.
+fn split_args<'a, const R: usize, const O: usize>(
+    func_name: &'static str,
+    span: Span,
+    args: &'a [AstNode],
+    required: [&'static str; R],
+    _optional: [&'static str; O],
+) -> CodeResult<([&'a AstNode; R], [Option<&'a AstNode>; O])> {
+    if let Some(extra_arg) = args.get(R + O) {
+        return Err(RunErrorMsg::TooManyArguments {
+            func_name: func_name.into(),
+            max_arg_count: R + O,
+        }
+        .with_span(extra_arg.span));
+    }
+    if let Some(&arg_name) = required.get(args.len()) {
+        return Err(RunErrorMsg::MissingRequiredArgument {
+            func_name: func_name.into(),
+            arg_name: arg_name.into(),
+        }
+        .with_span(span));
+    }
+
+    let required_args = std::array::from_fn(|i| &args[i]);
+    let optional_args = std::array::from_fn(|i| {
+        args.get(R + i)
+            .filter(|arg| !matches!(arg.inner, AstNodeContents::Empty))
+    });
+    Ok((required_args, optional_args))
+}
+
+/// Evaluates an expression and coerces it to an integer.
+fn eval_i64(ctx: &mut Ctx<'_>, only_parse: bool, node: &AstNode) -> CodeResult<Spanned<i64>> {
+    node.eval(ctx, only_parse)?.try_coerce::<i64>()
+}
+
+/// Evaluates an optional expression that must be a positive height or width,
+/// returning `default` if it is omitted.
+fn eval_len(
+    ctx: &mut Ctx<'_>,
+    only_parse: bool,
+    node: Option<&AstNode>,
+    default: u32,
+) -> CodeResult<i64> {
+    let Some(node) = node else {
+        return Ok(default as i64);
+    };
+    let len = eval_i64(ctx, only_parse, node)?;
+    if len.inner < 1 {
+        return Err(RunErrorMsg::InvalidArgument.with_span(len.span));
+    }
+    if len.inner > crate::limits::CELL_RANGE_LIMIT as i64 {
+        return Err(RunErrorMsg::ArrayTooBig.with_span(len.span));
+    }
+    Ok(len.inner)
+}
+
+/// Returns the region of an array with size `size` selected by the `row` and
+/// `column` arguments to `INDEX()`, relative to the top left of the array.
+fn index_region(
+    size: ArraySize,
+    row: Option<Spanned<i64>>,
+    column: Option<Spanned<i64>>,
+) -> CodeResult<Rect> {
+    // If the array is a single row, then a lone index selects a column.
+    let (row, column) = match (row, column) {
+        (Some(index), None) if size.h.get() == 1 => (None, Some(index)),
+        other => other,
+    };
+    let (x1, x2) = index_range(column, size.w.get())?;
+    let (y1, y2) = index_range(row, size.h.get())?;
+    Ok(Rect::new(x1, y1, x2, y2))
+}
+
+/// Returns the inclusive range of coordinates selected by a 1-based index
+/// along an axis with length `len`. An index of 0 or `None` selects the whole
+/// axis.
+fn index_range(index: Option<Spanned<i64>>, len: u32) -> CodeResult<(i64, i64)> {
+    match index {
+        None => Ok((0, len as i64 - 1)),
+        Some(index) if index.inner == 0 => Ok((0, len as i64 - 1)),
+        Some(index) if (1..=len as i64).contains(&index.inner) => {
+            Ok((index.inner - 1, index.inner - 1))
+        }
+        Some(index) => Err(RunErrorMsg::IndexOutOfBounds.with_span(index.span)),
+    }
+}
+
+/// Returns the 0-based index of the area selected by the 1-based `area_num`
+/// argument to `INDEX()` among `count` areas. If `area_num` is omitted, the
+/// first area is selected.
+fn area_index(area_num: Option<Spanned<i64>>, count: usize) -> CodeResult<usize> {
+    match area_num {
+        None => Ok(0),
+        Some(area_num) if (1..=count as i64).contains(&area_num.inner) => {
+            Ok(area_num.inner as usize - 1)
+        }
+        Some(area_num) => Err(RunErrorMsg::IndexOutOfBounds.with_span(area_num.span)),
+    }
+}
+
+/// Returns an array of consecutive integers starting at `start`, or a single
+/// value if `width` and `height` are both 1.
+fn sequence(span: Span, width: u32, height: u32, start: i64) -> CodeResult<Value> {
+    if std::cmp::max(width, height) > crate::limits::CELL_RANGE_LIMIT {
+        return Err(RunErrorMsg::ArrayTooBig.with_span(span));
+    }
+    let size = ArraySize::new_or_err(width, height)?;
+    if size.len() == 1 {
+        return Ok(Value::from(start));
+    }
+    let values = (start..).take(size.len()).map(CellValue::from).collect();
+    Ok(Array::new_row_major(size, values)?.into())
+}
@@ -210,6 +210,37 @@ fn test_bool_parsing() {
     assert_eq!("2", eval_to_string(&g, "IF(FALSE(), 1, 2)"));
 }
 
+#[test]
+fn test_error_literal_parsing() {
+    let g = Grid::new();
+
+    expect_err(&RunErrorMsg::BadCellReference, &g, "#REF!");
+    expect_err(&RunErrorMsg::BadCellReference, &g, "SUM(#ref!, 1)");
+    expect_err(&RunErrorMsg::NoMatch, &g, "#N/A");
+    expect_err(&RunErrorMsg::DivideByZero, &g, "#div/0! + 1");
+    expect_err(&RunErrorMsg::BadFunctionName, &g, "#NAME?");
+    assert_eq!("4", eval_to_string(&g, "ERROR.TYPE(#REF!)"));
+    assert_eq!("2", eval_to_string(&g, "ERROR.TYPE(#DIV/0!)"));
+    assert_eq!("3", eval_to_string(&g, "ERROR.TYPE(#VALUE!)"));
+    assert_eq!("6", eval_to_string(&g, "ERROR.TYPE(#NUM!)"));
+    assert_eq!("1", eval_to_string(&g, "ERROR.TYPE(#NULL!)"));
+    assert_eq!("0", eval_to_string(&g, "IFERROR(#REF!, 0)"));
+    assert_eq!("TRUE", eval_to_string(&g, "ISNA(#N/A)"));
+    assert_eq!("FALSE", eval_to_string(&g, "ISNA(#VALUE!)"));
+    assert_eq!("none", eval_to_string(&g, "IF(ISNA(#N/A), \"none\", 1)"));
+    assert_eq!("{1, #N/A}", eval_to_string(&g, "{1, #N/A}"));
+    assert!(crate::formulas::parse_and_check_formula("SUM(#REF!)", 0, 0));
+
+    // Excel error codes round-trip
+    for code in [
+        "#DIV/0!", "#VALUE!", "#REF!", "#NAME?", "#NUM!", "#N/A", "#SPILL!", "#CALC!", "#NULL!",
+    ] {
+        let msg = RunErrorMsg::from_excel_code(code).unwrap();
+        assert_eq!(code, msg.excel_code());
+    }
+    assert_eq!(None, RunErrorMsg::from_excel_code("#HASHTAG"));
+}
+
 #[test]
 fn test_leading_equals() {
     let g = Grid::new();
@@ -311,6 +342,32 @@ fn test_find_cell_references() {
                 pos: a1("\"plum\"!$A1"),
             },
         ),
+        // Whole-column range
+        (
+            "B:$D",
+            RangeRef::ColRange {
+                start: CellRefCoord::Relative(1),
+                end: CellRefCoord::Absolute(3),
+                sheet: None,
+            },
+        ),
+        // Whole-row range with a sheet reference
+        (
+            "'kiwi'!3:n1",
+            RangeRef::RowRange {
+                start: CellRefCoord::Relative(3),
+                end: CellRefCoord::Relative(-1),
+                sheet: Some("kiwi".to_string()),
+            },
+        ),
+        // Range that is not a whole-row range
+        (
+            "N3:N5",
+            RangeRef::CellRange {
+                start: a1("N3"),
+                end: a1("N5"),
+            },
+        ),
     ];
     let formula_string = test_cases.iter().map(|(string, _)| string).join(" + ");
     let cell_references_found = find_cell_references(&formula_string, Pos::ORIGIN)
@@ -361,9 +418,283 @@ fn test_sheet_references() {
     );
 }
 
+#[test]
+fn test_whole_column_and_row_references() {
+    let mut g = Grid::new();
+
+    let id1 = g.sheets()[0].id;
+    let sheet = g.try_sheet_mut(id1).unwrap();
+    let _ = sheet.set_cell_value(pos![B1], 1);
+    let _ = sheet.set_cell_value(pos![B3], 10);
+    let _ = sheet.set_cell_value(pos![C2], 100);
+    let _ = sheet.set_cell_value(pos![D3], 1000);
+    sheet.recalculate_bounds();
+
+    let id2 = g.add_sheet(None);
+    let name2 = "My Other Sheet".to_string();
+    g.sheets_mut()[1].name.clone_from(&name2);
+    let sheet = g.try_sheet_mut(id2).unwrap();
+    let _ = sheet.set_cell_value(pos![A5], 7);
+    let _ = sheet.set_cell_value(pos![A9], 70);
+    sheet.recalculate_bounds();
+
+    // Ranges are limited to the data bounds of the sheet, which are B1:D3.
+    assert_eq!("11", eval_to_string(&g, "SUM(B:B)"));
+    assert_eq!("111", eval_to_string(&g, "SUM($B:C)"));
+    assert_eq!("1010", eval_to_string(&g, "SUM(3:3)"));
+    assert_eq!("101", eval_to_string(&g, "SUM(1:$2)"));
+    assert_eq!("{1; 0; 10}", eval_to_string(&g, "B:B + 0"));
+    assert_eq!("{1, 0, 0}", eval_to_string(&g, "1:1 + 0"));
+    assert_eq!("{3, 4}", eval_to_string(&g, "COLUMN(C:D)"));
+    assert_eq!("{2; 3}", eval_to_string(&g, "ROW(2:3)"));
+    assert_eq!("0", eval_to_string(&g, "SUM(E:F)"));
+    assert_eq!("77", eval_to_string(&g, "SUM('My Other Sheet'!A:A)"));
+
+    // The whole columns and rows are accessed, not just the data bounds.
+    let mut ctx = Ctx::new(&g, Pos::ORIGIN.to_sheet_pos(id1));
+    let formula = parse_formula("SUM(C:C) + SUM(5:5)", Pos::ORIGIN).unwrap();
+    formula.eval(&mut ctx, false).unwrap();
+    let is_accessed = |pos: Pos| {
+        let sheet_rect = crate::SheetRect::from(pos.to_sheet_pos(id1));
+        ctx.cells_accessed
+            .iter()
+            .any(|rect| rect.intersects(sheet_rect))
+            || ctx
+                .lines_accessed
+                .iter()
+                .any(|lines| lines.intersects(sheet_rect))
+    };
+    assert!(is_accessed(pos![C1000000]));
+    assert!(is_accessed(pos![ZZ5]));
+    assert!(!is_accessed(pos![D4]));
+    // Only the cells that were read are included in `cells_accessed`.
+    assert!(ctx
+        .cells_accessed
+        .iter()
+        .all(|rect| rect.max.x < 10 && rect.max.y < 10));
+
+    // An empty sheet is limited to the row or column of the formula.
+    let g = Grid::new();
+    assert_eq!("0", eval_to_string(&g, "SUM(B:B)"));
+    expect_err(&RunErrorMsg::CircularReference, &g, "SUM(A:A)");
+    expect_err(&RunErrorMsg::CircularReference, &g, "SUM(0:0)");
+}
+
+#[test]
+fn test_implicit_intersection() {
+    let g = Grid::from_array(pos![A1], &array![1, 2; 3, 4; 5, 6]);
+    let sheet_id = g.sheets()[0].id;
+    let eval_at = |pos: Pos, s: &str| {
+        let mut ctx = Ctx::new(&g, pos.to_sheet_pos(sheet_id));
+        parse_formula(s, pos)?.eval(&mut ctx, false)
+    };
+
+    assert_eq!("6", eval_at(pos![C2], "@A:A * 2").unwrap().to_string());
+    assert_eq!("4", eval_at(pos![C2], "@B1:B3").unwrap().to_string());
+    assert_eq!("2", eval_at(pos![B5], "@A1:B1").unwrap().to_string());
+    assert_eq!("5", eval_at(pos![C3], "SUM(@A1:A3)").unwrap().to_string());
+    assert_eq!("2", eval_at(pos![C2], "ROW(@A:A)").unwrap().to_string());
+    assert_eq!(
+        "TRUE",
+        eval_at(pos![C2], "ISREF(@A:A)").unwrap().to_string()
+    );
+    assert_eq!(
+        "7",
+        eval_at(pos![C2], "@{7, 8; 9, 10}").unwrap().to_string()
+    );
+    assert_eq!("5", eval_at(pos![C2], "@5").unwrap().to_string());
+    assert_eq!("-3", eval_at(pos![C2], "-@A:A").unwrap().to_string());
+    assert_eq!("-1", eval_at(pos![C2], "-@A1").unwrap().to_string());
+    assert_eq!("-6", eval_at(pos![C2], "2*-@A:A").unwrap().to_string());
+    assert_eq!("-3", eval_at(pos![C2], "-@A1:A3").unwrap().to_string());
+    assert_eq!("4", eval_at(pos![C2], "+@B1:B3").unwrap().to_string());
+    assert_eq!("3", eval_at(pos![C2], "--@A:A").unwrap().to_string());
+
+    // There is no cell in the same row or column as the formula.
+    for (pos, formula) in [(pos![C2], "@A1:B3"), (pos![C9], "@A1:A3")] {
+        let error = eval_at(pos, formula).unwrap_err();
+        assert_eq!(RunErrorMsg::InvalidArgument, error.msg);
+    }
+}
+
+#[test]
+fn test_intersection_and_union_references() {
+    let g = Grid::from_array(
+        pos![A1],
+        &array![
+            1, 2, 3, 4;
+            5, 6, 7, 8;
+            9, 10, 11, 12;
+            13, 14, 15, 16;
+        ],
+    );
+
+    // Intersection
+    assert_eq!("34", eval_to_string(&g, "SUM((A1:C3 B2:D4))"));
+    assert_eq!("34", eval_to_string(&g, "SUM(A1:C3  B2:D4)"));
+    assert_eq!("{6, 7; 10, 11}", eval_to_string(&g, "A1:C3 B2:D4"));
+    assert_eq!("{7}", eval_to_string(&g, "A1:D4 C:C 2:2"));
+    assert_eq!("TRUE", eval_to_string(&g, "ISREF(A1:C3 B2:D4)"));
+    expect_err(&RunErrorMsg::EmptyIntersection, &g, "SUM(A1:A2 C3:C4)");
+    assert_eq!("1", eval_to_string(&g, "ERROR.TYPE(A1 B2)"));
+
+    // Union
+    assert_eq!("36", eval_to_string(&g, "SUM((A1:A3,C1:C3))"));
+    assert_eq!("{1; 5; 4}", eval_to_string(&g, "(A1:A2, D1)"));
+    assert_eq!("5", eval_to_string(&g, "COUNT((A1:B2, A1))"));
+    assert_eq!("TRUE", eval_to_string(&g, "ISREF((A1, B2))"));
+    assert_eq!("18", eval_to_string(&g, "SUM((A1:A4, C1:C4) B2:D3)"));
+    expect_err(
+        &RunErrorMsg::Expected {
+            expected: "single-area reference".into(),
+            got: Some("multi-area reference".into()),
+        },
+        &g,
+        "ROW((A1, B2))",
+    );
+
+    // Whitespace that is not followed by a reference is not an operator.
+    assert_eq!("3", eval_to_string(&g, "A1 + B1"));
+    assert_eq!("7", eval_to_string(&g, "SUM( A1 , B2 )"));
+    assert_eq!("2", eval_to_string(&g, "(A1 ) * 2"));
+}
+
+#[test]
+fn test_three_dimensional_references() {
+    let mut g = Grid::new();
+    let summary_id = g.sheets()[0].id;
+    let mut ids = vec![];
+    for (i, name) in ["Jan", "Feb", "Mar", "Apr 2024"].into_iter().enumerate() {
+        let id = g.add_sheet(None);
+        g.sheets_mut()[i + 1].name = name.to_string();
+        let sheet = g.try_sheet_mut(id).unwrap();
+        let _ = sheet.set_cell_value(pos![C9], 5);
+        let _ = sheet.set_cell_value(pos![C10], 10_i64.pow(i as u32));
+        sheet.recalculate_bounds();
+        ids.push(id);
+    }
+
+    assert_eq!("111", eval_to_string(&g, "SUM(Jan:Mar!C10)"));
+    assert_eq!("111", eval_to_string(&g, "SUM(Mar:Jan!C10)"));
+    assert_eq!("11", eval_to_string(&g, "SUM('Jan':'Feb'!$C$10)"));
+    assert_eq!("1110", eval_to_string(&g, "SUM(\"Feb\":\"Apr 2024\"!C10)"));
+    assert_eq!("126", eval_to_string(&g, "SUM(Jan:Mar!C9:C10)"));
+    assert_eq!("126", eval_to_string(&g, "SUM(Jan:Mar!C:C)"));
+    assert_eq!("37", eval_to_string(&g, "AVERAGE(Jan:Mar!C10)"));
+    assert_eq!("6", eval_to_string(&g, "COUNT(Jan:Mar!C9:C10)"));
+    assert_eq!("100", eval_to_string(&g, "MAX(Jan:Mar!C10)"));
+    assert_eq!("{1; 10; 100}", eval_to_string(&g, "Jan:Mar!C10"));
+    assert_eq!("TRUE", eval_to_string(&g, "ISREF(Jan:Mar!C10)"));
+
+    expect_err(&RunErrorMsg::BadCellReference, &g, "SUM(Jan:Missing!C10)");
+    expect_err(
+        &RunErrorMsg::Expected {
+            expected: "reference on a single sheet".into(),
+            got: Some("three-dimensional reference".into()),
+        },
+        &g,
+        "ROW(Jan:Mar!C10)",
+    );
+
+    // Cells are accessed on every sheet in the span.
+    let mut ctx = Ctx::new(&g, Pos::ORIGIN.to_sheet_pos(summary_id));
+    let formula = parse_formula("SUM(Jan:Mar!C10)", Pos::ORIGIN).unwrap();
+    formula.eval(&mut ctx, false).unwrap();
+    for (i, id) in ids.into_iter().enumerate() {
+        let accessed = crate::SheetRect::from(pos![C10].to_sheet_pos(id));
+        assert_eq!(i < 3, ctx.cells_accessed.contains(&accessed));
+    }
+}
+
+#[test]
+fn test_defined_names() {
+    let mut g = Grid::new();
+    let id1 = g.sheets()[0].id;
+    let sheet = g.try_sheet_mut(id1).unwrap();
+    let _ = sheet.set_cell_value(pos![A1], 1);
+    let _ = sheet.set_cell_value(pos![A2], 10);
+    let _ = sheet.set_cell_value(pos![A3], 100);
+
+    let id2 = g.add_sheet(None);
+    g.sheets_mut()[1].name = "Inputs".to_string();
+    let sheet = g.try_sheet_mut(id2).unwrap();
+    let _ = sheet.set_cell_value(pos![B2], 0.5);
+
+    g.set_defined_name("TaxRate", None, Some("Inputs!$B$2".into()));
+    g.set_defined_name("Sales", None, Some("$A$1:$A$3".into()));
+    g.set_defined_name("Twice", None, Some("TaxRate * 2".into()));
+    g.set_defined_name("Loop", None, Some("Loop + 1".into()));
+    g.set_defined_name("Local", Some(id2), Some("B2".into()));
+
+    assert_eq!("0.5", eval_to_string(&g, "TaxRate"));
+    assert_eq!("111", eval_to_string(&g, "SUM(sales)"));
+    assert_eq!("55.5", eval_to_string(&g, "SUM(Sales) * TAXRATE"));
+    assert_eq!("1", eval_to_string(&g, "Twice"));
+    assert_eq!("{1; 2; 3}", eval_to_string(&g, "ROW(Sales)"));
+    assert_eq!("10", eval_to_string(&g, "INDEX(Sales, 2)"));
+    assert_eq!("TRUE", eval_to_string(&g, "ISREF(Sales)"));
+    assert_eq!("FALSE", eval_to_string(&g, "ISREF(Twice)"));
+
+    // Names bound using `LET()` shadow defined names.
+    assert_eq!("3", eval_to_string(&g, "LET(Sales, 3, Sales)"));
+
+    // Sheet names are only visible on their own sheet.
+    expect_err(&RunErrorMsg::UnknownName("Local".into()), &g, "Local");
+    let pos = Pos::ORIGIN.to_sheet_pos(id2);
+    assert_eq!("0.5", eval_to_string_at(&g, pos, "Local"));
+
+    // Cells referenced by a name are accessed.
+    let mut ctx = Ctx::new(&g, Pos::ORIGIN.to_sheet_pos(id1));
+    let formula = parse_formula("TaxRate", Pos::ORIGIN).unwrap();
+    formula.eval(&mut ctx, false).unwrap();
+    let accessed = crate::SheetRect::from(pos![B2].to_sheet_pos(id2));
+    assert!(ctx.cells_accessed.contains(&accessed));
+
+    // Errors are reported at the name.
+    let err = eval_to_err(&g, "1 + Loop");
+    assert_eq!(RunErrorMsg::CircularReference, err.msg);
+    assert_eq!(Some(crate::Span { start: 4, end: 8 }), err.span);
+
+    // Unknown names are allowed when only checking the formula.
+    assert!(parse_and_check_formula("SUM(Missing)", 0, 0));
+}
+
 /// Regression test for quadratic#410
 #[test]
 fn test_currency_string() {
     let g = Grid::new();
     assert_eq!("30", eval_to_string(&g, "\"$10\" + 20"));
 }
+
+#[test]
+fn test_volatile_functions() {
+    let g = Grid::from_array(pos![A1], &array![1, 2; 3, 4]);
+    let is_volatile = |s: &str| {
+        let mut ctx = Ctx::new(&g, Pos::ORIGIN.to_sheet_pos(g.sheets()[0].id));
+        parse_formula(s, Pos::ORIGIN)
+            .unwrap()
+            .eval(&mut ctx, false)
+            .unwrap();
+        ctx.is_volatile
+    };
+
+    for name in [
+        "NOW",
+        "TODAY",
+        "RAND",
+        "RANDBETWEEN",
+        "RANDARRAY",
+        "INDIRECT",
+        "OFFSET",
+    ] {
+        assert!(functions::lookup_function(name).unwrap().is_volatile);
+    }
+    assert!(!functions::lookup_function("SUM").unwrap().is_volatile);
+
+    assert!(is_volatile("NOW()"));
+    assert!(is_volatile("SUM(A1:B2) + RAND()"));
+    assert!(is_volatile("SUM(OFFSET(A1, 1, 0, 1, 2))"));
+    assert!(is_volatile("LET(x, INDIRECT(\"B2\"), x * 2)"));
+    assert!(!is_volatile("SUM(A1:B2)"));
+    assert!(!is_volatile("IF(FALSE, NOW(), 1)"));
+}
@@ -3,9 +3,21 @@ use regex::{Regex, RegexBuilder};
 use crate::{RunError, RunErrorMsg};
 
 pub fn wildcard_pattern_to_regex(s: &str) -> Result<Regex, RunError> {
+    build_wildcard_regex(s, true)
+}
+
+/// Same as `wildcard_pattern_to_regex()`, but the resulting regex may match
+/// anywhere in the string instead of requiring the whole string to match.
+pub fn wildcard_pattern_to_unanchored_regex(s: &str) -> Result<Regex, RunError> {
+    build_wildcard_regex(s, false)
+}
+
+fn build_wildcard_regex(s: &str, match_whole_string: bool) -> Result<Regex, RunError> {
     let mut chars = s.chars();
     let mut regex_string = String::new();
-    regex_string.push('^'); // Match whole string using `^...$`.
+    if match_whole_string {
+        regex_string.push('^'); // Match whole string using `^...$`.
+    }
     while let Some(c) = chars.next() {
         match c {
             // Escape the next character, if there is one. Otherwise ignore.
@@ -20,7 +32,9 @@ pub fn wildcard_pattern_to_regex(s: &str) -> Result<Regex, RunError> {
             _ => regex_string.push_str(&regex::escape(&c.to_string())),
         }
     }
-    regex_string.push('$'); // Match whole string using `^...$`.
+    if match_whole_string {
+        regex_string.push('$'); // Match whole string using `^...$`.
+    }
     RegexBuilder::new(&regex_string)
         .case_insensitive(true)
         .build()
@@ -4,6 +4,7 @@
 //! any given CellValue::Code type (ie, if it doesn't exist then a run hasn't been
 //! performed yet).
 
+use super::SheetId;
 use crate::{ArraySize, CellValue, Pos, Rect, RunError, SheetPos, SheetRect, Value};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -19,9 +20,21 @@ pub struct CodeRun {
     pub std_out: Option<String>,
     pub std_err: Option<String>,
     pub cells_accessed: HashSet<SheetRect>,
+
+    /// Whole columns or rows accessed by the code, such as `B:C`, which are
+    /// not included in `cells_accessed` since they have no bounds.
+    #[serde(default)]
+    pub lines_accessed: HashSet<SheetLines>,
+
     pub result: CodeRunResult,
     pub return_type: Option<String>,
     pub spill_error: bool,
+
+    /// Whether the code called a volatile function, such as `NOW()`, so that
+    /// it must be rerun whenever the grid is recalculated.
+    #[serde(default)]
+    pub is_volatile: bool,
+
     pub line_number: Option<u32>,
     pub output_type: Option<String>,
     pub last_modified: DateTime<Utc>,
@@ -115,6 +128,51 @@ impl CodeRun {
             CodeRunResult::Err(error) => Some(error.to_owned()),
         }
     }
+
+    /// Returns whether the code accessed any cell in `sheet_rect` the last
+    /// time it was run.
+    pub fn accessed(&self, sheet_rect: SheetRect) -> bool {
+        self.cells_accessed
+            .iter()
+            .any(|cells| cells.intersects(sheet_rect))
+            || self
+                .lines_accessed
+                .iter()
+                .any(|lines| lines.intersects(sheet_rect))
+    }
+}
+
+/// Range of whole columns or rows on a sheet, such as `B:C` or `5:5`.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SheetLines {
+    Columns {
+        sheet_id: SheetId,
+        min: i64,
+        max: i64,
+    },
+    Rows {
+        sheet_id: SheetId,
+        min: i64,
+        max: i64,
+    },
+}
+
+impl SheetLines {
+    /// Returns whether any cell in `sheet_rect` is in the columns or rows.
+    pub fn intersects(self, sheet_rect: SheetRect) -> bool {
+        match self {
+            SheetLines::Columns { sheet_id, min, max } => {
+                sheet_id == sheet_rect.sheet_id
+                    && sheet_rect.min.x <= max
+                    && sheet_rect.max.x >= min
+            }
+            SheetLines::Rows { sheet_id, min, max } => {
+                sheet_id == sheet_rect.sheet_id
+                    && sheet_rect.min.y <= max
+                    && sheet_rect.max.y >= min
+            }
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
@@ -160,7 +218,7 @@ pub enum CodeRunResult {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{grid::SheetId, Array};
+    use crate::Array;
 
     #[test]
     fn test_output_size() {
@@ -170,11 +228,13 @@ mod test {
             std_err: None,
             formatted_code_string: None,
             cells_accessed: HashSet::new(),
+            lines_accessed: HashSet::new(),
             result: CodeRunResult::Ok(Value::Single(CellValue::Number(1.into()))),
             return_type: Some("number".into()),
             line_number: None,
             output_type: None,
             spill_error: false,
+            is_volatile: false,
             last_modified: Utc::now(),
         };
         assert_eq!(code_run.output_size(), ArraySize::_1X1);
@@ -195,6 +255,7 @@ mod test {
             std_err: None,
             formatted_code_string: None,
             cells_accessed: HashSet::new(),
+            lines_accessed: HashSet::new(),
             result: CodeRunResult::Ok(Value::Array(Array::new_empty(
                 ArraySize::new(10, 11).unwrap(),
             ))),
@@ -202,6 +263,7 @@ mod test {
             line_number: None,
             output_type: None,
             spill_error: false,
+            is_volatile: false,
             last_modified: Utc::now(),
         };
         assert_eq!(code_run.output_size().w.get(), 10);
@@ -227,6 +289,7 @@ mod test {
             std_out: None,
             std_err: None,
             cells_accessed: HashSet::new(),
+            lines_accessed: HashSet::new(),
             result: CodeRunResult::Ok(Value::Array(Array::new_empty(
                 ArraySize::new(10, 11).unwrap(),
             ))),
@@ -234,6 +297,7 @@ mod test {
             line_number: None,
             output_type: None,
             spill_error: true,
+            is_volatile: false,
             last_modified: Utc::now(),
         };
         assert_eq!(code_run.output_size().w.get(), 10);
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Grid, SheetId};
+
+/// Name that formulas can use in place of the formula it refers to, such as
+/// `TaxRate` for `Inputs!$B$2` or `Sales` for `Data!$A$1:$F$500`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "js", derive(ts_rs::TS))]
+pub struct DefinedName {
+    /// Name, which is matched case-insensitively.
+    pub name: String,
+    /// Sheet on which the name is visible, or `None` if the name is defined
+    /// on the workbook and visible on every sheet.
+    pub sheet_id: Option<SheetId>,
+    /// Formula that the name refers to, in A1 notation and without a leading
+    /// `=`.
+    pub refers_to: String,
+}
+
+impl Grid {
+    pub fn defined_names(&self) -> &[DefinedName] {
+        &self.defined_names
+    }
+
+    /// Returns the definition of `name` that is visible from the sheet
+    /// `sheet_id`. A name defined on the sheet takes precedence over the same
+    /// name defined on the workbook.
+    pub fn defined_name(&self, name: &str, sheet_id: SheetId) -> Option<&DefinedName> {
+        let find = |scope: Option<SheetId>| {
+            self.defined_names
+                .iter()
+                .find(|d| d.sheet_id == scope && d.name.eq_ignore_ascii_case(name))
+        };
+        find(Some(sheet_id)).or_else(|| find(None))
+    }
+
+    /// Sets the definition of `name` with the scope `sheet_id`, or removes it
+    /// if `refers_to` is `None`. Returns the previous definition, if any.
+    pub fn set_defined_name(
+        &mut self,
+        name: &str,
+        sheet_id: Option<SheetId>,
+        refers_to: Option<String>,
+    ) -> Option<DefinedName> {
+        let index = self
+            .defined_names
+            .iter()
+            .position(|d| d.sheet_id == sheet_id && d.name.eq_ignore_ascii_case(name));
+        let new = refers_to.map(|refers_to| DefinedName {
+            name: name.to_string(),
+            sheet_id,
+            refers_to,
+        });
+        match (index, new) {
+            (Some(i), Some(new)) => Some(std::mem::replace(&mut self.defined_names[i], new)),
+            (Some(i), None) => Some(self.defined_names.remove(i)),
+            (None, Some(new)) => {
+                self.defined_names.push(new);
+                None
+            }
+            (None, None) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_defined_name() {
+        let mut grid = Grid::new();
+        let sheet_id = grid.sheet_ids()[0];
+        let other_sheet_id = SheetId::new();
+
+        assert_eq!(grid.set_defined_name("Rate", None, Some("A1".into())), None);
+        assert_eq!(grid.defined_name("RATE", sheet_id).unwrap().refers_to, "A1");
+
+        // Sheet names take precedence over workbook names.
+        grid.set_defined_name("rate", Some(sheet_id), Some("B2".into()));
+        assert_eq!(grid.defined_name("Rate", sheet_id).unwrap().refers_to, "B2");
+        assert_eq!(
+            grid.defined_name("Rate", other_sheet_id).unwrap().refers_to,
+            "A1"
+        );
+
+        // Replacing a name returns the old definition.
+        let old = grid.set_defined_name("RATE", None, Some("C3".into()));
+        assert_eq!(old.unwrap().refers_to, "A1");
+        assert_eq!(grid.defined_names().len(), 2);
+
+        // Removing a name returns the old definition.
+        let old = grid.set_defined_name("rate", Some(sheet_id), None);
+        assert_eq!(old.unwrap().refers_to, "B2");
+        assert_eq!(grid.defined_name("Rate", sheet_id).unwrap().refers_to, "C3");
+        assert_eq!(grid.set_defined_name("missing", None, None), None);
+    }
+}
@@ -2,11 +2,12 @@ use crate::color::Rgba;
 use crate::grid::formats::format::Format;
 use crate::grid::{
     block::SameValue,
-    file::v1_5::schema::{self as current},
+    file::v1_6::schema::{self as current},
     formatting::RenderSize,
     generate_borders, set_rect_borders, BorderSelection, BorderStyle, CellAlign, CellBorderLine,
-    CellWrap, CodeCellLanguage, CodeRun, CodeRunResult, Column, ColumnData, ConnectionKind, Grid,
-    GridBounds, NumericFormat, NumericFormatKind, Sheet, SheetBorders, SheetId,
+    CellWrap, CodeCellLanguage, CodeRun, CodeRunResult, Column, ColumnData, ConnectionKind,
+    DefinedName, Grid, GridBounds, NumericFormat, NumericFormatKind, Sheet, SheetBorders, SheetId,
+    SheetLines,
 };
 use crate::sheet_offsets::SheetOffsets;
 use crate::{CellValue, CodeCellValue, Pos, Rect, Value};
@@ -255,6 +256,10 @@ fn import_code_cell_output(type_field: &str, value: &str) -> CellValue {
             "FALSE" => CellValue::Logical(false),
             _ => CellValue::Logical(false),
         },
+        "time instant" => serde_json::from_str(value).map_or(CellValue::Blank, CellValue::Instant),
+        "time duration" => {
+            serde_json::from_str(value).map_or(CellValue::Blank, CellValue::Duration)
+        }
         _ => CellValue::Blank,
     }
 }
@@ -269,6 +274,11 @@ fn import_code_cell_builder(sheet: &current::Sheet) -> Result<IndexMap<Pos, Code
             .iter()
             .map(|sheet_rect| crate::SheetRect::from(sheet_rect.clone()))
             .collect();
+        let lines_accessed = code_run
+            .lines_accessed
+            .iter()
+            .map(|lines| SheetLines::from(lines.clone()))
+            .collect();
 
         let result = match &code_run.result {
             current::CodeRunResult::Ok(output) => CodeRunResult::Ok(match output {
@@ -300,7 +310,9 @@ fn import_code_cell_builder(sheet: &current::Sheet) -> Result<IndexMap<Pos, Code
                 std_out: code_run.std_out.to_owned(),
                 std_err: code_run.std_err.to_owned(),
                 spill_error: code_run.spill_error,
+                is_volatile: code_run.is_volatile,
                 cells_accessed,
+                lines_accessed,
                 result,
                 return_type: code_run.return_type.to_owned(),
                 line_number: code_run.line_number.to_owned(),
@@ -381,6 +393,22 @@ pub fn import_sheet(sheet: &current::Sheet) -> Result<Sheet> {
     Ok(new_sheet)
 }
 
+fn import_defined_names(defined_names: Vec<current::DefinedName>) -> Result<Vec<DefinedName>> {
+    defined_names
+        .into_iter()
+        .map(|defined_name| {
+            Ok(DefinedName {
+                name: defined_name.name,
+                sheet_id: defined_name
+                    .sheet_id
+                    .map(|id| SheetId::from_str(&id.id))
+                    .transpose()?,
+                refers_to: defined_name.refers_to,
+            })
+        })
+        .collect()
+}
+
 pub fn import(file: current::GridSchema) -> Result<Grid> {
     Ok(Grid {
         sheets: file
@@ -388,6 +416,7 @@ pub fn import(file: current::GridSchema) -> Result<Grid> {
             .into_iter()
             .map(|sheet| import_sheet(&sheet))
             .collect::<Result<_>>()?,
+        defined_names: import_defined_names(file.defined_names)?,
     })
 }
 
@@ -595,12 +624,12 @@ fn export_column_builder(sheet: &Sheet) -> Vec<(i64, current::Column)> {
                                     CellValue::Logical(logical) => {
                                         current::CellValue::Logical(*logical)
                                     }
-                                    CellValue::Instant(instant) => {
-                                        current::CellValue::Instant(instant.to_string())
-                                    }
-                                    CellValue::Duration(duration) => {
-                                        current::CellValue::Duration(duration.to_string())
-                                    }
+                                    CellValue::Instant(instant) => current::CellValue::Instant(
+                                        serde_json::to_string(instant).unwrap_or_default(),
+                                    ),
+                                    CellValue::Duration(duration) => current::CellValue::Duration(
+                                        serde_json::to_string(duration).unwrap_or_default(),
+                                    ),
                                     CellValue::Error(error) => current::CellValue::Error(
                                         current::RunError::from_grid_run_error(error),
                                     ),
@@ -705,6 +734,18 @@ fn export_formats(formats: &BTreeMap<i64, (Format, i64)>) -> Vec<(i64, (current:
         .collect()
 }
 
+fn export_code_cell_output(cell_value: &CellValue) -> current::OutputValueValue {
+    let value = match cell_value {
+        CellValue::Instant(instant) => serde_json::to_string(instant).unwrap_or_default(),
+        CellValue::Duration(duration) => serde_json::to_string(duration).unwrap_or_default(),
+        _ => cell_value.to_string(),
+    };
+    current::OutputValueValue {
+        type_field: cell_value.type_name().into(),
+        value,
+    }
+}
+
 pub(crate) fn export_sheet(sheet: &Sheet) -> current::Sheet {
     current::Sheet {
         id: current::Id {
@@ -726,10 +767,7 @@ pub(crate) fn export_sheet(sheet: &Sheet) -> current::Sheet {
                 let result = match &code_run.result {
                     CodeRunResult::Ok(output) => current::CodeRunResult::Ok(match output {
                         Value::Single(cell_value) => {
-                            current::OutputValue::Single(current::OutputValueValue {
-                                type_field: cell_value.type_name().into(),
-                                value: cell_value.to_string(),
-                            })
+                            current::OutputValue::Single(export_code_cell_output(cell_value))
                         }
                         Value::Array(array) => current::OutputValue::Array(current::OutputArray {
                             size: current::OutputSize {
@@ -738,12 +776,7 @@ pub(crate) fn export_sheet(sheet: &Sheet) -> current::Sheet {
                             },
                             values: array
                                 .rows()
-                                .flat_map(|row| {
-                                    row.iter().map(|cell| current::OutputValueValue {
-                                        type_field: cell.type_name().into(),
-                                        value: cell.to_string(),
-                                    })
-                                })
+                                .flat_map(|row| row.iter().map(export_code_cell_output))
                                 .collect(),
                         }),
                     }),
@@ -760,11 +793,17 @@ pub(crate) fn export_sheet(sheet: &Sheet) -> current::Sheet {
                         std_out: code_run.std_out.clone(),
                         std_err: code_run.std_err.clone(),
                         spill_error: code_run.spill_error,
+                        is_volatile: code_run.is_volatile,
                         cells_accessed: code_run
                             .cells_accessed
                             .iter()
                             .map(|sheet_rect| current::SheetRect::from(*sheet_rect))
                             .collect(),
+                        lines_accessed: code_run
+                            .lines_accessed
+                            .iter()
+                            .map(|lines| current::SheetLines::from(*lines))
+                            .collect(),
                         result,
                         return_type: code_run.return_type.clone(),
                         line_number: code_run.line_number,
@@ -776,9 +815,21 @@ pub(crate) fn export_sheet(sheet: &Sheet) -> current::Sheet {
     }
 }
 
+fn export_defined_names(defined_names: &[DefinedName]) -> Vec<current::DefinedName> {
+    defined_names
+        .iter()
+        .map(|defined_name| current::DefinedName {
+            name: defined_name.name.clone(),
+            sheet_id: defined_name.sheet_id.map(current::Id::from),
+            refers_to: defined_name.refers_to.clone(),
+        })
+        .collect()
+}
+
 pub fn export(grid: &mut Grid) -> Result<current::GridSchema> {
     Ok(current::GridSchema {
         version: Some(CURRENT_VERSION.into()),
         sheets: grid.sheets().iter().map(export_sheet).collect(),
+        defined_names: export_defined_names(grid.defined_names()),
     })
 }
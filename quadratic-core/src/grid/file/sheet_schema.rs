@@ -1,5 +1,6 @@
 use super::current;
 use super::v1_5;
+use super::v1_6;
 use crate::grid::Sheet;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -8,13 +9,17 @@ use serde::{Deserialize, Serialize};
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum SheetSchema {
     V1_5(v1_5::schema::Sheet),
+    V1_6(v1_6::schema::Sheet),
 }
 
 impl SheetSchema {
     /// Imports a Sheet from the schema.
     pub fn into_latest(&self) -> Result<Sheet> {
         match self {
-            SheetSchema::V1_5(sheet) => current::import_sheet(sheet),
+            SheetSchema::V1_5(sheet) => {
+                current::import_sheet(&v1_5::file::upgrade_sheet(sheet.clone()))
+            }
+            SheetSchema::V1_6(sheet) => current::import_sheet(sheet),
         }
     }
 }
@@ -22,7 +27,7 @@ impl SheetSchema {
 /// Exports a Sheet to the latest schema version.
 pub fn export_sheet(sheet: &Sheet) -> SheetSchema {
     let schema = current::export_sheet(sheet);
-    SheetSchema::V1_5(schema)
+    SheetSchema::V1_6(schema)
 }
 
 #[cfg(test)]
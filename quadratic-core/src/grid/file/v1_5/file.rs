@@ -1,3 +1,53 @@
+use crate::grid::file::v1_5::schema as v1_5;
+use crate::grid::file::v1_6::schema as v1_6;
+use anyhow::Result;
+
+fn upgrade_code_run(code_run: v1_5::CodeRun) -> v1_6::CodeRun {
+    v1_6::CodeRun {
+        formatted_code_string: code_run.formatted_code_string,
+        std_out: code_run.std_out,
+        std_err: code_run.std_err,
+        cells_accessed: code_run.cells_accessed,
+        lines_accessed: vec![],
+        is_volatile: false,
+        result: code_run.result,
+        return_type: code_run.return_type,
+        line_number: code_run.line_number,
+        output_type: code_run.output_type,
+        spill_error: code_run.spill_error,
+        last_modified: code_run.last_modified,
+    }
+}
+
+pub(crate) fn upgrade_sheet(sheet: v1_5::Sheet) -> v1_6::Sheet {
+    v1_6::Sheet {
+        id: sheet.id,
+        name: sheet.name,
+        color: sheet.color,
+        order: sheet.order,
+        offsets: sheet.offsets,
+        columns: sheet.columns,
+        borders: sheet.borders,
+        code_runs: sheet
+            .code_runs
+            .into_iter()
+            .map(|(pos, code_run)| (pos, upgrade_code_run(code_run)))
+            .collect(),
+        formats_all: sheet.formats_all,
+        formats_columns: sheet.formats_columns,
+        formats_rows: sheet.formats_rows,
+    }
+}
+
+pub(crate) fn upgrade(schema: v1_5::GridSchema) -> Result<v1_6::GridSchema> {
+    let schema = v1_6::GridSchema {
+        version: Some("1.6".into()),
+        sheets: schema.sheets.into_iter().map(upgrade_sheet).collect(),
+        defined_names: vec![],
+    };
+    Ok(schema)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::grid::file::v1_5::schema::GridSchema;
@@ -22,4 +72,17 @@ mod tests {
         println!("{}", exported);
         // assert_eq!(V1_4_FILE, exported);
     }
+
+    #[test]
+    fn upgrade_a_v1_5_file() {
+        let imported = import(V1_5_FILE).unwrap();
+        let upgraded = super::upgrade(imported.clone()).unwrap();
+        assert_eq!(upgraded.version, Some("1.6".into()));
+        assert_eq!(upgraded.sheets.len(), imported.sheets.len());
+        let code_runs = &upgraded.sheets[0].code_runs;
+        assert_eq!(code_runs.len(), imported.sheets[0].code_runs.len());
+        assert!(code_runs
+            .iter()
+            .all(|(_, run)| run.lines_accessed.is_empty()));
+    }
 }
@@ -76,6 +76,10 @@ pub enum RunErrorMsg {
     IndexOutOfBounds,
     NoMatch,
     InvalidArgument,
+    NoConvergence,
+    SingularMatrix,
+    UnknownName(Cow<'static, str>),
+    EmptyIntersection,
 }
 
 // todo: There's probably a better way to do the From/Into between the types.
@@ -169,6 +173,10 @@ impl RunError {
                 crate::RunErrorMsg::IndexOutOfBounds => RunErrorMsg::IndexOutOfBounds,
                 crate::RunErrorMsg::NoMatch => RunErrorMsg::NoMatch,
                 crate::RunErrorMsg::InvalidArgument => RunErrorMsg::InvalidArgument,
+                crate::RunErrorMsg::NoConvergence => RunErrorMsg::NoConvergence,
+                crate::RunErrorMsg::SingularMatrix => RunErrorMsg::SingularMatrix,
+                crate::RunErrorMsg::UnknownName(name) => RunErrorMsg::UnknownName(name),
+                crate::RunErrorMsg::EmptyIntersection => RunErrorMsg::EmptyIntersection,
             },
         }
     }
@@ -266,6 +274,10 @@ impl From<RunError> for crate::RunError {
                 RunErrorMsg::IndexOutOfBounds => crate::RunErrorMsg::IndexOutOfBounds,
                 RunErrorMsg::NoMatch => crate::RunErrorMsg::NoMatch,
                 RunErrorMsg::InvalidArgument => crate::RunErrorMsg::InvalidArgument,
+                RunErrorMsg::NoConvergence => crate::RunErrorMsg::NoConvergence,
+                RunErrorMsg::SingularMatrix => crate::RunErrorMsg::SingularMatrix,
+                RunErrorMsg::UnknownName(name) => crate::RunErrorMsg::UnknownName(name),
+                RunErrorMsg::EmptyIntersection => crate::RunErrorMsg::EmptyIntersection,
             },
         }
     }
@@ -0,0 +1,25 @@
+#[cfg(test)]
+mod tests {
+    use crate::grid::file::v1_6::schema::GridSchema;
+    use anyhow::{anyhow, Result};
+
+    const V1_6_FILE: &str =
+        include_str!("../../../../../quadratic-rust-shared/data/grid/v1_6_simple.grid");
+
+    fn import(file_contents: &str) -> Result<GridSchema> {
+        serde_json::from_str::<GridSchema>(file_contents)
+            .map_err(|e| anyhow!("Could not import file: {:?}", e))
+    }
+
+    fn export(grid_schema: &GridSchema) -> Result<String> {
+        serde_json::to_string(grid_schema).map_err(|e| anyhow!("Could not export file: {:?}", e))
+    }
+
+    #[test]
+    fn import_and_export_a_v1_6_file() {
+        let imported = import(V1_6_FILE).unwrap();
+        assert_eq!(imported.defined_names.len(), 1);
+        let exported = export(&imported).unwrap();
+        assert_eq!(V1_6_FILE, exported);
+    }
+}
@@ -0,0 +1,107 @@
+use chrono::{serde::ts_seconds_option, DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub use crate::grid::file::v1_5::schema::*;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GridSchema {
+    pub sheets: Vec<Sheet>,
+    pub defined_names: Vec<DefinedName>,
+    pub version: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DefinedName {
+    pub name: String,
+    pub sheet_id: Option<Id>,
+    pub refers_to: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SheetLines {
+    Columns { sheet_id: Id, min: i64, max: i64 },
+    Rows { sheet_id: Id, min: i64, max: i64 },
+}
+impl From<crate::grid::SheetLines> for SheetLines {
+    fn from(lines: crate::grid::SheetLines) -> Self {
+        match lines {
+            crate::grid::SheetLines::Columns { sheet_id, min, max } => SheetLines::Columns {
+                sheet_id: sheet_id.into(),
+                min,
+                max,
+            },
+            crate::grid::SheetLines::Rows { sheet_id, min, max } => SheetLines::Rows {
+                sheet_id: sheet_id.into(),
+                min,
+                max,
+            },
+        }
+    }
+}
+
+impl From<SheetLines> for crate::grid::SheetLines {
+    fn from(lines: SheetLines) -> Self {
+        match lines {
+            SheetLines::Columns { sheet_id, min, max } => crate::grid::SheetLines::Columns {
+                sheet_id: sheet_id.into(),
+                min,
+                max,
+            },
+            SheetLines::Rows { sheet_id, min, max } => crate::grid::SheetLines::Rows {
+                sheet_id: sheet_id.into(),
+                min,
+                max,
+            },
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sheet {
+    pub id: Id,
+    pub name: String,
+    pub color: Option<String>,
+    pub order: String,
+    pub offsets: Offsets,
+    pub columns: Vec<(i64, Column)>,
+    pub borders: Borders,
+    pub code_runs: Vec<(Pos, CodeRun)>,
+
+    // The following skips are necessary since we're adding it mid-version. Next
+    // version we should remove them.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub formats_all: Option<Format>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub formats_columns: Vec<(i64, (Format, i64))>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub formats_rows: Vec<(i64, (Format, i64))>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeRun {
+    pub formatted_code_string: Option<String>,
+    pub std_out: Option<String>,
+    pub std_err: Option<String>,
+    pub cells_accessed: Vec<SheetRect>,
+
+    // The following skips are necessary since we're adding them mid-version.
+    // Next version we should remove them.
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub lines_accessed: Vec<SheetLines>,
+
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub is_volatile: bool,
+
+    pub result: CodeRunResult,
+    pub return_type: Option<String>,
+    pub line_number: Option<u32>,
+    pub output_type: Option<String>,
+    pub spill_error: bool,
+
+    // the Option is necessary to use serde
+    #[serde(with = "ts_seconds_option")]
+    pub last_modified: Option<DateTime<Utc>>,
+}
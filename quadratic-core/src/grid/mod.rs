@@ -12,6 +12,7 @@ pub use borders::{
 pub use bounds::GridBounds;
 pub use code_run::*;
 pub use column::{Column, ColumnData};
+pub use defined_names::DefinedName;
 pub use formatting::{
     Bold, CellAlign, CellFmtAttr, CellWrap, FillColor, Italic, NumericCommas, NumericDecimals,
     NumericFormat, NumericFormatKind, RenderSize, TextColor,
@@ -27,6 +28,7 @@ mod borders;
 mod bounds;
 mod code_run;
 mod column;
+mod defined_names;
 pub mod file;
 pub mod formats;
 pub mod formatting;
@@ -41,6 +43,8 @@ pub mod sheets;
 #[cfg_attr(feature = "js", wasm_bindgen)]
 pub struct Grid {
     sheets: Vec<Sheet>,
+    #[serde(default)]
+    defined_names: Vec<DefinedName>,
 }
 impl Default for Grid {
     fn default() -> Self {
@@ -54,7 +58,10 @@ impl Grid {
         ret
     }
     pub fn new_blank() -> Self {
-        Grid { sheets: vec![] }
+        Grid {
+            sheets: vec![],
+            defined_names: vec![],
+        }
     }
 
     #[cfg(test)]
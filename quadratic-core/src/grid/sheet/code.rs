@@ -98,6 +98,24 @@ impl Sheet {
             })
     }
 
+    /// Iterates over the formula cells in the sheet, whether or not they have
+    /// been run.
+    pub fn iter_formula_cells(&self) -> impl Iterator<Item = (Pos, &str)> {
+        self.columns.iter().flat_map(|(&x, column)| {
+            column
+                .values
+                .iter()
+                .filter_map(move |(&y, value)| match value {
+                    CellValue::Code(code_cell)
+                        if code_cell.language == CodeCellLanguage::Formula =>
+                    {
+                        Some((Pos { x, y }, code_cell.code.as_str()))
+                    }
+                    _ => None,
+                })
+        })
+    }
+
     /// returns the render-size for a html-like cell
     pub fn render_size(&self, pos: Pos) -> Option<RenderSize> {
         let column = self.get_column(pos.x)?;
@@ -250,11 +268,13 @@ mod test {
             formatted_code_string: None,
             last_modified: Utc::now(),
             cells_accessed: HashSet::new(),
+            lines_accessed: HashSet::new(),
             result: CodeRunResult::Ok(Value::Single(CellValue::Number(BigDecimal::from(2)))),
             return_type: Some("number".into()),
             line_number: None,
             output_type: None,
             spill_error: false,
+            is_volatile: false,
         };
         let old = sheet.set_code_run(Pos { x: 0, y: 0 }, Some(code_run.clone()));
         assert_eq!(old, None);
@@ -273,11 +293,13 @@ mod test {
             std_out: None,
             formatted_code_string: None,
             cells_accessed: HashSet::new(),
+            lines_accessed: HashSet::new(),
             result: CodeRunResult::Ok(Value::Single(CellValue::Number(BigDecimal::from(2)))),
             return_type: Some("number".into()),
             line_number: None,
             output_type: None,
             spill_error: false,
+            is_volatile: false,
             last_modified: Utc::now(),
         };
         sheet.set_code_run(Pos { x: 0, y: 0 }, Some(code_run.clone()));
@@ -306,11 +328,13 @@ mod test {
             std_out: None,
             formatted_code_string: None,
             cells_accessed: HashSet::new(),
+            lines_accessed: HashSet::new(),
             result: CodeRunResult::Ok(Value::Array(Array::from(vec![vec!["1", "2", "3"]]))),
             return_type: Some("number".into()),
             line_number: None,
             output_type: None,
             spill_error: false,
+            is_volatile: false,
             last_modified: Utc::now(),
         };
         sheet.set_code_run(Pos { x: 0, y: 0 }, Some(code_run.clone()));
@@ -391,6 +415,7 @@ mod test {
             std_out: None,
             formatted_code_string: None,
             cells_accessed: HashSet::new(),
+            lines_accessed: HashSet::new(),
             result: CodeRunResult::Ok(Value::Array(Array::from(vec![
                 vec!["1"],
                 vec!["2"],
@@ -400,6 +425,7 @@ mod test {
             line_number: None,
             output_type: None,
             spill_error: false,
+            is_volatile: false,
             last_modified: Utc::now(),
         };
         sheet.set_code_run(Pos { x: 0, y: 0 }, Some(code_run.clone()));
@@ -425,11 +451,13 @@ mod test {
             std_out: None,
             formatted_code_string: None,
             cells_accessed: HashSet::new(),
+            lines_accessed: HashSet::new(),
             result: CodeRunResult::Ok(Value::Array(Array::from(vec![vec!["1", "2", "3'"]]))),
             return_type: Some("number".into()),
             line_number: None,
             output_type: None,
             spill_error: false,
+            is_volatile: false,
             last_modified: Utc::now(),
         };
         sheet.set_code_run(Pos { x: 0, y: 0 }, Some(code_run.clone()));
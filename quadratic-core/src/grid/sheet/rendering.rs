@@ -50,22 +50,30 @@ impl Sheet {
                 special: Some(JsRenderCellSpecial::Chart),
             };
         } else if let CellValue::Error(error) = value {
-            let spill_error = matches!(error.msg, RunErrorMsg::Spill);
+            // Errors in values and formulas are shown as Excel error codes,
+            // such as `#N/A`, but errors in other code cells are not.
+            let special = match (&error.msg, &language) {
+                (RunErrorMsg::Spill, _) => Some(JsRenderCellSpecial::SpillError),
+                (_, Some(language)) if *language != CodeCellLanguage::Formula => {
+                    Some(JsRenderCellSpecial::RunError)
+                }
+                _ => None,
+            };
+            let value = match special {
+                Some(_) => "".to_string(),
+                None => error.msg.excel_code().to_string(),
+            };
             return JsRenderCell {
                 x,
                 y,
-                value: "".into(),
+                value,
                 language,
                 align: None,
                 wrap: None,
                 bold: None,
                 italic: None,
                 text_color: None,
-                special: Some(if spill_error {
-                    JsRenderCellSpecial::SpillError
-                } else {
-                    JsRenderCellSpecial::RunError
-                }),
+                special,
             };
         } else if let CellValue::Logical(logical) = value {
             return JsRenderCell {
@@ -551,7 +559,9 @@ mod tests {
                 std_err: None,
                 std_out: None,
                 spill_error: false,
+                is_volatile: false,
                 cells_accessed: HashSet::new(),
+                lines_accessed: HashSet::new(),
                 result: CodeRunResult::Ok(Value::Single(CellValue::Text("hello".to_string()))),
                 return_type: Some("text".into()),
                 line_number: None,
@@ -688,18 +698,58 @@ mod tests {
             JsRenderCell {
                 x: 3,
                 y: 3,
-                value: "".to_string(),
+                value: "#VALUE!".to_string(),
                 language: None,
                 align: None,
                 wrap: None,
                 bold: None,
                 italic: None,
                 text_color: None,
-                special: Some(JsRenderCellSpecial::RunError),
+                special: None,
             },
         );
     }
 
+    #[test]
+    fn render_error_codes() {
+        let mut gc = GridController::test();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_code_cell(
+            SheetPos {
+                x: 0,
+                y: 0,
+                sheet_id,
+            },
+            CodeCellLanguage::Formula,
+            "1/0".to_string(),
+            None,
+        );
+        gc.set_cell_value(
+            SheetPos {
+                x: 1,
+                y: 0,
+                sheet_id,
+            },
+            "#N/A".to_string(),
+            None,
+        );
+
+        let sheet = gc.sheet(sheet_id);
+        let render = sheet.get_render_cells(Rect::from_numbers(0, 0, 2, 1));
+        assert_eq!(render.len(), 2);
+        let get = |x: i64| render.iter().find(|cell| cell.x == x).unwrap();
+        assert_eq!(get(0).value, "#DIV/0!");
+        assert_eq!(get(0).special, None);
+        assert_eq!(get(1).value, "#N/A");
+        assert_eq!(get(1).special, None);
+
+        // errors in other languages are not shown as Excel error codes
+        let error = CellValue::Error(Box::new(RunErrorMsg::NoMatch.without_span()));
+        let cell = sheet.get_render_cell(0, 0, None, error, Some(CodeCellLanguage::Python));
+        assert_eq!(cell.value, "");
+        assert_eq!(cell.special, Some(JsRenderCellSpecial::RunError));
+    }
+
     #[test]
     fn test_get_html_output() {
         let mut gc = GridController::test();
@@ -785,11 +835,13 @@ mod tests {
             formatted_code_string: None,
             last_modified: Utc::now(),
             cells_accessed: HashSet::new(),
+            lines_accessed: HashSet::new(),
             result: CodeRunResult::Ok(Value::Array(
                 vec![vec!["1", "2", "3"], vec!["4", "5", "6"]].into(),
             )),
             return_type: Some("number".into()),
             spill_error: false,
+            is_volatile: false,
             line_number: None,
             output_type: None,
         };
@@ -905,9 +957,11 @@ mod tests {
             formatted_code_string: None,
             last_modified: Utc::now(),
             cells_accessed: HashSet::new(),
+            lines_accessed: HashSet::new(),
             result: CodeRunResult::Ok(Value::Single(CellValue::Number(2.into()))),
             return_type: Some("number".into()),
             spill_error: false,
+            is_volatile: false,
             line_number: None,
             output_type: None,
         };
@@ -950,9 +1004,11 @@ mod tests {
             formatted_code_string: None,
             last_modified: Utc::now(),
             cells_accessed: HashSet::new(),
+            lines_accessed: HashSet::new(),
             result: CodeRunResult::Ok(Value::Single(CellValue::Image(image.clone()))),
             return_type: Some("image".into()),
             spill_error: false,
+            is_volatile: false,
             line_number: None,
             output_type: None,
         };
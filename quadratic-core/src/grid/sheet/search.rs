@@ -471,7 +471,9 @@ mod test {
             std_out: None,
             std_err: None,
             cells_accessed: HashSet::new(),
+            lines_accessed: HashSet::new(),
             spill_error: false,
+            is_volatile: false,
             return_type: None,
             line_number: None,
             output_type: None,
@@ -512,7 +514,9 @@ mod test {
             std_out: None,
             std_err: None,
             cells_accessed: HashSet::new(),
+            lines_accessed: HashSet::new(),
             spill_error: false,
+            is_volatile: false,
             return_type: None,
             line_number: None,
             output_type: None,
@@ -66,11 +66,13 @@ impl Sheet {
                 std_err: None,
                 formatted_code_string: None,
                 cells_accessed: std::collections::HashSet::new(),
+                lines_accessed: std::collections::HashSet::new(),
                 result: crate::grid::CodeRunResult::Ok(crate::Value::Single(value)),
                 return_type: Some("number".into()),
                 line_number: None,
                 output_type: None,
                 spill_error: false,
+                is_volatile: false,
                 last_modified: chrono::Utc::now(),
             }),
         );
@@ -133,11 +135,13 @@ impl Sheet {
                 std_err: None,
                 formatted_code_string: None,
                 cells_accessed: HashSet::new(),
+                lines_accessed: HashSet::new(),
                 result: CodeRunResult::Ok(Value::Array(array)),
                 return_type: Some("number".into()),
                 line_number: None,
                 output_type: None,
                 spill_error: false,
+                is_volatile: false,
                 last_modified: Utc::now(),
             }),
         );
@@ -181,11 +185,13 @@ impl Sheet {
                 std_err: None,
                 formatted_code_string: None,
                 cells_accessed: HashSet::new(),
+                lines_accessed: HashSet::new(),
                 result: CodeRunResult::Ok(Value::Array(array)),
                 return_type: Some("number".into()),
                 line_number: None,
                 output_type: None,
                 spill_error: false,
+                is_volatile: false,
                 last_modified: Utc::now(),
             }),
         );
@@ -34,6 +34,15 @@ impl Grid {
         self.sheets.iter().find(|sheet| sheet.name == name)
     }
 
+    /// Returns the sheets from the sheet named `first` to the sheet named
+    /// `last` in sheet order, inclusive, or `None` if either sheet does not
+    /// exist. The sheets may be given in either order.
+    pub fn sheets_between(&self, first: &str, last: &str) -> Option<&[Sheet]> {
+        let index = |name: &str| self.sheets.iter().position(|sheet| sheet.name == name);
+        let (first, last) = (index(first)?, index(last)?);
+        Some(&self.sheets[first.min(last)..=first.max(last)])
+    }
+
     pub fn try_sheet_mut_from_name(&mut self, name: String) -> Option<&mut Sheet> {
         self.sheets.iter_mut().find(|sheet| sheet.name == name)
     }
@@ -276,6 +285,27 @@ mod test {
         assert_eq!(grid.next_sheet(grid.sheets[2].id), None);
     }
 
+    #[test]
+    fn test_sheets_between() {
+        let mut grid = create_three_sheets();
+        assert_eq!(grid.sheets_between("0", "1"), Some(&grid.sheets[0..=1]));
+        assert_eq!(grid.sheets_between("2", "0"), Some(&grid.sheets[..]));
+        assert_eq!(grid.sheets_between("1", "1"), Some(&grid.sheets[1..=1]));
+        assert_eq!(grid.sheets_between("0", "missing"), None);
+
+        // the span follows the sheet order
+        grid.sheets[0].order = String::from("a2");
+        grid.sheets[1].order = String::from("a3");
+        grid.sheets[2].order = String::from("a1");
+        grid.sort_sheets();
+        let names = grid
+            .sheets_between("2", "1")
+            .unwrap()
+            .iter()
+            .map(|sheet| &sheet.name);
+        assert_eq!(names.collect::<Vec<_>>(), ["2", "0", "1"]);
+    }
+
     #[test]
     fn test_sort_sheets() {
         let mut grid = create_three_sheets();
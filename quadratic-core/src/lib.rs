@@ -38,6 +38,9 @@ pub mod limits {
 
     /// Maximum cell range size allowed. Must be strictly less than `u32::MAX`.
     pub const CELL_RANGE_LIMIT: u32 = 1_000_000;
+
+    /// Maximum length, in bytes, of a string produced by a formula.
+    pub const STRING_LENGTH_LIMIT: usize = 1_000_000;
 }
 
 pub const DEFAULT_COLUMN_WIDTH: f64 = 100.0;
@@ -201,6 +201,21 @@ impl Rect {
             || other.min.y > self.max.y)
     }
 
+    /// Returns the region contained in both rectangles, or `None` if they do
+    /// not intersect.
+    pub fn intersection(self, other: Rect) -> Option<Rect> {
+        self.intersects(other).then(|| Rect {
+            min: Pos {
+                x: std::cmp::max(self.min.x, other.min.x),
+                y: std::cmp::max(self.min.y, other.min.y),
+            },
+            max: Pos {
+                x: std::cmp::min(self.max.x, other.max.x),
+                y: std::cmp::min(self.max.y, other.max.y),
+            },
+        })
+    }
+
     /// Returns the range of X values in the rectangle.
     pub fn x_range(self) -> Range<i64> {
         self.min.x..self.max.x + 1
@@ -719,6 +734,20 @@ mod test {
         assert!(!rect.intersects(Rect::from_ranges(1..4, 6..7)));
     }
 
+    #[test]
+    fn test_intersection() {
+        let rect = Rect::new(1, 2, 4, 5);
+        assert_eq!(
+            rect.intersection(Rect::new(3, 0, 6, 3)),
+            Some(Rect::new(3, 2, 4, 3))
+        );
+        assert_eq!(
+            rect.intersection(Rect::new(2, 3, 3, 4)),
+            Some(Rect::new(2, 3, 3, 4))
+        );
+        assert_eq!(rect.intersection(Rect::new(5, 2, 6, 5)), None);
+    }
+
     #[test]
     fn test_x_range() {
         let rect = Rect::from_ranges(1..4, 2..5);
@@ -65,7 +65,7 @@ impl fmt::Display for CellValue {
             CellValue::Logical(false) => write!(f, "FALSE"),
             CellValue::Instant(i) => write!(f, "{i}"),
             CellValue::Duration(d) => write!(f, "{d}"),
-            CellValue::Error(e) => write!(f, "{}", e.msg),
+            CellValue::Error(e) => write!(f, "{}", e.msg.excel_code()),
             CellValue::Html(s) => write!(f, "{}", s),
             CellValue::Code(code) => write!(f, "{:?}", code),
             CellValue::Image(s) => write!(f, "{}", s),
@@ -104,9 +104,9 @@ impl CellValue {
             CellValue::Number(n) => n.to_string(),
             CellValue::Logical(true) => "TRUE".to_string(),
             CellValue::Logical(false) => "FALSE".to_string(),
-            CellValue::Instant(_) => todo!("repr of Instant"),
-            CellValue::Duration(_) => todo!("repr of Duration"),
-            CellValue::Error(_) => "[error]".to_string(),
+            CellValue::Instant(i) => format!("{:?}", i.to_string()),
+            CellValue::Duration(d) => format!("{:?}", d.to_string()),
+            CellValue::Error(e) => e.msg.excel_code().to_string(),
             CellValue::Html(s) => s.clone(),
             CellValue::Code(_) => todo!("repr of code"),
             CellValue::Image(_) => todo!("repr of image"),
@@ -214,9 +214,9 @@ impl CellValue {
             }
             CellValue::Logical(true) => "true".to_string(),
             CellValue::Logical(false) => "false".to_string(),
-            CellValue::Instant(_) => todo!("repr of Instant"),
-            CellValue::Duration(_) => todo!("repr of Duration"),
-            CellValue::Error(_) => "[error]".to_string(),
+            CellValue::Instant(i) => i.to_string(),
+            CellValue::Duration(d) => d.to_string(),
+            CellValue::Error(e) => e.msg.excel_code().to_string(),
 
             // these should not render
             CellValue::Code(_) => String::new(),
@@ -232,9 +232,9 @@ impl CellValue {
             CellValue::Number(n) => n.to_string(),
             CellValue::Logical(true) => "true".to_string(),
             CellValue::Logical(false) => "false".to_string(),
-            CellValue::Instant(_) => todo!("repr of Instant"),
-            CellValue::Duration(_) => todo!("repr of Duration"),
-            CellValue::Error(_) => "[error]".to_string(),
+            CellValue::Instant(i) => i.to_string(),
+            CellValue::Duration(d) => d.to_string(),
+            CellValue::Error(e) => e.msg.excel_code().to_string(),
 
             // this should not be editable
             CellValue::Code(_) => String::new(),
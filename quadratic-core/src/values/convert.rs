@@ -1,6 +1,6 @@
 use bigdecimal::{BigDecimal, ToPrimitive, Zero};
 
-use super::{CellValue, IsBlank, Value};
+use super::{CellValue, Duration, Instant, IsBlank, Value};
 use crate::{CodeResult, CodeResultExt, RunErrorMsg, Span, Spanned, Unspan};
 
 const CURRENCY_PREFIXES: &[char] = &['$', '¥', '£', '€'];
@@ -42,6 +42,11 @@ impl From<f64> for CellValue {
             .map_or_else(|_| CellValue::Text(value.to_string()), CellValue::Number)
     }
 }
+impl From<BigDecimal> for CellValue {
+    fn from(value: BigDecimal) -> Self {
+        CellValue::Number(value)
+    }
+}
 impl From<i64> for CellValue {
     fn from(value: i64) -> Self {
         CellValue::Number(BigDecimal::from(value))
@@ -62,6 +67,16 @@ impl From<bool> for CellValue {
         CellValue::Logical(value)
     }
 }
+impl From<Instant> for CellValue {
+    fn from(value: Instant) -> Self {
+        CellValue::Instant(value)
+    }
+}
+impl From<Duration> for CellValue {
+    fn from(value: Duration) -> Self {
+        CellValue::Duration(value)
+    }
+}
 impl<T> From<CodeResult<T>> for CellValue
 where
     CellValue: From<T>,
@@ -133,6 +148,33 @@ impl<'a> TryFrom<&'a CellValue> for f64 {
         }
     }
 }
+impl<'a> TryFrom<&'a CellValue> for BigDecimal {
+    type Error = RunErrorMsg;
+
+    fn try_from(value: &'a CellValue) -> Result<Self, Self::Error> {
+        // Coerce the same values as `f64`, but without losing precision.
+        match value {
+            CellValue::Number(n) => Ok(n.clone()),
+            CellValue::Text(s) => {
+                let mut s = s.trim();
+                if s.is_empty() {
+                    return Ok(BigDecimal::zero());
+                }
+                if let Some(rest) = s.strip_prefix(CURRENCY_PREFIXES) {
+                    s = rest;
+                }
+                s.parse().map_err(|_| RunErrorMsg::Expected {
+                    expected: "number".into(),
+                    got: Some(value.type_name().into()),
+                })
+            }
+            CellValue::Logical(true) => Ok(BigDecimal::from(1)),
+            other => {
+                BigDecimal::try_from(f64::try_from(other)?).map_err(|_| RunErrorMsg::NotANumber)
+            }
+        }
+    }
+}
 impl<'a> TryFrom<&'a CellValue> for i64 {
     type Error = RunErrorMsg;
 
@@ -165,6 +207,42 @@ impl<'a> TryFrom<&'a CellValue> for bool {
         }
     }
 }
+impl<'a> TryFrom<&'a CellValue> for Instant {
+    type Error = RunErrorMsg;
+
+    fn try_from(value: &'a CellValue) -> Result<Self, Self::Error> {
+        let parsed = match value {
+            CellValue::Instant(i) => Some(*i),
+            CellValue::Text(s) => Instant::parse(s),
+            // Numbers are interpreted as Excel serial numbers.
+            CellValue::Number(n) => n.to_f64().map(Instant::from_excel_serial),
+            CellValue::Error(e) => return Err(e.msg.clone()),
+            _ => None,
+        };
+        parsed.ok_or_else(|| RunErrorMsg::Expected {
+            expected: "time instant".into(),
+            got: Some(value.type_name().into()),
+        })
+    }
+}
+impl<'a> TryFrom<&'a CellValue> for Duration {
+    type Error = RunErrorMsg;
+
+    fn try_from(value: &'a CellValue) -> Result<Self, Self::Error> {
+        let parsed = match value {
+            CellValue::Duration(d) => Some(*d),
+            CellValue::Text(s) => Duration::parse_time_of_day(s),
+            // Numbers are interpreted as a number of days.
+            CellValue::Number(n) => n.to_f64().map(Duration::from_days),
+            CellValue::Error(e) => return Err(e.msg.clone()),
+            _ => None,
+        };
+        parsed.ok_or_else(|| RunErrorMsg::Expected {
+            expected: "time duration".into(),
+            got: Some(value.type_name().into()),
+        })
+    }
+}
 
 impl TryFrom<CellValue> for String {
     type Error = RunErrorMsg;
@@ -189,8 +267,11 @@ macro_rules! impl_try_from_cell_value_for {
     };
 }
 impl_try_from_cell_value_for!(f64);
+impl_try_from_cell_value_for!(BigDecimal);
 impl_try_from_cell_value_for!(i64);
 impl_try_from_cell_value_for!(bool);
+impl_try_from_cell_value_for!(Instant);
+impl_try_from_cell_value_for!(Duration);
 
 impl<'a> TryFrom<&'a Value> for &'a CellValue {
     type Error = RunErrorMsg;
@@ -227,6 +308,7 @@ macro_rules! impl_try_from_value_for {
 }
 impl_try_from_value_for!(String);
 impl_try_from_value_for!(f64);
+impl_try_from_value_for!(BigDecimal);
 impl_try_from_value_for!(i64);
 impl_try_from_value_for!(bool);
 
@@ -19,7 +19,7 @@ pub use cellvalue::CellValue;
 pub use cellvalue::CodeCellValue;
 pub use convert::CoerceInto;
 pub use isblank::IsBlank;
-pub use time::{Duration, Instant};
+pub use time::{Duration, Instant, SECONDS_PER_DAY};
 
 use crate::{CodeResult, CodeResultExt, RunErrorMsg, SpannableIterExt, Spanned};
 
@@ -1,10 +1,46 @@
 use std::fmt::{self, Display};
 
 use anyhow::{bail, Result};
-use chrono::{DateTime, MappedLocalTime, NaiveDateTime};
+use chrono::{
+    DateTime, Datelike, MappedLocalTime, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike,
+};
 use serde::{Deserialize, Serialize};
 
-use crate::CellValue;
+/// Number of seconds in a day.
+pub const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Number of days from 0001-01-01 (day 1 of the common era) to 1970-01-01 (the
+/// Unix epoch).
+const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+/// Excel serial number of 1970-01-01 (the Unix epoch). Excel serial numbers
+/// count days since 1899-12-30.
+const UNIX_EPOCH_EXCEL_SERIAL: f64 = 25_569.0;
+
+/// Formats accepted when parsing a date and time from a string.
+const DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+    "%m/%d/%Y %H:%M:%S",
+    "%m/%d/%Y %H:%M",
+    "%m/%d/%Y %I:%M:%S %p",
+    "%m/%d/%Y %I:%M %p",
+];
+/// Formats accepted when parsing a date from a string.
+const DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d",
+    "%Y/%m/%d",
+    "%m/%d/%Y",
+    "%B %d, %Y",
+    "%b %d, %Y",
+    "%d %B %Y",
+    "%d %b %Y",
+    "%d-%b-%Y",
+];
+/// Formats accepted when parsing a time of day from a string.
+const TIME_FORMATS: &[&str] = &["%H:%M:%S", "%H:%M", "%I:%M:%S %p", "%I:%M %p"];
 
 #[cfg_attr(test, derive(proptest_derive::Arbitrary))]
 #[derive(Serialize, Deserialize, Debug, Copy, Clone)]
@@ -18,6 +54,86 @@ impl Instant {
     pub fn new(seconds: f64) -> Self {
         Self { seconds }
     }
+
+    /// Constructs an instant at midnight (UTC) on a date.
+    pub fn from_date(date: NaiveDate) -> Self {
+        let days = date.num_days_from_ce() - UNIX_EPOCH_DAYS_FROM_CE;
+        Self::new(days as f64 * SECONDS_PER_DAY)
+    }
+    /// Constructs an instant from an Excel serial number, which counts days
+    /// since 1899-12-30.
+    pub fn from_excel_serial(serial: f64) -> Self {
+        Self::new((serial - UNIX_EPOCH_EXCEL_SERIAL) * SECONDS_PER_DAY)
+    }
+    /// Parses an instant from a string containing a date, optionally followed
+    /// by a time. Returns `None` if the string is not a recognized format.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if let Some(datetime) = DATETIME_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+        {
+            return Some(Self::from(datetime));
+        }
+        DATE_FORMATS
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(s, format).ok())
+            .map(Self::from_date)
+    }
+
+    /// Returns the date and time (UTC) of the instant, or `None` if it is out
+    /// of range.
+    pub fn to_naive(self) -> Option<NaiveDateTime> {
+        let date = self.to_date()?;
+        let seconds_of_day = self.seconds_of_day();
+        let whole_seconds = seconds_of_day.floor();
+        let nanos = ((seconds_of_day - whole_seconds) * 1e9) as u32;
+        let time = NaiveTime::from_num_seconds_from_midnight_opt(whole_seconds as u32, nanos)?;
+        Some(date.and_time(time))
+    }
+    /// Returns the date (UTC) of the instant, or `None` if it is out of range.
+    pub fn to_date(self) -> Option<NaiveDate> {
+        let days = (self.seconds / SECONDS_PER_DAY).floor();
+        if !days.is_finite() || days.abs() > i32::MAX as f64 {
+            return None;
+        }
+        let days = (days as i32).checked_add(UNIX_EPOCH_DAYS_FROM_CE)?;
+        NaiveDate::from_num_days_from_ce_opt(days)
+    }
+    /// Returns the number of seconds since midnight (UTC).
+    pub fn seconds_of_day(self) -> f64 {
+        self.seconds.rem_euclid(SECONDS_PER_DAY)
+    }
+
+    /// Adds a number of months to the instant, keeping the time of day the
+    /// same. If the day of the month does not exist in the resulting month,
+    /// then the last day of that month is used instead. Returns `None` if the
+    /// result is out of range.
+    pub fn checked_add_months(self, months: i32) -> Option<Self> {
+        if months == 0 {
+            return Some(self);
+        }
+        let date = self.to_date()?;
+        let new_date = if months > 0 {
+            date.checked_add_months(Months::new(months.unsigned_abs()))?
+        } else {
+            date.checked_sub_months(Months::new(months.unsigned_abs()))?
+        };
+        Some(Self::new(
+            Self::from_date(new_date).seconds + self.seconds_of_day(),
+        ))
+    }
+    /// Adds a duration to the instant. Years and months are added first, then
+    /// seconds. Returns `None` if the result is out of range.
+    pub fn checked_add(self, duration: Duration) -> Option<Self> {
+        let months = duration
+            .years
+            .checked_mul(12)?
+            .checked_add(duration.months)?;
+        let seconds = self.checked_add_months(months)?.seconds + duration.seconds;
+        let ret = Self::new(seconds);
+        ret.to_date().map(|_| ret)
+    }
 }
 
 impl From<NaiveDateTime> for Instant {
@@ -30,12 +146,13 @@ impl From<NaiveDateTime> for Instant {
 
 impl fmt::Display for Instant {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            CellValue::unpack_unix_timestamp(self.seconds as i64).unwrap_or_default()
-        )
-        // write!(f, "{s} seconds", s = self.seconds)
+        match self.to_naive() {
+            Some(datetime) if self.seconds_of_day() == 0.0 => {
+                write!(f, "{}", datetime.format("%Y-%m-%d"))
+            }
+            Some(datetime) => write!(f, "{}", datetime.format("%Y-%m-%d %H:%M:%S")),
+            None => write!(f, "{s} seconds", s = self.seconds),
+        }
     }
 }
 
@@ -66,15 +183,93 @@ pub struct Duration {
     pub seconds: f64,
 }
 
+impl Duration {
+    /// Constructs a duration consisting only of seconds.
+    pub fn from_seconds(seconds: f64) -> Self {
+        Self {
+            years: 0,
+            months: 0,
+            seconds,
+        }
+    }
+    /// Constructs a duration consisting only of days (which may be
+    /// fractional).
+    pub fn from_days(days: f64) -> Self {
+        Self::from_seconds(days * SECONDS_PER_DAY)
+    }
+    /// Parses a time of day from a string, returning the duration since
+    /// midnight. Returns `None` if the string is not a recognized format.
+    pub fn parse_time_of_day(s: &str) -> Option<Self> {
+        let s = s.trim();
+        TIME_FORMATS
+            .iter()
+            .find_map(|format| NaiveTime::parse_from_str(s, format).ok())
+            .map(|time| {
+                let seconds =
+                    time.num_seconds_from_midnight() as f64 + time.nanosecond() as f64 / 1e9;
+                Self::from_seconds(seconds)
+            })
+    }
+}
+
 impl fmt::Display for Duration {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{y} years, {m} months, {s} seconds",
-            y = self.years,
-            m = self.months,
-            s = self.seconds,
-        )
+        fn plural(n: i64, unit: &str) -> String {
+            match n {
+                1 | -1 => format!("{n} {unit}"),
+                _ => format!("{n} {unit}s"),
+            }
+        }
+
+        let mut parts = vec![];
+        if self.years != 0 {
+            parts.push(plural(self.years as i64, "year"));
+        }
+        if self.months != 0 {
+            parts.push(plural(self.months as i64, "month"));
+        }
+        let sign = if self.seconds < 0.0 { "-" } else { "" };
+        let total_seconds = self.seconds.abs();
+        let days = (total_seconds / SECONDS_PER_DAY).floor();
+        if days != 0.0 {
+            parts.push(format!("{sign}{}", plural(days as i64, "day")));
+        }
+        let seconds_of_day = total_seconds - days * SECONDS_PER_DAY;
+        if seconds_of_day != 0.0 || parts.is_empty() {
+            let hours = (seconds_of_day / 3600.0).floor();
+            let minutes = ((seconds_of_day - hours * 3600.0) / 60.0).floor();
+            let seconds = seconds_of_day - hours * 3600.0 - minutes * 60.0;
+            let seconds = if seconds.fract() == 0.0 {
+                format!("{seconds:02}")
+            } else {
+                format!("{seconds:06.3}")
+            };
+            parts.push(format!("{sign}{hours:02}:{minutes:02}:{seconds}"));
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+impl std::ops::Add for Duration {
+    type Output = Duration;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Duration {
+            years: self.years.saturating_add(rhs.years),
+            months: self.months.saturating_add(rhs.months),
+            seconds: self.seconds + rhs.seconds,
+        }
+    }
+}
+impl std::ops::Neg for Duration {
+    type Output = Duration;
+
+    fn neg(self) -> Self::Output {
+        Duration {
+            years: self.years.saturating_neg(),
+            months: self.months.saturating_neg(),
+            seconds: -self.seconds,
+        }
     }
 }
 
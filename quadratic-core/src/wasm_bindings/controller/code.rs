@@ -46,6 +46,26 @@ impl GridController {
         }
     }
 
+    /// Called by an external calculation to get the cells of a defined name.
+    #[wasm_bindgen(js_name = "calculationGetCellsFromName")]
+    pub fn js_calculation_get_cells_from_name(
+        &mut self,
+        transaction_id: String,
+        name: String,
+        line_number: Option<u32>,
+    ) -> Result<String, JsValue> {
+        match self.calculation_get_cells_from_name(transaction_id, name, line_number) {
+            Ok(get_cells) => match serde_json::to_string(&get_cells) {
+                Ok(json) => Ok(json),
+                Err(_) => {
+                    dbgjs!("calculationGetCellsFromName: Failed to serialize calculation result");
+                    Err(JsValue::UNDEFINED)
+                }
+            },
+            Err(_) => Err(JsValue::UNDEFINED),
+        }
+    }
+
     /// Returns the code cell (which is a combination of CellValue::Code and CodeRun).
     /// If the cell is part of a code run, it returns the code run that caused the output.
     ///
@@ -95,6 +115,18 @@ impl GridController {
         }
     }
 
+    /// Recalculates formulas that use volatile functions.
+    #[wasm_bindgen(js_name = "recalculate")]
+    pub fn js_recalculate(&mut self, cursor: Option<String>) {
+        self.recalculate(cursor);
+    }
+
+    /// Recalculates all formulas in grid.
+    #[wasm_bindgen(js_name = "recalculateAllFormulas")]
+    pub fn js_recalculate_all_formulas(&mut self, cursor: Option<String>) {
+        self.recalculate_all_formulas(cursor);
+    }
+
     /// Reruns one code cell
     #[wasm_bindgen(js_name = "rerunCodeCell")]
     pub fn js_rerun_code_cell(&mut self, sheet_id: String, pos: String, cursor: Option<String>) {
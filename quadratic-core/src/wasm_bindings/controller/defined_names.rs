@@ -0,0 +1,30 @@
+use super::*;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Sets a defined name, or removes it if `refers_to` is undefined. The name
+    /// is visible on the sheet `sheet_id`, or on every sheet if `sheet_id` is
+    /// undefined.
+    #[wasm_bindgen(js_name = "setDefinedName")]
+    pub fn js_set_defined_name(
+        &mut self,
+        name: String,
+        sheet_id: Option<String>,
+        refers_to: Option<String>,
+        cursor: Option<String>,
+    ) -> Result<(), JsValue> {
+        let sheet_id = sheet_id
+            .map(|sheet_id| SheetId::from_str(&sheet_id))
+            .transpose()
+            .map_err(|e| e.to_string())?;
+        self.set_defined_name(name, sheet_id, refers_to, cursor)
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Returns all defined names as a stringified array of `DefinedName`.
+    #[wasm_bindgen(js_name = "getDefinedNames")]
+    pub fn js_get_defined_names(&self) -> Result<String, JsValue> {
+        Ok(serde_json::to_string(self.grid().defined_names()).map_err(|e| e.to_string())?)
+    }
+}
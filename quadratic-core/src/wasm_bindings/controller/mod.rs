@@ -9,6 +9,7 @@ pub mod bounds;
 pub mod cells;
 pub mod clipboard;
 pub mod code;
+pub mod defined_names;
 pub mod export;
 pub mod formatting;
 pub mod import;